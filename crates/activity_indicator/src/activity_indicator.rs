@@ -164,6 +164,26 @@ impl ActivityIndicator {
     }
 
     fn content_to_render(&mut self, cx: &mut ViewContext<Self>) -> Content {
+        // Show the progress of loading any large files.
+        let project = self.project.read(cx);
+        let mut large_file_loads = project.large_file_loads();
+        if let Some((path, load)) = large_file_loads.next() {
+            let mut message = format!("Loading {}", path.path.display());
+            if load.len > 0 {
+                write!(&mut message, " ({}%)", load.loaded * 100 / load.len).unwrap();
+            }
+            let additional_load_count = large_file_loads.count();
+            if additional_load_count > 0 {
+                write!(&mut message, " + {} more", additional_load_count).unwrap();
+            }
+
+            return Content {
+                icon: None,
+                message,
+                on_click: None,
+            };
+        }
+
         // Show any language server has pending activity.
         let mut pending_work = self.pending_language_server_work(cx);
         if let Some(PendingWork {
@@ -0,0 +1,5 @@
+pub mod completion;
+
+pub use completion::*;
+
+pub const ANTHROPIC_API_URL: &'static str = "https://api.anthropic.com/v1";
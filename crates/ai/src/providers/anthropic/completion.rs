@@ -0,0 +1,450 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    future::BoxFuture, io::BufReader, stream::BoxStream, AsyncBufReadExt, AsyncReadExt, FutureExt,
+    Stream, StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor};
+use isahc::{http::StatusCode, Request, RequestExt};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{env, io, sync::Arc};
+use util::ResultExt;
+
+use crate::{
+    auth::{CredentialProvider, ProviderCredential},
+    completion::{CompletionProvider, CompletionRequest},
+    models::LanguageModel,
+    providers::{
+        anthropic::ANTHROPIC_API_URL,
+        open_ai::{OpenAiLanguageModel, RequestMessage, Role},
+    },
+};
+
+const ANTHROPIC_API_VERSION: &'static str = "2023-06-01";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AnthropicRole {
+    User,
+    Assistant,
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct AnthropicMessage {
+    pub role: AnthropicRole,
+    pub content: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AnthropicRequest {
+    pub model: String,
+    pub messages: Vec<AnthropicMessage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub system: Option<String>,
+    pub max_tokens: usize,
+    pub stream: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop_sequences: Vec<String>,
+    pub temperature: f32,
+}
+
+impl AnthropicRequest {
+    /// Builds a request from OpenAI-style chat messages.
+    ///
+    /// The Messages API takes the system prompt as a separate parameter and requires
+    /// the conversation to alternate between the user and the assistant, starting with
+    /// the user, so system messages are hoisted and adjacent messages are merged.
+    pub fn new(
+        model: String,
+        messages: Vec<RequestMessage>,
+        stop_sequences: Vec<String>,
+        temperature: f32,
+        max_tokens: usize,
+    ) -> Self {
+        let mut system = Vec::new();
+        let mut anthropic_messages = Vec::<AnthropicMessage>::new();
+        for message in messages {
+            let role = match message.role {
+                Role::System => {
+                    system.push(message.content);
+                    continue;
+                }
+                Role::User => AnthropicRole::User,
+                Role::Assistant => AnthropicRole::Assistant,
+            };
+            if message.content.is_empty() {
+                continue;
+            }
+
+            match anthropic_messages.last_mut() {
+                Some(last_message) if last_message.role == role => {
+                    last_message.content.push_str("\n\n");
+                    last_message.content.push_str(&message.content);
+                }
+                None if role == AnthropicRole::Assistant => {
+                    anthropic_messages.push(AnthropicMessage {
+                        role: AnthropicRole::User,
+                        content: "Continue.".into(),
+                    });
+                    anthropic_messages.push(AnthropicMessage {
+                        role,
+                        content: message.content,
+                    });
+                }
+                _ => anthropic_messages.push(AnthropicMessage {
+                    role,
+                    content: message.content,
+                }),
+            }
+        }
+
+        Self {
+            model,
+            messages: anthropic_messages,
+            system: (!system.is_empty()).then(|| system.join("\n\n")),
+            max_tokens,
+            stream: true,
+            stop_sequences,
+            temperature,
+        }
+    }
+}
+
+impl CompletionRequest for AnthropicRequest {
+    fn data(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnthropicTextDelta {
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AnthropicError {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AnthropicResponseStreamEvent {
+    ContentBlockDelta {
+        index: u32,
+        delta: AnthropicTextDelta,
+    },
+    MessageStop,
+    Error {
+        error: AnthropicError,
+    },
+    #[serde(other)]
+    Other,
+}
+
+pub async fn stream_completion(
+    api_url: String,
+    credential: ProviderCredential,
+    executor: BackgroundExecutor,
+    request: Box<dyn CompletionRequest>,
+) -> Result<impl Stream<Item = Result<AnthropicResponseStreamEvent>>> {
+    let api_key = match credential {
+        ProviderCredential::Credentials { api_key } => api_key,
+        _ => {
+            return Err(anyhow!("no credentials provider for completion"));
+        }
+    };
+
+    let (tx, rx) = futures::channel::mpsc::unbounded::<Result<AnthropicResponseStreamEvent>>();
+
+    let json_data = request.data()?;
+    let mut response = Request::post(format!("{api_url}/messages"))
+        .header("Content-Type", "application/json")
+        .header("x-api-key", api_key)
+        .header("anthropic-version", ANTHROPIC_API_VERSION)
+        .body(json_data)?
+        .send_async()
+        .await?;
+
+    let status = response.status();
+    if status == StatusCode::OK {
+        executor
+            .spawn(async move {
+                let mut lines = BufReader::new(response.body_mut()).lines();
+
+                fn parse_line(
+                    line: Result<String, io::Error>,
+                ) -> Result<Option<AnthropicResponseStreamEvent>> {
+                    if let Some(data) = line?.strip_prefix("data: ") {
+                        let event = serde_json::from_str(data)?;
+                        Ok(Some(event))
+                    } else {
+                        Ok(None)
+                    }
+                }
+
+                while let Some(line) = lines.next().await {
+                    if let Some(event) = parse_line(line).transpose() {
+                        let done = matches!(
+                            event,
+                            Ok(AnthropicResponseStreamEvent::MessageStop)
+                                | Ok(AnthropicResponseStreamEvent::Error { .. })
+                        );
+                        if tx.unbounded_send(event).is_err() {
+                            break;
+                        }
+
+                        if done {
+                            break;
+                        }
+                    }
+                }
+
+                anyhow::Ok(())
+            })
+            .detach();
+
+        Ok(rx)
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        #[derive(Deserialize)]
+        struct AnthropicResponse {
+            error: AnthropicError,
+        }
+
+        match serde_json::from_str::<AnthropicResponse>(&body) {
+            Ok(response) if !response.error.message.is_empty() => Err(anyhow!(
+                "Failed to connect to Anthropic API: {}",
+                response.error.message,
+            )),
+
+            _ => Err(anyhow!(
+                "Failed to connect to Anthropic API: {} {}",
+                response.status(),
+                body,
+            )),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct AnthropicCompletionProvider {
+    api_url: String,
+    model: OpenAiLanguageModel,
+    credential: Arc<RwLock<ProviderCredential>>,
+    executor: BackgroundExecutor,
+}
+
+impl AnthropicCompletionProvider {
+    pub async fn new(
+        api_url: String,
+        model_name: String,
+        capacity: usize,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        // Anthropic doesn't publish a tokenizer for its current models, so their tokens
+        // are approximated with OpenAI's.
+        let model = executor
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(capacity) })
+            .await;
+        let credential = Arc::new(RwLock::new(ProviderCredential::NoCredentials));
+        Self {
+            api_url,
+            model,
+            credential,
+            executor,
+        }
+    }
+}
+
+impl CredentialProvider for AnthropicCompletionProvider {
+    fn has_credentials(&self) -> bool {
+        match *self.credential.read() {
+            ProviderCredential::Credentials { .. } => true,
+            _ => false,
+        }
+    }
+
+    fn retrieve_credentials(&self, cx: &mut AppContext) -> BoxFuture<ProviderCredential> {
+        let existing_credential = self.credential.read().clone();
+        let retrieved_credential = match existing_credential {
+            ProviderCredential::Credentials { .. } => {
+                return async move { existing_credential }.boxed()
+            }
+            _ => {
+                if let Some(api_key) = env::var("ANTHROPIC_API_KEY").log_err() {
+                    async move { ProviderCredential::Credentials { api_key } }.boxed()
+                } else {
+                    let credentials = cx.read_credentials(ANTHROPIC_API_URL);
+                    async move {
+                        if let Some(Some((_, api_key))) = credentials.await.log_err() {
+                            if let Some(api_key) = String::from_utf8(api_key).log_err() {
+                                ProviderCredential::Credentials { api_key }
+                            } else {
+                                ProviderCredential::NoCredentials
+                            }
+                        } else {
+                            ProviderCredential::NoCredentials
+                        }
+                    }
+                    .boxed()
+                }
+            }
+        };
+
+        async move {
+            let retrieved_credential = retrieved_credential.await;
+            *self.credential.write() = retrieved_credential.clone();
+            retrieved_credential
+        }
+        .boxed()
+    }
+
+    fn save_credentials(
+        &self,
+        cx: &mut AppContext,
+        credential: ProviderCredential,
+    ) -> BoxFuture<()> {
+        *self.credential.write() = credential.clone();
+        let write_credentials = match credential {
+            ProviderCredential::Credentials { api_key } => {
+                Some(cx.write_credentials(ANTHROPIC_API_URL, "Bearer", api_key.as_bytes()))
+            }
+            _ => None,
+        };
+
+        async move {
+            if let Some(write_credentials) = write_credentials {
+                write_credentials.await.log_err();
+            }
+        }
+        .boxed()
+    }
+
+    fn delete_credentials(&self, cx: &mut AppContext) -> BoxFuture<()> {
+        *self.credential.write() = ProviderCredential::NoCredentials;
+        let delete_credentials = cx.delete_credentials(ANTHROPIC_API_URL);
+        async move {
+            delete_credentials.await.log_err();
+        }
+        .boxed()
+    }
+}
+
+impl CompletionProvider for AnthropicCompletionProvider {
+    fn base_model(&self) -> Box<dyn LanguageModel> {
+        let model: Box<dyn LanguageModel> = Box::new(self.model.clone());
+        model
+    }
+    fn complete(
+        &self,
+        prompt: Box<dyn CompletionRequest>,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let credential = self.credential.read().clone();
+        let api_url = self.api_url.clone();
+        let request = stream_completion(api_url, credential, self.executor.clone(), prompt);
+        async move {
+            let response = request.await?;
+            let stream = response
+                .filter_map(|response| async move {
+                    match response {
+                        Ok(AnthropicResponseStreamEvent::ContentBlockDelta { delta, .. }) => {
+                            Some(Ok(delta.text?))
+                        }
+                        Ok(AnthropicResponseStreamEvent::Error { error }) => {
+                            Some(Err(anyhow!("{}: {}", error.kind, error.message)))
+                        }
+                        Ok(_) => None,
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(stream)
+        }
+        .boxed()
+    }
+    fn box_clone(&self) -> Box<dyn CompletionProvider> {
+        Box::new((*self).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anthropic_request_from_chat_messages() {
+        let request = AnthropicRequest::new(
+            "claude-3-opus-20240229".into(),
+            vec![
+                RequestMessage {
+                    role: Role::System,
+                    content: "You are a helpful assistant.".into(),
+                },
+                RequestMessage {
+                    role: Role::Assistant,
+                    content: "How can I help?".into(),
+                },
+                RequestMessage {
+                    role: Role::User,
+                    content: "Write a poem.".into(),
+                },
+                RequestMessage {
+                    role: Role::User,
+                    content: "Make it rhyme.".into(),
+                },
+                RequestMessage {
+                    role: Role::Assistant,
+                    content: "".into(),
+                },
+            ],
+            vec!["|END|>".into()],
+            1.0,
+            4096,
+        );
+
+        assert_eq!(
+            request.system.as_deref(),
+            Some("You are a helpful assistant.")
+        );
+        assert_eq!(
+            request.messages,
+            vec![
+                AnthropicMessage {
+                    role: AnthropicRole::User,
+                    content: "Continue.".into(),
+                },
+                AnthropicMessage {
+                    role: AnthropicRole::Assistant,
+                    content: "How can I help?".into(),
+                },
+                AnthropicMessage {
+                    role: AnthropicRole::User,
+                    content: "Write a poem.\n\nMake it rhyme.".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_anthropic_stream_events() {
+        let event: AnthropicResponseStreamEvent = serde_json::from_str(
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+        )
+        .unwrap();
+        match event {
+            AnthropicResponseStreamEvent::ContentBlockDelta { delta, .. } => {
+                assert_eq!(delta.text.as_deref(), Some("Hello"))
+            }
+            event => panic!("unexpected event {:?}", event),
+        }
+
+        let event: AnthropicResponseStreamEvent =
+            serde_json::from_str(r#"{"type":"ping"}"#).unwrap();
+        assert!(matches!(event, AnthropicResponseStreamEvent::Other));
+    }
+}
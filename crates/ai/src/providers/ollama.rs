@@ -0,0 +1,5 @@
+pub mod completion;
+
+pub use completion::*;
+
+pub const OLLAMA_API_URL: &'static str = "http://localhost:11434";
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    future::BoxFuture, io::BufReader, stream::BoxStream, AsyncBufReadExt, AsyncReadExt, FutureExt,
+    Stream, StreamExt,
+};
+use gpui::{AppContext, BackgroundExecutor};
+use isahc::{http::StatusCode, Request, RequestExt};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+use crate::{
+    auth::{CredentialProvider, ProviderCredential},
+    completion::{CompletionProvider, CompletionRequest},
+    models::LanguageModel,
+    providers::open_ai::{OpenAiLanguageModel, RequestMessage, ResponseMessage},
+};
+
+#[derive(Debug, Default, Serialize)]
+pub struct OllamaOptions {
+    pub temperature: f32,
+    /// The size of the context window, which Ollama otherwise defaults to 2048 tokens.
+    pub num_ctx: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub stop: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OllamaRequest {
+    pub model: String,
+    pub messages: Vec<RequestMessage>,
+    pub stream: bool,
+    pub options: OllamaOptions,
+}
+
+impl CompletionRequest for OllamaRequest {
+    fn data(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct OllamaResponseStreamEvent {
+    pub model: Option<String>,
+    pub message: Option<ResponseMessage>,
+    #[serde(default)]
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+pub async fn stream_completion(
+    api_url: String,
+    executor: BackgroundExecutor,
+    request: Box<dyn CompletionRequest>,
+) -> Result<impl Stream<Item = Result<OllamaResponseStreamEvent>>> {
+    let (tx, rx) = futures::channel::mpsc::unbounded::<Result<OllamaResponseStreamEvent>>();
+
+    let json_data = request.data()?;
+    let mut response = Request::post(format!("{api_url}/api/chat"))
+        .header("Content-Type", "application/json")
+        .body(json_data)?
+        .send_async()
+        .await?;
+
+    let status = response.status();
+    if status == StatusCode::OK {
+        executor
+            .spawn(async move {
+                let mut lines = BufReader::new(response.body_mut()).lines();
+
+                fn parse_line(
+                    line: Result<String, io::Error>,
+                ) -> Result<Option<OllamaResponseStreamEvent>> {
+                    let line = line?;
+                    if line.trim().is_empty() {
+                        return Ok(None);
+                    }
+
+                    let event: OllamaResponseStreamEvent = serde_json::from_str(&line)?;
+                    if let Some(error) = event.error.as_ref() {
+                        Err(anyhow!("{}", error))
+                    } else {
+                        Ok(Some(event))
+                    }
+                }
+
+                // Ollama streams newline-delimited JSON objects rather than server-sent events.
+                while let Some(line) = lines.next().await {
+                    if let Some(event) = parse_line(line).transpose() {
+                        let done = event.as_ref().map_or(true, |event| event.done);
+                        if tx.unbounded_send(event).is_err() {
+                            break;
+                        }
+
+                        if done {
+                            break;
+                        }
+                    }
+                }
+
+                anyhow::Ok(())
+            })
+            .detach();
+
+        Ok(rx)
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+
+        #[derive(Deserialize)]
+        struct OllamaResponse {
+            error: String,
+        }
+
+        match serde_json::from_str::<OllamaResponse>(&body) {
+            Ok(response) if !response.error.is_empty() => Err(anyhow!(
+                "Failed to connect to Ollama API: {}",
+                response.error,
+            )),
+
+            _ => Err(anyhow!(
+                "Failed to connect to Ollama API: {} {}",
+                response.status(),
+                body,
+            )),
+        }
+    }
+}
+
+/// A provider for models served by a local [Ollama](https://ollama.com) instance,
+/// which doesn't require credentials.
+#[derive(Clone)]
+pub struct OllamaCompletionProvider {
+    api_url: String,
+    model: OpenAiLanguageModel,
+    executor: BackgroundExecutor,
+}
+
+impl OllamaCompletionProvider {
+    pub async fn new(
+        api_url: String,
+        model_name: String,
+        capacity: usize,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        let model = executor
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(capacity) })
+            .await;
+        Self {
+            api_url,
+            model,
+            executor,
+        }
+    }
+}
+
+impl CredentialProvider for OllamaCompletionProvider {
+    fn has_credentials(&self) -> bool {
+        true
+    }
+
+    fn retrieve_credentials(&self, _cx: &mut AppContext) -> BoxFuture<ProviderCredential> {
+        async { ProviderCredential::NotNeeded }.boxed()
+    }
+
+    fn save_credentials(
+        &self,
+        _cx: &mut AppContext,
+        _credential: ProviderCredential,
+    ) -> BoxFuture<()> {
+        async {}.boxed()
+    }
+
+    fn delete_credentials(&self, _cx: &mut AppContext) -> BoxFuture<()> {
+        async {}.boxed()
+    }
+}
+
+impl CompletionProvider for OllamaCompletionProvider {
+    fn base_model(&self) -> Box<dyn LanguageModel> {
+        let model: Box<dyn LanguageModel> = Box::new(self.model.clone());
+        model
+    }
+    fn complete(
+        &self,
+        prompt: Box<dyn CompletionRequest>,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        let request = stream_completion(self.api_url.clone(), self.executor.clone(), prompt);
+        async move {
+            let response = request.await?;
+            let stream = response
+                .filter_map(|response| async move {
+                    match response {
+                        Ok(response) => Some(Ok(response.message?.content?)),
+                        Err(error) => Some(Err(error)),
+                    }
+                })
+                .boxed();
+            Ok(stream)
+        }
+        .boxed()
+    }
+    fn box_clone(&self) -> Box<dyn CompletionProvider> {
+        Box::new((*self).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ollama_stream_events() {
+        let event: OllamaResponseStreamEvent = serde_json::from_str(
+            r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","message":{"role":"assistant","content":"Hi"},"done":false}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            event.message.and_then(|message| message.content).as_deref(),
+            Some("Hi")
+        );
+        assert!(!event.done);
+
+        let event: OllamaResponseStreamEvent = serde_json::from_str(
+            r#"{"model":"llama2","created_at":"2024-01-01T00:00:00Z","done":true,"total_duration":100}"#,
+        )
+        .unwrap();
+        assert!(event.message.is_none());
+        assert!(event.done);
+    }
+}
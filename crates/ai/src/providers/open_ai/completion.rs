@@ -109,8 +109,9 @@ pub async fn stream_completion(
     request: Box<dyn CompletionRequest>,
 ) -> Result<impl Stream<Item = Result<OpenAiResponseStreamEvent>>> {
     let api_key = match credential {
-        ProviderCredential::Credentials { api_key } => api_key,
-        _ => {
+        ProviderCredential::Credentials { api_key } => Some(api_key),
+        ProviderCredential::NotNeeded => None,
+        ProviderCredential::NoCredentials => {
             return Err(anyhow!("no credentials provider for completion"));
         }
     };
@@ -118,12 +119,12 @@ pub async fn stream_completion(
     let (tx, rx) = futures::channel::mpsc::unbounded::<Result<OpenAiResponseStreamEvent>>();
 
     let json_data = request.data()?;
-    let mut response = Request::post(format!("{api_url}/chat/completions"))
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key))
-        .body(json_data)?
-        .send_async()
-        .await?;
+    let mut request = Request::post(format!("{api_url}/chat/completions"))
+        .header("Content-Type", "application/json");
+    if let Some(api_key) = api_key {
+        request = request.header("Authorization", format!("Bearer {}", api_key));
+    }
+    let mut response = request.body(json_data)?.send_async().await?;
 
     let status = response.status();
     if status == StatusCode::OK {
@@ -197,6 +198,8 @@ pub async fn stream_completion(
 #[derive(Clone)]
 pub struct OpenAiCompletionProvider {
     api_url: String,
+    credential_url: String,
+    requires_credentials: bool,
     model: OpenAiLanguageModel,
     credential: Arc<RwLock<ProviderCredential>>,
     executor: BackgroundExecutor,
@@ -210,6 +213,32 @@ impl OpenAiCompletionProvider {
         let credential = Arc::new(RwLock::new(ProviderCredential::NoCredentials));
         Self {
             api_url,
+            credential_url: OPEN_AI_API_URL.to_string(),
+            requires_credentials: true,
+            model,
+            credential,
+            executor,
+        }
+    }
+
+    /// Creates a provider for a server implementing OpenAI's chat completions API.
+    ///
+    /// Credentials for such servers are stored under their own URL, and requests are
+    /// sent without an `Authorization` header when no key has been saved.
+    pub async fn new_compatible(
+        api_url: String,
+        model_name: String,
+        capacity: usize,
+        executor: BackgroundExecutor,
+    ) -> Self {
+        let model = executor
+            .spawn(async move { OpenAiLanguageModel::load(&model_name).with_capacity(capacity) })
+            .await;
+        let credential = Arc::new(RwLock::new(ProviderCredential::NoCredentials));
+        Self {
+            credential_url: api_url.clone(),
+            api_url,
+            requires_credentials: false,
             model,
             credential,
             executor,
@@ -220,8 +249,8 @@ impl OpenAiCompletionProvider {
 impl CredentialProvider for OpenAiCompletionProvider {
     fn has_credentials(&self) -> bool {
         match *self.credential.read() {
-            ProviderCredential::Credentials { .. } => true,
-            _ => false,
+            ProviderCredential::Credentials { .. } | ProviderCredential::NotNeeded => true,
+            ProviderCredential::NoCredentials => false,
         }
     }
 
@@ -232,19 +261,29 @@ impl CredentialProvider for OpenAiCompletionProvider {
                 return async move { existing_credential }.boxed()
             }
             _ => {
-                if let Some(api_key) = env::var("OPENAI_API_KEY").log_err() {
+                let missing_credential = if self.requires_credentials {
+                    ProviderCredential::NoCredentials
+                } else {
+                    ProviderCredential::NotNeeded
+                };
+                let env_api_key = if self.requires_credentials {
+                    env::var("OPENAI_API_KEY").log_err()
+                } else {
+                    None
+                };
+                if let Some(api_key) = env_api_key {
                     async move { ProviderCredential::Credentials { api_key } }.boxed()
                 } else {
-                    let credentials = cx.read_credentials(OPEN_AI_API_URL);
+                    let credentials = cx.read_credentials(&self.credential_url);
                     async move {
                         if let Some(Some((_, api_key))) = credentials.await.log_err() {
                             if let Some(api_key) = String::from_utf8(api_key).log_err() {
                                 ProviderCredential::Credentials { api_key }
                             } else {
-                                ProviderCredential::NoCredentials
+                                missing_credential
                             }
                         } else {
-                            ProviderCredential::NoCredentials
+                            missing_credential
                         }
                     }
                     .boxed()
@@ -269,7 +308,7 @@ impl CredentialProvider for OpenAiCompletionProvider {
         let credential = credential.clone();
         let write_credentials = match credential {
             ProviderCredential::Credentials { api_key } => {
-                Some(cx.write_credentials(OPEN_AI_API_URL, "Bearer", api_key.as_bytes()))
+                Some(cx.write_credentials(&self.credential_url, "Bearer", api_key.as_bytes()))
             }
             _ => None,
         };
@@ -283,8 +322,12 @@ impl CredentialProvider for OpenAiCompletionProvider {
     }
 
     fn delete_credentials(&self, cx: &mut AppContext) -> BoxFuture<()> {
-        *self.credential.write() = ProviderCredential::NoCredentials;
-        let delete_credentials = cx.delete_credentials(OPEN_AI_API_URL);
+        *self.credential.write() = if self.requires_credentials {
+            ProviderCredential::NoCredentials
+        } else {
+            ProviderCredential::NotNeeded
+        };
+        let delete_credentials = cx.delete_credentials(&self.credential_url);
         async move {
             delete_credentials.await.log_err();
         }
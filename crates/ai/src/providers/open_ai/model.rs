@@ -1,14 +1,18 @@
 use anyhow::anyhow;
+use std::sync::Arc;
 use tiktoken_rs::CoreBPE;
 
 use crate::models::{LanguageModel, TruncationDirection};
 
 use super::OPEN_AI_BPE_TOKENIZER;
 
+/// A model whose tokens are counted with `tiktoken`, falling back to the `cl100k_base`
+/// encoding for models it doesn't know about. Clones share the loaded encoding.
 #[derive(Clone)]
 pub struct OpenAiLanguageModel {
     name: String,
-    bpe: Option<CoreBPE>,
+    bpe: Option<Arc<CoreBPE>>,
+    capacity: Option<usize>,
 }
 
 impl OpenAiLanguageModel {
@@ -17,9 +21,16 @@ impl OpenAiLanguageModel {
             tiktoken_rs::get_bpe_from_model(model_name).unwrap_or(OPEN_AI_BPE_TOKENIZER.to_owned());
         OpenAiLanguageModel {
             name: model_name.to_string(),
-            bpe: Some(bpe),
+            bpe: Some(Arc::new(bpe)),
+            capacity: None,
         }
     }
+
+    /// Overrides the context size reported by `tiktoken`, for models it doesn't know about.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
 }
 
 impl LanguageModel for OpenAiLanguageModel {
@@ -54,6 +65,9 @@ impl LanguageModel for OpenAiLanguageModel {
         }
     }
     fn capacity(&self) -> anyhow::Result<usize> {
-        anyhow::Ok(tiktoken_rs::model::get_context_size(&self.name))
+        anyhow::Ok(
+            self.capacity
+                .unwrap_or_else(|| tiktoken_rs::model::get_context_size(&self.name)),
+        )
     }
 }
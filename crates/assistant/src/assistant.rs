@@ -1,15 +1,18 @@
 pub mod assistant_panel;
 mod assistant_settings;
 mod codegen;
+mod context;
+mod context_picker;
 mod prompts;
 mod streaming_diff;
 
 use ai::providers::open_ai::Role;
 use anyhow::Result;
 pub use assistant_panel::AssistantPanel;
-use assistant_settings::OpenAiModel;
+use assistant_settings::AssistantProvider;
 use chrono::{DateTime, Local};
 use collections::HashMap;
+use context::SavedContextChip;
 use fs::Fs;
 use futures::StreamExt;
 use gpui::{actions, AppContext, SharedString};
@@ -31,6 +34,11 @@ actions!(
         InlineAssist,
         ToggleIncludeConversation,
         ToggleRetrieveContext,
+        AttachFile,
+        AttachSelection,
+        AttachSymbol,
+        AttachDiagnostics,
+        AttachTerminalOutput,
     ]
 );
 
@@ -69,11 +77,15 @@ struct SavedConversation {
     message_metadata: HashMap<MessageId, MessageMetadata>,
     summary: String,
     api_url: Option<String>,
-    model: OpenAiModel,
+    model: String,
+    #[serde(default)]
+    provider: Option<AssistantProvider>,
+    #[serde(default)]
+    context_chips: Vec<SavedContextChip>,
 }
 
 impl SavedConversation {
-    const VERSION: &'static str = "0.1.0";
+    const VERSION: &'static str = "0.2.0";
 }
 
 struct SavedConversationMetadata {
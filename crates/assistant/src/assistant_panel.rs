@@ -1,17 +1,19 @@
 use crate::{
-    assistant_settings::{AssistantDockPosition, AssistantSettings, OpenAiModel},
+    assistant_settings::{AssistantDockPosition, AssistantProvider, AssistantSettings},
     codegen::{self, Codegen, CodegenKind},
+    context::{self, AttachedContext, ContextChip, SavedContextChip},
+    context_picker,
     prompts::generate_content_prompt,
-    Assist, CycleMessageRole, InlineAssist, MessageId, MessageMetadata, MessageStatus,
-    NewConversation, QuoteSelection, ResetKey, Role, SavedConversation, SavedConversationMetadata,
-    SavedMessage, Split, ToggleFocus, ToggleIncludeConversation, ToggleRetrieveContext,
+    Assist, AttachDiagnostics, AttachFile, AttachSelection, AttachSymbol, AttachTerminalOutput,
+    CycleMessageRole, InlineAssist, MessageId, MessageMetadata, MessageStatus, NewConversation,
+    QuoteSelection, ResetKey, Role, SavedConversation, SavedConversationMetadata, SavedMessage,
+    Split, ToggleFocus, ToggleIncludeConversation, ToggleRetrieveContext,
 };
 use ai::prompts::repository_context::PromptCodeSnippet;
-use ai::providers::open_ai::OPEN_AI_API_URL;
 use ai::{
     auth::ProviderCredential,
     completion::{CompletionProvider, CompletionRequest},
-    providers::open_ai::{OpenAiCompletionProvider, OpenAiRequest, RequestMessage},
+    providers::open_ai::{RequestMessage, OPEN_AI_API_URL},
 };
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Local};
@@ -30,17 +32,18 @@ use fs::Fs;
 use futures::StreamExt;
 use gpui::{
     canvas, div, point, relative, rems, uniform_list, Action, AnyElement, AppContext,
-    AsyncAppContext, AsyncWindowContext, AvailableSpace, ClipboardItem, Context, EventEmitter,
-    FocusHandle, FocusableView, FontStyle, FontWeight, HighlightStyle, InteractiveElement,
-    IntoElement, Model, ModelContext, ParentElement, Pixels, PromptLevel, Render, SharedString,
-    StatefulInteractiveElement, Styled, Subscription, Task, TextStyle, UniformListScrollHandle,
-    View, ViewContext, VisualContext, WeakModel, WeakView, WhiteSpace, WindowContext,
+    AsyncAppContext, AsyncWindowContext, AvailableSpace, BackgroundExecutor, ClipboardItem,
+    Context, EventEmitter, FocusHandle, FocusableView, FontStyle, FontWeight, HighlightStyle,
+    InteractiveElement, IntoElement, Model, ModelContext, ParentElement, Pixels, PromptLevel,
+    Render, SharedString, StatefulInteractiveElement, Styled, Subscription, Task, TextStyle,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WeakModel, WeakView, WhiteSpace,
+    WindowContext,
 };
 use language::{language_settings::SoftWrap, Buffer, BufferId, LanguageRegistry, ToOffset as _};
 use project::Project;
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use semantic_index::{SemanticIndex, SemanticIndexStatus};
-use settings::Settings;
+use settings::{Settings, SettingsStore};
 use std::{
     cell::Cell,
     cmp,
@@ -54,9 +57,10 @@ use std::{
 };
 use theme::ThemeSettings;
 use ui::{
+    popover_menu,
     prelude::*,
     utils::{DateTimeType, FormatDistance},
-    ButtonLike, Tab, TabBar, Tooltip,
+    ButtonLike, ContextMenu, Tab, TabBar, Tooltip,
 };
 use util::{paths::CONVERSATIONS_DIR, post_inc, ResultExt, TryFutureExt};
 use uuid::Uuid;
@@ -76,7 +80,25 @@ pub fn init(cx: &mut AppContext) {
                 })
                 .register_action(AssistantPanel::inline_assist)
                 .register_action(AssistantPanel::cancel_last_inline_assist)
-                .register_action(ConversationEditor::quote_selection);
+                .register_action(ConversationEditor::quote_selection)
+                .register_action(|workspace, _: &AttachFile, cx| {
+                    context_picker::toggle_file_picker(workspace, cx)
+                })
+                .register_action(|workspace, _: &AttachSelection, cx| {
+                    let context = context::active_selection(workspace, cx);
+                    ConversationEditor::attach_context(workspace, context, cx)
+                })
+                .register_action(|workspace, _: &AttachSymbol, cx| {
+                    context_picker::toggle_symbol_picker(workspace, cx)
+                })
+                .register_action(|workspace, _: &AttachDiagnostics, cx| {
+                    let context = context::active_file_diagnostics(workspace, cx);
+                    ConversationEditor::attach_context(workspace, context, cx)
+                })
+                .register_action(|workspace, _: &AttachTerminalOutput, cx| {
+                    let context = context::terminal_output(workspace, cx);
+                    ConversationEditor::attach_context(workspace, context, cx)
+                });
         },
     )
     .detach();
@@ -94,7 +116,13 @@ pub struct AssistantPanel {
     zoomed: bool,
     focus_handle: FocusHandle,
     toolbar: View<Toolbar>,
+    /// The settings `completion_provider` was created from.
+    chat_provider: AssistantProvider,
     completion_provider: Arc<dyn CompletionProvider>,
+    /// The settings `inline_completion_provider` was created from.
+    inline_provider: AssistantProvider,
+    inline_completion_provider: Arc<dyn CompletionProvider>,
+    pending_provider_update: Task<Result<()>>,
     api_key_editor: Option<View<Editor>>,
     languages: Arc<LanguageRegistry>,
     fs: Arc<dyn Fs>,
@@ -122,19 +150,13 @@ impl AssistantPanel {
                 .await
                 .log_err()
                 .unwrap_or_default();
-            let (api_url, model_name) = cx
-                .update(|cx| {
-                    let settings = AssistantSettings::get_global(cx);
-                    (
-                        settings.openai_api_url.clone(),
-                        settings.default_open_ai_model.full_name().to_string(),
-                    )
-                })
-                .log_err()
-                .unwrap();
-            let completion_provider = OpenAiCompletionProvider::new(
-                api_url,
-                model_name,
+            let (chat_provider, inline_provider) = cx.update(|cx| {
+                let settings = AssistantSettings::get_global(cx);
+                (settings.chat_provider(), settings.inline_provider())
+            })?;
+            let (completion_provider, inline_completion_provider) = build_completion_providers(
+                &chat_provider,
+                &inline_provider,
                 cx.background_executor().clone(),
             )
             .await;
@@ -186,13 +208,19 @@ impl AssistantPanel {
                         zoomed: false,
                         focus_handle,
                         toolbar,
-                        completion_provider: Arc::new(completion_provider),
+                        chat_provider,
+                        completion_provider,
+                        inline_provider,
+                        inline_completion_provider,
+                        pending_provider_update: Task::ready(Ok(())),
                         api_key_editor: None,
                         languages: workspace.app_state().languages.clone(),
                         fs: workspace.app_state().fs.clone(),
                         width: None,
                         height: None,
-                        subscriptions: Default::default(),
+                        subscriptions: vec![
+                            cx.observe_global::<SettingsStore>(Self::handle_settings_changed)
+                        ],
                         next_inline_assist_id: 0,
                         pending_inline_assists: Default::default(),
                         pending_inline_assist_ids_by_editor: Default::default(),
@@ -207,6 +235,41 @@ impl AssistantPanel {
         })
     }
 
+    /// Recreates the completion providers when the providers configured in the settings
+    /// change, so that requests are always sent in the format of the provider receiving them.
+    fn handle_settings_changed(&mut self, cx: &mut ViewContext<Self>) {
+        let settings = AssistantSettings::get_global(cx);
+        let chat_provider = settings.chat_provider();
+        let inline_provider = settings.inline_provider();
+        if chat_provider == self.chat_provider && inline_provider == self.inline_provider {
+            // Drop any update to providers that have been configured in the meantime.
+            self.pending_provider_update = Task::ready(Ok(()));
+            return;
+        }
+
+        let executor = cx.background_executor().clone();
+        self.pending_provider_update = cx.spawn(|this, mut cx| async move {
+            let (completion_provider, inline_completion_provider) =
+                build_completion_providers(&chat_provider, &inline_provider, executor).await;
+            let load_credentials = this.update(&mut cx, |this, cx| {
+                this.chat_provider = chat_provider;
+                this.completion_provider = completion_provider;
+                this.inline_provider = inline_provider;
+                this.inline_completion_provider = inline_completion_provider;
+                this.load_credentials(cx)
+            })?;
+            load_credentials.await;
+            this.update(&mut cx, |this, cx| {
+                if this.has_credentials() {
+                    this.api_key_editor.take();
+                } else {
+                    this.build_api_key_editor(cx);
+                }
+                cx.notify();
+            })
+        });
+    }
+
     fn focus_in(&mut self, cx: &mut ViewContext<Self>) {
         self.toolbar
             .update(cx, |toolbar, cx| toolbar.focus_changed(true, cx));
@@ -309,7 +372,7 @@ impl AssistantPanel {
         };
 
         let inline_assist_id = post_inc(&mut self.next_inline_assist_id);
-        let provider = self.completion_provider.clone();
+        let provider = self.inline_completion_provider.clone();
 
         let codegen = cx.new_model(|cx| {
             Codegen::new(editor.read(cx).buffer().clone(), codegen_kind, provider, cx)
@@ -693,11 +756,8 @@ impl AssistantPanel {
             Task::ready(Ok(Vec::new()))
         };
 
-        let mut model = AssistantSettings::get_global(cx)
-            .default_open_ai_model
-            .clone();
-        let model_name = model.full_name();
-
+        let provider = self.inline_provider.clone();
+        let completion_provider = self.inline_completion_provider.clone();
         let prompt = cx.background_executor().spawn(async move {
             let snippets = snippets.await?;
 
@@ -708,7 +768,7 @@ impl AssistantPanel {
                 buffer,
                 range,
                 snippets,
-                model_name,
+                completion_provider.base_model().into(),
                 project_name,
             )
         });
@@ -722,7 +782,6 @@ impl AssistantPanel {
                     .messages(cx)
                     .map(|message| message.to_open_ai_message(buffer)),
             );
-            model = conversation.model.clone();
         }
 
         cx.spawn(|_, mut cx| async move {
@@ -734,13 +793,8 @@ impl AssistantPanel {
                 content: prompt,
             });
 
-            let request = Box::new(OpenAiRequest {
-                model: model.full_name().into(),
-                messages,
-                stream: true,
-                stop: vec!["|END|>".to_string()],
-                temperature,
-            });
+            let request =
+                provider.completion_request(messages, vec!["|END|>".to_string()], temperature);
 
             codegen.update(&mut cx, |codegen, cx| codegen.start(request, cx))?;
             anyhow::Ok(())
@@ -801,6 +855,7 @@ impl AssistantPanel {
     fn new_conversation(&mut self, cx: &mut ViewContext<Self>) -> View<ConversationEditor> {
         let editor = cx.new_view(|cx| {
             ConversationEditor::new(
+                self.chat_provider.clone(),
                 self.completion_provider.clone(),
                 self.languages.clone(),
                 self.fs.clone(),
@@ -867,13 +922,20 @@ impl AssistantPanel {
                     api_key: api_key.clone(),
                 };
 
-                let completion_provider = self.completion_provider.clone();
+                let Some((completion_provider, _)) = self.provider_needing_credentials() else {
+                    return;
+                };
                 cx.spawn(|this, mut cx| async move {
                     cx.update(|cx| completion_provider.save_credentials(cx, credential))?
                         .await;
 
                     this.update(&mut cx, |this, cx| {
-                        this.api_key_editor.take();
+                        // The inline assistant may use a different provider that also needs a key.
+                        if this.has_credentials() {
+                            this.api_key_editor.take();
+                        } else {
+                            this.build_api_key_editor(cx);
+                        }
                         this.focus_handle.focus(cx);
                         cx.notify();
                     })
@@ -887,11 +949,17 @@ impl AssistantPanel {
 
     fn reset_credentials(&mut self, _: &ResetKey, cx: &mut ViewContext<Self>) {
         let completion_provider = self.completion_provider.clone();
+        let inline_completion_provider = self.inline_completion_provider.clone();
         cx.spawn(|this, mut cx| async move {
             cx.update(|cx| completion_provider.delete_credentials(cx))?
                 .await;
+            cx.update(|cx| inline_completion_provider.delete_credentials(cx))?
+                .await;
             this.update(&mut cx, |this, cx| {
-                this.build_api_key_editor(cx);
+                // Providers that don't use credentials, such as Ollama, have nothing to reset.
+                if !this.has_credentials() {
+                    this.build_api_key_editor(cx);
+                }
                 this.focus_handle.focus(cx);
                 cx.notify();
             })
@@ -1006,6 +1074,7 @@ impl AssistantPanel {
             vec![
                 Self::render_split_button(cx).into_any_element(),
                 Self::render_quote_button(cx).into_any_element(),
+                Self::render_attach_button().into_any_element(),
                 Self::render_assist_button(cx).into_any_element(),
             ]
         } else {
@@ -1050,6 +1119,25 @@ impl AssistantPanel {
             .tooltip(|cx| Tooltip::for_action("Quote Selection", &QuoteSelection, cx))
     }
 
+    fn render_attach_button() -> impl IntoElement {
+        popover_menu("attach_context")
+            .menu(|cx| {
+                ContextMenu::build(cx, |menu, _| {
+                    menu.action("Attach File…", AttachFile.boxed_clone())
+                        .action("Attach Selection", AttachSelection.boxed_clone())
+                        .action("Attach Symbol…", AttachSymbol.boxed_clone())
+                        .action("Attach Diagnostics", AttachDiagnostics.boxed_clone())
+                        .action("Attach Terminal Output", AttachTerminalOutput.boxed_clone())
+                })
+                .into()
+            })
+            .trigger(
+                IconButton::new("attach_button", IconName::Plus)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::text("Attach Context", cx)),
+            )
+    }
+
     fn render_plus_button(cx: &mut ViewContext<Self>) -> impl IntoElement {
         IconButton::new("plus_button", IconName::Plus)
             .on_click(cx.listener(|this, _event, cx| {
@@ -1143,21 +1231,57 @@ impl AssistantPanel {
 
     fn has_credentials(&mut self) -> bool {
         self.completion_provider.has_credentials()
+            && self.inline_completion_provider.has_credentials()
+    }
+
+    /// Returns the first completion provider that still needs an API key, along with the
+    /// settings it was created from.
+    fn provider_needing_credentials(
+        &self,
+    ) -> Option<(Arc<dyn CompletionProvider>, &AssistantProvider)> {
+        if !self.completion_provider.has_credentials() {
+            Some((self.completion_provider.clone(), &self.chat_provider))
+        } else if !self.inline_completion_provider.has_credentials() {
+            Some((
+                self.inline_completion_provider.clone(),
+                &self.inline_provider,
+            ))
+        } else {
+            None
+        }
     }
 
     fn load_credentials(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
         let completion_provider = self.completion_provider.clone();
+        let inline_completion_provider = self.inline_completion_provider.clone();
         cx.spawn(|_, mut cx| async move {
-            if let Some(retrieve_credentials) = cx
-                .update(|cx| completion_provider.retrieve_credentials(cx))
-                .log_err()
-            {
-                retrieve_credentials.await;
+            for provider in [completion_provider, inline_completion_provider] {
+                if let Some(retrieve_credentials) =
+                    cx.update(|cx| provider.retrieve_credentials(cx)).log_err()
+                {
+                    retrieve_credentials.await;
+                }
             }
         })
     }
 }
 
+/// Creates the completion providers for conversations and inline assists, sharing one
+/// provider when both are configured the same way.
+async fn build_completion_providers(
+    chat_provider: &AssistantProvider,
+    inline_provider: &AssistantProvider,
+    executor: BackgroundExecutor,
+) -> (Arc<dyn CompletionProvider>, Arc<dyn CompletionProvider>) {
+    let completion_provider = chat_provider.completion_provider(executor.clone()).await;
+    let inline_completion_provider = if inline_provider == chat_provider {
+        completion_provider.clone()
+    } else {
+        inline_provider.completion_provider(executor).await
+    };
+    (completion_provider, inline_completion_provider)
+}
+
 fn build_api_key_editor(cx: &mut WindowContext) -> View<Editor> {
     cx.new_view(|cx| {
         let mut editor = Editor::single_line(cx);
@@ -1169,7 +1293,7 @@ fn build_api_key_editor(cx: &mut WindowContext) -> View<Editor> {
 impl Render for AssistantPanel {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         if let Some(api_key_editor) = self.api_key_editor.clone() {
-            const INSTRUCTIONS: [&'static str; 6] = [
+            const OPEN_AI_INSTRUCTIONS: [&'static str; 6] = [
                 "To use the assistant panel or inline assistant, you need to add your OpenAI API key.",
                 " - You can create an API key at: platform.openai.com/api-keys",
                 " - Make sure your OpenAI account has credits",
@@ -1177,6 +1301,29 @@ impl Render for AssistantPanel {
                 " ",
                 "Paste your OpenAI API key and press Enter to use the assistant:"
             ];
+            const ANTHROPIC_INSTRUCTIONS: [&'static str; 6] = [
+                "To use the assistant panel or inline assistant, you need to add your Anthropic API key.",
+                " - You can create an API key at: console.anthropic.com/settings/keys",
+                " - Make sure your Anthropic account has credits",
+                " - Having a subscription for another service like GitHub Copilot won't work.",
+                " ",
+                "Paste your Anthropic API key and press Enter to use the assistant:"
+            ];
+            const OPEN_AI_COMPATIBLE_INSTRUCTIONS: [&'static str; 6] = [
+                "To use the assistant panel or inline assistant, you need to add an API key for the configured server.",
+                " - The key is sent in the Authorization header of each request",
+                " - Run the reset key action to go back to sending requests without a key",
+                " ",
+                " ",
+                "Paste your API key and press Enter to use the assistant:"
+            ];
+            let instructions = match self.provider_needing_credentials() {
+                Some((_, AssistantProvider::Anthropic { .. })) => ANTHROPIC_INSTRUCTIONS,
+                Some((_, AssistantProvider::OpenAiCompatible { .. })) => {
+                    OPEN_AI_COMPATIBLE_INSTRUCTIONS
+                }
+                _ => OPEN_AI_INSTRUCTIONS,
+            };
 
             v_flex()
                 .p_4()
@@ -1184,7 +1331,7 @@ impl Render for AssistantPanel {
                 .on_action(cx.listener(AssistantPanel::save_credentials))
                 .track_focus(&self.focus_handle)
                 .children(
-                    INSTRUCTIONS.map(|instruction| Label::new(instruction).size(LabelSize::Small)),
+                    instructions.map(|instruction| Label::new(instruction).size(LabelSize::Small)),
                 )
                 .child(
                     h_flex()
@@ -1415,12 +1562,12 @@ struct Conversation {
     message_anchors: Vec<MessageAnchor>,
     messages_metadata: HashMap<MessageId, MessageMetadata>,
     next_message_id: MessageId,
+    context_chips: Vec<ContextChip>,
     summary: Option<Summary>,
     pending_summary: Task<Option<()>>,
     completion_count: usize,
     pending_completions: Vec<PendingCompletion>,
-    model: OpenAiModel,
-    api_url: Option<String>,
+    provider: AssistantProvider,
     token_count: Option<usize>,
     max_token_count: usize,
     pending_token_count: Task<Option<()>>,
@@ -1436,6 +1583,7 @@ impl Conversation {
     fn new(
         language_registry: Arc<LanguageRegistry>,
         cx: &mut ModelContext<Self>,
+        provider: AssistantProvider,
         completion_provider: Arc<dyn CompletionProvider>,
     ) -> Self {
         let markdown = language_registry.language_for_name("Markdown");
@@ -1453,24 +1601,20 @@ impl Conversation {
             buffer
         });
 
-        let settings = AssistantSettings::get_global(cx);
-        let model = settings.default_open_ai_model.clone();
-        let api_url = settings.openai_api_url.clone();
-
         let mut this = Self {
             id: Some(Uuid::new_v4().to_string()),
             message_anchors: Default::default(),
             messages_metadata: Default::default(),
             next_message_id: Default::default(),
+            context_chips: Default::default(),
             summary: None,
             pending_summary: Task::ready(None),
             completion_count: Default::default(),
             pending_completions: Default::default(),
             token_count: None,
-            max_token_count: tiktoken_rs::model::get_context_size(&model.full_name()),
+            max_token_count: provider.max_tokens(),
             pending_token_count: Task::ready(None),
-            api_url: Some(api_url),
-            model: model.clone(),
+            provider,
             _subscriptions: vec![cx.subscribe(&buffer, Self::handle_buffer_event)],
             pending_save: Task::ready(Ok(())),
             path: None,
@@ -1514,8 +1658,18 @@ impl Conversation {
                 .as_ref()
                 .map(|summary| summary.text.clone())
                 .unwrap_or_default(),
-            model: self.model.clone(),
-            api_url: self.api_url.clone(),
+            api_url: Some(self.provider.api_url().to_string()),
+            model: self.provider.model_name().to_string(),
+            provider: Some(self.provider.clone()),
+            context_chips: self
+                .context_chips
+                .iter()
+                .map(|chip| SavedContextChip {
+                    range: chip.range.start.to_offset(self.buffer.read(cx))
+                        ..chip.range.end.to_offset(self.buffer.read(cx)),
+                    context: chip.context.clone(),
+                })
+                .collect(),
         }
     }
 
@@ -1529,24 +1683,28 @@ impl Conversation {
             Some(id) => Some(id),
             None => Some(Uuid::new_v4().to_string()),
         };
-        let model = saved_conversation.model;
-        let api_url = saved_conversation.api_url;
-        let completion_provider: Arc<dyn CompletionProvider> = Arc::new(
-            OpenAiCompletionProvider::new(
-                api_url
-                    .clone()
+        // Conversations saved before other providers were supported always used OpenAI.
+        let provider = saved_conversation
+            .provider
+            .unwrap_or_else(|| AssistantProvider::OpenAi {
+                default_model: serde_json::from_value(serde_json::Value::String(
+                    saved_conversation.model,
+                ))
+                .unwrap_or_default(),
+                api_url: saved_conversation
+                    .api_url
                     .unwrap_or_else(|| OPEN_AI_API_URL.to_string()),
-                model.full_name().into(),
-                cx.background_executor().clone(),
-            )
-            .await,
-        );
+            });
+        let completion_provider = provider
+            .completion_provider(cx.background_executor().clone())
+            .await;
         cx.update(|cx| completion_provider.retrieve_credentials(cx))?
             .await;
 
         let markdown = language_registry.language_for_name("Markdown");
         let mut message_anchors = Vec::new();
         let mut next_message_id = MessageId(0);
+        let mut context_chips = Vec::new();
         let buffer = cx.new_model(|cx| {
             let mut buffer = Buffer::new(
                 0,
@@ -1560,6 +1718,13 @@ impl Conversation {
                 });
                 next_message_id = cmp::max(next_message_id, MessageId(message.id.0 + 1));
             }
+            for chip in saved_conversation.context_chips {
+                context_chips.push(ContextChip {
+                    range: buffer.anchor_after(chip.range.start)
+                        ..buffer.anchor_before(chip.range.end),
+                    context: chip.context,
+                });
+            }
             buffer.set_language_registry(language_registry);
             cx.spawn(|buffer, mut cx| async move {
                 let markdown = markdown.await?;
@@ -1578,6 +1743,7 @@ impl Conversation {
                 message_anchors,
                 messages_metadata: saved_conversation.message_metadata,
                 next_message_id,
+                context_chips,
                 summary: Some(Summary {
                     text: saved_conversation.summary,
                     done: true,
@@ -1586,10 +1752,9 @@ impl Conversation {
                 completion_count: Default::default(),
                 pending_completions: Default::default(),
                 token_count: None,
-                max_token_count: tiktoken_rs::model::get_context_size(&model.full_name()),
+                max_token_count: provider.max_tokens(),
                 pending_token_count: Task::ready(None),
-                api_url,
-                model,
+                provider,
                 _subscriptions: vec![cx.subscribe(&buffer, Self::handle_buffer_event)],
                 pending_save: Task::ready(Ok(())),
                 path: Some(path),
@@ -1609,6 +1774,15 @@ impl Conversation {
     ) {
         match event {
             language::Event::Edited => {
+                // Drop the chips whose label was edited, as they no longer refer to their context.
+                let buffer = self.buffer.read(cx);
+                self.context_chips.retain(|chip| {
+                    chip.range.start.is_valid(buffer)
+                        && buffer
+                            .text_for_range(chip.range.clone())
+                            .collect::<String>()
+                            == chip.context.source.label()
+                });
                 self.count_remaining_tokens(cx);
                 cx.emit(ConversationEvent::MessagesEdited);
             }
@@ -1617,43 +1791,31 @@ impl Conversation {
     }
 
     fn count_remaining_tokens(&mut self, cx: &mut ModelContext<Self>) {
-        let messages = self
-            .messages(cx)
-            .into_iter()
-            .filter_map(|message| {
-                Some(tiktoken_rs::ChatCompletionRequestMessage {
-                    role: match message.role {
-                        Role::User => "user".into(),
-                        Role::Assistant => "assistant".into(),
-                        Role::System => "system".into(),
-                    },
-                    content: Some(
-                        self.buffer
-                            .read(cx)
-                            .text_for_range(message.offset_range)
-                            .collect(),
-                    ),
-                    name: None,
-                    function_call: None,
-                })
-            })
-            .collect::<Vec<_>>();
-        let model = self.model.clone();
+        let (mut messages, attachments) = self.request_messages(self.messages(cx), cx);
+        let provider = self.provider.clone();
+        let completion_provider = self.completion_provider.clone();
         self.pending_token_count = cx.spawn(|this, mut cx| {
             async move {
                 cx.background_executor()
                     .timer(Duration::from_millis(200))
                     .await;
+                // Count the attached context as it would be sent, truncated to fit.
                 let token_count = cx
                     .background_executor()
                     .spawn(async move {
-                        tiktoken_rs::num_tokens_from_messages(&model.full_name(), &messages)
+                        let model = completion_provider.base_model();
+                        context::expand_context(
+                            &mut messages,
+                            attachments,
+                            model.as_ref(),
+                            context::token_budget(provider.max_tokens()),
+                        )?;
+                        provider.count_tokens(model.as_ref(), &messages)
                     })
                     .await?;
 
                 this.update(&mut cx, |this, cx| {
-                    this.max_token_count =
-                        tiktoken_rs::model::get_context_size(&this.model.full_name());
+                    this.max_token_count = this.provider.max_tokens();
                     this.token_count = Some(token_count);
                     cx.notify()
                 })?;
@@ -1663,12 +1825,39 @@ impl Conversation {
         });
     }
 
+    /// Converts the given messages into request messages, along with the context attached
+    /// to them, as pairs of a message's index and the context to append to it.
+    fn request_messages(
+        &self,
+        messages: impl Iterator<Item = Message>,
+        cx: &AppContext,
+    ) -> (Vec<RequestMessage>, Vec<(usize, AttachedContext)>) {
+        let buffer = self.buffer.read(cx);
+        let messages = messages.collect::<Vec<_>>();
+        let attachments = self
+            .context_chips
+            .iter()
+            .filter_map(|chip| {
+                let offset = chip.range.start.to_offset(buffer);
+                let message_ix = messages
+                    .iter()
+                    .position(|message| message.offset_range.contains(&offset))?;
+                Some((message_ix, chip.context.clone()))
+            })
+            .collect();
+        let messages = messages
+            .into_iter()
+            .map(|message| message.to_open_ai_message(buffer))
+            .collect();
+        (messages, attachments)
+    }
+
     fn remaining_tokens(&self) -> Option<isize> {
         Some(self.max_token_count as isize - self.token_count? as isize)
     }
 
-    fn set_model(&mut self, model: OpenAiModel, cx: &mut ModelContext<Self>) {
-        self.model = model;
+    fn cycle_model(&mut self, cx: &mut ModelContext<Self>) {
+        self.provider.cycle_model();
         self.count_remaining_tokens(cx);
         cx.notify();
     }
@@ -1721,19 +1910,25 @@ impl Conversation {
                 return Default::default();
             }
 
-            let request: Box<dyn CompletionRequest> = Box::new(OpenAiRequest {
-                model: self.model.full_name().to_string(),
-                messages: self
-                    .messages(cx)
-                    .filter(|message| matches!(message.status, MessageStatus::Done))
-                    .map(|message| message.to_open_ai_message(self.buffer.read(cx)))
-                    .collect(),
-                stream: true,
-                stop: vec![],
-                temperature: 1.0,
+            let messages = self
+                .messages(cx)
+                .filter(|message| matches!(message.status, MessageStatus::Done));
+            let (mut messages, attachments) = self.request_messages(messages, cx);
+
+            // Tokenizing the attached context can be slow, so build the request in the background.
+            let provider = self.provider.clone();
+            let completion_provider = self.completion_provider.clone();
+            let stream = cx.background_executor().spawn(async move {
+                context::expand_context(
+                    &mut messages,
+                    attachments,
+                    completion_provider.base_model().as_ref(),
+                    context::token_budget(provider.max_tokens()),
+                )?;
+                let request: Box<dyn CompletionRequest> =
+                    provider.completion_request(messages, vec![], 1.0);
+                completion_provider.complete(request).await
             });
-
-            let stream = self.completion_provider.complete(request);
             let assistant_message = self
                 .insert_message_after(last_message_id, Role::Assistant, MessageStatus::Pending, cx)
                 .unwrap();
@@ -1815,6 +2010,26 @@ impl Conversation {
         user_messages
     }
 
+    /// Inserts a chip for the given context at `offset`, returning the offset after it.
+    fn insert_context(
+        &mut self,
+        offset: usize,
+        context: AttachedContext,
+        cx: &mut ModelContext<Self>,
+    ) -> usize {
+        let label = context.source.label();
+        let (range, end) = self.buffer.update(cx, |buffer, cx| {
+            let offset = offset.min(buffer.len());
+            buffer.edit([(offset..offset, format!("{label} "))], None, cx);
+            let range = buffer.anchor_after(offset)..buffer.anchor_before(offset + label.len());
+            (range, offset + label.len() + 1)
+        });
+        self.context_chips.push(ContextChip { range, context });
+        self.context_chips
+            .sort_by_key(|chip| chip.range.start.to_offset(self.buffer.read(cx)));
+        end
+    }
+
     fn cancel_last_assist(&mut self) -> bool {
         self.pending_completions.pop().is_some()
     }
@@ -2004,13 +2219,9 @@ impl Conversation {
                     content: "Summarize the conversation into a short title without punctuation"
                         .into(),
                 }));
-            let request: Box<dyn CompletionRequest> = Box::new(OpenAiRequest {
-                model: self.model.full_name().to_string(),
-                messages: messages.collect(),
-                stream: true,
-                stop: vec![],
-                temperature: 1.0,
-            });
+            let request: Box<dyn CompletionRequest> =
+                self.provider
+                    .completion_request(messages.collect(), vec![], 1.0);
 
             let stream = self.completion_provider.complete(request);
             self.pending_summary = cx.spawn(|this, mut cx| {
@@ -2187,7 +2398,7 @@ struct ScrollPosition {
     cursor: Anchor,
 }
 
-struct ConversationEditor {
+pub(crate) struct ConversationEditor {
     conversation: Model<Conversation>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
@@ -2199,14 +2410,16 @@ struct ConversationEditor {
 
 impl ConversationEditor {
     fn new(
+        provider: AssistantProvider,
         completion_provider: Arc<dyn CompletionProvider>,
         language_registry: Arc<LanguageRegistry>,
         fs: Arc<dyn Fs>,
         workspace: WeakView<Workspace>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        let conversation =
-            cx.new_model(|cx| Conversation::new(language_registry, cx, completion_provider));
+        let conversation = cx.new_model(|cx| {
+            Conversation::new(language_registry, cx, provider, completion_provider)
+        });
         Self::for_conversation(conversation, fs, workspace, cx)
     }
 
@@ -2240,6 +2453,7 @@ impl ConversationEditor {
             _subscriptions,
         };
         this.update_message_headers(cx);
+        this.update_context_chips(cx);
         this
     }
 
@@ -2321,6 +2535,7 @@ impl ConversationEditor {
         match event {
             ConversationEvent::MessagesEdited => {
                 self.update_message_headers(cx);
+                self.update_context_chips(cx);
                 self.conversation.update(cx, |conversation, cx| {
                     conversation.save(Some(Duration::from_millis(500)), self.fs.clone(), cx);
                 });
@@ -2480,6 +2695,68 @@ impl ConversationEditor {
         });
     }
 
+    fn update_context_chips(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            let buffer = editor.buffer().read(cx).snapshot(cx);
+            let excerpt_id = *buffer.as_singleton().unwrap().0;
+            let ranges = self
+                .conversation
+                .read(cx)
+                .context_chips
+                .iter()
+                .map(|chip| {
+                    buffer.anchor_in_excerpt(excerpt_id, chip.range.start)
+                        ..buffer.anchor_in_excerpt(excerpt_id, chip.range.end)
+                })
+                .collect();
+            editor.highlight_background::<ContextChip>(
+                ranges,
+                |theme| theme.editor_document_highlight_read_background,
+                cx,
+            );
+        });
+    }
+
+    pub(crate) fn attach_context(
+        workspace: &mut Workspace,
+        context: Option<AttachedContext>,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(panel) = workspace.panel::<AssistantPanel>(cx) else {
+            return;
+        };
+        let Some(context) = context else {
+            return;
+        };
+
+        // Activate the panel
+        if !panel.focus_handle(cx).contains_focused(cx) {
+            workspace.toggle_panel_focus::<AssistantPanel>(cx);
+        }
+
+        panel.update(cx, |panel, cx| {
+            let conversation = panel
+                .active_editor()
+                .cloned()
+                .unwrap_or_else(|| panel.new_conversation(cx));
+            conversation.update(cx, |conversation, cx| {
+                conversation.insert_context(context, cx)
+            });
+        });
+    }
+
+    fn insert_context(&mut self, context: AttachedContext, cx: &mut ViewContext<Self>) {
+        let cursor = self.editor.read(cx).selections.newest::<usize>(cx).head();
+        let end = self.conversation.update(cx, |conversation, cx| {
+            conversation.insert_context(cursor, context, cx)
+        });
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([end..end])
+            });
+        });
+    }
+
     fn quote_selection(
         workspace: &mut Workspace,
         _: &QuoteSelection,
@@ -2595,10 +2872,8 @@ impl ConversationEditor {
     }
 
     fn cycle_model(&mut self, cx: &mut ViewContext<Self>) {
-        self.conversation.update(cx, |conversation, cx| {
-            let new_model = conversation.model.cycle();
-            conversation.set_model(new_model, cx);
-        });
+        self.conversation
+            .update(cx, |conversation, cx| conversation.cycle_model(cx));
     }
 
     fn title(&self, cx: &AppContext) -> String {
@@ -2613,7 +2888,11 @@ impl ConversationEditor {
     fn render_current_model(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         Button::new(
             "current_model",
-            self.conversation.read(cx).model.short_name(),
+            self.conversation
+                .read(cx)
+                .provider
+                .short_model_name()
+                .to_string(),
         )
         .style(ButtonStyle::Filled)
         .tooltip(move |cx| Tooltip::text("Change Model", cx))
@@ -3247,7 +3526,6 @@ mod tests {
     use crate::MessageId;
     use ai::test::FakeCompletionProvider;
     use gpui::{AppContext, TestAppContext};
-    use settings::SettingsStore;
 
     #[gpui::test]
     fn test_inserting_and_removing_messages(cx: &mut AppContext) {
@@ -3256,8 +3534,10 @@ mod tests {
         init(cx);
         let registry = Arc::new(LanguageRegistry::test());
 
+        let provider = AssistantSettings::get_global(cx).chat_provider();
         let completion_provider = Arc::new(FakeCompletionProvider::new());
-        let conversation = cx.new_model(|cx| Conversation::new(registry, cx, completion_provider));
+        let conversation =
+            cx.new_model(|cx| Conversation::new(registry, cx, provider, completion_provider));
         let buffer = conversation.read(cx).buffer.clone();
 
         let message_1 = conversation.read(cx).message_anchors[0].clone();
@@ -3386,9 +3666,11 @@ mod tests {
         cx.set_global(settings_store);
         init(cx);
         let registry = Arc::new(LanguageRegistry::test());
+        let provider = AssistantSettings::get_global(cx).chat_provider();
         let completion_provider = Arc::new(FakeCompletionProvider::new());
 
-        let conversation = cx.new_model(|cx| Conversation::new(registry, cx, completion_provider));
+        let conversation =
+            cx.new_model(|cx| Conversation::new(registry, cx, provider, completion_provider));
         let buffer = conversation.read(cx).buffer.clone();
 
         let message_1 = conversation.read(cx).message_anchors[0].clone();
@@ -3485,8 +3767,10 @@ mod tests {
         cx.set_global(settings_store);
         init(cx);
         let registry = Arc::new(LanguageRegistry::test());
+        let provider = AssistantSettings::get_global(cx).chat_provider();
         let completion_provider = Arc::new(FakeCompletionProvider::new());
-        let conversation = cx.new_model(|cx| Conversation::new(registry, cx, completion_provider));
+        let conversation =
+            cx.new_model(|cx| Conversation::new(registry, cx, provider, completion_provider));
         let buffer = conversation.read(cx).buffer.clone();
 
         let message_1 = conversation.read(cx).message_anchors[0].clone();
@@ -3569,9 +3853,10 @@ mod tests {
         cx.set_global(settings_store);
         cx.update(init);
         let registry = Arc::new(LanguageRegistry::test());
+        let provider = cx.update(|cx| AssistantSettings::get_global(cx).chat_provider());
         let completion_provider = Arc::new(FakeCompletionProvider::new());
-        let conversation =
-            cx.new_model(|cx| Conversation::new(registry.clone(), cx, completion_provider));
+        let conversation = cx
+            .new_model(|cx| Conversation::new(registry.clone(), cx, provider, completion_provider));
         let buffer = conversation.read_with(cx, |conversation, _| conversation.buffer.clone());
         let message_0 =
             conversation.read_with(cx, |conversation, _| conversation.message_anchors[0].id);
@@ -3654,9 +3939,15 @@ fn report_assistant_event(
     let client = workspace.read(cx).project().read(cx).client();
     let telemetry = client.telemetry();
 
-    let model = AssistantSettings::get_global(cx)
-        .default_open_ai_model
-        .clone();
+    let settings = AssistantSettings::get_global(cx);
+    let provider = match assistant_kind {
+        AssistantKind::Panel => settings.chat_provider(),
+        AssistantKind::Inline => settings.inline_provider(),
+    };
 
-    telemetry.report_assistant_event(conversation_id, assistant_kind, model.full_name())
+    telemetry.report_assistant_event(
+        conversation_id,
+        assistant_kind,
+        provider.model_name().to_string(),
+    )
 }
@@ -1,15 +1,26 @@
+use ai::{
+    completion::{CompletionProvider, CompletionRequest},
+    models::LanguageModel,
+    providers::{
+        anthropic::{AnthropicCompletionProvider, AnthropicRequest, ANTHROPIC_API_URL},
+        ollama::{OllamaCompletionProvider, OllamaOptions, OllamaRequest, OLLAMA_API_URL},
+        open_ai::{OpenAiCompletionProvider, OpenAiRequest, RequestMessage, OPEN_AI_API_URL},
+    },
+};
 use anyhow;
-use gpui::Pixels;
+use gpui::{BackgroundExecutor, Pixels};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use std::sync::Arc;
 
-#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub enum OpenAiModel {
     #[serde(rename = "gpt-3.5-turbo-0613")]
     ThreePointFiveTurbo,
     #[serde(rename = "gpt-4-0613")]
     Four,
+    #[default]
     #[serde(rename = "gpt-4-1106-preview")]
     FourTurbo,
 }
@@ -40,6 +51,284 @@ impl OpenAiModel {
     }
 }
 
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub enum AnthropicModel {
+    #[serde(rename = "claude-3-opus-20240229")]
+    ThreeOpus,
+    #[default]
+    #[serde(rename = "claude-3-sonnet-20240229")]
+    ThreeSonnet,
+    #[serde(rename = "claude-3-haiku-20240307")]
+    ThreeHaiku,
+}
+
+impl AnthropicModel {
+    pub fn full_name(&self) -> &'static str {
+        match self {
+            AnthropicModel::ThreeOpus => "claude-3-opus-20240229",
+            AnthropicModel::ThreeSonnet => "claude-3-sonnet-20240229",
+            AnthropicModel::ThreeHaiku => "claude-3-haiku-20240307",
+        }
+    }
+
+    pub fn short_name(&self) -> &'static str {
+        match self {
+            AnthropicModel::ThreeOpus => "claude-3-opus",
+            AnthropicModel::ThreeSonnet => "claude-3-sonnet",
+            AnthropicModel::ThreeHaiku => "claude-3-haiku",
+        }
+    }
+
+    pub fn cycle(&self) -> Self {
+        match self {
+            AnthropicModel::ThreeOpus => AnthropicModel::ThreeSonnet,
+            AnthropicModel::ThreeSonnet => AnthropicModel::ThreeHaiku,
+            AnthropicModel::ThreeHaiku => AnthropicModel::ThreeOpus,
+        }
+    }
+
+    pub fn context_size(&self) -> usize {
+        200_000
+    }
+}
+
+/// The service used to complete messages, along with the model to request from it.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum AssistantProvider {
+    #[serde(rename = "openai")]
+    OpenAi {
+        /// The OpenAI model to use.
+        ///
+        /// Default: gpt-4-1106-preview
+        #[serde(default)]
+        default_model: OpenAiModel,
+        /// The OpenAI API base URL.
+        ///
+        /// Default: https://api.openai.com/v1
+        #[serde(default = "open_ai_api_url")]
+        api_url: String,
+    },
+    Anthropic {
+        /// The Anthropic model to use.
+        ///
+        /// Default: claude-3-sonnet-20240229
+        #[serde(default)]
+        default_model: AnthropicModel,
+        /// The Anthropic API base URL.
+        ///
+        /// Default: https://api.anthropic.com/v1
+        #[serde(default = "anthropic_api_url")]
+        api_url: String,
+    },
+    Ollama {
+        /// The name of a model pulled into Ollama, such as "codellama:7b".
+        default_model: String,
+        /// The URL of the Ollama server.
+        ///
+        /// Default: http://localhost:11434
+        #[serde(default = "ollama_api_url")]
+        api_url: String,
+        /// The context size to request from Ollama, in tokens.
+        ///
+        /// Default: 4096
+        #[serde(default = "default_max_tokens")]
+        max_tokens: usize,
+    },
+    /// Any server implementing OpenAI's chat completions API.
+    #[serde(rename = "openai_compatible")]
+    OpenAiCompatible {
+        /// The name of the model to request.
+        default_model: String,
+        /// The API base URL, to which `/chat/completions` is appended.
+        api_url: String,
+        /// The model's context size, in tokens.
+        ///
+        /// Default: 4096
+        #[serde(default = "default_max_tokens")]
+        max_tokens: usize,
+    },
+}
+
+fn open_ai_api_url() -> String {
+    OPEN_AI_API_URL.to_string()
+}
+
+fn anthropic_api_url() -> String {
+    ANTHROPIC_API_URL.to_string()
+}
+
+fn ollama_api_url() -> String {
+    OLLAMA_API_URL.to_string()
+}
+
+fn default_max_tokens() -> usize {
+    4096
+}
+
+impl AssistantProvider {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AssistantProvider::OpenAi { .. } => "OpenAI",
+            AssistantProvider::Anthropic { .. } => "Anthropic",
+            AssistantProvider::Ollama { .. } => "Ollama",
+            AssistantProvider::OpenAiCompatible { .. } => "OpenAI-compatible",
+        }
+    }
+
+    pub fn api_url(&self) -> &str {
+        match self {
+            AssistantProvider::OpenAi { api_url, .. }
+            | AssistantProvider::Anthropic { api_url, .. }
+            | AssistantProvider::Ollama { api_url, .. }
+            | AssistantProvider::OpenAiCompatible { api_url, .. } => api_url,
+        }
+    }
+
+    pub fn model_name(&self) -> &str {
+        match self {
+            AssistantProvider::OpenAi { default_model, .. } => default_model.full_name(),
+            AssistantProvider::Anthropic { default_model, .. } => default_model.full_name(),
+            AssistantProvider::Ollama { default_model, .. }
+            | AssistantProvider::OpenAiCompatible { default_model, .. } => default_model,
+        }
+    }
+
+    pub fn short_model_name(&self) -> &str {
+        match self {
+            AssistantProvider::OpenAi { default_model, .. } => default_model.short_name(),
+            AssistantProvider::Anthropic { default_model, .. } => default_model.short_name(),
+            AssistantProvider::Ollama { default_model, .. }
+            | AssistantProvider::OpenAiCompatible { default_model, .. } => default_model,
+        }
+    }
+
+    /// Switches to the next model offered by this provider, if it has a known set of models.
+    pub fn cycle_model(&mut self) {
+        match self {
+            AssistantProvider::OpenAi { default_model, .. } => {
+                *default_model = default_model.cycle()
+            }
+            AssistantProvider::Anthropic { default_model, .. } => {
+                *default_model = default_model.cycle()
+            }
+            AssistantProvider::Ollama { .. } | AssistantProvider::OpenAiCompatible { .. } => {}
+        }
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        match self {
+            AssistantProvider::OpenAi { default_model, .. } => {
+                tiktoken_rs::model::get_context_size(default_model.full_name())
+            }
+            AssistantProvider::Anthropic { default_model, .. } => default_model.context_size(),
+            AssistantProvider::Ollama { max_tokens, .. }
+            | AssistantProvider::OpenAiCompatible { max_tokens, .. } => *max_tokens,
+        }
+    }
+
+    pub fn requires_credentials(&self) -> bool {
+        match self {
+            AssistantProvider::OpenAi { .. } | AssistantProvider::Anthropic { .. } => true,
+            AssistantProvider::Ollama { .. } | AssistantProvider::OpenAiCompatible { .. } => false,
+        }
+    }
+
+    /// Counts the tokens that the given messages will consume in this provider's context window,
+    /// using the tokenizer of the completion provider created from it.
+    pub fn count_tokens(
+        &self,
+        model: &dyn LanguageModel,
+        messages: &[RequestMessage],
+    ) -> anyhow::Result<usize> {
+        let mut token_count = 0;
+        for message in messages {
+            token_count += model.count_tokens(&message.content)?;
+        }
+        if let AssistantProvider::OpenAi { .. } = self {
+            // OpenAI's chat format wraps each message with its role and separators, and primes
+            // the reply with a few more tokens.
+            token_count += messages.len() * OPEN_AI_TOKENS_PER_MESSAGE + OPEN_AI_TOKENS_PER_REPLY;
+        }
+        Ok(token_count)
+    }
+
+    pub async fn completion_provider(
+        &self,
+        executor: BackgroundExecutor,
+    ) -> Arc<dyn CompletionProvider> {
+        let api_url = self.api_url().to_string();
+        let model_name = self.model_name().to_string();
+        match self {
+            AssistantProvider::OpenAi { .. } => {
+                Arc::new(OpenAiCompletionProvider::new(api_url, model_name, executor).await)
+            }
+            AssistantProvider::Anthropic { .. } => Arc::new(
+                AnthropicCompletionProvider::new(api_url, model_name, self.max_tokens(), executor)
+                    .await,
+            ),
+            AssistantProvider::Ollama { max_tokens, .. } => Arc::new(
+                OllamaCompletionProvider::new(api_url, model_name, *max_tokens, executor).await,
+            ),
+            AssistantProvider::OpenAiCompatible { max_tokens, .. } => Arc::new(
+                OpenAiCompletionProvider::new_compatible(
+                    api_url,
+                    model_name,
+                    *max_tokens,
+                    executor,
+                )
+                .await,
+            ),
+        }
+    }
+
+    /// Builds a streaming request in the format expected by this provider's API.
+    pub fn completion_request(
+        &self,
+        messages: Vec<RequestMessage>,
+        stop: Vec<String>,
+        temperature: f32,
+    ) -> Box<dyn CompletionRequest> {
+        let model = self.model_name().to_string();
+        match self {
+            AssistantProvider::OpenAi { .. } | AssistantProvider::OpenAiCompatible { .. } => {
+                Box::new(OpenAiRequest {
+                    model,
+                    messages,
+                    stream: true,
+                    stop,
+                    temperature,
+                })
+            }
+            AssistantProvider::Anthropic { .. } => Box::new(AnthropicRequest::new(
+                model,
+                messages,
+                stop,
+                temperature,
+                MAX_COMPLETION_TOKENS,
+            )),
+            AssistantProvider::Ollama { max_tokens, .. } => Box::new(OllamaRequest {
+                model,
+                messages,
+                stream: true,
+                options: OllamaOptions {
+                    temperature,
+                    num_ctx: *max_tokens,
+                    stop,
+                },
+            }),
+        }
+    }
+}
+
+/// The Anthropic API requires an upper bound on the length of each completion.
+const MAX_COMPLETION_TOKENS: usize = 4096;
+
+/// The tokens taken by each message's role and separators in OpenAI's chat format.
+const OPEN_AI_TOKENS_PER_MESSAGE: usize = 4;
+/// The tokens OpenAI adds to prime the reply.
+const OPEN_AI_TOKENS_PER_REPLY: usize = 3;
+
 #[derive(Clone, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AssistantDockPosition {
@@ -56,6 +345,27 @@ pub struct AssistantSettings {
     pub default_height: Pixels,
     pub default_open_ai_model: OpenAiModel,
     pub openai_api_url: String,
+    pub provider: Option<AssistantProvider>,
+    pub inline_assist_provider: Option<AssistantProvider>,
+}
+
+impl AssistantSettings {
+    /// The provider used for conversations in the assistant panel.
+    pub fn chat_provider(&self) -> AssistantProvider {
+        self.provider
+            .clone()
+            .unwrap_or_else(|| AssistantProvider::OpenAi {
+                default_model: self.default_open_ai_model.clone(),
+                api_url: self.openai_api_url.clone(),
+            })
+    }
+
+    /// The provider used for inline assists, which falls back to the chat provider.
+    pub fn inline_provider(&self) -> AssistantProvider {
+        self.inline_assist_provider
+            .clone()
+            .unwrap_or_else(|| self.chat_provider())
+    }
 }
 
 /// Assistant panel settings
@@ -78,13 +388,23 @@ pub struct AssistantSettingsContent {
     /// Default: 320
     pub default_height: Option<f32>,
     /// The default OpenAI model to use when starting new conversations.
+    /// Ignored when `provider` is set.
     ///
     /// Default: gpt-4-1106-preview
     pub default_open_ai_model: Option<OpenAiModel>,
     /// OpenAI API base URL to use when starting new conversations.
+    /// Ignored when `provider` is set.
     ///
     /// Default: https://api.openai.com/v1
     pub openai_api_url: Option<String>,
+    /// The provider and model to use for conversations in the assistant panel.
+    ///
+    /// Default: OpenAI, configured by `default_open_ai_model` and `openai_api_url`
+    pub provider: Option<AssistantProvider>,
+    /// The provider and model to use for inline assists.
+    ///
+    /// Default: the same as `provider`
+    pub inline_assist_provider: Option<AssistantProvider>,
 }
 
 impl Settings for AssistantSettings {
@@ -100,3 +420,55 @@ impl Settings for AssistantSettings {
         Self::load_via_json_merge(default_value, user_values)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai::providers::open_ai::{OpenAiLanguageModel, Role};
+
+    #[test]
+    fn test_deserialize_provider() {
+        let provider: AssistantProvider = serde_json::from_str(
+            r#"{ "name": "ollama", "default_model": "codellama:7b", "max_tokens": 16384 }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            provider,
+            AssistantProvider::Ollama {
+                default_model: "codellama:7b".into(),
+                api_url: OLLAMA_API_URL.into(),
+                max_tokens: 16384,
+            }
+        );
+        assert_eq!(provider.max_tokens(), 16384);
+        assert!(!provider.requires_credentials());
+
+        let mut provider: AssistantProvider =
+            serde_json::from_str(r#"{ "name": "anthropic" }"#).unwrap();
+        assert_eq!(provider.model_name(), "claude-3-sonnet-20240229");
+        assert_eq!(provider.api_url(), ANTHROPIC_API_URL);
+        provider.cycle_model();
+        assert_eq!(provider.short_model_name(), "claude-3-haiku");
+    }
+
+    #[test]
+    fn test_count_tokens_per_provider() {
+        let messages = vec![RequestMessage {
+            role: Role::User,
+            content: "Hello, world!".into(),
+        }];
+        let provider = AssistantProvider::OpenAiCompatible {
+            default_model: "mistral-7b-instruct".into(),
+            api_url: "http://localhost:8080/v1".into(),
+            max_tokens: 8192,
+        };
+        let model = OpenAiLanguageModel::load(provider.model_name());
+        assert_eq!(provider.count_tokens(&model, &messages).unwrap(), 4);
+
+        let provider = AssistantProvider::OpenAi {
+            default_model: OpenAiModel::Four,
+            api_url: OPEN_AI_API_URL.into(),
+        };
+        assert_eq!(provider.count_tokens(&model, &messages).unwrap(), 11);
+    }
+}
@@ -0,0 +1,393 @@
+use ai::{
+    models::{LanguageModel, TruncationDirection},
+    providers::open_ai::RequestMessage,
+};
+use anyhow::Result;
+use editor::Editor;
+use gpui::{AppContext, ViewContext};
+use language::{BufferSnapshot, DiagnosticSeverity, Point};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+use terminal_view::{terminal_panel::TerminalPanel, TerminalView};
+use workspace::Workspace;
+
+/// The number of tokens left free in the context window for the model's response when
+/// attached context is truncated.
+const RESERVED_COMPLETION_TOKENS: usize = 1024;
+
+/// The number of tokens that a conversation's messages and attached context can take up
+/// in a model's context window.
+pub fn token_budget(max_tokens: usize) -> usize {
+    max_tokens.saturating_sub(RESERVED_COMPLETION_TOKENS)
+}
+
+/// Where a piece of context attached to a conversation came from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ContextSource {
+    File { path: PathBuf },
+    Selection { path: PathBuf, rows: Range<u32> },
+    Symbol { path: PathBuf, name: String },
+    Diagnostics { path: PathBuf },
+    TerminalOutput { title: String },
+}
+
+impl ContextSource {
+    /// The text of the chip that represents this context in the conversation.
+    pub fn label(&self) -> String {
+        match self {
+            ContextSource::File { path } => format!("[@{}]", path.display()),
+            ContextSource::Selection { path, rows } => {
+                format!("[@{}:{}-{}]", path.display(), rows.start + 1, rows.end + 1)
+            }
+            ContextSource::Symbol { path, name } => format!("[@{}#{}]", path.display(), name),
+            ContextSource::Diagnostics { path } => {
+                format!("[@diagnostics:{}]", path.display())
+            }
+            ContextSource::TerminalOutput { title } => format!("[@terminal:{}]", title),
+        }
+    }
+
+    /// The most relevant part of terminal output is the latest, so it loses its start
+    /// when it doesn't fit, whereas everything else loses its end.
+    fn truncation_direction(&self) -> TruncationDirection {
+        match self {
+            ContextSource::TerminalOutput { .. } => TruncationDirection::Start,
+            _ => TruncationDirection::End,
+        }
+    }
+}
+
+/// A snapshot of some context, taken when it was attached to the conversation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttachedContext {
+    pub source: ContextSource,
+    pub language_name: Option<String>,
+    pub text: String,
+}
+
+impl AttachedContext {
+    fn to_prompt(&self, text: &str) -> String {
+        let language_name = self.language_name.as_deref().unwrap_or("").to_lowercase();
+        format!("{}:\n```{language_name}\n{text}\n```", self.source.label())
+    }
+}
+
+/// A chip in the conversation buffer, which is expanded into the context it refers to
+/// when the conversation is sent to the model.
+#[derive(Clone, Debug)]
+pub struct ContextChip {
+    pub range: Range<language::Anchor>,
+    pub context: AttachedContext,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedContextChip {
+    pub range: Range<usize>,
+    pub context: AttachedContext,
+}
+
+/// Appends each piece of context to the message at the given index, truncating the context
+/// so that the messages fit within `token_budget` tokens.
+///
+/// The budget left over by the messages is split evenly among the attachments, and any
+/// attachment that needs less than its share passes the remainder on to the larger ones.
+pub fn expand_context(
+    messages: &mut [RequestMessage],
+    attachments: Vec<(usize, AttachedContext)>,
+    model: &dyn LanguageModel,
+    token_budget: usize,
+) -> Result<()> {
+    let mut message_tokens = 0;
+    for message in messages.iter() {
+        message_tokens += model.count_tokens(&message.content)?;
+    }
+    let mut remaining_tokens = token_budget.saturating_sub(message_tokens);
+
+    let mut attachments = attachments
+        .into_iter()
+        .map(|(message_ix, context)| {
+            let tokens = model.count_tokens(&context.to_prompt(&context.text))?;
+            let overhead = model.count_tokens(&context.to_prompt(""))?;
+            Ok((message_ix, context, tokens, overhead))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut order = (0..attachments.len()).collect::<Vec<_>>();
+    order.sort_by_key(|ix| attachments[*ix].2);
+    let mut budgets = vec![0; attachments.len()];
+    for (position, ix) in order.into_iter().enumerate() {
+        let share = remaining_tokens / (attachments.len() - position);
+        let budget = attachments[ix].2.min(share);
+        budgets[ix] = budget;
+        remaining_tokens -= budget;
+    }
+
+    for ((message_ix, context, tokens, overhead), budget) in attachments.drain(..).zip(budgets) {
+        let prompt = if tokens <= budget {
+            context.to_prompt(&context.text)
+        } else if budget > overhead {
+            let text = match context.source.truncation_direction() {
+                TruncationDirection::End => {
+                    model.truncate(&context.text, budget - overhead, TruncationDirection::End)?
+                }
+                // Truncating from the start takes the number of tokens to remove.
+                TruncationDirection::Start => {
+                    let text_tokens = model.count_tokens(&context.text)?;
+                    model.truncate(
+                        &context.text,
+                        text_tokens.saturating_sub(budget - overhead),
+                        TruncationDirection::Start,
+                    )?
+                }
+            };
+            context.to_prompt(&text)
+        } else {
+            format!(
+                "{} (omitted, as it doesn't fit in the context window)",
+                context.source.label()
+            )
+        };
+
+        if let Some(message) = messages.get_mut(message_ix) {
+            if !message.content.is_empty() {
+                message.content.push_str("\n\n");
+            }
+            message.content.push_str(&prompt);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the full text of the given buffer.
+pub fn file_context(buffer: &BufferSnapshot) -> AttachedContext {
+    AttachedContext {
+        source: ContextSource::File {
+            path: buffer_path(buffer),
+        },
+        language_name: buffer
+            .language()
+            .map(|language| language.name().to_string()),
+        text: buffer.text(),
+    }
+}
+
+/// Returns the lines spanned by the newest selection in the active editor.
+pub fn active_selection(
+    workspace: &Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Option<AttachedContext> {
+    let (buffer, range) = active_buffer_selection(workspace, cx)?;
+    let start = buffer.offset_to_point(range.start);
+    let mut end = buffer.offset_to_point(range.end);
+    if end.column == 0 && end.row > start.row {
+        end.row -= 1;
+    }
+    let row_range = Point::new(start.row, 0)..Point::new(end.row, buffer.line_len(end.row));
+    Some(AttachedContext {
+        source: ContextSource::Selection {
+            path: buffer_path(&buffer),
+            rows: start.row..end.row,
+        },
+        language_name: buffer
+            .language_at(range.start)
+            .map(|language| language.name().to_string()),
+        text: buffer.text_for_range(row_range).collect(),
+    })
+}
+
+/// Returns the text of a symbol in the given buffer.
+pub fn symbol_context(
+    buffer: &BufferSnapshot,
+    name: String,
+    range: Range<usize>,
+) -> AttachedContext {
+    AttachedContext {
+        source: ContextSource::Symbol {
+            path: buffer_path(buffer),
+            name,
+        },
+        language_name: buffer
+            .language_at(range.start)
+            .map(|language| language.name().to_string()),
+        text: buffer.text_for_range(range).collect(),
+    }
+}
+
+/// Returns the diagnostics reported for the file open in the active editor.
+pub fn active_file_diagnostics(
+    workspace: &Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Option<AttachedContext> {
+    let (buffer, _) = active_buffer_selection(workspace, cx)?;
+    let path = buffer_path(&buffer);
+    let mut text = String::new();
+    for entry in buffer.diagnostics_in_range::<_, Point>(0..buffer.len(), false) {
+        let severity = match entry.diagnostic.severity {
+            DiagnosticSeverity::ERROR => "error",
+            DiagnosticSeverity::WARNING => "warning",
+            DiagnosticSeverity::INFORMATION => "info",
+            _ => "hint",
+        };
+        writeln!(
+            text,
+            "{}:{}:{}: {severity}: {}",
+            path.display(),
+            entry.range.start.row + 1,
+            entry.range.start.column + 1,
+            entry.diagnostic.message
+        )
+        .unwrap();
+    }
+    if text.is_empty() {
+        text.push_str("No diagnostics.");
+    }
+
+    Some(AttachedContext {
+        source: ContextSource::Diagnostics { path },
+        language_name: None,
+        text: text.trim_end().to_string(),
+    })
+}
+
+/// Returns the visible output of the active terminal, preferring the workspace's active
+/// item over the terminal panel.
+pub fn terminal_output(
+    workspace: &Workspace,
+    cx: &mut ViewContext<Workspace>,
+) -> Option<AttachedContext> {
+    let terminal_view = workspace
+        .active_item(cx)
+        .and_then(|item| item.downcast::<TerminalView>())
+        .or_else(|| {
+            workspace
+                .panel::<TerminalPanel>(cx)?
+                .read(cx)
+                .active_terminal_view(cx)
+        })?;
+    let terminal = terminal_view.read(cx).terminal().read(cx);
+    Some(AttachedContext {
+        source: ContextSource::TerminalOutput {
+            title: terminal.title(true),
+        },
+        language_name: None,
+        text: terminal.visible_text(),
+    })
+}
+
+/// Returns the buffer of the active editor, along with its newest selection.
+pub fn active_buffer_selection(
+    workspace: &Workspace,
+    cx: &AppContext,
+) -> Option<(BufferSnapshot, Range<usize>)> {
+    let editor = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))?;
+    let editor = editor.read(cx);
+    let selection = editor.selections.newest::<usize>(cx).range();
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let (buffer, start) = snapshot.point_to_buffer_offset(selection.start)?;
+    let (end_buffer, end) = snapshot.point_to_buffer_offset(selection.end)?;
+    let end = if end_buffer.remote_id() == buffer.remote_id() {
+        end
+    } else {
+        start
+    };
+    Some((buffer.clone(), start..end))
+}
+
+fn buffer_path(buffer: &BufferSnapshot) -> PathBuf {
+    buffer
+        .file()
+        .map(|file| file.path().to_path_buf())
+        .unwrap_or_else(|| Path::new("untitled").to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai::{providers::open_ai::Role, test::FakeLanguageModel};
+
+    fn message(content: &str) -> RequestMessage {
+        RequestMessage {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    fn context(source: ContextSource, text: &str) -> AttachedContext {
+        AttachedContext {
+            source,
+            language_name: None,
+            text: text.into(),
+        }
+    }
+
+    #[test]
+    fn test_expand_context() {
+        let model = FakeLanguageModel { capacity: 1000 };
+        let file = context(
+            ContextSource::File {
+                path: "a.rs".into(),
+            },
+            "fn a() {}",
+        );
+        let terminal = context(
+            ContextSource::TerminalOutput {
+                title: "zsh".into(),
+            },
+            &"x".repeat(100),
+        );
+
+        // Everything fits.
+        let mut messages = vec![message("hello"), message("world")];
+        expand_context(
+            &mut messages,
+            vec![(0, file.clone()), (1, terminal.clone())],
+            &model,
+            1000,
+        )
+        .unwrap();
+        assert_eq!(
+            messages[0].content,
+            "hello\n\n[@a.rs]:\n```\nfn a() {}\n```"
+        );
+        assert_eq!(
+            messages[1].content,
+            format!("world\n\n[@terminal:zsh]:\n```\n{}\n```", "x".repeat(100))
+        );
+
+        // The small attachment is kept whole and the terminal output loses its start.
+        let mut messages = vec![message("hello")];
+        let budget = 5 + file.to_prompt(&file.text).len() + terminal.to_prompt("").len() + 10;
+        let mut terminal = terminal;
+        terminal.text = format!("{}{}", "y".repeat(90), "z".repeat(10));
+        expand_context(
+            &mut messages,
+            vec![(0, file.clone()), (0, terminal)],
+            &model,
+            budget,
+        )
+        .unwrap();
+        assert_eq!(
+            messages[0].content,
+            format!(
+                "hello\n\n[@a.rs]:\n```\nfn a() {{}}\n```\n\n[@terminal:zsh]:\n```\n{}\n```",
+                "z".repeat(10)
+            )
+        );
+
+        // Attachments that don't fit at all are omitted.
+        let mut messages = vec![message("hello")];
+        expand_context(&mut messages, vec![(0, file)], &model, 5).unwrap();
+        assert_eq!(
+            messages[0].content,
+            "hello\n\n[@a.rs] (omitted, as it doesn't fit in the context window)"
+        );
+    }
+}
@@ -0,0 +1,380 @@
+use crate::{
+    assistant_panel::ConversationEditor,
+    context::{self, AttachedContext},
+};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render, Task, View,
+    WeakView,
+};
+use language::{Anchor, BufferSnapshot, Outline, ToOffset as _};
+use picker::{Picker, PickerDelegate};
+use project::{Project, ProjectPath};
+use std::{cmp::Reverse, sync::Arc};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// A modal for choosing a piece of context to attach to the active conversation.
+pub struct ContextPicker<D: PickerDelegate> {
+    picker: View<Picker<D>>,
+}
+
+impl<D: PickerDelegate> Render for ContextPicker<D> {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl<D: PickerDelegate> FocusableView for ContextPicker<D> {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl<D: PickerDelegate> EventEmitter<DismissEvent> for ContextPicker<D> {}
+impl<D: PickerDelegate> ModalView for ContextPicker<D> {}
+
+/// Opens a picker for attaching one of the project's files, starting with the file open
+/// in the active editor.
+pub fn toggle_file_picker(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let project = workspace.project().clone();
+    let active_path = context::active_buffer_selection(workspace, cx).and_then(|(buffer, _)| {
+        let file = buffer.file()?;
+        Some((file.worktree_id(), file.path().clone()))
+    });
+    let weak_workspace = cx.view().downgrade();
+    workspace.toggle_modal(cx, move |cx| {
+        let delegate = FileContextDelegate::new(
+            cx.view().downgrade(),
+            weak_workspace,
+            project,
+            active_path,
+            cx,
+        );
+        ContextPicker {
+            picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+        }
+    });
+}
+
+/// Opens a picker for attaching one of the symbols in the outline of the active editor's
+/// file, starting with the innermost symbol containing the cursor.
+pub fn toggle_symbol_picker(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some((buffer, selection)) = context::active_buffer_selection(workspace, cx) else {
+        return;
+    };
+    let Some(outline) = buffer.outline(None) else {
+        return;
+    };
+    let weak_workspace = cx.view().downgrade();
+    workspace.toggle_modal(cx, move |cx| {
+        let delegate = SymbolContextDelegate {
+            context_picker: cx.view().downgrade(),
+            workspace: weak_workspace,
+            cursor: selection.start,
+            buffer,
+            outline: Arc::new(outline),
+            matches: Vec::new(),
+            selected_index: 0,
+        };
+        ContextPicker {
+            picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+        }
+    });
+}
+
+fn attach_context(
+    workspace: &WeakView<Workspace>,
+    context: AttachedContext,
+    cx: &mut WindowContext,
+) {
+    workspace
+        .update(cx, |workspace, cx| {
+            ConversationEditor::attach_context(workspace, Some(context), cx)
+        })
+        .log_err();
+}
+
+pub struct FileContextDelegate {
+    context_picker: WeakView<ContextPicker<Self>>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    paths: Vec<ProjectPath>,
+    candidates: Vec<StringMatchCandidate>,
+    active_path_ix: Option<usize>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl FileContextDelegate {
+    fn new(
+        context_picker: WeakView<ContextPicker<Self>>,
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        active_path: Option<(usize, Arc<std::path::Path>)>,
+        cx: &mut ViewContext<ContextPicker<Self>>,
+    ) -> Self {
+        let mut paths = Vec::new();
+        let mut candidates = Vec::new();
+        let mut active_path_ix = None;
+        let include_root_name = project.read(cx).visible_worktrees(cx).count() > 1;
+        for worktree in project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            for entry in worktree.files(false, 0) {
+                let full_path = if include_root_name {
+                    std::path::Path::new(worktree.root_name()).join(&entry.path)
+                } else {
+                    entry.path.to_path_buf()
+                };
+                if active_path.as_ref().map_or(false, |(worktree_id, path)| {
+                    *worktree_id == worktree.id().to_usize() && *path == entry.path
+                }) {
+                    active_path_ix = Some(paths.len());
+                }
+                candidates.push(StringMatchCandidate::new(
+                    paths.len(),
+                    full_path.to_string_lossy().into_owned(),
+                ));
+                paths.push(ProjectPath {
+                    worktree_id: worktree.id(),
+                    path: entry.path.clone(),
+                });
+            }
+        }
+
+        Self {
+            context_picker,
+            workspace,
+            project,
+            paths,
+            candidates,
+            active_path_ix,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for FileContextDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Attach a file...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        let active_path_ix = self.active_path_ix;
+        cx.spawn(|this, mut cx| async move {
+            let mut matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+            if query.is_empty() {
+                // Offer the active file first.
+                matches.sort_by_key(|mat| Reverse(Some(mat.candidate_id) == active_path_ix));
+            }
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let path = self.paths[mat.candidate_id].clone();
+            let open_buffer = self
+                .project
+                .update(cx, |project, cx| project.open_buffer(path, cx));
+            let workspace = self.workspace.clone();
+            cx.spawn(|_, mut cx| async move {
+                let buffer = open_buffer.await?;
+                cx.update(|cx| {
+                    let context = context::file_context(&buffer.read(cx).snapshot());
+                    attach_context(&workspace, context, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+pub struct SymbolContextDelegate {
+    context_picker: WeakView<ContextPicker<Self>>,
+    workspace: WeakView<Workspace>,
+    buffer: BufferSnapshot,
+    cursor: usize,
+    outline: Arc<Outline<Anchor>>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for SymbolContextDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Attach a symbol...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        if query.is_empty() {
+            self.matches = self
+                .outline
+                .items
+                .iter()
+                .enumerate()
+                .map(|(ix, item)| StringMatch {
+                    candidate_id: ix,
+                    string: item.text.clone(),
+                    positions: Vec::new(),
+                    score: 0.0,
+                })
+                .collect();
+            // Start with the innermost symbol containing the cursor.
+            self.selected_index = self
+                .outline
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    let range = item.range.start.to_offset(&self.buffer)
+                        ..item.range.end.to_offset(&self.buffer);
+                    range.contains(&self.cursor)
+                })
+                .max_by_key(|(_, item)| item.depth)
+                .map_or(0, |(ix, _)| ix);
+            cx.notify();
+            return Task::ready(());
+        }
+
+        let background = cx.background_executor().clone();
+        let outline = self.outline.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = outline.search(&query, background).await;
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = 0;
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let item = &self.outline.items[mat.candidate_id];
+            let context = context::symbol_context(
+                &self.buffer,
+                item.text.clone(),
+                item.range.start.to_offset(&self.buffer)..item.range.end.to_offset(&self.buffer),
+            );
+            attach_context(&self.workspace, context, cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let item = &self.outline.items[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    div()
+                        .pl(rems(item.depth as f32))
+                        .child(HighlightedLabel::new(
+                            item.text.clone(),
+                            mat.positions.clone(),
+                        )),
+                ),
+        )
+    }
+}
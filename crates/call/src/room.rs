@@ -393,11 +393,17 @@ impl Room {
                 });
             }
         }
+        // When the connection was lost rather than the room left on purpose, keep
+        // projects holding edits the host may not have received, so they can be
+        // replayed when the project is joined again.
+        let connection_lost = self.status == RoomStatus::Rejoining;
         for project in self.joined_projects.drain() {
             if let Some(project) = project.upgrade() {
                 project.update(cx, |project, cx| {
                     project.disconnected_from_host(cx);
-                    project.close(cx);
+                    if !(connection_lost && project.has_offline_edits()) {
+                        project.close(cx);
+                    }
                 });
             }
         }
@@ -6,12 +6,17 @@ use client::{Client, UserStore};
 use gpui::{AppContext, Model};
 use std::sync::Arc;
 
-pub use channel_buffer::{ChannelBuffer, ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL};
+pub use channel_buffer::{
+    ChannelBuffer, ChannelBufferEvent, ChannelNotesVersion, ACKNOWLEDGE_DEBOUNCE_INTERVAL,
+};
 pub use channel_chat::{
     mentions_to_proto, ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId,
-    MessageParams,
+    ChannelMessageReaction, MessageParams,
+};
+pub use channel_store::{
+    Channel, ChannelEvent, ChannelId, ChannelMembership, ChannelProjectLink, ChannelStore,
+    MessageSearchQuery, MessageSearchResult,
 };
-pub use channel_store::{Channel, ChannelEvent, ChannelId, ChannelMembership, ChannelStore};
 
 #[cfg(test)]
 mod channel_store_tests;
@@ -1,15 +1,16 @@
 use crate::{Channel, ChannelId, ChannelStore};
-use anyhow::Result;
-use client::{Client, Collaborator, UserStore, ZED_ALWAYS_ACTIVE};
-use collections::HashMap;
+use anyhow::{anyhow, Result};
+use client::{Client, Collaborator, User, UserStore, ZED_ALWAYS_ACTIVE};
+use collections::{HashMap, HashSet};
 use gpui::{AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task};
-use language::proto::serialize_version;
+use language::proto::{deserialize_version, serialize_version};
 use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
-use text::BufferId;
+use std::{ops::Range, sync::Arc, time::Duration};
+use text::{Anchor, BufferId};
+use time::OffsetDateTime;
 use util::ResultExt;
 
 pub const ACKNOWLEDGE_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(250);
@@ -32,6 +33,14 @@ pub struct ChannelBuffer {
     acknowledge_task: Option<Task<Result<()>>>,
 }
 
+/// A version of a channel's notes, saved when everyone stopped editing them.
+#[derive(Clone, Debug)]
+pub struct ChannelNotesVersion {
+    pub epoch: u64,
+    pub created_at: OffsetDateTime,
+    pub authors: Vec<Arc<User>>,
+}
+
 pub enum ChannelBufferEvent {
     CollaboratorsChanged,
     Disconnected,
@@ -225,6 +234,118 @@ impl ChannelBuffer {
         }));
     }
 
+    /// Lists the saved versions of the notes, most recent first.
+    pub fn history(&self, cx: &mut ModelContext<Self>) -> Task<Result<Vec<ChannelNotesVersion>>> {
+        let request = self.client.request(proto::GetChannelNotesHistory {
+            channel_id: self.channel_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let versions = request.await?.versions;
+            let user_ids = versions
+                .iter()
+                .flat_map(|version| version.author_ids.iter().copied())
+                .collect::<HashSet<_>>();
+            let users = user_store
+                .update(&mut cx, |store, cx| {
+                    store.get_users(user_ids.into_iter().collect(), cx)
+                })?
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user))
+                .collect::<HashMap<_, _>>();
+            versions
+                .into_iter()
+                .map(|version| {
+                    anyhow::Ok(ChannelNotesVersion {
+                        epoch: version.epoch,
+                        created_at: OffsetDateTime::from_unix_timestamp(version.created_at as i64)?,
+                        authors: version
+                            .author_ids
+                            .iter()
+                            .filter_map(|user_id| users.get(user_id).cloned())
+                            .collect(),
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Retrieves the text of a saved version of the notes.
+    pub fn version_text(&self, epoch: u64, cx: &mut ModelContext<Self>) -> Task<Result<String>> {
+        let request = self.client.request(proto::GetChannelNotesVersion {
+            channel_id: self.channel_id,
+            epoch,
+        });
+        cx.background_executor()
+            .spawn(async move { Ok(request.await?.text) })
+    }
+
+    /// Attributes each part of the notes to the user who wrote it, if it's known.
+    pub fn authorship(
+        &self,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<(Range<Anchor>, Option<Arc<User>>)>>> {
+        let request = self.client.request(proto::GetChannelNotesAuthorship {
+            channel_id: self.channel_id,
+        });
+        let user_store = self.user_store.clone();
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            let user_ids = response
+                .ranges
+                .iter()
+                .filter_map(|range| range.user_id)
+                .collect::<HashSet<_>>();
+            let users = user_store
+                .update(&mut cx, |store, cx| {
+                    store.get_users(user_ids.into_iter().collect(), cx)
+                })?
+                .await?
+                .into_iter()
+                .map(|user| (user.id, user))
+                .collect::<HashMap<_, _>>();
+
+            this.update(&mut cx, |this, cx| {
+                if response.epoch != this.buffer_epoch {
+                    return Err(anyhow!("the notes were saved while loading their authors"));
+                }
+                let buffer = this.buffer.read(cx);
+                let version = deserialize_version(&response.version);
+                if !buffer.version().observed_all(&version) {
+                    return Err(anyhow!("the notes haven't finished syncing"));
+                }
+
+                // Map the ranges, which refer to the text the server has seen, onto the
+                // current text, which may contain edits the server hasn't seen yet.
+                let edits = buffer.edits_since::<usize>(&version).collect::<Vec<_>>();
+                let map_offset = |offset: usize| {
+                    let mut delta = 0isize;
+                    for edit in &edits {
+                        if edit.old.start > offset {
+                            break;
+                        } else if edit.old.end <= offset {
+                            delta += edit.new.len() as isize - edit.old.len() as isize;
+                        } else {
+                            return edit.new.start;
+                        }
+                    }
+                    (offset as isize + delta) as usize
+                };
+                Ok(response
+                    .ranges
+                    .iter()
+                    .map(|range| {
+                        let start = buffer.anchor_before(map_offset(range.start as usize));
+                        let end = buffer.anchor_after(map_offset(range.end as usize));
+                        let user = range.user_id.and_then(|id| users.get(&id).cloned());
+                        (start..end, user)
+                    })
+                    .collect())
+            })?
+        })
+    }
+
     pub fn epoch(&self) -> u64 {
         self.buffer_epoch
     }
@@ -5,7 +5,7 @@ use client::{
     user::{User, UserStore},
     Client, Subscription, TypedEnvelope, UserId,
 };
-use collections::HashSet;
+use collections::{HashMap, HashSet};
 use futures::lock::Mutex;
 use gpui::{
     AppContext, AsyncAppContext, Context, EventEmitter, Model, ModelContext, Task, WeakModel,
@@ -23,6 +23,8 @@ pub struct ChannelChat {
     pub channel_id: ChannelId,
     messages: SumTree<ChannelMessage>,
     acknowledged_message_ids: HashSet<u64>,
+    threads: HashMap<u64, ThreadSummary>,
+    observed_thread_message_ids: HashMap<u64, u64>,
     channel_store: Model<ChannelStore>,
     loaded_all_messages: bool,
     last_acknowledged_id: Option<u64>,
@@ -51,6 +53,21 @@ pub struct ChannelMessage {
     pub nonce: u128,
     pub mentions: Vec<(Range<usize>, UserId)>,
     pub reply_to_message_id: Option<u64>,
+    pub edited_at: Option<OffsetDateTime>,
+    pub reactions: Vec<ChannelMessageReaction>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelMessageReaction {
+    pub emoji: String,
+    pub user_ids: Vec<UserId>,
+}
+
+/// The replies to a message, which form the thread anchored to it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ThreadSummary {
+    reply_count: usize,
+    latest_reply_id: Option<u64>,
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -93,6 +110,7 @@ impl EventEmitter<ChannelChatEvent> for ChannelChat {}
 pub fn init(client: &Arc<Client>) {
     client.add_model_message_handler(ChannelChat::handle_message_sent);
     client.add_model_message_handler(ChannelChat::handle_message_removed);
+    client.add_model_message_handler(ChannelChat::handle_message_updated);
 }
 
 impl ChannelChat {
@@ -120,6 +138,8 @@ impl ChannelChat {
                 outgoing_messages_lock: Default::default(),
                 messages: Default::default(),
                 acknowledged_message_ids: Default::default(),
+                threads: Default::default(),
+                observed_thread_message_ids: Default::default(),
                 loaded_all_messages: false,
                 next_pending_message_id: 0,
                 last_acknowledged_id: None,
@@ -128,6 +148,9 @@ impl ChannelChat {
                 _subscription: subscription.set_model(&cx.handle(), &mut cx.to_async()),
             }
         })?;
+        handle.update(&mut cx, |this, _| {
+            this.set_observed_threads(response.observed_threads)
+        })?;
         Self::handle_loaded_messages(
             handle.downgrade(),
             user_store,
@@ -187,6 +210,8 @@ impl ChannelChat {
                     mentions: message.mentions.clone(),
                     nonce,
                     reply_to_message_id: message.reply_to_message_id,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
                 &(),
             ),
@@ -212,6 +237,14 @@ impl ChannelChat {
             let id = response.id;
             let message = ChannelMessage::from_proto(response, &user_store, &mut cx).await?;
             this.update(&mut cx, |this, cx| {
+                // The server records that users have seen their own replies.
+                if let Some(thread_id) = message.reply_to_message_id {
+                    let observed_id = this
+                        .observed_thread_message_ids
+                        .entry(thread_id)
+                        .or_default();
+                    *observed_id = (*observed_id).max(id);
+                }
                 this.insert_messages(SumTree::from_item(message, &()), cx);
             })?;
             Ok(id)
@@ -232,6 +265,63 @@ impl ChannelChat {
         })
     }
 
+    /// Replaces the body and mentions of one of the current user's messages.
+    pub fn update_message(
+        &mut self,
+        id: u64,
+        message: MessageParams,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Task<Result<()>>> {
+        if message.text.trim().is_empty() {
+            Err(anyhow!("message body can't be empty"))?;
+        }
+
+        let request = self.rpc.request(proto::UpdateChannelMessage {
+            channel_id: self.channel_id,
+            message_id: id,
+            body: message.text,
+            mentions: mentions_to_proto(&message.mentions),
+        });
+        Ok(cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        }))
+    }
+
+    pub fn add_reaction(
+        &mut self,
+        id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::AddChannelMessageReaction {
+            channel_id: self.channel_id,
+            message_id: id,
+            emoji,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
+    pub fn remove_reaction(
+        &mut self,
+        id: u64,
+        emoji: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.rpc.request(proto::RemoveChannelMessageReaction {
+            channel_id: self.channel_id,
+            message_id: id,
+            emoji,
+        });
+        cx.background_executor().spawn(async move {
+            request.await?;
+            Ok(())
+        })
+    }
+
     pub fn load_more_messages(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<Option<()>>> {
         if self.loaded_all_messages {
             return None;
@@ -325,6 +415,7 @@ impl ChannelChat {
                     .send(proto::AckChannelMessage {
                         channel_id: self.channel_id,
                         message_id: latest_message_id,
+                        thread_id: None,
                     })
                     .ok();
                 self.last_acknowledged_id = Some(latest_message_id);
@@ -335,6 +426,66 @@ impl ChannelChat {
         }
     }
 
+    /// The loaded replies to the given message, in the order they were sent.
+    ///
+    /// Replies are always newer than the message they reply to, so every reply has
+    /// been loaded once the history has been loaded back to the thread's parent.
+    pub fn thread_replies(&self, thread_id: u64) -> impl Iterator<Item = &ChannelMessage> {
+        let mut cursor = self.messages.cursor::<ChannelMessageId>();
+        cursor.seek(&ChannelMessageId::Saved(thread_id), Bias::Right, &());
+        cursor.filter(move |message| message.reply_to_message_id == Some(thread_id))
+    }
+
+    pub fn reply_count(&self, thread_id: u64) -> usize {
+        self.threads
+            .get(&thread_id)
+            .map_or(0, |thread| thread.reply_count)
+    }
+
+    pub fn thread_has_unread_replies(&self, thread_id: u64) -> bool {
+        let Some(latest_reply_id) = self
+            .threads
+            .get(&thread_id)
+            .and_then(|thread| thread.latest_reply_id)
+        else {
+            return false;
+        };
+        self.observed_thread_message_ids
+            .get(&thread_id)
+            .map_or(true, |observed_id| *observed_id < latest_reply_id)
+    }
+
+    pub fn acknowledge_thread(&mut self, thread_id: u64) {
+        if !self.thread_has_unread_replies(thread_id) {
+            return;
+        }
+        if let Some(latest_reply_id) = self
+            .threads
+            .get(&thread_id)
+            .and_then(|thread| thread.latest_reply_id)
+        {
+            self.rpc
+                .send(proto::AckChannelMessage {
+                    channel_id: self.channel_id,
+                    message_id: latest_reply_id,
+                    thread_id: Some(thread_id),
+                })
+                .ok();
+            self.observed_thread_message_ids
+                .insert(thread_id, latest_reply_id);
+        }
+    }
+
+    fn set_observed_threads(&mut self, observed_threads: Vec<proto::ObservedChannelThread>) {
+        for thread in observed_threads {
+            let observed_id = self
+                .observed_thread_message_ids
+                .entry(thread.thread_id)
+                .or_default();
+            *observed_id = (*observed_id).max(thread.message_id);
+        }
+    }
+
     async fn handle_loaded_messages(
         this: WeakModel<Self>,
         user_store: Model<UserStore>,
@@ -402,6 +553,9 @@ impl ChannelChat {
         cx.spawn(move |this, mut cx| {
             async move {
                 let response = rpc.request(proto::JoinChannelChat { channel_id }).await?;
+                this.update(&mut cx, |this, _| {
+                    this.set_observed_threads(response.observed_threads)
+                })?;
                 Self::handle_loaded_messages(
                     this.clone(),
                     user_store.clone(),
@@ -463,6 +617,7 @@ impl ChannelChat {
                 .send(proto::AckChannelMessage {
                     channel_id: self.channel_id,
                     message_id: id,
+                    thread_id: None,
                 })
                 .ok();
         }
@@ -517,18 +672,41 @@ impl ChannelChat {
         Ok(())
     }
 
+    async fn handle_message_updated(
+        this: Model<Self>,
+        message: TypedEnvelope<proto::ChannelMessageUpdate>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let user_store = this.update(&mut cx, |this, _| this.user_store.clone())?;
+        let message = message
+            .payload
+            .message
+            .ok_or_else(|| anyhow!("empty message"))?;
+        let message_id = message.id;
+        let message = ChannelMessage::from_proto(message, &user_store, &mut cx).await?;
+        this.update(&mut cx, |this, cx| {
+            if this.find_loaded_message(message_id).is_some() {
+                this.insert_messages(SumTree::from_item(message, &()), cx);
+            }
+        })?;
+        Ok(())
+    }
+
     fn insert_messages(&mut self, messages: SumTree<ChannelMessage>, cx: &mut ModelContext<Self>) {
         if let Some((first_message, last_message)) = messages.first().zip(messages.last()) {
             let nonces = messages
                 .cursor::<()>()
                 .map(|m| m.nonce)
                 .collect::<HashSet<_>>();
+            let added_replies = saved_replies(messages.iter());
 
             let mut old_cursor = self.messages.cursor::<(ChannelMessageId, Count)>();
             let mut new_messages = old_cursor.slice(&first_message.id, Bias::Left, &());
             let start_ix = old_cursor.start().1 .0;
             let removed_messages = old_cursor.slice(&last_message.id, Bias::Right, &());
             let removed_count = removed_messages.summary().count;
+            let removed_replies = saved_replies(removed_messages.iter());
             let new_count = messages.summary().count;
             let end_ix = start_ix + removed_count;
 
@@ -560,6 +738,7 @@ impl ChannelChat {
 
             drop(old_cursor);
             self.messages = new_messages;
+            self.update_threads(&removed_replies, &added_replies);
 
             for range in ranges.into_iter().rev() {
                 cx.emit(ChannelChatEvent::MessagesUpdated {
@@ -582,10 +761,12 @@ impl ChannelChat {
         if let Some(item) = cursor.item() {
             if item.id == ChannelMessageId::Saved(id) {
                 let ix = messages.summary().count;
+                let removed_replies = saved_replies([item]);
                 cursor.next(&());
                 messages.append(cursor.suffix(&()), &());
                 drop(cursor);
                 self.messages = messages;
+                self.update_threads(&removed_replies, &[]);
                 cx.emit(ChannelChatEvent::MessagesUpdated {
                     old_range: ix..ix + 1,
                     new_count: 0,
@@ -593,6 +774,54 @@ impl ChannelChat {
             }
         }
     }
+
+    /// Updates the summaries of the threads whose replies were removed from or added
+    /// to the loaded messages, given as pairs of thread and reply ids.
+    fn update_threads(&mut self, removed_replies: &[(u64, u64)], added_replies: &[(u64, u64)]) {
+        // Threads whose latest reply was removed, without a newer one being added.
+        let mut stale_threads = HashSet::default();
+        for (thread_id, reply_id) in removed_replies {
+            if let Some(thread) = self.threads.get_mut(thread_id) {
+                thread.reply_count = thread.reply_count.saturating_sub(1);
+                if thread.latest_reply_id == Some(*reply_id) {
+                    stale_threads.insert(*thread_id);
+                }
+            }
+        }
+        for (thread_id, reply_id) in added_replies {
+            let thread = self.threads.entry(*thread_id).or_default();
+            thread.reply_count += 1;
+            if thread.latest_reply_id <= Some(*reply_id) {
+                thread.latest_reply_id = Some(*reply_id);
+                stale_threads.remove(thread_id);
+            }
+        }
+
+        for thread_id in stale_threads {
+            let latest_reply_id = self
+                .thread_replies(thread_id)
+                .filter_map(|reply| match reply.id {
+                    ChannelMessageId::Saved(id) => Some(id),
+                    ChannelMessageId::Pending(_) => None,
+                })
+                .last();
+            if let Some(thread) = self.threads.get_mut(&thread_id) {
+                thread.latest_reply_id = latest_reply_id;
+            }
+        }
+        self.threads.retain(|_, thread| thread.reply_count > 0);
+    }
+}
+
+/// The thread and reply ids of the saved replies among the given messages.
+fn saved_replies<'a>(messages: impl IntoIterator<Item = &'a ChannelMessage>) -> Vec<(u64, u64)> {
+    messages
+        .into_iter()
+        .filter_map(|message| match (message.reply_to_message_id, message.id) {
+            (Some(thread_id), ChannelMessageId::Saved(reply_id)) => Some((thread_id, reply_id)),
+            _ => None,
+        })
+        .collect()
 }
 
 async fn messages_from_proto(
@@ -635,6 +864,18 @@ impl ChannelMessage {
                 .ok_or_else(|| anyhow!("nonce is required"))?
                 .into(),
             reply_to_message_id: message.reply_to_message_id,
+            edited_at: message
+                .edited_at
+                .map(|edited_at| OffsetDateTime::from_unix_timestamp(edited_at as i64))
+                .transpose()?,
+            reactions: message
+                .reactions
+                .into_iter()
+                .map(|reaction| ChannelMessageReaction {
+                    emoji: reaction.emoji,
+                    user_ids: reaction.user_ids,
+                })
+                .collect(),
         })
     }
 
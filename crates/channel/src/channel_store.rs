@@ -1,6 +1,8 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage, ChannelMessageId,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{Client, Subscription, User, UserId, UserStore};
@@ -17,6 +19,7 @@ use rpc::{
     TypedEnvelope,
 };
 use std::{mem, sync::Arc, time::Duration};
+use time::OffsetDateTime;
 use util::{async_maybe, maybe, ResultExt};
 
 pub fn init(client: &Arc<Client>, user_store: Model<UserStore>, cx: &mut AppContext) {
@@ -61,6 +64,22 @@ pub struct Channel {
     pub parent_path: Vec<u64>,
 }
 
+/// A search through the messages of the channels that the user can access.
+#[derive(Clone, Debug, Default)]
+pub struct MessageSearchQuery {
+    pub query: String,
+    pub channel_id: Option<ChannelId>,
+    pub sender_id: Option<u64>,
+    pub sent_after: Option<OffsetDateTime>,
+    pub sent_before: Option<OffsetDateTime>,
+}
+
+#[derive(Clone, Debug)]
+pub struct MessageSearchResult {
+    pub channel_id: ChannelId,
+    pub message: ChannelMessage,
+}
+
 #[derive(Default)]
 pub struct ChannelState {
     latest_chat_message: Option<u64>,
@@ -108,6 +127,30 @@ impl Channel {
     }
 }
 
+/// A repository linked to a channel, which members open when they join it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChannelProjectLink {
+    pub id: u64,
+    pub channel_id: ChannelId,
+    pub remote_url: String,
+    /// The linked directory, relative to the root of the repository.
+    pub path: String,
+    /// The user whose clone of the repository is shared into the channel's room.
+    pub host_user_id: Option<UserId>,
+}
+
+impl ChannelProjectLink {
+    pub fn from_proto(link: proto::ChannelProjectLink) -> Self {
+        Self {
+            id: link.id,
+            channel_id: link.channel_id,
+            remote_url: link.remote_url,
+            path: link.path,
+            host_user_id: link.host_user_id,
+        }
+    }
+}
+
 pub struct ChannelMembership {
     pub user: Arc<User>,
     pub kind: proto::channel_member::Kind,
@@ -336,6 +379,58 @@ impl ChannelStore {
         })
     }
 
+    /// Searches the messages in the channels that the user can access, returning the
+    /// most recent matches first.
+    pub fn search_messages(
+        &self,
+        query: MessageSearchQuery,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<MessageSearchResult>>> {
+        let client = self.client.clone();
+        let user_store = self.user_store.clone();
+        let request = client.request(proto::SearchChannelMessages {
+            query: query.query,
+            channel_id: query.channel_id,
+            sender_id: query.sender_id,
+            sent_after: query
+                .sent_after
+                .map(|timestamp| timestamp.unix_timestamp() as u64),
+            sent_before: query
+                .sent_before
+                .map(|timestamp| timestamp.unix_timestamp() as u64),
+        });
+        cx.spawn(|_, mut cx| async move {
+            let results = request.await?.results;
+            if results.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let response = client
+                .request(proto::GetChannelMessagesById {
+                    message_ids: results.iter().map(|result| result.message_id).collect(),
+                })
+                .await?;
+            let mut messages =
+                ChannelMessage::from_proto_vec(response.messages, &user_store, &mut cx)
+                    .await?
+                    .into_iter()
+                    .filter_map(|message| match message.id {
+                        ChannelMessageId::Saved(id) => Some((id, message)),
+                        ChannelMessageId::Pending(_) => None,
+                    })
+                    .collect::<HashMap<_, _>>();
+            Ok(results
+                .into_iter()
+                .filter_map(|result| {
+                    Some(MessageSearchResult {
+                        channel_id: result.channel_id,
+                        message: messages.remove(&result.message_id)?,
+                    })
+                })
+                .collect())
+        })
+    }
+
     pub fn has_channel_buffer_changed(&self, channel_id: ChannelId) -> bool {
         self.channel_states
             .get(&channel_id)
@@ -745,7 +840,7 @@ impl ChannelStore {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<()>> {
         let client = self.client.clone();
-        cx.background_executor().spawn(async move {
+        cx.spawn(move |_, _| async move {
             client
                 .request(proto::RespondToChannelInvite { channel_id, accept })
                 .await?;
@@ -787,6 +882,61 @@ impl ChannelStore {
         })
     }
 
+    pub fn project_links(
+        &self,
+        channel_id: ChannelId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<ChannelProjectLink>>> {
+        let client = self.client.clone();
+        cx.spawn(move |_, _| async move {
+            let response = client
+                .request(proto::GetChannelProjectLinks { channel_id })
+                .await?;
+            Ok(response
+                .links
+                .into_iter()
+                .map(ChannelProjectLink::from_proto)
+                .collect())
+        })
+    }
+
+    pub fn link_project(
+        &mut self,
+        channel_id: ChannelId,
+        remote_url: String,
+        path: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ChannelProjectLink>> {
+        let client = self.client.clone();
+        cx.spawn(move |_, _| async move {
+            let response = client
+                .request(proto::AddChannelProjectLink {
+                    channel_id,
+                    remote_url,
+                    path,
+                })
+                .await?;
+            let link = response
+                .link
+                .ok_or_else(|| anyhow!("invalid project link"))?;
+            Ok(ChannelProjectLink::from_proto(link))
+        })
+    }
+
+    pub fn unlink_project(
+        &mut self,
+        link_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let client = self.client.clone();
+        cx.spawn(move |_, _| async move {
+            client
+                .request(proto::RemoveChannelProjectLink { link_id })
+                .await?;
+            Ok(())
+        })
+    }
+
     pub fn remove_channel(&self, channel_id: ChannelId) -> impl Future<Output = Result<()>> {
         let client = self.client.clone();
         async move {
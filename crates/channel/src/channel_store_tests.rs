@@ -185,6 +185,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     nonce: Some(1.into()),
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
                 proto::ChannelMessage {
                     id: 11,
@@ -194,9 +196,12 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     mentions: vec![],
                     nonce: Some(2.into()),
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
             ],
             done: false,
+            observed_threads: Vec::new(),
         },
     );
 
@@ -242,6 +247,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
             mentions: vec![],
             nonce: Some(3.into()),
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: Vec::new(),
         }),
     });
 
@@ -296,6 +303,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(4.into()),
                     mentions: vec![],
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
                 proto::ChannelMessage {
                     id: 9,
@@ -305,6 +314,8 @@ async fn test_channel_messages(cx: &mut TestAppContext) {
                     nonce: Some(5.into()),
                     mentions: vec![],
                     reply_to_message_id: None,
+                    edited_at: None,
+                    reactions: Vec::new(),
                 },
             ],
         },
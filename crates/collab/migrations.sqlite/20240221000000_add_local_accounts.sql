@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS "local_accounts" (
+    "user_id" INTEGER PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    "password_hash" VARCHAR,
+    "oidc_subject" VARCHAR
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "index_local_accounts_on_oidc_subject" ON "local_accounts" ("oidc_subject");
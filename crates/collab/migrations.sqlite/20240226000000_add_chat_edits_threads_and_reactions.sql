@@ -0,0 +1,24 @@
+ALTER TABLE "channel_messages" ADD "edited_at" TIMESTAMP DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS "index_channel_messages_on_reply_to_message_id" ON "channel_messages" ("reply_to_message_id");
+
+CREATE TABLE IF NOT EXISTS "channel_message_reactions" (
+    "id" INTEGER PRIMARY KEY AUTOINCREMENT,
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "index_channel_message_reactions_on_message_id_user_id_emoji" ON "channel_message_reactions" ("message_id", "user_id", "emoji");
+
+-- SQLite can't change a table's primary key, so the table is rebuilt with the thread in it.
+CREATE TABLE "observed_channel_messages_new" (
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "channel_message_id" INTEGER NOT NULL,
+    "thread_id" INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, channel_id, thread_id)
+);
+INSERT INTO "observed_channel_messages_new" ("user_id", "channel_id", "channel_message_id")
+    SELECT "user_id", "channel_id", "channel_message_id" FROM "observed_channel_messages";
+DROP TABLE "observed_channel_messages";
+ALTER TABLE "observed_channel_messages_new" RENAME TO "observed_channel_messages";
@@ -0,0 +1,13 @@
+CREATE VIRTUAL TABLE IF NOT EXISTS "channel_messages_fts" USING fts5 (body, content='channel_messages', content_rowid='id', tokenize='porter unicode61');
+INSERT INTO "channel_messages_fts" ("channel_messages_fts") VALUES ('rebuild');
+
+CREATE TRIGGER IF NOT EXISTS "channel_messages_fts_insert" AFTER INSERT ON "channel_messages" BEGIN
+    INSERT INTO "channel_messages_fts" (rowid, body) VALUES (new.id, new.body);
+END;
+CREATE TRIGGER IF NOT EXISTS "channel_messages_fts_delete" AFTER DELETE ON "channel_messages" BEGIN
+    INSERT INTO "channel_messages_fts" ("channel_messages_fts", rowid, body) VALUES ('delete', old.id, old.body);
+END;
+CREATE TRIGGER IF NOT EXISTS "channel_messages_fts_update" AFTER UPDATE OF body ON "channel_messages" BEGIN
+    INSERT INTO "channel_messages_fts" ("channel_messages_fts", rowid, body) VALUES ('delete', old.id, old.body);
+    INSERT INTO "channel_messages_fts" (rowid, body) VALUES (new.id, new.body);
+END;
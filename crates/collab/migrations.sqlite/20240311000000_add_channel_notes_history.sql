@@ -0,0 +1,16 @@
+ALTER TABLE "buffer_operations" ADD "user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL;
+
+-- SQLite can't add a column with a non-constant default, so the table is rebuilt with it.
+CREATE TABLE "buffer_snapshots_new" (
+    "buffer_id" INTEGER NOT NULL REFERENCES buffers (id) ON DELETE CASCADE,
+    "epoch" INTEGER NOT NULL,
+    "text" TEXT NOT NULL,
+    "operation_serialization_version" INTEGER NOT NULL,
+    "created_at" TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    "authorship" BLOB,
+    PRIMARY KEY(buffer_id, epoch)
+);
+INSERT INTO "buffer_snapshots_new" ("buffer_id", "epoch", "text", "operation_serialization_version")
+    SELECT "buffer_id", "epoch", "text", "operation_serialization_version" FROM "buffer_snapshots";
+DROP TABLE "buffer_snapshots";
+ALTER TABLE "buffer_snapshots_new" RENAME TO "buffer_snapshots";
@@ -0,0 +1,16 @@
+ALTER TABLE "channel_messages" ADD "edited_at" TIMESTAMP WITHOUT TIME ZONE DEFAULT NULL;
+CREATE INDEX IF NOT EXISTS "index_channel_messages_on_reply_to_message_id" ON "channel_messages" ("reply_to_message_id");
+
+CREATE TABLE IF NOT EXISTS "channel_message_reactions" (
+    "id" SERIAL PRIMARY KEY,
+    "message_id" INTEGER NOT NULL REFERENCES channel_messages (id) ON DELETE CASCADE,
+    "user_id" INTEGER NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    "emoji" VARCHAR NOT NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "index_channel_message_reactions_on_message_id_user_id_emoji" ON "channel_message_reactions" ("message_id", "user_id", "emoji");
+
+ALTER TABLE "observed_channel_messages" ADD "thread_id" INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE "observed_channel_messages" DROP CONSTRAINT "observed_channel_messages_pkey";
+DROP INDEX "index_observed_channel_messages_user_and_channel_id";
+ALTER TABLE "observed_channel_messages" ADD PRIMARY KEY ("user_id", "channel_id", "thread_id");
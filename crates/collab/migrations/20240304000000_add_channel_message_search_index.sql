@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS "index_channel_messages_on_body_tsvector" ON "channel_messages" USING GIN (to_tsvector('english', "body"));
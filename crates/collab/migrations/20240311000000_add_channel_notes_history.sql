@@ -0,0 +1,3 @@
+ALTER TABLE "buffer_operations" ADD "user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL;
+ALTER TABLE "buffer_snapshots" ADD "created_at" TIMESTAMP WITHOUT TIME ZONE NOT NULL DEFAULT now();
+ALTER TABLE "buffer_snapshots" ADD "authorship" BYTEA;
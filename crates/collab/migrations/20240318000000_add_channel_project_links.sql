@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS "channel_project_links" (
+    "id" SERIAL PRIMARY KEY,
+    "channel_id" INTEGER NOT NULL REFERENCES channels (id) ON DELETE CASCADE,
+    "remote_url" VARCHAR NOT NULL,
+    "path" VARCHAR NOT NULL,
+    "host_user_id" INTEGER REFERENCES users (id) ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "index_channel_project_links_on_channel_id_remote_url_path" ON "channel_project_links" ("channel_id", "remote_url", "path");
@@ -1,3 +1,4 @@
+mod admin;
 mod extensions;
 
 use crate::{
@@ -33,6 +34,7 @@ pub fn routes(rpc_server: Arc<rpc::Server>, state: Arc<AppState>) -> Router<Body
         .route("/contributors", get(get_contributors).post(add_contributor))
         .route("/contributor", get(check_is_contributor))
         .merge(extensions::router())
+        .merge(admin::router())
         .layer(
             ServiceBuilder::new()
                 .layer(Extension(state))
@@ -0,0 +1,243 @@
+use crate::{
+    db::{ActiveRoom, ChannelId, ChannelRole, ChannelVisibility, User, UserId},
+    rpc, AppState, Error, Result,
+};
+use ::rpc::{proto::channel_member::Kind, ConnectionId};
+use axum::{
+    extract::{Path, Query},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Routes for server administrators, which are only reachable with the API token.
+pub fn router() -> Router {
+    Router::new()
+        .route("/admin/users", get(get_users))
+        .route(
+            "/admin/users/:id",
+            get(get_user).put(update_user).delete(delete_user),
+        )
+        .route(
+            "/admin/users/:id/access_tokens",
+            delete(revoke_access_tokens),
+        )
+        .route("/admin/users/:id/disconnect", post(disconnect_user))
+        .route(
+            "/admin/connections/:owner_id/:id/disconnect",
+            post(disconnect_connection),
+        )
+        .route("/admin/channels", get(get_channels))
+        .route("/admin/channels/:id/members", get(get_channel_members))
+        .route(
+            "/admin/channels/:id/members/:user_id",
+            put(set_channel_member_role).delete(remove_channel_member),
+        )
+        .route("/admin/rooms", get(get_rooms))
+}
+
+#[derive(Debug, Deserialize)]
+struct GetUsersParams {
+    query: Option<String>,
+    #[serde(default)]
+    page: u32,
+    limit: Option<u32>,
+}
+
+async fn get_users(
+    Query(params): Query<GetUsersParams>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<User>>> {
+    let limit = params.limit.unwrap_or(100);
+    let users = if let Some(query) = params.query {
+        app.db.fuzzy_search_users(&query, limit).await?
+    } else {
+        app.db.get_all_users(params.page, limit).await?
+    };
+    Ok(Json(users))
+}
+
+#[derive(Debug, Serialize)]
+struct GetUserResponse {
+    user: User,
+    connection_count: usize,
+}
+
+async fn get_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<Json<GetUserResponse>> {
+    let user = app
+        .db
+        .get_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| Error::Http(StatusCode::NOT_FOUND, "user not found".to_string()))?;
+    Ok(Json(GetUserResponse {
+        user,
+        connection_count: rpc_server.user_connection_count(user_id),
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateUserParams {
+    admin: bool,
+}
+
+async fn update_user(
+    Path(user_id): Path<UserId>,
+    Json(params): Json<UpdateUserParams>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<()> {
+    app.db.set_user_is_admin(user_id, params.admin).await
+}
+
+async fn delete_user(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<()> {
+    app.db.destroy_user(user_id).await?;
+    rpc_server.disconnect_user(user_id);
+    Ok(())
+}
+
+#[derive(Debug, Serialize)]
+struct RevokeAccessTokensResponse {
+    revoked_count: u64,
+    disconnected_count: usize,
+}
+
+async fn revoke_access_tokens(
+    Path(user_id): Path<UserId>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<Json<RevokeAccessTokensResponse>> {
+    let revoked_count = app.db.delete_access_tokens_for_user(user_id).await?;
+    // Connections that authenticated with the revoked tokens would otherwise stay
+    // connected until they reconnect.
+    let disconnected_count = rpc_server.disconnect_user(user_id);
+    Ok(Json(RevokeAccessTokensResponse {
+        revoked_count,
+        disconnected_count,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+struct DisconnectUserResponse {
+    disconnected_count: usize,
+}
+
+async fn disconnect_user(
+    Path(user_id): Path<UserId>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<Json<DisconnectUserResponse>> {
+    Ok(Json(DisconnectUserResponse {
+        disconnected_count: rpc_server.disconnect_user(user_id),
+    }))
+}
+
+async fn disconnect_connection(
+    Path((owner_id, id)): Path<(u32, u32)>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<()> {
+    if rpc_server.disconnect_connection(ConnectionId { owner_id, id }) {
+        Ok(())
+    } else {
+        Err(Error::Http(
+            StatusCode::NOT_FOUND,
+            "connection not found on this server".to_string(),
+        ))
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelResponse {
+    id: ChannelId,
+    name: String,
+    visibility: ChannelVisibility,
+    parent_path: Vec<ChannelId>,
+}
+
+async fn get_channels(
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<ChannelResponse>>> {
+    let channels = app.db.get_all_channels().await?;
+    Ok(Json(
+        channels
+            .into_iter()
+            .map(|channel| ChannelResponse {
+                id: channel.id,
+                name: channel.name,
+                visibility: channel.visibility,
+                parent_path: channel.parent_path,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Serialize)]
+struct ChannelMemberResponse {
+    user_id: UserId,
+    role: ChannelRole,
+    accepted: bool,
+}
+
+async fn get_channel_members(
+    Path(channel_id): Path<ChannelId>,
+    Extension(app): Extension<Arc<AppState>>,
+) -> Result<Json<Vec<ChannelMemberResponse>>> {
+    let members = app.db.get_channel_members(channel_id).await?;
+    Ok(Json(
+        members
+            .into_iter()
+            .map(|member| ChannelMemberResponse {
+                user_id: member.user_id,
+                role: member.role,
+                accepted: member.kind == Kind::Member,
+            })
+            .collect(),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+struct SetChannelMemberRoleParams {
+    role: ChannelRole,
+}
+
+async fn set_channel_member_role(
+    Path((channel_id, user_id)): Path<(ChannelId, UserId)>,
+    Json(params): Json<SetChannelMemberRoleParams>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<()> {
+    app.db
+        .get_user_by_id(user_id)
+        .await?
+        .ok_or_else(|| Error::Http(StatusCode::NOT_FOUND, "user not found".to_string()))?;
+    let membership_update = app
+        .db
+        .admin_set_channel_member_role(channel_id, user_id, params.role)
+        .await?;
+    rpc_server.channel_membership_updated(user_id, membership_update);
+    Ok(())
+}
+
+async fn remove_channel_member(
+    Path((channel_id, user_id)): Path<(ChannelId, UserId)>,
+    Extension(app): Extension<Arc<AppState>>,
+    Extension(rpc_server): Extension<Arc<rpc::Server>>,
+) -> Result<()> {
+    let result = app
+        .db
+        .admin_remove_channel_member(channel_id, user_id)
+        .await?;
+    rpc_server.channel_member_removed(user_id, result);
+    Ok(())
+}
+
+async fn get_rooms(Extension(app): Extension<Arc<AppState>>) -> Result<Json<Vec<ActiveRoom>>> {
+    Ok(Json(app.db.get_active_rooms().await?))
+}
@@ -25,6 +25,10 @@ lazy_static! {
         exponential_buckets(10.0, 2.0, 10).unwrap(),
     )
     .unwrap();
+    /// A hash that passwords are checked against when signing in as an account that
+    /// doesn't exist or has no password, so that the check takes as long as for an
+    /// account that does.
+    static ref DUMMY_PASSWORD_HASH: String = hash_secret("dummy password").unwrap();
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
@@ -142,6 +146,15 @@ pub async fn create_access_token(
 }
 
 fn hash_access_token(token: &str) -> Result<String> {
+    hash_secret(token)
+}
+
+/// Hashes the password of a local account, for storing it in the database.
+pub fn hash_password(password: &str) -> Result<String> {
+    hash_secret(password)
+}
+
+fn hash_secret(secret: &str) -> Result<String> {
     // Avoid slow hashing in debug mode.
     let params = if cfg!(debug_assertions) {
         scrypt::Params::new(1, 1, 1).unwrap()
@@ -151,7 +164,7 @@ fn hash_access_token(token: &str) -> Result<String> {
 
     Ok(Scrypt
         .hash_password(
-            token.as_bytes(),
+            secret.as_bytes(),
             None,
             params,
             &SaltString::generate(thread_rng()),
@@ -160,6 +173,20 @@ fn hash_access_token(token: &str) -> Result<String> {
         .to_string())
 }
 
+/// Checks the given password against the hash stored for a local account. An account
+/// without a hash never matches, but the password is still hashed so that the response
+/// time doesn't reveal which accounts exist.
+pub fn verify_password(password: &str, password_hash: Option<&str>) -> bool {
+    let (password_hash, exists) = match password_hash {
+        Some(password_hash) => (password_hash, true),
+        None => (DUMMY_PASSWORD_HASH.as_str(), false),
+    };
+    let is_valid = PasswordHash::new(password_hash)
+        .map(|hash| Scrypt.verify_password(password.as_bytes(), &hash).is_ok())
+        .unwrap_or(false);
+    exists && is_valid
+}
+
 /// Encrypts the given access token with the given public key to avoid leaking it on the way
 /// to the client.
 pub fn encrypt_access_token(access_token: &str, public_key: String) -> Result<String> {
@@ -458,6 +458,22 @@ pub struct CreatedChannelMessage {
     pub notifications: NotificationBatch,
 }
 
+pub struct UpdatedChannelMessage {
+    pub message: proto::ChannelMessage,
+    pub participant_connection_ids: Vec<ConnectionId>,
+    pub notifications: NotificationBatch,
+}
+
+/// The filters with which to search the messages in the channels a user can access.
+#[derive(Debug, Default)]
+pub struct ChannelMessageSearch {
+    pub query: String,
+    pub channel_id: Option<ChannelId>,
+    pub sender_id: Option<UserId>,
+    pub sent_after: Option<PrimitiveDateTime>,
+    pub sent_before: Option<PrimitiveDateTime>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, FromQueryResult, Serialize, Deserialize)]
 pub struct Invite {
     pub email_address: String,
@@ -565,6 +581,31 @@ impl Channel {
     }
 }
 
+/// A room that has participants, as reported to server administrators.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ActiveRoom {
+    pub id: RoomId,
+    pub channel_id: Option<ChannelId>,
+    pub participants: Vec<ActiveRoomParticipant>,
+    pub projects: Vec<ActiveProject>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ActiveRoomParticipant {
+    pub user_id: UserId,
+    /// The participant's connection, which is missing while they're still being called.
+    pub connection_id: Option<ConnectionId>,
+    pub connection_lost: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ActiveProject {
+    pub id: ProjectId,
+    pub host_user_id: UserId,
+    /// The number of connections collaborating on the project, including the host's.
+    pub connection_count: usize,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub struct ChannelMember {
     pub role: ChannelRole,
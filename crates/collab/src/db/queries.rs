@@ -2,10 +2,12 @@ use super::*;
 
 pub mod access_tokens;
 pub mod buffers;
+pub mod channel_project_links;
 pub mod channels;
 pub mod contacts;
 pub mod contributors;
 pub mod extensions;
+pub mod local_accounts;
 pub mod messages;
 pub mod notifications;
 pub mod projects;
@@ -55,4 +55,18 @@ impl Database {
         })
         .await
     }
+
+    /// Deletes every access token issued to the given user, returning how many were
+    /// deleted. Connections that already authenticated with them aren't affected, so
+    /// callers should disconnect the user as well.
+    pub async fn delete_access_tokens_for_user(&self, user_id: UserId) -> Result<u64> {
+        self.transaction(|tx| async move {
+            let result = access_token::Entity::delete_many()
+                .filter(access_token::Column::UserId.eq(user_id))
+                .exec(&*tx)
+                .await?;
+            Ok(result.rows_affected)
+        })
+        .await
+    }
 }
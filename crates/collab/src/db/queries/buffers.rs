@@ -1,6 +1,9 @@
 use super::*;
 use prost::Message;
+use std::ops::Range;
+use sum_tree::{Bias, SumTree};
 use text::{EditOperation, UndoOperation};
+use time::OffsetDateTime;
 
 pub struct LeftChannelBuffer {
     pub channel_id: ChannelId,
@@ -46,6 +49,8 @@ impl Database {
                     operation_serialization_version: ActiveValue::Set(
                         storage::SERIALIZATION_VERSION,
                     ),
+                    created_at: ActiveValue::NotSet,
+                    authorship: ActiveValue::NotSet,
                 }
                 .insert(&*tx)
                 .await?;
@@ -478,7 +483,7 @@ impl Database {
 
             let operations = operations
                 .iter()
-                .filter_map(|op| operation_to_storage(op, &buffer, serialization_version))
+                .filter_map(|op| operation_to_storage(op, &buffer, user, serialization_version))
                 .collect::<Vec<_>>();
 
             let mut channel_members;
@@ -672,6 +677,7 @@ impl Database {
                 lamport_timestamp: row.lamport_timestamp,
                 replica_id: row.replica_id,
                 value: Default::default(),
+                user_id: row.user_id,
             });
             operations.push(proto::Operation {
                 variant: Some(operation_from_storage(row, version)?),
@@ -687,24 +693,23 @@ impl Database {
         tx: &DatabaseTransaction,
     ) -> Result<()> {
         let buffer = self.get_channel_buffer(channel_id, tx).await?;
-        let (base_text, operations, _) = self.get_buffer_state(&buffer, tx).await?;
-        if operations.is_empty() {
+        let (text_buffer, authorship, operation_count) =
+            self.replay_buffer_epoch(&buffer, tx).await?;
+        if operation_count == 0 {
             return Ok(());
         }
 
-        let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
-        text_buffer
-            .apply_ops(operations.into_iter().filter_map(operation_from_wire))
-            .unwrap();
-
         let base_text = text_buffer.text();
         let epoch = buffer.epoch + 1;
+        let now = OffsetDateTime::now_utc();
 
         buffer_snapshot::Model {
             buffer_id: buffer.id,
             epoch,
             text: base_text,
             operation_serialization_version: storage::SERIALIZATION_VERSION,
+            created_at: PrimitiveDateTime::new(now.date(), now.time()),
+            authorship: Some(authorship.to_storage().encode_to_vec()),
         }
         .into_active_model()
         .insert(tx)
@@ -721,6 +726,195 @@ impl Database {
         Ok(())
     }
 
+    /// Applies the operations of a buffer's current epoch to the text of its latest
+    /// snapshot, one at a time, attributing the text that each of them inserts to the
+    /// user who wrote it. Also returns the number of operations that were applied.
+    async fn replay_buffer_epoch(
+        &self,
+        buffer: &buffer::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<(text::Buffer, Authorship, usize)> {
+        let (base_text, serialization_version, authorship) = if buffer.epoch > 0 {
+            let snapshot = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.eq(buffer.epoch))
+                .one(tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such snapshot"))?;
+            let authorship = match snapshot.authorship.as_deref() {
+                Some(authorship) => Authorship::from_storage(
+                    storage::Authorship::decode(authorship)
+                        .map_err(|error| anyhow!("failed to decode authorship: {:?}", error))?,
+                ),
+                None => Authorship::default(),
+            };
+            (
+                snapshot.text,
+                snapshot.operation_serialization_version,
+                authorship,
+            )
+        } else {
+            (
+                String::new(),
+                storage::SERIALIZATION_VERSION,
+                Authorship::default(),
+            )
+        };
+
+        // Parts of the text whose history is missing aren't attributed to anyone.
+        let mut authorship = if authorship.len() == base_text.len() {
+            authorship
+        } else {
+            let mut authorship = Authorship::default();
+            authorship.push(base_text.len(), None);
+            authorship
+        };
+
+        let mut text_buffer = text::Buffer::new(0, text::BufferId::new(1).unwrap(), base_text);
+        let mut operation_count = 0;
+        let mut rows = buffer_operation::Entity::find()
+            .filter(buffer_operation::Column::BufferId.eq(buffer.id))
+            .filter(buffer_operation::Column::Epoch.eq(buffer.epoch))
+            .order_by_asc(buffer_operation::Column::LamportTimestamp)
+            .order_by_asc(buffer_operation::Column::ReplicaId)
+            .stream(tx)
+            .await?;
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            let author = row.user_id;
+            let operation = proto::Operation {
+                variant: Some(operation_from_storage(row, serialization_version)?),
+            };
+            operation_count += 1;
+            let Some(operation) = operation_from_wire(operation) else {
+                continue;
+            };
+            let previous_version = text_buffer.version();
+            text_buffer.apply_ops([operation])?;
+            let edits = text_buffer
+                .edits_since::<usize>(&previous_version)
+                .collect::<Vec<_>>();
+            for edit in edits.into_iter().rev() {
+                authorship.splice(edit.old, edit.new.len(), author);
+            }
+        }
+
+        Ok((text_buffer, authorship, operation_count))
+    }
+
+    /// Returns the versions of a channel's notes that were saved when everyone stopped
+    /// editing them, most recent first, along with the users who wrote each of them.
+    pub async fn get_channel_buffer_history(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ChannelNotesVersion>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &*tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &*tx).await?;
+
+            let mut authors = HashMap::<i32, Vec<u64>>::default();
+            let rows = buffer_operation::Entity::find()
+                .filter(buffer_operation::Column::BufferId.eq(buffer.id))
+                .filter(buffer_operation::Column::UserId.is_not_null())
+                .select_only()
+                .column(buffer_operation::Column::Epoch)
+                .column(buffer_operation::Column::UserId)
+                .distinct()
+                .into_tuple::<(i32, UserId)>()
+                .all(&*tx)
+                .await?;
+            for (epoch, user_id) in rows {
+                authors.entry(epoch).or_default().push(user_id.to_proto());
+            }
+
+            // Each snapshot contains the text that resulted from the previous epoch's
+            // operations. The first snapshot is always empty.
+            let snapshots = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.gt(0))
+                .order_by_desc(buffer_snapshot::Column::Epoch)
+                .all(&*tx)
+                .await?;
+            Ok(snapshots
+                .into_iter()
+                .map(|snapshot| {
+                    let mut author_ids = authors.remove(&(snapshot.epoch - 1)).unwrap_or_default();
+                    author_ids.sort_unstable();
+                    proto::ChannelNotesVersion {
+                        epoch: snapshot.epoch as u64,
+                        created_at: snapshot.created_at.assume_utc().unix_timestamp() as u64,
+                        author_ids,
+                    }
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Returns the text of a channel's notes as of the given epoch.
+    pub async fn get_channel_buffer_version_text(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        epoch: i32,
+    ) -> Result<String> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &*tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &*tx).await?;
+            let snapshot = buffer_snapshot::Entity::find()
+                .filter(buffer_snapshot::Column::BufferId.eq(buffer.id))
+                .filter(buffer_snapshot::Column::Epoch.eq(epoch))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such version"))?;
+            Ok(snapshot.text)
+        })
+        .await
+    }
+
+    /// Attributes each part of the current text of a channel's notes to the user who
+    /// wrote it. The authorship of each version is saved along with its text, so only the
+    /// operations of the current epoch are replayed.
+    pub async fn get_channel_buffer_authorship(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<proto::GetChannelNotesAuthorshipResponse> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &*tx)
+                .await?;
+            let buffer = self.get_channel_buffer(channel_id, &*tx).await?;
+            let (text_buffer, authorship, _) = self.replay_buffer_epoch(&buffer, &*tx).await?;
+
+            let mut ranges = Vec::<proto::ChannelNotesAuthoredRange>::new();
+            let mut offset = 0;
+            for run in authorship.runs.iter() {
+                let user_id = run.user_id.map(|user_id| user_id.to_proto());
+                match ranges.last_mut() {
+                    Some(range) if range.user_id == user_id => range.end += run.len as u64,
+                    _ => ranges.push(proto::ChannelNotesAuthoredRange {
+                        start: offset as u64,
+                        end: (offset + run.len) as u64,
+                        user_id,
+                    }),
+                }
+                offset += run.len;
+            }
+            Ok(proto::GetChannelNotesAuthorshipResponse {
+                epoch: buffer.epoch as u64,
+                version: version_to_wire(&text_buffer.version()),
+                ranges,
+            })
+        })
+        .await
+    }
+
     pub async fn observe_buffer_version(
         &self,
         buffer_id: BufferId,
@@ -851,9 +1045,108 @@ impl Database {
     }
 }
 
+/// The users who wrote a buffer's text, as consecutive runs of bytes.
+#[derive(Default)]
+struct Authorship {
+    runs: SumTree<AuthoredRun>,
+}
+
+#[derive(Clone, Debug)]
+struct AuthoredRun {
+    len: usize,
+    user_id: Option<UserId>,
+}
+
+impl sum_tree::Item for AuthoredRun {
+    type Summary = AuthorshipSummary;
+
+    fn summary(&self) -> Self::Summary {
+        AuthorshipSummary { len: self.len }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct AuthorshipSummary {
+    len: usize,
+}
+
+impl sum_tree::Summary for AuthorshipSummary {
+    type Context = ();
+
+    fn add_summary(&mut self, summary: &Self, _: &()) {
+        self.len += summary.len;
+    }
+}
+
+impl<'a> sum_tree::Dimension<'a, AuthorshipSummary> for usize {
+    fn add_summary(&mut self, summary: &'a AuthorshipSummary, _: &()) {
+        *self += summary.len;
+    }
+}
+
+impl Authorship {
+    fn from_storage(authorship: storage::Authorship) -> Self {
+        let mut result = Self::default();
+        for run in authorship.runs {
+            result.push(run.len as usize, run.user_id.map(UserId));
+        }
+        result
+    }
+
+    fn to_storage(&self) -> storage::Authorship {
+        storage::Authorship {
+            runs: self
+                .runs
+                .iter()
+                .map(|run| storage::AuthoredRun {
+                    len: run.len as u64,
+                    user_id: run.user_id.map(|user_id| user_id.0),
+                })
+                .collect(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.runs.summary().len
+    }
+
+    fn push(&mut self, len: usize, user_id: Option<UserId>) {
+        push_authored_run(&mut self.runs, len, user_id);
+    }
+
+    /// Replaces the given range with text of the given length, written by the given user.
+    fn splice(&mut self, range: Range<usize>, new_len: usize, user_id: Option<UserId>) {
+        let mut cursor = self.runs.cursor::<usize>();
+        let mut new_runs = cursor.slice(&range.start, Bias::Right, &());
+        if let Some(run) = cursor.item() {
+            push_authored_run(&mut new_runs, range.start - cursor.start(), run.user_id);
+        }
+        push_authored_run(&mut new_runs, new_len, user_id);
+        cursor.seek_forward(&range.end, Bias::Right, &());
+        if let Some(run) = cursor.item() {
+            push_authored_run(&mut new_runs, cursor.end(&()) - range.end, run.user_id);
+            cursor.next(&());
+        }
+        new_runs.append(cursor.suffix(&()), &());
+        self.runs = new_runs;
+    }
+}
+
+fn push_authored_run(runs: &mut SumTree<AuthoredRun>, len: usize, user_id: Option<UserId>) {
+    if len == 0 {
+        return;
+    }
+    if runs.last().map_or(false, |run| run.user_id == user_id) {
+        runs.update_last(|run| run.len += len, &());
+    } else {
+        runs.push(AuthoredRun { len, user_id }, &());
+    }
+}
+
 fn operation_to_storage(
     operation: &proto::Operation,
     buffer: &buffer::Model,
+    user_id: UserId,
     _format: i32,
 ) -> Option<buffer_operation::ActiveModel> {
     let (replica_id, lamport_timestamp, value) = match operation.variant.as_ref()? {
@@ -903,6 +1196,7 @@ fn operation_to_storage(
         replica_id: ActiveValue::Set(replica_id as i32),
         lamport_timestamp: ActiveValue::Set(lamport_timestamp as i32),
         value: ActiveValue::Set(value.encode_to_vec()),
+        user_id: ActiveValue::Set(Some(user_id)),
     })
 }
 
@@ -1070,6 +1364,20 @@ mod storage {
         pub end: u64,
     }
 
+    #[derive(Message)]
+    pub struct Authorship {
+        #[prost(message, repeated, tag = "1")]
+        pub runs: Vec<AuthoredRun>,
+    }
+
+    #[derive(Message)]
+    pub struct AuthoredRun {
+        #[prost(uint64, tag = "1")]
+        pub len: u64,
+        #[prost(int32, optional, tag = "2")]
+        pub user_id: Option<i32>,
+    }
+
     #[derive(Message)]
     pub struct UndoCount {
         #[prost(uint32, tag = "1")]
@@ -0,0 +1,98 @@
+use super::*;
+
+impl Database {
+    /// Returns the repositories linked to the given channel.
+    pub async fn get_channel_project_links(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<channel_project_link::Model>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.check_user_is_channel_participant(&channel, user_id, &*tx)
+                .await?;
+            Ok(channel_project_link::Entity::find()
+                .filter(channel_project_link::Column::ChannelId.eq(channel_id))
+                .order_by_asc(channel_project_link::Column::Id)
+                .all(&*tx)
+                .await?)
+        })
+        .await
+    }
+
+    /// Links a repository to the given channel, with the admin adding it as the
+    /// link's host. Linking a repository that is already linked makes the admin
+    /// its host instead.
+    pub async fn add_channel_project_link(
+        &self,
+        channel_id: ChannelId,
+        remote_url: &str,
+        path: &str,
+        admin_id: UserId,
+    ) -> Result<channel_project_link::Model> {
+        // Members open the linked path within their own clone, so it must not be able
+        // to lead anywhere else.
+        let is_valid_path = path
+            .split('/')
+            .filter(|component| !component.is_empty())
+            .all(|component| component != "." && component != ".." && !component.contains('\\'));
+        if !is_valid_path {
+            Err(anyhow!("invalid project link path"))?;
+        }
+
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &*tx)
+                .await?;
+
+            let existing_link = channel_project_link::Entity::find()
+                .filter(
+                    channel_project_link::Column::ChannelId
+                        .eq(channel_id)
+                        .and(channel_project_link::Column::RemoteUrl.eq(remote_url))
+                        .and(channel_project_link::Column::Path.eq(path)),
+                )
+                .one(&*tx)
+                .await?;
+            let link = if let Some(existing_link) = existing_link {
+                let mut link = existing_link.into_active_model();
+                link.host_user_id = ActiveValue::Set(Some(admin_id));
+                link.update(&*tx).await?
+            } else {
+                channel_project_link::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    channel_id: ActiveValue::Set(channel_id),
+                    remote_url: ActiveValue::Set(remote_url.to_string()),
+                    path: ActiveValue::Set(path.to_string()),
+                    host_user_id: ActiveValue::Set(Some(admin_id)),
+                }
+                .insert(&*tx)
+                .await?
+            };
+            Ok(link)
+        })
+        .await
+    }
+
+    /// Removes a repository link from its channel, returning the link.
+    pub async fn remove_channel_project_link(
+        &self,
+        link_id: ChannelProjectLinkId,
+        admin_id: UserId,
+    ) -> Result<channel_project_link::Model> {
+        self.transaction(|tx| async move {
+            let link = channel_project_link::Entity::find_by_id(link_id)
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such project link"))?;
+            let channel = self.get_channel_internal(link.channel_id, &*tx).await?;
+            self.check_user_is_channel_admin(&channel, admin_id, &*tx)
+                .await?;
+            channel_project_link::Entity::delete_by_id(link_id)
+                .exec(&*tx)
+                .await?;
+            Ok(link)
+        })
+        .await
+    }
+}
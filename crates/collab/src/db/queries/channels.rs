@@ -3,6 +3,20 @@ use rpc::{proto::channel_member::Kind, ErrorCode, ErrorCodeExt};
 use sea_orm::TryGetableMany;
 
 impl Database {
+    /// Returns every channel on the server, with each channel following its parent.
+    pub async fn get_all_channels(&self) -> Result<Vec<Channel>> {
+        self.transaction(|tx| async move {
+            Ok(channel::Entity::find()
+                .order_by_asc(Expr::cust("parent_path || id || '/'"))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(Channel::from_model)
+                .collect())
+        })
+        .await
+    }
+
     #[cfg(test)]
     pub async fn all_channels(&self) -> Result<Vec<(ChannelId, String)>> {
         self.transaction(move |tx| async move {
@@ -473,39 +487,64 @@ impl Database {
                     .await?;
             }
 
-            let result = channel_member::Entity::delete_many()
-                .filter(
-                    channel_member::Column::ChannelId
-                        .eq(channel_id)
-                        .and(channel_member::Column::UserId.eq(member_id)),
-                )
-                .exec(&*tx)
-                .await?;
-
-            if result.rows_affected == 0 {
-                Err(anyhow!("no such member"))?;
-            }
+            self.remove_channel_member_internal(&channel, member_id, &*tx)
+                .await
+        })
+        .await
+    }
 
-            Ok(RemoveChannelMemberResult {
-                membership_update: self
-                    .calculate_membership_updated(&channel, member_id, &*tx)
-                    .await?,
-                notification_id: self
-                    .remove_notification(
-                        member_id,
-                        rpc::Notification::ChannelInvitation {
-                            channel_id: channel_id.to_proto(),
-                            channel_name: Default::default(),
-                            inviter_id: Default::default(),
-                        },
-                        &*tx,
-                    )
-                    .await?,
-            })
+    /// Removes a channel member on behalf of a server administrator, without
+    /// checking the permissions of a user in the channel.
+    pub async fn admin_remove_channel_member(
+        &self,
+        channel_id: ChannelId,
+        member_id: UserId,
+    ) -> Result<RemoveChannelMemberResult> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            self.remove_channel_member_internal(&channel, member_id, &*tx)
+                .await
         })
         .await
     }
 
+    async fn remove_channel_member_internal(
+        &self,
+        channel: &channel::Model,
+        member_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<RemoveChannelMemberResult> {
+        let result = channel_member::Entity::delete_many()
+            .filter(
+                channel_member::Column::ChannelId
+                    .eq(channel.id)
+                    .and(channel_member::Column::UserId.eq(member_id)),
+            )
+            .exec(tx)
+            .await?;
+
+        if result.rows_affected == 0 {
+            Err(anyhow!("no such member"))?;
+        }
+
+        Ok(RemoveChannelMemberResult {
+            membership_update: self
+                .calculate_membership_updated(channel, member_id, tx)
+                .await?,
+            notification_id: self
+                .remove_notification(
+                    member_id,
+                    rpc::Notification::ChannelInvitation {
+                        channel_id: channel.id.to_proto(),
+                        channel_name: Default::default(),
+                        inviter_id: Default::default(),
+                    },
+                    tx,
+                )
+                .await?,
+        })
+    }
+
     /// Returns all channel invites for the user with the given ID.
     pub async fn get_channel_invites_for_user(&self, user_id: UserId) -> Result<Vec<Channel>> {
         self.transaction(|tx| async move {
@@ -707,6 +746,74 @@ impl Database {
         .await
     }
 
+    /// Makes the user an accepted member of the channel with the given role, on
+    /// behalf of a server administrator. Any pending invite is accepted.
+    pub async fn admin_set_channel_member_role(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        role: ChannelRole,
+    ) -> Result<MembershipUpdated> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            if !channel.is_root() {
+                Err(ErrorCode::NotARootChannel.anyhow())?
+            }
+
+            let membership = channel_member::Entity::find()
+                .filter(
+                    channel_member::Column::ChannelId
+                        .eq(channel_id)
+                        .and(channel_member::Column::UserId.eq(user_id)),
+                )
+                .one(&*tx)
+                .await?;
+            if let Some(membership) = membership {
+                let mut update = membership.into_active_model();
+                update.role = ActiveValue::Set(role);
+                update.accepted = ActiveValue::Set(true);
+                channel_member::Entity::update(update).exec(&*tx).await?;
+            } else {
+                channel_member::ActiveModel {
+                    id: ActiveValue::NotSet,
+                    channel_id: ActiveValue::Set(channel_id),
+                    user_id: ActiveValue::Set(user_id),
+                    accepted: ActiveValue::Set(true),
+                    role: ActiveValue::Set(role),
+                }
+                .insert(&*tx)
+                .await?;
+            }
+
+            self.calculate_membership_updated(&channel, user_id, &*tx)
+                .await
+        })
+        .await
+    }
+
+    /// Returns every member and invitee of the specified channel, for server
+    /// administrators.
+    pub async fn get_channel_members(&self, channel_id: ChannelId) -> Result<Vec<ChannelMember>> {
+        self.transaction(|tx| async move {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            Ok(self
+                .get_channel_participant_details_internal(&channel, &*tx)
+                .await?
+                .into_iter()
+                .map(|member| ChannelMember {
+                    role: member.role,
+                    user_id: member.user_id,
+                    kind: if member.accepted {
+                        Kind::Member
+                    } else {
+                        Kind::Invitee
+                    },
+                })
+                .collect())
+        })
+        .await
+    }
+
     /// Returns the details for the specified channel member.
     pub async fn get_channel_participant_details(
         &self,
@@ -0,0 +1,196 @@
+use super::*;
+
+impl Database {
+    /// Creates a user who signs in with a password, rather than a GitHub account.
+    pub async fn create_local_account(
+        &self,
+        login: &str,
+        email_address: Option<&str>,
+        admin: bool,
+        password_hash: &str,
+    ) -> Result<User> {
+        self.transaction(|tx| async move {
+            if user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+                .is_some()
+            {
+                Err(anyhow!("a user with login {login} already exists"))?;
+            }
+
+            let user = self
+                .create_local_user(login, email_address, admin, &*tx)
+                .await?;
+            local_account::ActiveModel {
+                user_id: ActiveValue::set(user.id),
+                password_hash: ActiveValue::set(Some(password_hash.into())),
+                oidc_subject: ActiveValue::set(None),
+            }
+            .insert(&*tx)
+            .await?;
+            Ok(user)
+        })
+        .await
+    }
+
+    /// Replaces the password of the user with the given login, creating a local
+    /// account for an existing user if necessary.
+    pub async fn set_local_account_password(&self, login: &str, password_hash: &str) -> Result<()> {
+        self.transaction(|tx| async move {
+            let user = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such user {login}"))?;
+            local_account::Entity::insert(local_account::ActiveModel {
+                user_id: ActiveValue::set(user.id),
+                password_hash: ActiveValue::set(Some(password_hash.into())),
+                oidc_subject: ActiveValue::NotSet,
+            })
+            .on_conflict(
+                OnConflict::column(local_account::Column::UserId)
+                    .update_column(local_account::Column::PasswordHash)
+                    .to_owned(),
+            )
+            .exec_without_returning(&*tx)
+            .await?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Returns the user with the given login, along with its local account, if it has one.
+    pub async fn get_local_account_by_login(
+        &self,
+        login: &str,
+    ) -> Result<Option<(User, local_account::Model)>> {
+        self.transaction(|tx| async move {
+            let Some(user) = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+            else {
+                return Ok(None);
+            };
+            let account = local_account::Entity::find_by_id(user.id).one(&*tx).await?;
+            Ok(account.map(|account| (user, account)))
+        })
+        .await
+    }
+
+    /// Returns the user who signs in with the given OpenID Connect subject. On the
+    /// subject's first sign-in, a user is created with the given login, unless it's
+    /// taken, in which case `None` is returned: existing users can only sign in with a
+    /// subject that an administrator has linked to them.
+    pub async fn get_or_create_user_by_oidc_subject(
+        &self,
+        subject: &str,
+        login: &str,
+        email_address: Option<&str>,
+    ) -> Result<Option<User>> {
+        self.transaction(|tx| async move {
+            if let Some(account) = local_account::Entity::find()
+                .filter(local_account::Column::OidcSubject.eq(subject))
+                .one(&*tx)
+                .await?
+            {
+                return Ok(Some(
+                    user::Entity::find_by_id(account.user_id)
+                        .one(&*tx)
+                        .await?
+                        .ok_or_else(|| anyhow!("no user for oidc subject {subject}"))?,
+                ));
+            }
+
+            if user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+                .is_some()
+            {
+                return Ok(None);
+            }
+
+            let user = self
+                .create_local_user(login, email_address, false, &*tx)
+                .await?;
+            self.insert_oidc_account(user.id, subject, &*tx).await?;
+            Ok(Some(user))
+        })
+        .await
+    }
+
+    /// Links an OpenID Connect subject to the existing user with the given login, so
+    /// that they can sign in through the provider.
+    pub async fn link_oidc_subject(&self, login: &str, subject: &str) -> Result<()> {
+        self.transaction(|tx| async move {
+            let user = user::Entity::find()
+                .filter(user::Column::GithubLogin.eq(login))
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("no such user {login}"))?;
+            if let Some(account) = local_account::Entity::find()
+                .filter(local_account::Column::OidcSubject.eq(subject))
+                .one(&*tx)
+                .await?
+            {
+                if account.user_id != user.id {
+                    Err(anyhow!("oidc subject {subject} belongs to another user"))?;
+                }
+                return Ok(());
+            }
+
+            match local_account::Entity::find_by_id(user.id).one(&*tx).await? {
+                Some(account) => {
+                    if account.oidc_subject.is_some() {
+                        Err(anyhow!("{login} is already linked to another oidc subject"))?;
+                    }
+                    let mut account = account.into_active_model();
+                    account.oidc_subject = ActiveValue::set(Some(subject.into()));
+                    account.update(&*tx).await?;
+                }
+                None => self.insert_oidc_account(user.id, subject, &*tx).await?,
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn insert_oidc_account(
+        &self,
+        user_id: UserId,
+        subject: &str,
+        tx: &DatabaseTransaction,
+    ) -> Result<()> {
+        local_account::ActiveModel {
+            user_id: ActiveValue::set(user_id),
+            password_hash: ActiveValue::set(None),
+            oidc_subject: ActiveValue::set(Some(subject.into())),
+        }
+        .insert(tx)
+        .await?;
+        Ok(())
+    }
+
+    async fn create_local_user(
+        &self,
+        login: &str,
+        email_address: Option<&str>,
+        admin: bool,
+        tx: &DatabaseTransaction,
+    ) -> Result<User> {
+        Ok(user::Entity::insert(user::ActiveModel {
+            email_address: ActiveValue::set(email_address.map(|email| email.into())),
+            github_login: ActiveValue::set(login.into()),
+            github_user_id: ActiveValue::set(None),
+            admin: ActiveValue::set(admin),
+            invite_count: ActiveValue::set(0),
+            invite_code: ActiveValue::set(None),
+            metrics_id: ActiveValue::set(Uuid::new_v4()),
+            ..Default::default()
+        })
+        .exec_with_returning(tx)
+        .await?)
+    }
+}
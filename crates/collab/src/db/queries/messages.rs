@@ -142,6 +142,91 @@ impl Database {
         .await
     }
 
+    /// Searches the bodies of the messages in the channels that the user can access,
+    /// returning the most recent matches first.
+    pub async fn search_channel_messages(
+        &self,
+        user_id: UserId,
+        search: &ChannelMessageSearch,
+        limit: usize,
+    ) -> Result<Vec<proto::ChannelMessageId>> {
+        self.transaction(|tx| async move {
+            let channel_ids = if let Some(channel_id) = search.channel_id {
+                let channel = self.get_channel_internal(channel_id, &*tx).await?;
+                self.check_user_is_channel_participant(&channel, user_id, &*tx)
+                    .await?;
+                vec![channel_id]
+            } else {
+                self.get_user_channels(user_id, None, &*tx)
+                    .await?
+                    .channels
+                    .into_iter()
+                    .map(|channel| channel.id)
+                    .collect()
+            };
+
+            let match_condition = match self.pool.get_database_backend() {
+                sea_orm::DatabaseBackend::Postgres => Expr::cust_with_values(
+                    "to_tsvector('english', channel_messages.body) @@ plainto_tsquery('english', $1)",
+                    [search.query.clone()],
+                ),
+                _ => {
+                    let Some(query) = Self::fts_query_string(&search.query) else {
+                        return Ok(Vec::new());
+                    };
+                    Expr::cust_with_values(
+                        "channel_messages.id IN (SELECT rowid FROM channel_messages_fts WHERE channel_messages_fts MATCH $1)",
+                        [query],
+                    )
+                }
+            };
+
+            let mut condition = Condition::all()
+                .add(channel_message::Column::ChannelId.is_in(channel_ids))
+                .add(match_condition);
+            if let Some(sender_id) = search.sender_id {
+                condition = condition.add(channel_message::Column::SenderId.eq(sender_id));
+            }
+            if let Some(sent_after) = search.sent_after {
+                condition = condition.add(channel_message::Column::SentAt.gte(sent_after));
+            }
+            if let Some(sent_before) = search.sent_before {
+                condition = condition.add(channel_message::Column::SentAt.lt(sent_before));
+            }
+
+            let rows = channel_message::Entity::find()
+                .filter(condition)
+                .order_by_desc(channel_message::Column::Id)
+                .limit(limit as u64)
+                .all(&*tx)
+                .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| proto::ChannelMessageId {
+                    channel_id: row.channel_id.to_proto(),
+                    message_id: row.id.to_proto(),
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Converts a search query into an FTS5 query matching messages that contain
+    /// every word in it, quoting each word so that its punctuation is matched literally.
+    fn fts_query_string(query: &str) -> Option<String> {
+        let mut result = String::new();
+        for word in query.split_whitespace() {
+            if !result.is_empty() {
+                result.push(' ');
+            }
+            result.push('"');
+            result.push_str(&word.replace('"', "\"\""));
+            result.push('"');
+        }
+        (!result.is_empty()).then_some(result)
+    }
+
     async fn load_channel_messages(
         &self,
         rows: Vec<channel_message::Model>,
@@ -162,6 +247,10 @@ impl Database {
                         lower_half: nonce.1,
                     }),
                     reply_to_message_id: row.reply_to_message_id.map(|id| id.to_proto()),
+                    edited_at: row
+                        .edited_at
+                        .map(|edited_at| edited_at.assume_utc().unix_timestamp() as u64),
+                    reactions: Vec::new(),
                 }
             })
             .collect::<Vec<_>>();
@@ -195,10 +284,58 @@ impl Database {
                 }
             }
         }
+        drop(mentions);
+
+        let message_ixs = messages
+            .iter()
+            .enumerate()
+            .map(|(ix, message)| (message.id, ix))
+            .collect::<HashMap<_, _>>();
+        let mut reactions = channel_message_reaction::Entity::find()
+            .filter(
+                channel_message_reaction::Column::MessageId.is_in(messages.iter().map(|m| m.id)),
+            )
+            .order_by_asc(channel_message_reaction::Column::Id)
+            .stream(&*tx)
+            .await?;
+        while let Some(reaction) = reactions.next().await {
+            let reaction = reaction?;
+            let Some(message) = message_ixs
+                .get(&reaction.message_id.to_proto())
+                .and_then(|ix| messages.get_mut(*ix))
+            else {
+                continue;
+            };
+            let user_id = reaction.user_id.to_proto();
+            if let Some(existing) = message
+                .reactions
+                .iter_mut()
+                .find(|existing| existing.emoji == reaction.emoji)
+            {
+                existing.user_ids.push(user_id);
+            } else {
+                message.reactions.push(proto::ChannelMessageReaction {
+                    emoji: reaction.emoji,
+                    user_ids: vec![user_id],
+                });
+            }
+        }
 
         Ok(messages)
     }
 
+    async fn load_channel_message(
+        &self,
+        row: channel_message::Model,
+        tx: &DatabaseTransaction,
+    ) -> Result<proto::ChannelMessage> {
+        Ok(self
+            .load_channel_messages(vec![row], tx)
+            .await?
+            .pop()
+            .ok_or_else(|| anyhow!("failed to load message"))?)
+    }
+
     /// Creates a new channel message.
     pub async fn create_channel_message(
         &self,
@@ -248,6 +385,7 @@ impl Database {
                 nonce: ActiveValue::Set(Uuid::from_u128(nonce)),
                 id: ActiveValue::NotSet,
                 reply_to_message_id: ActiveValue::Set(reply_to_message_id),
+                edited_at: ActiveValue::NotSet,
             })
             .on_conflict(
                 OnConflict::columns([
@@ -266,50 +404,32 @@ impl Database {
             match result {
                 TryInsertResult::Inserted(result) => {
                     message_id = result.last_insert_id;
-                    let mentioned_user_ids =
-                        mentions.iter().map(|m| m.user_id).collect::<HashSet<_>>();
-
-                    let mentions = mentions
-                        .iter()
-                        .filter_map(|mention| {
-                            let range = mention.range.as_ref()?;
-                            if !body.is_char_boundary(range.start as usize)
-                                || !body.is_char_boundary(range.end as usize)
-                            {
-                                return None;
-                            }
-                            Some(channel_message_mention::ActiveModel {
-                                message_id: ActiveValue::Set(message_id),
-                                start_offset: ActiveValue::Set(range.start as i32),
-                                end_offset: ActiveValue::Set(range.end as i32),
-                                user_id: ActiveValue::Set(UserId::from_proto(mention.user_id)),
-                            })
-                        })
-                        .collect::<Vec<_>>();
-                    if !mentions.is_empty() {
-                        channel_message_mention::Entity::insert_many(mentions)
-                            .exec(&*tx)
-                            .await?;
-                    }
-
-                    for mentioned_user in mentioned_user_ids {
-                        notifications.extend(
-                            self.create_notification(
-                                UserId::from_proto(mentioned_user),
-                                rpc::Notification::ChannelMessageMention {
-                                    message_id: message_id.to_proto(),
-                                    sender_id: user_id.to_proto(),
-                                    channel_id: channel_id.to_proto(),
-                                },
-                                false,
-                                &*tx,
-                            )
-                            .await?,
-                        );
-                    }
+                    notifications = self
+                        .create_channel_message_mentions(
+                            channel_id,
+                            message_id,
+                            user_id,
+                            body,
+                            mentions,
+                            &HashSet::default(),
+                            &*tx,
+                        )
+                        .await?;
 
-                    self.observe_channel_message_internal(channel_id, user_id, message_id, &*tx)
+                    self.observe_channel_message_internal(
+                        channel_id, user_id, message_id, None, &*tx,
+                    )
+                    .await?;
+                    if let Some(thread_id) = reply_to_message_id {
+                        self.observe_channel_message_internal(
+                            channel_id,
+                            user_id,
+                            message_id,
+                            Some(thread_id),
+                            &*tx,
+                        )
                         .await?;
+                    }
                 }
                 _ => {
                     message_id = channel_message::Entity::find()
@@ -334,14 +454,17 @@ impl Database {
         .await
     }
 
+    /// Records that the user has seen the channel's messages up to the given one, or
+    /// the replies to the given thread's parent message, if a thread is given.
     pub async fn observe_channel_message(
         &self,
         channel_id: ChannelId,
         user_id: UserId,
         message_id: MessageId,
+        thread_id: Option<MessageId>,
     ) -> Result<NotificationBatch> {
         self.transaction(|tx| async move {
-            self.observe_channel_message_internal(channel_id, user_id, message_id, &*tx)
+            self.observe_channel_message_internal(channel_id, user_id, message_id, thread_id, &*tx)
                 .await?;
             let mut batch = NotificationBatch::default();
             batch.extend(
@@ -366,17 +489,20 @@ impl Database {
         channel_id: ChannelId,
         user_id: UserId,
         message_id: MessageId,
+        thread_id: Option<MessageId>,
         tx: &DatabaseTransaction,
     ) -> Result<()> {
         observed_channel_messages::Entity::insert(observed_channel_messages::ActiveModel {
             user_id: ActiveValue::Set(user_id),
             channel_id: ActiveValue::Set(channel_id),
             channel_message_id: ActiveValue::Set(message_id),
+            thread_id: ActiveValue::Set(thread_id.unwrap_or_default()),
         })
         .on_conflict(
             OnConflict::columns([
                 observed_channel_messages::Column::ChannelId,
                 observed_channel_messages::Column::UserId,
+                observed_channel_messages::Column::ThreadId,
             ])
             .update_column(observed_channel_messages::Column::ChannelMessageId)
             .action_cond_where(observed_channel_messages::Column::ChannelMessageId.lt(message_id))
@@ -400,6 +526,7 @@ impl Database {
                 observed_channel_messages::Column::ChannelId
                     .is_in(channel_ids.iter().map(|id| id.0)),
             )
+            .filter(observed_channel_messages::Column::ThreadId.eq(MessageId::default()))
             .all(&*tx)
             .await?;
 
@@ -412,6 +539,32 @@ impl Database {
             .collect())
     }
 
+    /// Returns the latest reply that the user has seen in each of the channel's
+    /// threads that they've viewed.
+    pub async fn observed_channel_threads(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Vec<proto::ObservedChannelThread>> {
+        self.transaction(|tx| async move {
+            let rows = observed_channel_messages::Entity::find()
+                .filter(observed_channel_messages::Column::UserId.eq(user_id))
+                .filter(observed_channel_messages::Column::ChannelId.eq(channel_id))
+                .filter(observed_channel_messages::Column::ThreadId.ne(MessageId::default()))
+                .all(&*tx)
+                .await?;
+
+            Ok(rows
+                .into_iter()
+                .map(|row| proto::ObservedChannelThread {
+                    thread_id: row.thread_id.to_proto(),
+                    message_id: row.channel_message_id.to_proto(),
+                })
+                .collect())
+        })
+        .await
+    }
+
     pub async fn latest_channel_messages(
         &self,
         channel_ids: &[ChannelId],
@@ -521,4 +674,246 @@ impl Database {
         })
         .await
     }
+
+    /// Replaces the body and mentions of a channel message, which only its sender can do.
+    pub async fn update_channel_message(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        edited_at: OffsetDateTime,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .chat_participant_connection_ids(channel_id, user_id, &*tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &*tx)
+                .await?;
+            if message.sender_id != user_id {
+                Err(anyhow!("only the sender of a message can edit it"))?;
+            }
+
+            let edited_at = edited_at.to_offset(time::UtcOffset::UTC);
+            let edited_at = time::PrimitiveDateTime::new(edited_at.date(), edited_at.time());
+            let mut message = message.into_active_model();
+            message.body = ActiveValue::Set(body.to_string());
+            message.edited_at = ActiveValue::Set(Some(edited_at));
+            let message = message.update(&*tx).await?;
+
+            let previous_mentions = channel_message_mention::Entity::find()
+                .filter(channel_message_mention::Column::MessageId.eq(message_id))
+                .all(&*tx)
+                .await?
+                .into_iter()
+                .map(|mention| mention.user_id.to_proto())
+                .collect::<HashSet<_>>();
+            channel_message_mention::Entity::delete_many()
+                .filter(channel_message_mention::Column::MessageId.eq(message_id))
+                .exec(&*tx)
+                .await?;
+            let notifications = self
+                .create_channel_message_mentions(
+                    channel_id,
+                    message_id,
+                    user_id,
+                    body,
+                    mentions,
+                    &previous_mentions,
+                    &*tx,
+                )
+                .await?;
+
+            Ok(UpdatedChannelMessage {
+                message: self.load_channel_message(message, &*tx).await?,
+                participant_connection_ids,
+                notifications,
+            })
+        })
+        .await
+    }
+
+    /// Adds the user's reaction with the given emoji to a channel message.
+    pub async fn add_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .chat_participant_connection_ids(channel_id, user_id, &*tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &*tx)
+                .await?;
+
+            channel_message_reaction::Entity::insert(channel_message_reaction::ActiveModel {
+                id: ActiveValue::NotSet,
+                message_id: ActiveValue::Set(message_id),
+                user_id: ActiveValue::Set(user_id),
+                emoji: ActiveValue::Set(emoji.to_string()),
+            })
+            .on_conflict(
+                OnConflict::columns([
+                    channel_message_reaction::Column::MessageId,
+                    channel_message_reaction::Column::UserId,
+                    channel_message_reaction::Column::Emoji,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .do_nothing()
+            .exec(&*tx)
+            .await?;
+
+            Ok(UpdatedChannelMessage {
+                message: self.load_channel_message(message, &*tx).await?,
+                participant_connection_ids,
+                notifications: NotificationBatch::default(),
+            })
+        })
+        .await
+    }
+
+    /// Removes the user's reaction with the given emoji from a channel message.
+    pub async fn remove_channel_message_reaction(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        user_id: UserId,
+        emoji: &str,
+    ) -> Result<UpdatedChannelMessage> {
+        self.transaction(|tx| async move {
+            let participant_connection_ids = self
+                .chat_participant_connection_ids(channel_id, user_id, &*tx)
+                .await?;
+            let message = self
+                .get_channel_message_internal(channel_id, message_id, &*tx)
+                .await?;
+
+            channel_message_reaction::Entity::delete_many()
+                .filter(channel_message_reaction::Column::MessageId.eq(message_id))
+                .filter(channel_message_reaction::Column::UserId.eq(user_id))
+                .filter(channel_message_reaction::Column::Emoji.eq(emoji))
+                .exec(&*tx)
+                .await?;
+
+            Ok(UpdatedChannelMessage {
+                message: self.load_channel_message(message, &*tx).await?,
+                participant_connection_ids,
+                notifications: NotificationBatch::default(),
+            })
+        })
+        .await
+    }
+
+    async fn get_channel_message_internal(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        tx: &DatabaseTransaction,
+    ) -> Result<channel_message::Model> {
+        Ok(channel_message::Entity::find_by_id(message_id)
+            .filter(channel_message::Column::ChannelId.eq(channel_id))
+            .one(tx)
+            .await?
+            .ok_or_else(|| anyhow!("no such message"))?)
+    }
+
+    /// Returns the connections of the channel's chat participants, failing if the
+    /// given user isn't one of them, or is no longer allowed in the channel.
+    async fn chat_participant_connection_ids(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<Vec<ConnectionId>> {
+        let channel = self.get_channel_internal(channel_id, tx).await?;
+        self.check_user_is_channel_participant(&channel, user_id, tx)
+            .await?;
+
+        let mut rows = channel_chat_participant::Entity::find()
+            .filter(channel_chat_participant::Column::ChannelId.eq(channel_id))
+            .stream(tx)
+            .await?;
+
+        let mut is_participant = false;
+        let mut participant_connection_ids = Vec::new();
+        while let Some(row) = rows.next().await {
+            let row = row?;
+            if row.user_id == user_id {
+                is_participant = true;
+            }
+            participant_connection_ids.push(row.connection());
+        }
+        drop(rows);
+
+        if !is_participant {
+            Err(anyhow!("not a chat participant"))?;
+        }
+        Ok(participant_connection_ids)
+    }
+
+    /// Stores the mentions in a channel message, and notifies the mentioned users who
+    /// haven't been notified already.
+    async fn create_channel_message_mentions(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        sender_id: UserId,
+        body: &str,
+        mentions: &[proto::ChatMention],
+        notified_user_ids: &HashSet<u64>,
+        tx: &DatabaseTransaction,
+    ) -> Result<NotificationBatch> {
+        let mentioned_user_ids = mentions.iter().map(|m| m.user_id).collect::<HashSet<_>>();
+
+        let mentions = mentions
+            .iter()
+            .filter_map(|mention| {
+                let range = mention.range.as_ref()?;
+                if !body.is_char_boundary(range.start as usize)
+                    || !body.is_char_boundary(range.end as usize)
+                {
+                    return None;
+                }
+                Some(channel_message_mention::ActiveModel {
+                    message_id: ActiveValue::Set(message_id),
+                    start_offset: ActiveValue::Set(range.start as i32),
+                    end_offset: ActiveValue::Set(range.end as i32),
+                    user_id: ActiveValue::Set(UserId::from_proto(mention.user_id)),
+                })
+            })
+            .collect::<Vec<_>>();
+        if !mentions.is_empty() {
+            channel_message_mention::Entity::insert_many(mentions)
+                .exec(tx)
+                .await?;
+        }
+
+        let mut notifications = NotificationBatch::default();
+        for mentioned_user in mentioned_user_ids {
+            if notified_user_ids.contains(&mentioned_user) {
+                continue;
+            }
+            notifications.extend(
+                self.create_notification(
+                    UserId::from_proto(mentioned_user),
+                    rpc::Notification::ChannelMessageMention {
+                        message_id: message_id.to_proto(),
+                        sender_id: sender_id.to_proto(),
+                        channel_id: channel_id.to_proto(),
+                    },
+                    false,
+                    tx,
+                )
+                .await?,
+            );
+        }
+        Ok(notifications)
+    }
 }
@@ -1154,6 +1154,76 @@ impl Database {
         Ok(room)
     }
 
+    /// Returns every room that has participants, with their connections and the
+    /// projects shared in the room.
+    pub async fn get_active_rooms(&self) -> Result<Vec<ActiveRoom>> {
+        self.transaction(|tx| async move {
+            let mut rooms = BTreeMap::<RoomId, ActiveRoom>::default();
+            let participants = room_participant::Entity::find()
+                .order_by_asc(room_participant::Column::Id)
+                .all(&*tx)
+                .await?;
+            for participant in participants {
+                rooms
+                    .entry(participant.room_id)
+                    .or_insert_with(|| ActiveRoom {
+                        id: participant.room_id,
+                        channel_id: None,
+                        participants: Vec::new(),
+                        projects: Vec::new(),
+                    })
+                    .participants
+                    .push(ActiveRoomParticipant {
+                        user_id: participant.user_id,
+                        connection_id: participant.answering_connection(),
+                        connection_lost: participant.answering_connection_lost,
+                    });
+            }
+
+            let room_ids = rooms.keys().copied().collect::<Vec<_>>();
+            let db_rooms = room::Entity::find()
+                .filter(room::Column::Id.is_in(room_ids.iter().copied()))
+                .all(&*tx)
+                .await?;
+            for db_room in db_rooms {
+                if let Some(room) = rooms.get_mut(&db_room.id) {
+                    room.channel_id = db_room.channel_id;
+                }
+            }
+
+            let projects = project::Entity::find()
+                .filter(project::Column::RoomId.is_in(room_ids))
+                .order_by_asc(project::Column::Id)
+                .all(&*tx)
+                .await?;
+            let mut connection_counts = HashMap::<ProjectId, usize>::default();
+            let collaborators = project_collaborator::Entity::find()
+                .filter(
+                    project_collaborator::Column::ProjectId
+                        .is_in(projects.iter().map(|project| project.id)),
+                )
+                .all(&*tx)
+                .await?;
+            for collaborator in collaborators {
+                *connection_counts
+                    .entry(collaborator.project_id)
+                    .or_default() += 1;
+            }
+            for project in projects {
+                if let Some(room) = rooms.get_mut(&project.room_id) {
+                    room.projects.push(ActiveProject {
+                        id: project.id,
+                        host_user_id: project.host_user_id,
+                        connection_count: connection_counts.get(&project.id).copied().unwrap_or(0),
+                    });
+                }
+            }
+
+            Ok(rooms.into_values().collect())
+        })
+        .await
+    }
+
     pub async fn room_connection_ids(
         &self,
         room_id: RoomId,
@@ -8,6 +8,8 @@ pub mod channel_chat_participant;
 pub mod channel_member;
 pub mod channel_message;
 pub mod channel_message_mention;
+pub mod channel_message_reaction;
+pub mod channel_project_link;
 pub mod contact;
 pub mod contributor;
 pub mod extension;
@@ -15,6 +17,7 @@ pub mod extension_version;
 pub mod feature_flag;
 pub mod follower;
 pub mod language_server;
+pub mod local_account;
 pub mod notification;
 pub mod notification_kind;
 pub mod observed_buffer_edits;
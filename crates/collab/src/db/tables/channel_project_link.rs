@@ -0,0 +1,50 @@
+use crate::db::{ChannelId, ChannelProjectLinkId, UserId};
+use rpc::proto;
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "channel_project_links")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: ChannelProjectLinkId,
+    pub channel_id: ChannelId,
+    pub remote_url: String,
+    pub path: String,
+    pub host_user_id: Option<UserId>,
+}
+
+impl Model {
+    pub fn to_proto(&self) -> proto::ChannelProjectLink {
+        proto::ChannelProjectLink {
+            id: self.id.to_proto(),
+            channel_id: self.channel_id.to_proto(),
+            remote_url: self.remote_url.clone(),
+            path: self.path.clone(),
+            host_user_id: self.host_user_id.map(|id| id.to_proto()),
+        }
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::channel::Entity",
+        from = "Column::ChannelId",
+        to = "super::channel::Column::Id"
+    )]
+    Channel,
+    #[sea_orm(
+        belongs_to = "super::user::Entity",
+        from = "Column::HostUserId",
+        to = "super::user::Column::Id"
+    )]
+    HostUser,
+}
+
+impl Related<super::channel::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Channel.def()
+    }
+}
@@ -8,6 +8,9 @@ pub struct Model {
     pub user_id: UserId,
     pub channel_id: ChannelId,
     pub channel_message_id: MessageId,
+    /// The message whose replies were observed, or zero for the channel's messages
+    /// as a whole.
+    pub thread_id: MessageId,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
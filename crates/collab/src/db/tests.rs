@@ -36,17 +36,22 @@ impl TestDb {
             let mut db = Database::new(options, Executor::Deterministic(background))
                 .await
                 .unwrap();
-            let sql = include_str!(concat!(
-                env!("CARGO_MANIFEST_DIR"),
-                "/migrations.sqlite/20221109000000_test_schema.sql"
-            ));
-            db.pool
-                .execute(sea_orm::Statement::from_string(
-                    db.pool.get_database_backend(),
-                    sql,
-                ))
-                .await
-                .unwrap();
+            let migrations_path = concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite");
+            let mut migration_paths = std::fs::read_dir(migrations_path)
+                .unwrap()
+                .map(|entry| entry.unwrap().path())
+                .collect::<Vec<_>>();
+            migration_paths.sort();
+            for migration_path in migration_paths {
+                let sql = std::fs::read_to_string(&migration_path).unwrap();
+                db.pool
+                    .execute(sea_orm::Statement::from_string(
+                        db.pool.get_database_backend(),
+                        sql,
+                    ))
+                    .await
+                    .unwrap();
+            }
             db.initialize_notification_kinds().await.unwrap();
             db
         });
@@ -373,6 +373,149 @@ async fn test_channel_buffers_last_operations(db: &Database) {
     );
 }
 
+test_both_dbs!(
+    test_channel_buffer_history,
+    test_channel_buffer_history_postgres,
+    test_channel_buffer_history_sqlite
+);
+
+async fn test_channel_buffer_history(db: &Arc<Database>) {
+    let a_id = new_test_user(db, "user_a@example.com").await;
+    let b_id = new_test_user(db, "user_b@example.com").await;
+    let c_id = new_test_user(db, "user_c@example.com").await;
+    let owner_id = db.create_server("production").await.unwrap().0 as u32;
+    let connection_id_a = ConnectionId { owner_id, id: 1 };
+    let connection_id_b = ConnectionId { owner_id, id: 2 };
+
+    let channel_id = db.create_root_channel("zed", a_id).await.unwrap();
+    db.invite_channel_member(channel_id, b_id, a_id, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel_id, b_id, true)
+        .await
+        .unwrap();
+
+    // Both users edit the notes in the first epoch.
+    db.join_channel_buffer(channel_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    db.join_channel_buffer(channel_id, b_id, connection_id_b)
+        .await
+        .unwrap();
+    let mut buffer_a = Buffer::new(0, text::BufferId::new(1).unwrap(), "".to_string());
+    let mut buffer_b = Buffer::new(1, text::BufferId::new(1).unwrap(), "".to_string());
+    let operation = buffer_a.edit([(0..0, "hello")]);
+    buffer_b.apply_ops([operation.clone()]).unwrap();
+    update_buffer(channel_id, a_id, db, vec![operation]).await;
+    let operation = buffer_b.edit([(5..5, " world")]);
+    update_buffer(channel_id, b_id, db, vec![operation]).await;
+
+    let authorship = db
+        .get_channel_buffer_authorship(channel_id, a_id)
+        .await
+        .unwrap();
+    assert_eq!(authorship.epoch, 0);
+    assert_eq!(
+        authorship.ranges,
+        &[
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 0,
+                end: 5,
+                user_id: Some(a_id.to_proto()),
+            },
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 5,
+                end: 11,
+                user_id: Some(b_id.to_proto()),
+            },
+        ]
+    );
+    assert!(db
+        .get_channel_buffer_history(channel_id, a_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // When everyone leaves, the notes are saved as a version.
+    db.leave_channel_buffer(channel_id, connection_id_a)
+        .await
+        .unwrap();
+    db.leave_channel_buffer(channel_id, connection_id_b)
+        .await
+        .unwrap();
+    let history = db
+        .get_channel_buffer_history(channel_id, a_id)
+        .await
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].epoch, 1);
+    assert_eq!(history[0].author_ids, &[a_id.to_proto(), b_id.to_proto()]);
+    assert_eq!(
+        db.get_channel_buffer_version_text(channel_id, a_id, 1)
+            .await
+            .unwrap(),
+        "hello world"
+    );
+
+    // Authorship carries over from previous epochs.
+    let response = db
+        .join_channel_buffer(channel_id, a_id, connection_id_a)
+        .await
+        .unwrap();
+    let mut buffer_a = Buffer::new(0, text::BufferId::new(1).unwrap(), response.base_text);
+    let operation = buffer_a.edit([(5..5, ", cruel")]);
+    update_buffer(channel_id, a_id, db, vec![operation]).await;
+    let authorship = db
+        .get_channel_buffer_authorship(channel_id, b_id)
+        .await
+        .unwrap();
+    assert_eq!(authorship.epoch, 1);
+    assert_eq!(
+        authorship.ranges,
+        &[
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 0,
+                end: 12,
+                user_id: Some(a_id.to_proto()),
+            },
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 12,
+                end: 18,
+                user_id: Some(b_id.to_proto()),
+            },
+        ]
+    );
+
+    // Deletions shrink the ranges they overlap.
+    let operation = buffer_a.edit([(10..14, "")]);
+    update_buffer(channel_id, a_id, db, vec![operation]).await;
+    let authorship = db
+        .get_channel_buffer_authorship(channel_id, b_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        authorship.ranges,
+        &[
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 0,
+                end: 10,
+                user_id: Some(a_id.to_proto()),
+            },
+            rpc::proto::ChannelNotesAuthoredRange {
+                start: 10,
+                end: 14,
+                user_id: Some(b_id.to_proto()),
+            },
+        ]
+    );
+
+    // Users outside of the channel can't see its history.
+    assert!(db
+        .get_channel_buffer_history(channel_id, c_id)
+        .await
+        .is_err());
+}
+
 async fn update_buffer(
     channel_id: ChannelId,
     user_id: UserId,
@@ -400,6 +543,7 @@ fn assert_operations(
             lamport_timestamp: op.lamport_timestamp,
             replica_id: op.replica_id,
             value: vec![],
+            user_id: None,
         })
         .collect::<Vec<_>>();
     let expected = expected
@@ -410,6 +554,7 @@ fn assert_operations(
             lamport_timestamp: buffer.lamport_clock.value as i32 - 1,
             replica_id: buffer.replica_id() as i32,
             value: vec![],
+            user_id: None,
         })
         .collect::<Vec<_>>();
     assert_eq!(actual, expected, "unexpected operations")
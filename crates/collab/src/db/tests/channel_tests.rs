@@ -744,3 +744,156 @@ fn assert_channel_tree(actual: Vec<Channel>, expected: &[(ChannelId, &[ChannelId
         "wrong channel ids and parent paths"
     );
 }
+
+test_both_dbs!(
+    test_admin_channel_membership,
+    test_admin_channel_membership_postgres,
+    test_admin_channel_membership_sqlite
+);
+
+async fn test_admin_channel_membership(db: &Arc<Database>) {
+    let admin = new_test_user(db, "admin@example.com").await;
+    let user_2 = new_test_user(db, "user2@example.com").await;
+    let user_3 = new_test_user(db, "user3@example.com").await;
+
+    let zed = db.create_root_channel("zed", admin).await.unwrap();
+    let sub = db.create_sub_channel("sub", zed, admin).await.unwrap();
+
+    let channel_ids = db
+        .get_all_channels()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|channel| channel.id)
+        .collect::<Vec<_>>();
+    assert_eq!(channel_ids, vec![zed, sub]);
+
+    // Memberships can only be managed on root channels.
+    assert!(db
+        .admin_set_channel_member_role(sub, user_2, ChannelRole::Member)
+        .await
+        .is_err());
+
+    db.admin_set_channel_member_role(zed, user_2, ChannelRole::Guest)
+        .await
+        .unwrap();
+    db.invite_channel_member(zed, user_3, admin, ChannelRole::Member)
+        .await
+        .unwrap();
+
+    let member = |members: &[crate::db::ChannelMember], user_id| {
+        members
+            .iter()
+            .find(|member| member.user_id == user_id)
+            .map(|member| (member.role, member.kind))
+    };
+    let members = db.get_channel_members(zed).await.unwrap();
+    assert_eq!(
+        member(&members, user_2),
+        Some((ChannelRole::Guest, proto::channel_member::Kind::Member))
+    );
+    assert_eq!(
+        member(&members, user_3),
+        Some((ChannelRole::Member, proto::channel_member::Kind::Invitee))
+    );
+
+    // Setting the role of an invitee accepts the invitation on their behalf.
+    db.admin_set_channel_member_role(zed, user_3, ChannelRole::Admin)
+        .await
+        .unwrap();
+    let members = db.get_channel_members(zed).await.unwrap();
+    assert_eq!(
+        member(&members, user_3),
+        Some((ChannelRole::Admin, proto::channel_member::Kind::Member))
+    );
+    assert!(db.get_channel(sub, user_3).await.is_ok());
+
+    db.admin_remove_channel_member(zed, user_2).await.unwrap();
+    let members = db.get_channel_members(zed).await.unwrap();
+    assert_eq!(member(&members, user_2), None);
+    assert!(db.admin_remove_channel_member(zed, user_2).await.is_err());
+}
+
+test_both_dbs!(
+    test_channel_project_links,
+    test_channel_project_links_postgres,
+    test_channel_project_links_sqlite
+);
+
+async fn test_channel_project_links(db: &Arc<Database>) {
+    let admin = new_test_user(db, "admin@example.com").await;
+    let member = new_test_user(db, "member@example.com").await;
+    let outsider = new_test_user(db, "outsider@example.com").await;
+
+    let zed = db.create_root_channel("zed", admin).await.unwrap();
+    let sub = db.create_sub_channel("sub", zed, admin).await.unwrap();
+    db.invite_channel_member(zed, member, admin, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(zed, member, true)
+        .await
+        .unwrap();
+
+    // Only admins can link repositories.
+    assert!(db
+        .add_channel_project_link(sub, "git@github.com:zed-industries/zed.git", "", member)
+        .await
+        .is_err());
+
+    // Linked paths can't lead outside of the repository.
+    for path in ["..", "crates/../..", "./crates", "..\\.."] {
+        assert!(db
+            .add_channel_project_link(sub, "git@github.com:zed-industries/zed.git", path, admin)
+            .await
+            .is_err());
+    }
+
+    let link_1 = db
+        .add_channel_project_link(sub, "git@github.com:zed-industries/zed.git", "", admin)
+        .await
+        .unwrap();
+    let link_2 = db
+        .add_channel_project_link(
+            sub,
+            "git@github.com:zed-industries/zed.git",
+            "crates/collab",
+            admin,
+        )
+        .await
+        .unwrap();
+    assert_eq!(link_1.host_user_id, Some(admin));
+
+    // Members of ancestor channels can see the links, but outsiders can't.
+    let links = db.get_channel_project_links(sub, member).await.unwrap();
+    assert_eq!(links, vec![link_1.clone(), link_2.clone()]);
+    assert!(db.get_channel_project_links(sub, outsider).await.is_err());
+    assert_eq!(
+        db.get_channel_project_links(zed, admin).await.unwrap(),
+        vec![]
+    );
+
+    // Linking the same repository again changes its host.
+    db.set_channel_member_role(zed, admin, member, ChannelRole::Admin)
+        .await
+        .unwrap();
+    let relinked = db
+        .add_channel_project_link(sub, "git@github.com:zed-industries/zed.git", "", member)
+        .await
+        .unwrap();
+    assert_eq!(relinked.id, link_1.id);
+    assert_eq!(relinked.host_user_id, Some(member));
+
+    assert!(db
+        .remove_channel_project_link(link_2.id, outsider)
+        .await
+        .is_err());
+    db.remove_channel_project_link(link_2.id, admin)
+        .await
+        .unwrap();
+    let links = db.get_channel_project_links(sub, admin).await.unwrap();
+    assert_eq!(links, vec![relinked]);
+    assert!(db
+        .remove_channel_project_link(link_2.id, admin)
+        .await
+        .is_err());
+}
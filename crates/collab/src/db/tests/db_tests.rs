@@ -280,6 +280,32 @@ async fn test_create_access_tokens(db: &Arc<Database>) {
     );
 }
 
+test_both_dbs!(
+    test_admin_user_operations,
+    test_admin_user_operations_postgres,
+    test_admin_user_operations_sqlite
+);
+
+async fn test_admin_user_operations(db: &Arc<Database>) {
+    let user_1 = new_test_user(db, "u1@example.com").await;
+    let user_2 = new_test_user(db, "u2@example.com").await;
+
+    db.set_user_is_admin(user_1, true).await.unwrap();
+    assert!(db.get_user_by_id(user_1).await.unwrap().unwrap().admin);
+    db.set_user_is_admin(user_1, false).await.unwrap();
+    assert!(!db.get_user_by_id(user_1).await.unwrap().unwrap().admin);
+    assert!(db.set_user_is_admin(UserId(-1), true).await.is_err());
+
+    let token_1 = db.create_access_token(user_1, None, "h1", 5).await.unwrap();
+    let token_2 = db.create_access_token(user_1, None, "h2", 5).await.unwrap();
+    let token_3 = db.create_access_token(user_2, None, "h3", 5).await.unwrap();
+    assert_eq!(db.delete_access_tokens_for_user(user_1).await.unwrap(), 2);
+    assert!(db.get_access_token(token_1).await.is_err());
+    assert!(db.get_access_token(token_2).await.is_err());
+    assert!(db.get_access_token(token_3).await.is_ok());
+    assert_eq!(db.delete_access_tokens_for_user(user_1).await.unwrap(), 0);
+}
+
 test_both_dbs!(
     test_add_contacts,
     test_add_contacts_postgres,
@@ -558,6 +584,39 @@ async fn test_project_count(db: &Arc<Database>) {
     assert_eq!(db.project_count_excluding_admins().await.unwrap(), 0);
 }
 
+test_both_dbs!(
+    test_get_active_rooms,
+    test_get_active_rooms_postgres,
+    test_get_active_rooms_sqlite
+);
+
+async fn test_get_active_rooms(db: &Arc<Database>) {
+    let server_id = db.create_server("test").await.unwrap();
+    let user_1 = new_test_user(db, "user1@example.com").await;
+    let channel = db.create_root_channel("zed", user_1).await.unwrap();
+
+    assert_eq!(db.get_active_rooms().await.unwrap(), vec![]);
+
+    let connection = new_test_connection(server_id);
+    let (joined_room, _, _) = db.join_channel(channel, user_1, connection).await.unwrap();
+    let room_id = RoomId::from_proto(joined_room.room.id);
+    drop(joined_room);
+    let project_id = db.share_project(room_id, connection, &[]).await.unwrap().0;
+
+    let rooms = db.get_active_rooms().await.unwrap();
+    assert_eq!(rooms.len(), 1);
+    assert_eq!(rooms[0].id, room_id);
+    assert_eq!(rooms[0].channel_id, Some(channel));
+    assert_eq!(rooms[0].participants.len(), 1);
+    assert_eq!(rooms[0].participants[0].user_id, user_1);
+    assert_eq!(rooms[0].participants[0].connection_id, Some(connection));
+    assert!(!rooms[0].participants[0].connection_lost);
+    assert_eq!(rooms[0].projects.len(), 1);
+    assert_eq!(rooms[0].projects[0].id, project_id);
+    assert_eq!(rooms[0].projects[0].host_user_id, user_1);
+    assert_eq!(rooms[0].projects[0].connection_count, 1);
+}
+
 #[test]
 fn test_fuzzy_like_string() {
     assert_eq!(Database::fuzzy_like_string("abcd"), "%a%b%c%d%");
@@ -611,3 +670,79 @@ async fn test_fuzzy_search_users(cx: &mut TestAppContext) {
             .collect::<Vec<_>>()
     }
 }
+
+test_both_dbs!(
+    test_local_accounts,
+    test_local_accounts_postgres,
+    test_local_accounts_sqlite
+);
+
+async fn test_local_accounts(db: &Arc<Database>) {
+    let user = db
+        .create_local_account("alice", Some("alice@example.com"), true, "hash-1")
+        .await
+        .unwrap();
+    assert_eq!(&user.github_login, "alice");
+    assert_eq!(user.github_user_id, None);
+    assert!(user.admin);
+    assert!(db
+        .create_local_account("alice", None, false, "hash-2")
+        .await
+        .is_err());
+
+    let (found_user, account) = db
+        .get_local_account_by_login("alice")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found_user.id, user.id);
+    assert_eq!(account.password_hash.as_deref(), Some("hash-1"));
+
+    db.set_local_account_password("alice", "hash-2")
+        .await
+        .unwrap();
+    let (_, account) = db
+        .get_local_account_by_login("alice")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.password_hash.as_deref(), Some("hash-2"));
+    assert!(db
+        .get_local_account_by_login("bob")
+        .await
+        .unwrap()
+        .is_none());
+
+    // A new OIDC subject can't sign in as an existing user, unless an administrator
+    // has linked them.
+    assert_eq!(
+        db.get_or_create_user_by_oidc_subject("subject-1", "alice", None)
+            .await
+            .unwrap(),
+        None
+    );
+    db.link_oidc_subject("alice", "subject-1").await.unwrap();
+    let oidc_user = db
+        .get_or_create_user_by_oidc_subject("subject-1", "renamed-alice", None)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(oidc_user.id, user.id);
+    assert!(db.link_oidc_subject("alice", "subject-2").await.is_err());
+
+    // New logins create new users.
+    let bob = db
+        .get_or_create_user_by_oidc_subject("subject-2", "bob", Some("bob@example.com"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(bob.id, user.id);
+    assert_eq!(bob.email_address.as_deref(), Some("bob@example.com"));
+    assert_eq!(
+        db.get_or_create_user_by_oidc_subject("subject-3", "bob", None)
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(db.link_oidc_subject("bob", "subject-1").await.is_err());
+}
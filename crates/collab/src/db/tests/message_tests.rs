@@ -1,6 +1,6 @@
 use super::new_test_user;
 use crate::{
-    db::{ChannelRole, Database, MessageId},
+    db::{ChannelMessageSearch, ChannelRole, Database, MessageId},
     test_both_dbs,
 };
 use channel::mentions_to_proto;
@@ -419,3 +419,382 @@ async fn test_channel_message_mentions(db: &Arc<Database>) {
         ]
     );
 }
+
+test_both_dbs!(
+    test_channel_message_edits_and_reactions,
+    test_channel_message_edits_and_reactions_postgres,
+    test_channel_message_edits_and_reactions_sqlite
+);
+
+async fn test_channel_message_edits_and_reactions(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let message_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "hi @user_b",
+            &mentions_to_proto(&[(3..10, user_b.to_proto())]),
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+        )
+        .await
+        .unwrap()
+        .message_id;
+
+    // Only the sender can edit a message.
+    db.update_channel_message(
+        channel,
+        message_id,
+        user_b,
+        "hijacked",
+        &[],
+        OffsetDateTime::now_utc(),
+    )
+    .await
+    .unwrap_err();
+
+    // Users who were already mentioned aren't notified again.
+    let updated = db
+        .update_channel_message(
+            channel,
+            message_id,
+            user_a,
+            "hello @user_b",
+            &mentions_to_proto(&[(6..13, user_b.to_proto())]),
+            OffsetDateTime::now_utc(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.message.body, "hello @user_b");
+    assert!(updated.message.edited_at.is_some());
+    assert!(updated.notifications.is_empty());
+    assert_eq!(updated.participant_connection_ids.len(), 2);
+
+    db.add_channel_message_reaction(channel, message_id, user_a, "👍")
+        .await
+        .unwrap();
+    db.add_channel_message_reaction(channel, message_id, user_b, "👍")
+        .await
+        .unwrap();
+    db.add_channel_message_reaction(channel, message_id, user_b, "🎉")
+        .await
+        .unwrap();
+    // Reacting twice with the same emoji has no effect.
+    db.add_channel_message_reaction(channel, message_id, user_b, "🎉")
+        .await
+        .unwrap();
+    let updated = db
+        .remove_channel_message_reaction(channel, message_id, user_a, "👍")
+        .await
+        .unwrap();
+    assert_eq!(
+        updated.message.reactions,
+        &[
+            rpc::proto::ChannelMessageReaction {
+                emoji: "👍".into(),
+                user_ids: vec![user_b.to_proto()],
+            },
+            rpc::proto::ChannelMessageReaction {
+                emoji: "🎉".into(),
+                user_ids: vec![user_b.to_proto()],
+            },
+        ]
+    );
+
+    let messages = db
+        .get_channel_messages(channel, user_b, 5, None)
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].body, "hello @user_b");
+    assert_eq!(
+        messages[0].mentions,
+        mentions_to_proto(&[(6..13, user_b.to_proto())])
+    );
+    assert_eq!(messages[0].reactions, updated.message.reactions);
+
+    // Users removed from the channel can no longer react, even while their connection
+    // is still in the chat.
+    db.remove_channel_member(channel, user_b, user_a)
+        .await
+        .unwrap();
+    db.add_channel_message_reaction(channel, message_id, user_b, "👀")
+        .await
+        .unwrap_err();
+    db.remove_channel_message_reaction(channel, message_id, user_b, "🎉")
+        .await
+        .unwrap_err();
+}
+
+test_both_dbs!(
+    test_observed_channel_threads,
+    test_observed_channel_threads_postgres,
+    test_observed_channel_threads_sqlite
+);
+
+async fn test_observed_channel_threads(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+
+    let channel = db.create_root_channel("channel", user_a).await.unwrap();
+    db.invite_channel_member(channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(channel, user_b, true)
+        .await
+        .unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 0 }, user_a)
+        .await
+        .unwrap();
+    db.join_channel_chat(channel, rpc::ConnectionId { owner_id, id: 1 }, user_b)
+        .await
+        .unwrap();
+
+    let parent_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "parent",
+            &[],
+            OffsetDateTime::now_utc(),
+            1,
+            None,
+        )
+        .await
+        .unwrap()
+        .message_id;
+    let reply_id = db
+        .create_channel_message(
+            channel,
+            user_a,
+            "reply",
+            &[],
+            OffsetDateTime::now_utc(),
+            2,
+            Some(parent_id),
+        )
+        .await
+        .unwrap()
+        .message_id;
+
+    // Sending a reply marks the thread as observed by its sender.
+    assert_eq!(
+        db.observed_channel_threads(channel, user_a).await.unwrap(),
+        &[rpc::proto::ObservedChannelThread {
+            thread_id: parent_id.to_proto(),
+            message_id: reply_id.to_proto(),
+        }]
+    );
+    assert!(db
+        .observed_channel_threads(channel, user_b)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Observing a thread doesn't affect the observed messages of the channel itself.
+    db.observe_channel_message(channel, user_b, reply_id, Some(parent_id))
+        .await
+        .unwrap();
+    assert_eq!(
+        db.observed_channel_threads(channel, user_b).await.unwrap(),
+        &[rpc::proto::ObservedChannelThread {
+            thread_id: parent_id.to_proto(),
+            message_id: reply_id.to_proto(),
+        }]
+    );
+    let observed_messages = db
+        .transaction(
+            |tx| async move { db.observed_channel_messages(&[channel], user_b, &*tx).await },
+        )
+        .await
+        .unwrap();
+    assert!(observed_messages.is_empty());
+
+    db.observe_channel_message(channel, user_b, parent_id, None)
+        .await
+        .unwrap();
+    let observed_messages = db
+        .transaction(
+            |tx| async move { db.observed_channel_messages(&[channel], user_b, &*tx).await },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        observed_messages,
+        &[rpc::proto::ChannelMessageId {
+            channel_id: channel.to_proto(),
+            message_id: parent_id.to_proto(),
+        }]
+    );
+}
+
+test_both_dbs!(
+    test_channel_message_search,
+    test_channel_message_search_postgres,
+    test_channel_message_search_sqlite
+);
+
+async fn test_channel_message_search(db: &Arc<Database>) {
+    let user_a = new_test_user(db, "user_a@example.com").await;
+    let user_b = new_test_user(db, "user_b@example.com").await;
+
+    let shared_channel = db.create_root_channel("shared", user_a).await.unwrap();
+    db.invite_channel_member(shared_channel, user_b, user_a, ChannelRole::Member)
+        .await
+        .unwrap();
+    db.respond_to_channel_invite(shared_channel, user_b, true)
+        .await
+        .unwrap();
+    let private_channel = db.create_root_channel("private", user_a).await.unwrap();
+
+    let owner_id = db.create_server("test").await.unwrap().0 as u32;
+    for (ix, (channel, user)) in [
+        (shared_channel, user_a),
+        (shared_channel, user_b),
+        (private_channel, user_a),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        db.join_channel_chat(
+            channel,
+            rpc::ConnectionId {
+                owner_id,
+                id: ix as u32,
+            },
+            user,
+        )
+        .await
+        .unwrap();
+    }
+
+    let start = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+    let mut message_ids = Vec::new();
+    for (ix, (channel, sender, body)) in [
+        (shared_channel, user_a, "the quick brown fox"),
+        (shared_channel, user_b, "lazy dogs sleep all day"),
+        (shared_channel, user_b, "quick reply"),
+        (private_channel, user_a, "quick secret"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let message_id = db
+            .create_channel_message(
+                channel,
+                sender,
+                body,
+                &[],
+                start + time::Duration::hours(ix as i64),
+                ix as u128,
+                None,
+            )
+            .await
+            .unwrap()
+            .message_id;
+        message_ids.push(message_id);
+    }
+
+    let search = |user_id, search| {
+        let db = db.clone();
+        async move {
+            db.search_channel_messages(user_id, &search, 10)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|result| MessageId::from_proto(result.message_id))
+                .collect::<Vec<_>>()
+        }
+    };
+
+    // Users only find messages in the channels they can access.
+    let query = |query: &str| ChannelMessageSearch {
+        query: query.into(),
+        ..Default::default()
+    };
+    assert_eq!(
+        search(user_a, query("quick")).await,
+        &[message_ids[3], message_ids[2], message_ids[0]]
+    );
+    assert_eq!(
+        search(user_b, query("quick")).await,
+        &[message_ids[2], message_ids[0]]
+    );
+    assert!(db
+        .search_channel_messages(
+            user_b,
+            &ChannelMessageSearch {
+                channel_id: Some(private_channel),
+                ..query("quick")
+            },
+            10
+        )
+        .await
+        .is_err());
+
+    // Words are matched regardless of their inflection, and every word must match.
+    assert_eq!(search(user_b, query("dog")).await, &[message_ids[1]]);
+    assert_eq!(search(user_b, query("quick fox")).await, &[message_ids[0]]);
+    assert!(search(user_b, query("quick cat")).await.is_empty());
+
+    // Results can be filtered by channel, sender and date.
+    assert_eq!(
+        search(
+            user_a,
+            ChannelMessageSearch {
+                channel_id: Some(shared_channel),
+                ..query("quick")
+            }
+        )
+        .await,
+        &[message_ids[2], message_ids[0]]
+    );
+    assert_eq!(
+        search(
+            user_a,
+            ChannelMessageSearch {
+                sender_id: Some(user_b),
+                ..query("quick")
+            }
+        )
+        .await,
+        &[message_ids[2]]
+    );
+    let timestamp = |hours| {
+        let timestamp = start + time::Duration::hours(hours);
+        time::PrimitiveDateTime::new(timestamp.date(), timestamp.time())
+    };
+    assert_eq!(
+        search(
+            user_a,
+            ChannelMessageSearch {
+                sent_after: Some(timestamp(1)),
+                sent_before: Some(timestamp(3)),
+                ..query("quick")
+            }
+        )
+        .await,
+        &[message_ids[2]]
+    );
+}
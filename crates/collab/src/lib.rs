@@ -3,6 +3,7 @@ pub mod auth;
 pub mod db;
 pub mod env;
 pub mod executor;
+pub mod local_auth;
 pub mod rpc;
 
 #[cfg(test)]
@@ -109,12 +110,29 @@ pub struct Config {
     pub blob_store_secret_key: Option<String>,
     pub blob_store_bucket: Option<String>,
     pub zed_environment: Arc<str>,
+    /// The URL at which clients reach a self-hosted server. Clients looking up the
+    /// RPC endpoint are redirected to it.
+    pub public_url: Option<String>,
+    /// Whether users sign in with accounts managed by this server, rather than
+    /// through zed.dev.
+    pub local_auth: Option<bool>,
+    pub oidc_issuer_url: Option<String>,
+    pub oidc_client_id: Option<String>,
+    pub oidc_client_secret: Option<String>,
 }
 
 impl Config {
     pub fn is_development(&self) -> bool {
         self.zed_environment == "development".into()
     }
+
+    pub fn is_local_auth_enabled(&self) -> bool {
+        self.local_auth.unwrap_or(false)
+    }
+
+    pub fn is_sqlite(&self) -> bool {
+        self.database_url.starts_with("sqlite:")
+    }
 }
 
 #[derive(Default, Deserialize)]
@@ -155,7 +173,11 @@ impl AppState {
         let this = Self {
             db: Arc::new(db),
             live_kit_client,
-            blob_store_client: build_blob_store_client(&config).await.log_err(),
+            blob_store_client: if config.blob_store_url.is_some() {
+                build_blob_store_client(&config).await.log_err()
+            } else {
+                None
+            },
             config,
         };
         Ok(Arc::new(this))
@@ -0,0 +1,542 @@
+//! Signing in to a self-hosted server with the accounts that it manages, either with a
+//! password or through an OpenID Connect provider.
+//!
+//! These routes stand in for the sign-in pages of zed.dev: the client opens
+//! `/native_app_signin` in a browser, which is eventually redirected to the client's
+//! local HTTP server with the user's id and an access token encrypted with the
+//! client's public key.
+
+use crate::{auth, db::UserId, AppState, Config, Error, Result};
+use anyhow::{anyhow, Context};
+use axum::{
+    body::Body,
+    extract::{Form, Query},
+    headers::Cookie,
+    http::{self, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Redirect, Response},
+    routing::get,
+    Extension, Router, TypedHeader,
+};
+use dashmap::DashMap;
+use rand::Rng as _;
+use serde::Deserialize;
+use sha2::{Digest as _, Sha256};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The cookie that binds an OpenID Connect sign-in to the browser that started it.
+const OIDC_STATE_COOKIE: &str = "zed_oidc_state";
+/// How long a user has to complete signing in with the OpenID Connect provider.
+const OIDC_SIGN_IN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// How many times signing in to an account with a wrong password is allowed within
+/// `FAILED_SIGN_IN_WINDOW`, before further attempts are refused until it ends.
+const MAX_FAILED_SIGN_INS: u32 = 5;
+const FAILED_SIGN_IN_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+pub fn routes(state: Arc<AppState>) -> Router<Body> {
+    Router::new()
+        .route(
+            "/native_app_signin",
+            get(show_sign_in_page).post(sign_in_with_password),
+        )
+        .route("/native_app_signin_succeeded", get(show_signed_in_page))
+        .route("/oidc/callback", get(handle_oidc_callback))
+        .layer(Extension(state))
+        .layer(Extension(Arc::new(SignIns::default())))
+}
+
+/// The sign-ins in progress, which are only tracked in memory, so that restarting the
+/// server cancels them.
+#[derive(Default)]
+struct SignIns {
+    /// OpenID Connect sign-ins that were started, by the random `state` they were
+    /// started with.
+    oidc: DashMap<String, PendingOidcSignIn>,
+    /// Recent password sign-ins that failed, by login.
+    failed: DashMap<String, FailedSignIns>,
+}
+
+struct PendingOidcSignIn {
+    native_app: NativeAppParams,
+    code_verifier: String,
+    started_at: Instant,
+}
+
+struct FailedSignIns {
+    count: u32,
+    window_started_at: Instant,
+}
+
+impl SignIns {
+    fn start_oidc_sign_in(&self, native_app: NativeAppParams) -> (String, String) {
+        self.oidc
+            .retain(|_, sign_in| sign_in.started_at.elapsed() < OIDC_SIGN_IN_TIMEOUT);
+        let state = random_token();
+        let code_verifier = random_token();
+        self.oidc.insert(
+            state.clone(),
+            PendingOidcSignIn {
+                native_app,
+                code_verifier: code_verifier.clone(),
+                started_at: Instant::now(),
+            },
+        );
+        (state, code_verifier)
+    }
+
+    /// Completes the OpenID Connect sign-in with the given `state`, which must have been
+    /// started by the browser that sent the given cookie. Each sign-in can only be
+    /// completed once.
+    fn finish_oidc_sign_in(&self, state: &str, cookie: Option<&str>) -> Option<PendingOidcSignIn> {
+        if !cookie.map_or(false, |cookie| constant_time_eq(cookie, state)) {
+            return None;
+        }
+        let (_, sign_in) = self.oidc.remove(state)?;
+        (sign_in.started_at.elapsed() < OIDC_SIGN_IN_TIMEOUT).then_some(sign_in)
+    }
+
+    fn is_rate_limited(&self, login: &str) -> bool {
+        self.failed.get(login).map_or(false, |failed| {
+            failed.window_started_at.elapsed() < FAILED_SIGN_IN_WINDOW
+                && failed.count >= MAX_FAILED_SIGN_INS
+        })
+    }
+
+    fn record_failure(&self, login: &str) {
+        self.failed
+            .retain(|_, failed| failed.window_started_at.elapsed() < FAILED_SIGN_IN_WINDOW);
+        let mut failed = self
+            .failed
+            .entry(login.to_string())
+            .or_insert_with(|| FailedSignIns {
+                count: 0,
+                window_started_at: Instant::now(),
+            });
+        failed.count += 1;
+    }
+}
+
+/// Clients look up the RPC endpoint by requesting `/rpc` without upgrading the
+/// connection, and expect to be redirected to it, which zed.dev does for the hosted
+/// service. A self-hosted server with a public URL redirects them to itself.
+pub async fn redirect_rpc_lookup<B>(req: Request<B>, next: Next<B>) -> Response {
+    let is_upgrade = req.headers().contains_key(http::header::UPGRADE);
+    let public_url = req
+        .extensions()
+        .get::<Arc<AppState>>()
+        .and_then(|state| state.config.public_url.clone());
+    match public_url {
+        Some(public_url) if !is_upgrade => {
+            Redirect::temporary(&format!("{}/rpc", public_url.trim_end_matches('/')))
+                .into_response()
+        }
+        _ => next.run(req).await,
+    }
+}
+
+/// The parameters with which the client opens the sign-in page, which identify where
+/// to send the access token, and how to encrypt it.
+#[derive(Clone, Debug, Deserialize)]
+struct NativeAppParams {
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct PasswordSignInParams {
+    login: String,
+    password: String,
+    native_app_port: u16,
+    native_app_public_key: String,
+}
+
+#[derive(Deserialize)]
+struct OidcCallbackParams {
+    code: String,
+    state: String,
+}
+
+async fn show_sign_in_page(
+    Query(params): Query<NativeAppParams>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(sign_ins): Extension<Arc<SignIns>>,
+) -> Result<Response> {
+    if let Some(provider) = OidcProvider::from_config(&state.config)? {
+        let (oidc_state, code_verifier) = sign_ins.start_oidc_sign_in(params);
+        let url = provider
+            .authorization_url(&oidc_state, &code_verifier)
+            .await?;
+        let secure = if provider.redirect_url.starts_with("https:") {
+            "; Secure"
+        } else {
+            ""
+        };
+        let cookie = format!(
+            "{}={oidc_state}; Path=/oidc; Max-Age={}; HttpOnly; SameSite=Lax{secure}",
+            OIDC_STATE_COOKIE,
+            OIDC_SIGN_IN_TIMEOUT.as_secs()
+        );
+        let mut response = Redirect::to(&url).into_response();
+        response.headers_mut().insert(
+            http::header::SET_COOKIE,
+            HeaderValue::from_str(&cookie).context("invalid cookie")?,
+        );
+        return Ok(response);
+    }
+    Ok(sign_in_page(&params, None).into_response())
+}
+
+async fn sign_in_with_password(
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(sign_ins): Extension<Arc<SignIns>>,
+    Form(params): Form<PasswordSignInParams>,
+) -> Result<Response> {
+    let native_app = NativeAppParams {
+        native_app_port: params.native_app_port,
+        native_app_public_key: params.native_app_public_key,
+    };
+    if sign_ins.is_rate_limited(&params.login) {
+        return Ok((
+            StatusCode::TOO_MANY_REQUESTS,
+            sign_in_page(
+                &native_app,
+                Some("Too many failed sign-in attempts. Please try again later."),
+            ),
+        )
+            .into_response());
+    }
+
+    let (user_id, password_hash) = match state.db.get_local_account_by_login(&params.login).await? {
+        Some((user, account)) => (Some(user.id), account.password_hash),
+        None => (None, None),
+    };
+    let is_valid = auth::verify_password(&params.password, password_hash.as_deref());
+    let Some(user_id) = user_id.filter(|_| is_valid) else {
+        sign_ins.record_failure(&params.login);
+        return Ok((
+            StatusCode::UNAUTHORIZED,
+            sign_in_page(&native_app, Some("Invalid login or password.")),
+        )
+            .into_response());
+    };
+    sign_ins.failed.remove(&params.login);
+    redirect_to_native_app(&state, user_id, native_app).await
+}
+
+async fn handle_oidc_callback(
+    Query(params): Query<OidcCallbackParams>,
+    cookie: Option<TypedHeader<Cookie>>,
+    Extension(state): Extension<Arc<AppState>>,
+    Extension(sign_ins): Extension<Arc<SignIns>>,
+) -> Result<Response> {
+    let provider = OidcProvider::from_config(&state.config)?.ok_or_else(|| {
+        Error::Http(
+            StatusCode::NOT_FOUND,
+            "OpenID Connect isn't configured".to_string(),
+        )
+    })?;
+    let cookie = cookie
+        .as_ref()
+        .and_then(|TypedHeader(cookie)| cookie.get(OIDC_STATE_COOKIE));
+    let sign_in = sign_ins
+        .finish_oidc_sign_in(&params.state, cookie)
+        .ok_or_else(|| {
+            Error::Http(
+                StatusCode::BAD_REQUEST,
+                "This sign-in has expired or was started elsewhere. Please sign in again."
+                    .to_string(),
+            )
+        })?;
+    let user_info = provider
+        .user_info(&params.code, &sign_in.code_verifier)
+        .await?;
+    let login = user_info
+        .preferred_username
+        .clone()
+        .or_else(|| {
+            let email = user_info.email.as_ref()?;
+            Some(email.split('@').next()?.to_string())
+        })
+        .unwrap_or_else(|| user_info.sub.clone());
+    let user = state
+        .db
+        .get_or_create_user_by_oidc_subject(&user_info.sub, &login, user_info.email.as_deref())
+        .await?
+        .ok_or_else(|| {
+            Error::Http(
+                StatusCode::CONFLICT,
+                format!(
+                    "The login {login} is taken. Please ask an administrator to link your account."
+                ),
+            )
+        })?;
+    redirect_to_native_app(&state, user.id, sign_in.native_app).await
+}
+
+async fn show_signed_in_page() -> Html<&'static str> {
+    Html(concat!(
+        "<!DOCTYPE html><html><head><title>Signed in</title></head>",
+        "<body><p>You're signed in. You can close this page and return to Zed.</p></body></html>"
+    ))
+}
+
+async fn redirect_to_native_app(
+    state: &AppState,
+    user_id: UserId,
+    native_app: NativeAppParams,
+) -> Result<Response> {
+    let access_token = auth::create_access_token(&state.db, user_id, None).await?;
+    let encrypted_access_token =
+        auth::encrypt_access_token(&access_token, native_app.native_app_public_key)?;
+    let url = reqwest::Url::parse_with_params(
+        &format!("http://127.0.0.1:{}/", native_app.native_app_port),
+        &[
+            ("user_id", user_id.to_string()),
+            ("access_token", encrypted_access_token),
+        ],
+    )
+    .context("failed to build native app url")?;
+    Ok(Redirect::to(url.as_str()).into_response())
+}
+
+fn sign_in_page(native_app: &NativeAppParams, error: Option<&str>) -> Html<String> {
+    let error = error
+        .map(|error| format!("<p class=\"error\">{}</p>", escape_html(error)))
+        .unwrap_or_default();
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html>
+<head><title>Sign in to Zed</title></head>
+<body>
+<h1>Sign in to Zed</h1>
+{error}
+<form method="post" action="/native_app_signin">
+<input type="hidden" name="native_app_port" value="{port}">
+<input type="hidden" name="native_app_public_key" value="{public_key}">
+<p><label>Login <input name="login" autofocus required></label></p>
+<p><label>Password <input name="password" type="password" required></label></p>
+<p><button type="submit">Sign in</button></p>
+</form>
+</body>
+</html>"#,
+        port = native_app.native_app_port,
+        public_key = escape_html(&native_app.native_app_public_key),
+    ))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(character),
+        }
+    }
+    escaped
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill(&mut bytes);
+    base64::encode_config(bytes, base64::URL_SAFE_NO_PAD)
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// An OpenID Connect provider, with which users sign in using the authorization code
+/// flow.
+struct OidcProvider {
+    issuer_url: String,
+    client_id: String,
+    client_secret: String,
+    redirect_url: String,
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct OidcDiscovery {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    userinfo_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct OidcTokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    preferred_username: Option<String>,
+    email: Option<String>,
+}
+
+impl OidcProvider {
+    fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(issuer_url) = config.oidc_issuer_url.clone() else {
+            return Ok(None);
+        };
+        let client_id = config
+            .oidc_client_id
+            .clone()
+            .ok_or_else(|| anyhow!("missing oidc_client_id"))?;
+        let client_secret = config
+            .oidc_client_secret
+            .clone()
+            .ok_or_else(|| anyhow!("missing oidc_client_secret"))?;
+        let public_url = config
+            .public_url
+            .as_ref()
+            .ok_or_else(|| anyhow!("missing public_url, which OpenID Connect requires"))?;
+        Ok(Some(Self {
+            issuer_url,
+            client_id,
+            client_secret,
+            redirect_url: format!("{}/oidc/callback", public_url.trim_end_matches('/')),
+            http: reqwest::Client::new(),
+        }))
+    }
+
+    async fn discover(&self) -> Result<OidcDiscovery> {
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.issuer_url.trim_end_matches('/')
+        );
+        let discovery = self
+            .http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("failed to fetch openid configuration")?
+            .json()
+            .await
+            .context("invalid openid configuration")?;
+        Ok(discovery)
+    }
+
+    /// Returns the URL at which users sign in with the provider. The sign-in is
+    /// protected with PKCE, so the provider only issues a token for the authorization
+    /// code along with the given `code_verifier`.
+    async fn authorization_url(&self, state: &str, code_verifier: &str) -> Result<String> {
+        let discovery = self.discover().await?;
+        let code_challenge = base64::encode_config(
+            Sha256::digest(code_verifier.as_bytes()),
+            base64::URL_SAFE_NO_PAD,
+        );
+        let url = reqwest::Url::parse_with_params(
+            &discovery.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", &self.client_id),
+                ("redirect_uri", &self.redirect_url),
+                ("scope", "openid profile email"),
+                ("state", state),
+                ("code_challenge", &code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("invalid authorization endpoint")?;
+        Ok(url.into())
+    }
+
+    async fn user_info(&self, code: &str, code_verifier: &str) -> Result<OidcUserInfo> {
+        let discovery = self.discover().await?;
+        let token: OidcTokenResponse = self
+            .http
+            .post(&discovery.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.redirect_url),
+                ("client_id", &self.client_id),
+                ("client_secret", &self.client_secret),
+                ("code_verifier", code_verifier),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("failed to exchange authorization code")?
+            .json()
+            .await
+            .context("invalid token response")?;
+        let user_info = self
+            .http
+            .get(&discovery.userinfo_endpoint)
+            .bearer_auth(token.access_token)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .context("failed to fetch user info")?
+            .json()
+            .await
+            .context("invalid user info")?;
+        Ok(user_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn native_app() -> NativeAppParams {
+        NativeAppParams {
+            native_app_port: 1234,
+            native_app_public_key: "public-key".into(),
+        }
+    }
+
+    #[test]
+    fn test_oidc_sign_in_is_bound_to_browser() {
+        let sign_ins = SignIns::default();
+        let (state, code_verifier) = sign_ins.start_oidc_sign_in(native_app());
+        assert_ne!(state, code_verifier);
+
+        // A callback without the cookie set when the sign-in started is rejected,
+        // without cancelling the sign-in.
+        assert!(sign_ins.finish_oidc_sign_in(&state, None).is_none());
+        assert!(sign_ins
+            .finish_oidc_sign_in(&state, Some("another-state"))
+            .is_none());
+
+        let sign_in = sign_ins.finish_oidc_sign_in(&state, Some(&state)).unwrap();
+        assert_eq!(sign_in.code_verifier, code_verifier);
+        assert_eq!(sign_in.native_app.native_app_port, 1234);
+
+        // Each sign-in can only be completed once.
+        assert!(sign_ins.finish_oidc_sign_in(&state, Some(&state)).is_none());
+    }
+
+    #[test]
+    fn test_failed_sign_ins_are_rate_limited() {
+        let sign_ins = SignIns::default();
+        for _ in 0..MAX_FAILED_SIGN_INS {
+            assert!(!sign_ins.is_rate_limited("alice"));
+            sign_ins.record_failure("alice");
+        }
+        assert!(sign_ins.is_rate_limited("alice"));
+        assert!(!sign_ins.is_rate_limited("bob"));
+    }
+
+    #[test]
+    fn test_verify_password() {
+        let password_hash = auth::hash_password("secret").unwrap();
+        assert!(auth::verify_password("secret", Some(&password_hash)));
+        assert!(!auth::verify_password("wrong", Some(&password_hash)));
+        assert!(!auth::verify_password("dummy password", None));
+    }
+}
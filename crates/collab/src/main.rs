@@ -1,8 +1,8 @@
-use anyhow::anyhow;
+use anyhow::{anyhow, Context};
 use axum::{routing::get, Extension, Router};
 use collab::{
-    api::fetch_extensions_from_blob_store_periodically, db, env, executor::Executor, AppState,
-    Config, MigrateConfig, Result,
+    api::fetch_extensions_from_blob_store_periodically, auth, db, env, executor::Executor,
+    AppState, Config, MigrateConfig, Result,
 };
 use db::Database;
 use std::{
@@ -35,6 +35,9 @@ async fn main() -> Result<()> {
         Some("migrate") => {
             run_migrations().await?;
         }
+        Some("account") => {
+            manage_accounts(args().skip(2).collect()).await?;
+        }
         Some("serve") => {
             let config = envy::from_env::<Config>().expect("error loading config");
             init_tracing(&config);
@@ -55,7 +58,7 @@ async fn main() -> Result<()> {
 
             fetch_extensions_from_blob_store_periodically(state.clone(), Executor::Production);
 
-            let app = collab::api::routes(rpc_server.clone(), state.clone())
+            let mut app = collab::api::routes(rpc_server.clone(), state.clone())
                 .merge(collab::rpc::routes(rpc_server.clone()))
                 .merge(
                     Router::new()
@@ -63,6 +66,9 @@ async fn main() -> Result<()> {
                         .route("/healthz", get(handle_liveness_probe))
                         .layer(Extension(state.clone())),
                 );
+            if state.config.is_local_auth_enabled() {
+                app = app.merge(collab::local_auth::routes(state.clone()));
+            }
 
             axum::Server::from_tcp(listener)?
                 .serve(app.into_make_service_with_connect_info::<SocketAddr>())
@@ -81,7 +87,9 @@ async fn main() -> Result<()> {
                 .await?;
         }
         _ => {
-            Err(anyhow!("usage: collab <version | migrate | serve>"))?;
+            Err(anyhow!(
+                "usage: collab <version | migrate | serve | account>"
+            ))?;
         }
     }
     Ok(())
@@ -92,10 +100,13 @@ async fn run_migrations() -> Result<()> {
     let db_options = db::ConnectOptions::new(config.database_url.clone());
     let db = Database::new(db_options, Executor::Production).await?;
 
-    let migrations_path = config
-        .migrations_path
-        .as_deref()
-        .unwrap_or_else(|| Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations")));
+    let migrations_path = config.migrations_path.as_deref().unwrap_or_else(|| {
+        if config.database_url.starts_with("sqlite:") {
+            Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations.sqlite"))
+        } else {
+            Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/migrations"))
+        }
+    });
 
     let migrations = db.migrate(&migrations_path, false).await?;
     for (migration, duration) in migrations {
@@ -110,6 +121,59 @@ async fn run_migrations() -> Result<()> {
     return Ok(());
 }
 
+/// Manages the accounts of users who sign in to a self-hosted server with a password.
+async fn manage_accounts(args: Vec<String>) -> Result<()> {
+    let config = envy::from_env::<MigrateConfig>().expect("error loading config");
+    let db_options = db::ConnectOptions::new(config.database_url.clone());
+    let db = Database::new(db_options, Executor::Production).await?;
+
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+    match args.as_slice() {
+        ["add", login, flags @ ..] => {
+            let admin = flags.contains(&"--admin");
+            let password_hash = auth::hash_password(&read_password()?)?;
+            let user = db
+                .create_local_account(login, None, admin, &password_hash)
+                .await?;
+            println!("created user {} with id {}", user.github_login, user.id);
+        }
+        ["password", login] => {
+            let password_hash = auth::hash_password(&read_password()?)?;
+            db.set_local_account_password(login, &password_hash).await?;
+            println!("changed the password of {login}");
+        }
+        ["link-oidc", login, subject] => {
+            db.link_oidc_subject(login, subject).await?;
+            println!("linked {login} to oidc subject {subject}");
+        }
+        _ => {
+            Err(anyhow!(
+                "usage: collab account <add <login> [--admin] | password <login> | link-oidc <login> <subject>>"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the password of an account from the `COLLAB_ACCOUNT_PASSWORD` environment
+/// variable, or from the standard input.
+fn read_password() -> Result<String> {
+    if let Ok(password) = std::env::var("COLLAB_ACCOUNT_PASSWORD") {
+        return Ok(password);
+    }
+
+    eprint!("password: ");
+    let mut password = String::new();
+    std::io::stdin()
+        .read_line(&mut password)
+        .context("failed to read password")?;
+    let password = password.trim_end_matches(&['\r', '\n'][..]);
+    if password.is_empty() {
+        Err(anyhow!("the password can't be empty"))?;
+    }
+    Ok(password.to_string())
+}
+
 async fn handle_root() -> String {
     format!("collab v{} ({})", VERSION, REVISION.unwrap_or("unknown"))
 }
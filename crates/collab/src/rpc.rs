@@ -3,9 +3,10 @@ mod connection_pool;
 use crate::{
     auth::{self, Impersonator},
     db::{
-        self, BufferId, ChannelId, ChannelRole, ChannelsForUser, CreatedChannelMessage, Database,
-        InviteMemberResult, MembershipUpdated, MessageId, NotificationId, ProjectId,
-        RemoveChannelMemberResult, RespondToChannelInvite, RoomId, ServerId, User, UserId,
+        self, BufferId, ChannelId, ChannelMessageSearch, ChannelProjectLinkId, ChannelRole,
+        ChannelsForUser, CreatedChannelMessage, Database, InviteMemberResult, MembershipUpdated,
+        MessageId, NotificationId, ProjectId, RemoveChannelMemberResult, RespondToChannelInvite,
+        RoomId, ServerId, UpdatedChannelMessage, User, UserId,
     },
     executor::Executor,
     AppState, Error, Result,
@@ -36,7 +37,10 @@ use futures::{
     FutureExt, SinkExt, StreamExt, TryStreamExt,
 };
 use lazy_static::lazy_static;
-use prometheus::{register_int_gauge, IntGauge};
+use prometheus::{
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge,
+    HistogramVec, IntCounterVec, IntGauge,
+};
 use rpc::{
     proto::{
         self, Ack, AnyTypedEnvelope, EntityMessage, EnvelopedMessage, LiveKitConnectionInfo,
@@ -71,6 +75,8 @@ pub const CLEANUP_TIMEOUT: Duration = Duration::from_secs(10);
 
 const MESSAGE_COUNT_PER_PAGE: usize = 100;
 const MAX_MESSAGE_LEN: usize = 1024;
+const MAX_REACTION_LEN: usize = 32;
+const MESSAGE_SEARCH_RESULT_LIMIT: usize = 50;
 const NOTIFICATION_COUNT_PER_PAGE: usize = 50;
 
 lazy_static! {
@@ -81,6 +87,19 @@ lazy_static! {
         "number of open projects with one or more guests"
     )
     .unwrap();
+    static ref METRIC_MESSAGES_HANDLED: IntCounterVec = register_int_counter_vec!(
+        "collab_messages_handled",
+        "number of messages handled, by message type and outcome",
+        &["message_type", "outcome"]
+    )
+    .unwrap();
+    static ref METRIC_MESSAGE_HANDLING_TIME: HistogramVec = register_histogram_vec!(
+        "collab_message_handling_time",
+        "time spent handling messages in seconds, by message type",
+        &["message_type"],
+        exponential_buckets(0.0005, 2.0, 16).unwrap()
+    )
+    .unwrap();
 }
 
 type MessageHandler =
@@ -227,6 +246,13 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
+            // Guests without write access can still take part in reviews.
+            .add_request_handler(forward_read_only_project_request::<proto::GetReviewThreads>)
+            .add_request_handler(forward_read_only_project_request::<proto::CreateReviewThread>)
+            .add_request_handler(forward_read_only_project_request::<proto::ReplyToReviewThread>)
+            .add_request_handler(
+                forward_read_only_project_request::<proto::SetReviewThreadResolved>,
+            )
             .add_request_handler(forward_mutating_project_request::<proto::GetCompletions>)
             .add_request_handler(
                 forward_mutating_project_request::<proto::ApplyCompletionAdditionalEdits>,
@@ -247,6 +273,8 @@ impl Server {
             .add_request_handler(forward_mutating_project_request::<proto::ExpandProjectEntry>)
             .add_request_handler(forward_mutating_project_request::<proto::OnTypeFormatting>)
             .add_request_handler(forward_mutating_project_request::<proto::SaveBuffer>)
+            // Only participants whose role lets them edit projects can type into terminals.
+            .add_request_handler(forward_mutating_project_request::<proto::TerminalInput>)
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
@@ -254,6 +282,9 @@ impl Server {
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateDiffBase>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateReviewThread>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UpdateSharedTerminal>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::UnshareTerminal>)
             .add_request_handler(get_users)
             .add_request_handler(fuzzy_search_users)
             .add_request_handler(request_contact)
@@ -266,10 +297,16 @@ impl Server {
             .add_request_handler(set_channel_member_role)
             .add_request_handler(set_channel_visibility)
             .add_request_handler(rename_channel)
+            .add_request_handler(get_channel_project_links)
+            .add_request_handler(add_channel_project_link)
+            .add_request_handler(remove_channel_project_link)
             .add_request_handler(join_channel_buffer)
             .add_request_handler(leave_channel_buffer)
             .add_message_handler(update_channel_buffer)
             .add_request_handler(rejoin_channel_buffers)
+            .add_request_handler(get_channel_notes_history)
+            .add_request_handler(get_channel_notes_version)
+            .add_request_handler(get_channel_notes_authorship)
             .add_request_handler(get_channel_members)
             .add_request_handler(respond_to_channel_invite)
             .add_request_handler(join_channel)
@@ -277,8 +314,12 @@ impl Server {
             .add_message_handler(leave_channel_chat)
             .add_request_handler(send_channel_message)
             .add_request_handler(remove_channel_message)
+            .add_request_handler(update_channel_message)
+            .add_request_handler(add_channel_message_reaction)
+            .add_request_handler(remove_channel_message_reaction)
             .add_request_handler(get_channel_messages)
             .add_request_handler(get_channel_messages_by_id)
+            .add_request_handler(search_channel_messages)
             .add_request_handler(get_notifications)
             .add_request_handler(mark_notification_as_read)
             .add_request_handler(move_channel)
@@ -482,17 +523,29 @@ impl Server {
                         "message received"
                     );
                 });
+                let payload_type = envelope.payload_type_name();
                 let start_time = Instant::now();
                 let future = (handler)(*envelope, session);
                 async move {
                     let result = future.await;
-                    let duration_ms = start_time.elapsed().as_micros() as f64 / 1000.0;
-                    match result {
+                    let duration = start_time.elapsed();
+                    let duration_ms = duration.as_micros() as f64 / 1000.0;
+                    METRIC_MESSAGE_HANDLING_TIME
+                        .with_label_values(&[payload_type])
+                        .observe(duration.as_secs_f64());
+                    let outcome = match result {
                         Err(error) => {
-                            tracing::error!(%error, ?duration_ms, "error handling message")
+                            tracing::error!(%error, ?duration_ms, "error handling message");
+                            "error"
                         }
-                        Ok(()) => tracing::info!(?duration_ms, "finished handling message"),
-                    }
+                        Ok(()) => {
+                            tracing::info!(?duration_ms, "finished handling message");
+                            "ok"
+                        }
+                    };
+                    METRIC_MESSAGES_HANDLED
+                        .with_label_values(&[payload_type, outcome])
+                        .inc();
                 }
                 .instrument(span)
                 .boxed()
@@ -744,6 +797,56 @@ impl Server {
         Ok(())
     }
 
+    /// Closes the given connection if it's handled by this server, returning whether
+    /// it was. The client is free to reconnect unless its access tokens were revoked.
+    pub fn disconnect_connection(&self, connection_id: ConnectionId) -> bool {
+        if self
+            .connection_pool
+            .lock()
+            .contains_connection(connection_id)
+        {
+            self.peer.disconnect(connection_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Closes every connection of the given user that's handled by this server,
+    /// returning how many were closed.
+    pub fn disconnect_user(&self, user_id: UserId) -> usize {
+        let connection_ids = self
+            .connection_pool
+            .lock()
+            .user_connection_ids(user_id)
+            .collect::<Vec<_>>();
+        for connection_id in &connection_ids {
+            self.peer.disconnect(*connection_id);
+        }
+        connection_ids.len()
+    }
+
+    pub fn user_connection_count(&self, user_id: UserId) -> usize {
+        self.connection_pool
+            .lock()
+            .user_connection_ids(user_id)
+            .count()
+    }
+
+    pub fn channel_membership_updated(
+        &self,
+        user_id: UserId,
+        membership_update: MembershipUpdated,
+    ) {
+        let connection_pool = self.connection_pool.lock();
+        notify_membership_updated(&connection_pool, membership_update, user_id, &self.peer);
+    }
+
+    pub fn channel_member_removed(&self, member_id: UserId, result: RemoveChannelMemberResult) {
+        let connection_pool = self.connection_pool.lock();
+        notify_channel_member_removed(&connection_pool, result, member_id, &self.peer);
+    }
+
     pub async fn snapshot<'a>(self: &'a Arc<Self>) -> ServerSnapshot<'a> {
         ServerSnapshot {
             connection_pool: ConnectionPoolGuard {
@@ -856,6 +959,7 @@ pub fn routes(server: Arc<Server>) -> Router<Body> {
         .layer(
             ServiceBuilder::new()
                 .layer(Extension(server.app_state.clone()))
+                .layer(middleware::from_fn(crate::local_auth::redirect_rpc_lookup))
                 .layer(middleware::from_fn(auth::validate_header)),
         )
         .route("/metrics", get(handle_metrics))
@@ -2448,38 +2552,41 @@ async fn remove_channel_member(
     let channel_id = ChannelId::from_proto(request.channel_id);
     let member_id = UserId::from_proto(request.user_id);
 
-    let RemoveChannelMemberResult {
-        membership_update,
-        notification_id,
-    } = db
+    let result = db
         .remove_channel_member(channel_id, member_id, session.user_id)
         .await?;
 
     let connection_pool = &session.connection_pool().await;
-    notify_membership_updated(
-        &connection_pool,
-        membership_update,
-        member_id,
-        &session.peer,
-    );
-    for connection_id in connection_pool.user_connection_ids(member_id) {
-        if let Some(notification_id) = notification_id {
-            session
-                .peer
-                .send(
-                    connection_id,
-                    proto::DeleteNotification {
-                        notification_id: notification_id.to_proto(),
-                    },
-                )
-                .trace_err();
-        }
-    }
+    notify_channel_member_removed(&connection_pool, result, member_id, &session.peer);
 
     response.send(proto::Ack {})?;
     Ok(())
 }
 
+fn notify_channel_member_removed(
+    connection_pool: &ConnectionPool,
+    result: RemoveChannelMemberResult,
+    member_id: UserId,
+    peer: &Peer,
+) {
+    let RemoveChannelMemberResult {
+        membership_update,
+        notification_id,
+    } = result;
+    notify_membership_updated(connection_pool, membership_update, member_id, peer);
+    if let Some(notification_id) = notification_id {
+        for connection_id in connection_pool.user_connection_ids(member_id) {
+            peer.send(
+                connection_id,
+                proto::DeleteNotification {
+                    notification_id: notification_id.to_proto(),
+                },
+            )
+            .trace_err();
+        }
+    }
+}
+
 /// Toggle the channel between public and private.
 /// Care is taken to maintain the invariant that public channels only descend from public channels,
 /// (though members-only channels can appear at any point in the hierarchy).
@@ -2600,6 +2707,63 @@ async fn rename_channel(
     Ok(())
 }
 
+/// Get the repositories linked to a channel.
+async fn get_channel_project_links(
+    request: proto::GetChannelProjectLinks,
+    response: Response<proto::GetChannelProjectLinks>,
+    session: Session,
+) -> Result<()> {
+    let links = session
+        .db()
+        .await
+        .get_channel_project_links(ChannelId::from_proto(request.channel_id), session.user_id)
+        .await?;
+    response.send(proto::GetChannelProjectLinksResponse {
+        links: links.iter().map(|link| link.to_proto()).collect(),
+    })?;
+    Ok(())
+}
+
+/// Link a repository to a channel, hosted by the admin who links it.
+async fn add_channel_project_link(
+    request: proto::AddChannelProjectLink,
+    response: Response<proto::AddChannelProjectLink>,
+    session: Session,
+) -> Result<()> {
+    let link = session
+        .db()
+        .await
+        .add_channel_project_link(
+            ChannelId::from_proto(request.channel_id),
+            &request.remote_url,
+            &request.path,
+            session.user_id,
+        )
+        .await?;
+    response.send(proto::AddChannelProjectLinkResponse {
+        link: Some(link.to_proto()),
+    })?;
+    Ok(())
+}
+
+/// Remove a repository link from a channel.
+async fn remove_channel_project_link(
+    request: proto::RemoveChannelProjectLink,
+    response: Response<proto::RemoveChannelProjectLink>,
+    session: Session,
+) -> Result<()> {
+    session
+        .db()
+        .await
+        .remove_channel_project_link(
+            ChannelProjectLinkId::from_proto(request.link_id),
+            session.user_id,
+        )
+        .await?;
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
 /// Move a channel to a new parent.
 async fn move_channel(
     request: proto::MoveChannel,
@@ -2943,6 +3107,55 @@ async fn leave_channel_buffer(
     Ok(())
 }
 
+/// List the saved versions of a channel's notes
+async fn get_channel_notes_history(
+    request: proto::GetChannelNotesHistory,
+    response: Response<proto::GetChannelNotesHistory>,
+    session: Session,
+) -> Result<()> {
+    let versions = session
+        .db()
+        .await
+        .get_channel_buffer_history(ChannelId::from_proto(request.channel_id), session.user_id)
+        .await?;
+    response.send(proto::GetChannelNotesHistoryResponse { versions })?;
+    Ok(())
+}
+
+/// Retrieve the text of a saved version of a channel's notes
+async fn get_channel_notes_version(
+    request: proto::GetChannelNotesVersion,
+    response: Response<proto::GetChannelNotesVersion>,
+    session: Session,
+) -> Result<()> {
+    let text = session
+        .db()
+        .await
+        .get_channel_buffer_version_text(
+            ChannelId::from_proto(request.channel_id),
+            session.user_id,
+            request.epoch as i32,
+        )
+        .await?;
+    response.send(proto::GetChannelNotesVersionResponse { text })?;
+    Ok(())
+}
+
+/// Attribute the current text of a channel's notes to the users who wrote it
+async fn get_channel_notes_authorship(
+    request: proto::GetChannelNotesAuthorship,
+    response: Response<proto::GetChannelNotesAuthorship>,
+    session: Session,
+) -> Result<()> {
+    let authorship = session
+        .db()
+        .await
+        .get_channel_buffer_authorship(ChannelId::from_proto(request.channel_id), session.user_id)
+        .await?;
+    response.send(authorship)?;
+    Ok(())
+}
+
 fn channel_buffer_updated<T: EnvelopedMessage>(
     sender_id: ConnectionId,
     collaborators: impl IntoIterator<Item = ConnectionId>,
@@ -3029,6 +3242,8 @@ async fn send_channel_message(
         timestamp: timestamp.unix_timestamp() as u64,
         nonce: Some(nonce),
         reply_to_message_id: request.reply_to_message_id,
+        edited_at: None,
+        reactions: Vec::new(),
     };
     broadcast(
         Some(session.connection_id),
@@ -3091,6 +3306,118 @@ async fn remove_channel_message(
     Ok(())
 }
 
+/// Edit a channel message
+async fn update_channel_message(
+    request: proto::UpdateChannelMessage,
+    response: Response<proto::UpdateChannelMessage>,
+    session: Session,
+) -> Result<()> {
+    let body = request.body.trim().to_string();
+    if body.len() > MAX_MESSAGE_LEN {
+        return Err(anyhow!("message is too long"))?;
+    }
+    if body.is_empty() {
+        return Err(anyhow!("message can't be blank"))?;
+    }
+
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_message = session
+        .db()
+        .await
+        .update_channel_message(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+            &body,
+            &request.mentions,
+            OffsetDateTime::now_utc(),
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, &session).await;
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// React to a channel message with an emoji
+async fn add_channel_message_reaction(
+    request: proto::AddChannelMessageReaction,
+    response: Response<proto::AddChannelMessageReaction>,
+    session: Session,
+) -> Result<()> {
+    validate_reaction(&request.emoji)?;
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_message = session
+        .db()
+        .await
+        .add_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+            &request.emoji,
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, &session).await;
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+/// Take back a reaction to a channel message
+async fn remove_channel_message_reaction(
+    request: proto::RemoveChannelMessageReaction,
+    response: Response<proto::RemoveChannelMessageReaction>,
+    session: Session,
+) -> Result<()> {
+    let channel_id = ChannelId::from_proto(request.channel_id);
+    let updated_message = session
+        .db()
+        .await
+        .remove_channel_message_reaction(
+            channel_id,
+            MessageId::from_proto(request.message_id),
+            session.user_id,
+            &request.emoji,
+        )
+        .await?;
+    broadcast_channel_message_update(channel_id, updated_message, &session).await;
+    response.send(proto::Ack {})?;
+    Ok(())
+}
+
+fn validate_reaction(emoji: &str) -> Result<()> {
+    if emoji.is_empty() || emoji.len() > MAX_REACTION_LEN || emoji.contains(char::is_whitespace) {
+        return Err(anyhow!("invalid reaction"))?;
+    }
+    Ok(())
+}
+
+/// Send the new state of a message to everyone in the channel's chat, including
+/// the user who changed it.
+async fn broadcast_channel_message_update(
+    channel_id: ChannelId,
+    updated_message: UpdatedChannelMessage,
+    session: &Session,
+) {
+    let UpdatedChannelMessage {
+        message,
+        participant_connection_ids,
+        notifications,
+    } = updated_message;
+    broadcast(None, participant_connection_ids, |connection| {
+        session.peer.send(
+            connection,
+            proto::ChannelMessageUpdate {
+                channel_id: channel_id.to_proto(),
+                message: Some(message.clone()),
+            },
+        )
+    });
+    send_notifications(
+        &*session.connection_pool().await,
+        &session.peer,
+        notifications,
+    );
+}
+
 /// Mark a channel message as read
 async fn acknowledge_channel_message(
     request: proto::AckChannelMessage,
@@ -3098,10 +3425,11 @@ async fn acknowledge_channel_message(
 ) -> Result<()> {
     let channel_id = ChannelId::from_proto(request.channel_id);
     let message_id = MessageId::from_proto(request.message_id);
+    let thread_id = request.thread_id.map(MessageId::from_proto);
     let notifications = session
         .db()
         .await
-        .observe_channel_message(channel_id, session.user_id, message_id)
+        .observe_channel_message(channel_id, session.user_id, message_id, thread_id)
         .await?;
     send_notifications(
         &*session.connection_pool().await,
@@ -3144,9 +3472,13 @@ async fn join_channel_chat(
     let messages = db
         .get_channel_messages(channel_id, session.user_id, MESSAGE_COUNT_PER_PAGE, None)
         .await?;
+    let observed_threads = db
+        .observed_channel_threads(channel_id, session.user_id)
+        .await?;
     response.send(proto::JoinChannelChatResponse {
         done: messages.len() < MESSAGE_COUNT_PER_PAGE,
         messages,
+        observed_threads,
     })?;
     Ok(())
 }
@@ -3209,6 +3541,41 @@ async fn get_channel_messages_by_id(
     Ok(())
 }
 
+/// Search the messages in the channels the user can access
+async fn search_channel_messages(
+    request: proto::SearchChannelMessages,
+    response: Response<proto::SearchChannelMessages>,
+    session: Session,
+) -> Result<()> {
+    let query = request.query.trim();
+    if query.is_empty() {
+        return Err(anyhow!("search query can't be blank"))?;
+    }
+
+    let timestamp = |timestamp: u64| -> Result<time::PrimitiveDateTime> {
+        let timestamp = OffsetDateTime::from_unix_timestamp(timestamp as i64)
+            .map_err(|_| anyhow!("invalid timestamp {timestamp}"))?;
+        Ok(time::PrimitiveDateTime::new(
+            timestamp.date(),
+            timestamp.time(),
+        ))
+    };
+    let search = ChannelMessageSearch {
+        query: query.to_string(),
+        channel_id: request.channel_id.map(ChannelId::from_proto),
+        sender_id: request.sender_id.map(UserId::from_proto),
+        sent_after: request.sent_after.map(timestamp).transpose()?,
+        sent_before: request.sent_before.map(timestamp).transpose()?,
+    };
+    let results = session
+        .db()
+        .await
+        .search_channel_messages(session.user_id, &search, MESSAGE_SEARCH_RESULT_LIMIT)
+        .await?;
+    response.send(proto::SearchChannelMessagesResponse { results })?;
+    Ok(())
+}
+
 /// Retrieve the current users notifications
 async fn get_notifications(
     request: proto::GetNotifications,
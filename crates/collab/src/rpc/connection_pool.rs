@@ -53,6 +53,10 @@ impl ConnectionPool {
         Ok(())
     }
 
+    pub fn contains_connection(&self, connection_id: ConnectionId) -> bool {
+        self.connections.contains_key(&connection_id)
+    }
+
     pub fn connections(&self) -> impl Iterator<Item = &Connection> {
         self.connections.values()
     }
@@ -12,6 +12,8 @@ mod notification_tests;
 mod random_channel_buffer_tests;
 mod random_project_collaboration_tests;
 mod randomized_test_helpers;
+mod review_thread_tests;
+mod terminal_sharing_tests;
 mod test_server;
 
 pub use randomized_test_helpers::{
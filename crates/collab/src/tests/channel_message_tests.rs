@@ -466,3 +466,137 @@ async fn test_chat_replies(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext)
         )
     });
 }
+
+#[gpui::test]
+async fn test_chat_edits_reactions_and_threads(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_chat_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+
+    let msg_id = channel_chat_a
+        .update(cx_a, |c, cx| c.send_message("one".into(), cx).unwrap())
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Client A edits their message, and both clients see the new body.
+    channel_chat_a
+        .update(cx_a, |c, cx| {
+            c.update_message(msg_id, "uno".into(), cx).unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    for (chat, cx) in [(&channel_chat_a, &mut *cx_a), (&channel_chat_b, &mut *cx_b)] {
+        chat.read_with(cx, |chat, _| {
+            let message = chat.find_loaded_message(msg_id).unwrap();
+            assert_eq!(message.body, "uno");
+            assert!(message.edited_at.is_some());
+        });
+    }
+    assert_messages(&channel_chat_b, &["uno"], cx_b);
+
+    // Client B can't edit client A's message.
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.update_message(msg_id, "dos".into(), cx).unwrap()
+        })
+        .await
+        .unwrap_err();
+
+    // Client B reacts to the message, and client A sees the reaction.
+    channel_chat_b
+        .update(cx_b, |c, cx| c.add_reaction(msg_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    let user_b_id = client_b.user_id().unwrap();
+    channel_chat_a.read_with(cx_a, |chat, _| {
+        let reactions = &chat.find_loaded_message(msg_id).unwrap().reactions;
+        assert_eq!(reactions.len(), 1);
+        assert_eq!(reactions[0].emoji, "🎉");
+        assert_eq!(reactions[0].user_ids, vec![user_b_id]);
+    });
+
+    channel_chat_b
+        .update(cx_b, |c, cx| c.remove_reaction(msg_id, "🎉".into(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    channel_chat_a.read_with(cx_a, |chat, _| {
+        assert!(chat
+            .find_loaded_message(msg_id)
+            .unwrap()
+            .reactions
+            .is_empty());
+    });
+
+    // Client B replies in the message's thread, which is unread for client A.
+    channel_chat_b
+        .update(cx_b, |c, cx| {
+            c.send_message(
+                MessageParams {
+                    text: "reply".into(),
+                    reply_to_message_id: Some(msg_id),
+                    mentions: Vec::new(),
+                },
+                cx,
+            )
+            .unwrap()
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    channel_chat_a.update(cx_a, |chat, _| {
+        assert_eq!(chat.reply_count(msg_id), 1);
+        assert_eq!(
+            chat.thread_replies(msg_id)
+                .map(|message| message.body.clone())
+                .collect::<Vec<_>>(),
+            &["reply"]
+        );
+        assert!(chat.thread_has_unread_replies(msg_id));
+        chat.acknowledge_thread(msg_id);
+        assert!(!chat.thread_has_unread_replies(msg_id));
+    });
+    channel_chat_b.read_with(cx_b, |chat, _| {
+        assert!(!chat.thread_has_unread_replies(msg_id));
+    });
+    cx_a.run_until_parked();
+
+    // The thread is still read when client A opens the chat again.
+    drop(channel_chat_a);
+    cx_a.run_until_parked();
+    let channel_chat_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_chat(channel_id, cx))
+        .await
+        .unwrap();
+    channel_chat_a.read_with(cx_a, |chat, _| {
+        assert_eq!(chat.reply_count(msg_id), 1);
+        assert!(!chat.thread_has_unread_replies(msg_id));
+    });
+}
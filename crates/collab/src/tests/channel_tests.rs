@@ -12,7 +12,8 @@ use rpc::{
     proto::{self, ChannelRole},
     RECEIVE_TIMEOUT,
 };
-use std::sync::Arc;
+use serde_json::json;
+use std::{path::Path, sync::Arc};
 
 #[gpui::test]
 async fn test_core_channels(
@@ -1456,3 +1457,130 @@ fn assert_channels_list_shape(
     });
     pretty_assertions::assert_eq!(actual, expected_channels);
 }
+
+#[gpui::test]
+async fn test_channel_project_links(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/code/zed",
+            json!({
+                ".git": {},
+                "crates": {
+                    "collab": {
+                        "main.rs": "fn main() {}",
+                    },
+                },
+            }),
+        )
+        .await;
+    // The clone is found by any of its remotes, not just `origin`.
+    client_a.fs().set_remote_url_for_repo(
+        Path::new("/code/zed/.git"),
+        "origin",
+        "git@github.com:some-fork/zed.git",
+    );
+    client_a.fs().set_remote_url_for_repo(
+        Path::new("/code/zed/.git"),
+        "upstream",
+        "git@github.com:zed-industries/zed.git",
+    );
+    let (project_a, _) = client_a
+        .build_local_project("/code/zed/crates/collab", cx_a)
+        .await;
+    client_a.build_workspace(&project_a, cx_a);
+
+    // Members can't link repositories to the channel.
+    client_b
+        .channel_store()
+        .update(cx_b, |channel_store, cx| {
+            channel_store.link_project(
+                channel_id,
+                "https://github.com/zed-industries/zed".into(),
+                "".into(),
+                cx,
+            )
+        })
+        .await
+        .unwrap_err();
+
+    let link = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.link_project(
+                channel_id,
+                "https://github.com/zed-industries/zed".into(),
+                "crates/collab".into(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_eq!(link.host_user_id, client_a.user_id());
+    let links = client_b
+        .channel_store()
+        .update(cx_b, |channel_store, cx| {
+            channel_store.project_links(channel_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(links, vec![link.clone()]);
+
+    // Client A already has their clone of the linked repository open, and hosts the
+    // link, so joining the channel offers to share it.
+    let window_a = cx_a.update(|cx| {
+        cx.active_window()
+            .unwrap()
+            .downcast::<workspace::Workspace>()
+            .unwrap()
+    });
+    let join_channel = cx_a.update(|cx| {
+        workspace::join_channel(channel_id, client_a.app_state.clone(), Some(window_a), cx)
+    });
+    cx_a.run_until_parked();
+    assert!(!project_a.read_with(cx_a, |project, _| project.is_shared()));
+    assert!(cx_a.has_pending_prompt());
+    cx_a.simulate_prompt_answer(0);
+    join_channel.await.unwrap();
+    cx_a.run_until_parked();
+    assert!(project_a.read_with(cx_a, |project, _| project.is_shared()));
+
+    // Client B joins the shared project rather than looking for a clone of their own.
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    let (workspace_b, cx_b) = client_b.active_workspace(cx_b);
+    assert!(workspace_b.update(cx_b, |workspace, cx| workspace
+        .project()
+        .read(cx)
+        .is_remote()));
+
+    client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.unlink_project(link.id, cx)
+        })
+        .await
+        .unwrap();
+    let links = client_a
+        .channel_store()
+        .update(cx_a, |channel_store, cx| {
+            channel_store.project_links(channel_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(links, vec![]);
+}
@@ -19,7 +19,10 @@ use language::{
 use live_kit_client::MacOSDisplay;
 use lsp::LanguageServerId;
 use project::{
-    search::SearchQuery, DiagnosticSummary, FormatTrigger, HoverBlockKind, Project, ProjectPath,
+    offline_edits::{OfflineEditConflict, OfflineEditsReport},
+    project_settings::{LspSettings, ProjectSettings},
+    search::SearchQuery,
+    DiagnosticSummary, FormatTrigger, HoverBlockKind, Project, ProjectPath,
 };
 use rand::prelude::*;
 use rpc::proto::ChannelRole;
@@ -1792,6 +1795,202 @@ async fn test_project_reconnect(
     buffer_b1.read_with(cx_b, |buffer, _| assert_eq!(buffer.text(), "WXaYZ"));
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_replaying_offline_edits(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree(
+            "/a",
+            json!({
+                "a.txt": "a-contents",
+                "b.txt": "b-contents",
+                "c.txt": "c-contents",
+                "e.txt": "e-contents",
+            }),
+        )
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    let buffer_a1 = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    let mut buffers_b = Vec::new();
+    for path in ["a.txt", "b.txt", "c.txt", "e.txt"] {
+        buffers_b.push(
+            project_b
+                .update(cx_b, |p, cx| p.open_buffer((worktree_id, path), cx))
+                .await
+                .unwrap(),
+        );
+    }
+    executor.run_until_parked();
+
+    // Client B loses its connection for longer than the room waits for it, after
+    // editing every buffer it has open.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    for buffer in &buffers_b {
+        buffer.update(cx_b, |buffer, cx| buffer.edit([(0..0, "B")], None, cx));
+    }
+
+    // Meanwhile, the host edits one of the files, renames another and deletes a third.
+    buffer_a1.update(cx_a, |buffer, cx| buffer.edit([(1..1, "A")], None, cx));
+    client_a
+        .fs()
+        .rename("/a/b.txt".as_ref(), "/a/d.txt".as_ref(), Default::default())
+        .await
+        .unwrap();
+    client_a
+        .fs()
+        .remove_file("/a/e.txt".as_ref(), Default::default())
+        .await
+        .unwrap();
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+
+    // The guest's project is disconnected, but kept around with its unsent edits.
+    active_call_b.read_with(cx_b, |call, _| assert!(call.room().is_none()));
+    project_b.read_with(cx_b, |project, _| {
+        assert!(project.is_disconnected());
+        assert!(project.has_offline_edits());
+    });
+
+    // Client B reconnects, joins the call again and replays its edits into the project.
+    server.allow_connections();
+    executor.advance_clock(RECEIVE_TIMEOUT);
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.invite(client_b.user_id().unwrap(), None, cx)
+        })
+        .await
+        .unwrap();
+    let mut incoming_call_b = active_call_b.read_with(cx_b, |call, _| call.incoming());
+    incoming_call_b.next().await.unwrap().unwrap();
+    active_call_b
+        .update(cx_b, |call, cx| call.accept_incoming(cx))
+        .await
+        .unwrap();
+    let project_b2 = client_b.build_remote_project(project_id, cx_b).await;
+    let report = project_b2
+        .update(cx_b, |project, cx| {
+            project.replay_offline_edits(project_b.clone(), cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let path = |path: &str| ProjectPath {
+        worktree_id,
+        path: Path::new(path).into(),
+    };
+    assert_eq!(
+        report,
+        OfflineEditsReport {
+            replayed: vec![path("a.txt"), path("d.txt"), path("c.txt")],
+            conflicts: vec![
+                OfflineEditConflict::HostDiverged(path("a.txt")),
+                OfflineEditConflict::FileRemoved(path("e.txt")),
+            ],
+        }
+    );
+    project_b.read_with(cx_b, |project, _| assert!(!project.has_offline_edits()));
+
+    // The replayed edits were merged with the host's.
+    buffer_a1.read_with(cx_a, |buffer, _| assert_eq!(buffer.text(), "BaA-contents"));
+    let buffer_a3 = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "c.txt"), cx))
+        .await
+        .unwrap();
+    buffer_a3.read_with(cx_a, |buffer, _| assert_eq!(buffer.text(), "Bc-contents"));
+    // Edits to a renamed file follow it to its new path.
+    let buffer_a4 = project_a
+        .update(cx_a, |p, cx| p.open_buffer((worktree_id, "d.txt"), cx))
+        .await
+        .unwrap();
+    buffer_a4.read_with(cx_a, |buffer, _| assert_eq!(buffer.text(), "Bb-contents"));
+    let buffer_b1 = project_b2
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    buffer_b1.read_with(cx_b, |buffer, _| assert_eq!(buffer.text(), "BaA-contents"));
+}
+
+#[gpui::test(iterations = 10)]
+async fn test_saving_offline_edits(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "a-contents" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "a.txt"), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // Client B edits a buffer after losing its connection for good.
+    server.forbid_connections();
+    server.disconnect_client(client_b.peer_id().unwrap());
+    buffer_b.update(cx_b, |buffer, cx| buffer.edit([(0..0, "B")], None, cx));
+    executor.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    project_b.read_with(cx_b, |project, _| assert!(project.has_offline_edits()));
+
+    // Instead of being replayed, the edits are saved to disk so the project can be
+    // closed without losing them.
+    let paths = project_b
+        .update(cx_b, |project, cx| {
+            project.save_offline_edits("/offline".as_ref(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(paths, [PathBuf::from("/offline/a/a.txt")]);
+    assert_eq!(
+        client_b
+            .fs()
+            .load("/offline/a/a.txt".as_ref())
+            .await
+            .unwrap(),
+        "Ba-contents"
+    );
+    project_b.read_with(cx_b, |project, _| assert!(!project.has_offline_edits()));
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_active_call_events(
     executor: BackgroundExecutor,
@@ -3353,6 +3552,97 @@ async fn test_local_settings(
     });
 }
 
+#[gpui::test]
+async fn test_local_editorconfigs(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // As client A, open a project with EditorConfig files inside and above its root.
+    client_a
+        .fs()
+        .insert_tree(
+            "/dir",
+            json!({
+                ".editorconfig": "root = true\n[*]\nindent_size = 2\n",
+                "project": {
+                    "a": {
+                        ".editorconfig": "[*.rs]\nindent_size = 4\n",
+                        "a.rs": "",
+                    },
+                    "b.rs": "",
+                }
+            }),
+        )
+        .await;
+    let (project_a, _) = client_a.build_local_project("/dir/project", cx_a).await;
+    executor.run_until_parked();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // As client B, join that project and observe the EditorConfig files.
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let worktree_b = project_b.read_with(cx_b, |project, _| project.worktrees().next().unwrap());
+    executor.run_until_parked();
+    cx_b.read(|cx| {
+        let store = cx.global::<SettingsStore>();
+        let root_id = worktree_b.read(cx).id().to_usize();
+        assert_eq!(
+            store.local_editorconfigs(root_id).collect::<Vec<_>>(),
+            &[(Path::new("a").into(), "[*.rs]\nindent_size = 4\n")]
+        );
+        assert_eq!(
+            store.parent_editorconfigs(root_id).collect::<Vec<_>>(),
+            &[(
+                Path::new("project").into(),
+                "root = true\n[*]\nindent_size = 2\n"
+            )]
+        );
+        let tab_size = |path: &str| {
+            store
+                .editorconfig_properties(root_id, &Path::new(path).into())
+                .and_then(|properties| properties.tab_size())
+                .map(|tab_size| tab_size.get())
+        };
+        assert_eq!(tab_size("a/a.rs"), Some(4));
+        assert_eq!(tab_size("b.rs"), Some(2));
+    });
+
+    // As client A, update an EditorConfig file. As client B, see the changed properties.
+    client_a
+        .fs()
+        .insert_file(
+            "/dir/project/a/.editorconfig",
+            "[*.rs]\nindent_size = 8\n".into(),
+        )
+        .await;
+    executor.run_until_parked();
+    cx_b.read(|cx| {
+        let store = cx.global::<SettingsStore>();
+        let properties = store
+            .editorconfig_properties(
+                worktree_b.read(cx).id().to_usize(),
+                &Path::new("a/a.rs").into(),
+            )
+            .unwrap();
+        assert_eq!(
+            properties.tab_size().map(|tab_size| tab_size.get()),
+            Some(8)
+        );
+    });
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_buffer_conflict_after_save(
     executor: BackgroundExecutor,
@@ -4053,6 +4343,116 @@ async fn test_collaborating_with_diagnostics(
     });
 }
 
+#[gpui::test(iterations = 10)]
+async fn test_code_actions_from_multiple_language_servers(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    // Client A runs a second language server for Rust files.
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_language_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+    client_a.language_registry().add(Arc::new(language));
+    cx_a.update(|cx| {
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.insert(
+                    Arc::from("rust-linter"),
+                    LspSettings {
+                        languages: vec![Arc::from("Rust")],
+                        ..Default::default()
+                    },
+                );
+            });
+        });
+    });
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "main.rs": "fn main() {}" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let buffer_b = project_b
+        .update(cx_b, |p, cx| p.open_buffer((worktree_id, "main.rs"), cx))
+        .await
+        .unwrap();
+
+    for _ in 0..2 {
+        let fake_language_server = fake_language_servers.next().await.unwrap();
+        executor.run_until_parked();
+        let name = project_a.read_with(cx_a, |project, cx| {
+            project
+                .language_server_details(cx)
+                .into_iter()
+                .find(|details| details.id == Some(fake_language_server.server.server_id()))
+                .unwrap()
+                .name
+                .0
+                .to_string()
+        });
+        fake_language_server.handle_request::<lsp::request::CodeActionRequest, _, _>(
+            move |_, _| {
+                let name = name.clone();
+                async move {
+                    Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                        lsp::CodeAction {
+                            title: format!("{name} action"),
+                            ..Default::default()
+                        },
+                    )]))
+                }
+            },
+        );
+    }
+
+    // The guest gets the code actions of both of the host's servers.
+    let actions = project_b
+        .update(cx_b, |project, cx| {
+            project.code_actions(&buffer_b, 0..0, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        actions
+            .into_iter()
+            .map(|action| action.lsp_action.title)
+            .collect::<Vec<_>>(),
+        ["rust-lsp action", "rust-linter action"]
+    );
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_collaborating_with_lsp_progress_updates_and_diagnostics_ordering(
     executor: BackgroundExecutor,
@@ -0,0 +1,129 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use gpui::{BackgroundExecutor, Model, TestAppContext};
+use language::ToOffset as _;
+use project::{
+    review_threads::{ReviewThreadId, MAX_REVIEW_COMMENT_LEN},
+    Project,
+};
+use serde_json::json;
+use std::path::PathBuf;
+
+#[gpui::test]
+async fn test_review_threads(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b), (&client_c, cx_c)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.rs": "fn one() {}\nfn two() {}\n" }))
+        .await;
+    let (project_a, worktree_id) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+    let project_c = client_c.build_remote_project(project_id, cx_c).await;
+
+    // Client B starts a thread, replies to it and resolves it.
+    let buffer_b = project_b
+        .update(cx_b, |project, cx| {
+            project.open_buffer((worktree_id, "a.rs"), cx)
+        })
+        .await
+        .unwrap();
+    let thread_id = project_b
+        .update(cx_b, |project, cx| {
+            let buffer = buffer_b.read(cx);
+            let range = buffer.anchor_before(12)..buffer.anchor_after(23);
+            project.create_review_thread(&buffer_b, range, "Rename this".into(), cx)
+        })
+        .await
+        .unwrap();
+    project_b
+        .update(cx_b, |project, cx| {
+            project.reply_to_review_thread(thread_id, "Or not".into(), cx)
+        })
+        .await
+        .unwrap();
+    project_b
+        .update(cx_b, |project, cx| {
+            project.set_review_thread_resolved(thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    // The host and the other guest see the same thread.
+    let user_id_b = client_b.user_id().unwrap();
+    for (project, cx) in [
+        (&project_a, &mut *cx_a),
+        (&project_b, &mut *cx_b),
+        (&project_c, &mut *cx_c),
+    ] {
+        assert_eq!(
+            thread_summary(project, thread_id, cx),
+            (
+                PathBuf::from("a.rs"),
+                true,
+                vec![
+                    (Some(user_id_b), "Rename this".to_string()),
+                    (Some(user_id_b), "Or not".to_string())
+                ]
+            )
+        );
+    }
+
+    // The thread is anchored to the code that client B commented on.
+    project_a.read_with(cx_a, |project, cx| {
+        let thread = project.review_thread(thread_id).unwrap();
+        let buffer = project.buffer_for_id(thread.buffer_id).unwrap();
+        let buffer = buffer.read(cx);
+        let range = thread.range.start.to_offset(buffer)..thread.range.end.to_offset(buffer);
+        assert_eq!(
+            buffer.text_for_range(range).collect::<String>(),
+            "fn two() {}"
+        );
+    });
+
+    // The host rejects comments that are too long.
+    let result = project_c
+        .update(cx_c, |project, cx| {
+            project.reply_to_review_thread(thread_id, "a".repeat(MAX_REVIEW_COMMENT_LEN + 1), cx)
+        })
+        .await;
+    assert!(result.is_err());
+    executor.run_until_parked();
+    assert_eq!(thread_summary(&project_a, thread_id, cx_a).2.len(), 2);
+}
+
+fn thread_summary(
+    project: &Model<Project>,
+    thread_id: ReviewThreadId,
+    cx: &mut TestAppContext,
+) -> (PathBuf, bool, Vec<(Option<u64>, String)>) {
+    project.read_with(cx, |project, _| {
+        let thread = project.review_thread(thread_id).unwrap();
+        (
+            thread.project_path.as_ref().unwrap().path.to_path_buf(),
+            thread.resolved,
+            thread
+                .comments
+                .iter()
+                .map(|comment| (comment.author_id, comment.body.clone()))
+                .collect(),
+        )
+    })
+}
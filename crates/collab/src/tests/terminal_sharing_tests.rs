@@ -0,0 +1,248 @@
+use crate::tests::TestServer;
+use call::ActiveCall;
+use futures::{channel::mpsc, StreamExt as _};
+use gpui::{
+    point, px, BackgroundExecutor, Context as _, Model, ScrollDelta, ScrollWheelEvent,
+    TestAppContext, TouchPhase, VisualContext as _,
+};
+use rpc::proto;
+use serde_json::json;
+use std::time::Duration;
+use terminal::{Terminal, TerminalBuilder};
+use terminal_view::TerminalView;
+
+#[gpui::test]
+async fn test_shared_terminal_input(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+    let active_call_b = cx_b.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/a", json!({ "a.txt": "" }))
+        .await;
+    let (project_a, _) = client_a.build_local_project("/a", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.build_remote_project(project_id, cx_b).await;
+
+    // Client A shares a terminal, which client B can watch but not type into.
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+    write_output(&terminal_a, "$ ls", cx_a);
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    executor.run_until_parked();
+
+    let terminal_id = project_a.read_with(cx_a, |project, _| {
+        project.terminal_share_id(&terminal_a).unwrap()
+    });
+    let terminal_b = project_b.read_with(cx_b, |project, _| {
+        project.remote_terminal(terminal_id).unwrap()
+    });
+    assert_eq!(screen_lines(&terminal_b, cx_b)[0], "$ ls");
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+
+    // The host rejects input from guests it didn't allow to type.
+    let input = proto::TerminalInput {
+        project_id,
+        terminal_id,
+        input: b"pwd\n".to_vec(),
+    };
+    assert!(client_b.request(input.clone()).await.is_err());
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+
+    // Once allowed, client B's input reaches the host's terminal.
+    let peer_id_b = client_b.peer_id().unwrap();
+    project_a.update(cx_a, |project, cx| {
+        project.set_terminal_input_allowed(&terminal_a, peer_id_b, true, cx)
+    });
+    executor.run_until_parked();
+    assert!(!terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+    terminal_b.update(cx_b, |terminal, _| terminal.input("pwd\n".into()));
+    executor.run_until_parked();
+    assert_eq!(input_rx.next().await.unwrap(), b"pwd\n");
+
+    // Further output only updates the lines that changed.
+    write_output(&terminal_a, "\r\n/a", cx_a);
+    executor.advance_clock(Duration::from_millis(100));
+    executor.run_until_parked();
+    let lines = screen_lines(&terminal_b, cx_b);
+    assert_eq!(&lines[..2], ["$ ls", "/a"]);
+
+    // Leaving the project revokes client B's permission to type.
+    active_call_b
+        .update(cx_b, |call, cx| call.hang_up(cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    assert!(!project_a.read_with(cx_a, |project, _| {
+        project.is_terminal_input_allowed(&terminal_a, peer_id_b)
+    }));
+}
+
+#[gpui::test]
+async fn test_read_only_guests_cannot_type_into_shared_terminals(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    // Client A shares a project in a public channel, which client B joins as a guest.
+    let project_a = client_a.build_test_project(cx_a).await;
+    active_call_a
+        .update(cx_a, |call, cx| call.join_channel(channel_id, cx))
+        .await
+        .unwrap();
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    executor.run_until_parked();
+
+    let (input_tx, mut input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+    project_a
+        .update(cx_a, |project, cx| project.share_terminal(&terminal_a, cx))
+        .unwrap();
+    let terminal_id = project_a.read_with(cx_a, |project, _| {
+        project.terminal_share_id(&terminal_a).unwrap()
+    });
+
+    // Even when the host allows it, the server doesn't forward a read-only guest's
+    // input.
+    let peer_id_b = client_b.peer_id().unwrap();
+    project_a.update(cx_a, |project, cx| {
+        project.set_terminal_input_allowed(&terminal_a, peer_id_b, true, cx)
+    });
+    executor.run_until_parked();
+    assert!(client_b
+        .request(proto::TerminalInput {
+            project_id,
+            terminal_id,
+            input: b"ls\n".to_vec(),
+        })
+        .await
+        .is_err());
+    executor.run_until_parked();
+    assert!(input_rx.try_next().is_err());
+}
+
+#[gpui::test]
+async fn test_following_unshared_terminal(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+    let peer_id_a = client_a.peer_id().unwrap();
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+
+    // Client A opens a terminal without sharing it, with more output than fits on its
+    // screen.
+    let (input_tx, _input_rx) = mpsc::unbounded();
+    let terminal_a = cx_a.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+    let output = (0..10)
+        .map(|ix| format!("line {ix}"))
+        .collect::<Vec<_>>()
+        .join("\r\n");
+    write_output(&terminal_a, &output, cx_a);
+    workspace_a.update(cx_a, |workspace, cx| {
+        let workspace_id = workspace.database_id();
+        let weak_workspace = cx.view().downgrade();
+        let view = cx
+            .new_view(|cx| TerminalView::new(terminal_a.clone(), weak_workspace, workspace_id, cx));
+        workspace.add_item(Box::new(view), cx);
+    });
+    cx_a.run_until_parked();
+
+    // Client B follows client A into a read-only copy of the terminal.
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(peer_id_a, cx));
+    cx_a.run_until_parked();
+    let terminal_b = workspace_b.update(cx_b, |workspace, cx| {
+        let view = workspace.active_item_as::<TerminalView>(cx).unwrap();
+        view.read(cx).terminal().clone()
+    });
+    assert!(terminal_b.read_with(cx_b, |terminal, _| terminal.is_read_only()));
+    assert_eq!(screen_lines(&terminal_b, cx_b)[0], "line 4");
+
+    // Client B sees the terminal scrolled back as far as client A scrolls it.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.scroll_wheel(
+            &ScrollWheelEvent {
+                delta: ScrollDelta::Lines(point(0., 1.)),
+                touch_phase: TouchPhase::Moved,
+                ..Default::default()
+            },
+            point(px(0.), px(0.)),
+        );
+        terminal.sync(cx);
+    });
+    cx_a.run_until_parked();
+    assert_eq!(screen_lines(&terminal_b, cx_b)[0], "line 0");
+
+    // ...and sees new output once client A scrolls back down.
+    terminal_a.update(cx_a, |terminal, cx| {
+        terminal.scroll_wheel(
+            &ScrollWheelEvent {
+                delta: ScrollDelta::Lines(point(0., -1.)),
+                touch_phase: TouchPhase::Moved,
+                ..Default::default()
+            },
+            point(px(0.), px(0.)),
+        );
+        terminal.sync(cx);
+    });
+    write_output(&terminal_a, "\r\nline 10", cx_a);
+    cx_a.run_until_parked();
+    assert_eq!(screen_lines(&terminal_b, cx_b)[5], "line 10");
+}
+
+fn write_output(terminal: &Model<Terminal>, output: &str, cx: &mut TestAppContext) {
+    terminal.update(cx, |terminal, cx| {
+        terminal.write_output(output.as_bytes());
+        cx.emit(terminal::Event::Wakeup);
+    });
+}
+
+fn screen_lines(terminal: &Model<Terminal>, cx: &mut TestAppContext) -> Vec<String> {
+    let screen = terminal.read_with(cx, |terminal, _| terminal.screen());
+    screen
+        .cells
+        .chunks(screen.columns)
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|cell| cell.c)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
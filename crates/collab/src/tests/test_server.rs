@@ -272,6 +272,7 @@ impl TestServer {
             notifications::init(client.clone(), user_store, cx);
             collab_ui::init(&app_state, cx);
             file_finder::init(cx);
+            terminal_view::init(cx);
             menu::init();
             settings::KeymapFile::load_asset("keymaps/default.json", cx).unwrap();
         });
@@ -497,6 +498,11 @@ impl TestServer {
                 blob_store_access_key: None,
                 blob_store_secret_key: None,
                 blob_store_bucket: None,
+                public_url: None,
+                local_auth: None,
+                oidc_issuer_url: None,
+                oidc_client_id: None,
+                oidc_client_secret: None,
             },
         })
     }
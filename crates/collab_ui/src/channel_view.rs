@@ -15,6 +15,7 @@ use gpui::{
     FocusableView, IntoElement as _, Model, Pixels, Point, Render, Subscription, Task, View,
     ViewContext, VisualContext as _, WeakView, WindowContext,
 };
+use language::ToOffset;
 use project::Project;
 use std::{
     any::{Any, TypeId},
@@ -29,7 +30,11 @@ use workspace::{
     ItemNavHistory, Pane, SaveIntent, Toast, ViewId, Workspace, WorkspaceId,
 };
 
-actions!(collab, [CopyLink]);
+mod notes_history;
+
+use notes_history::ChannelNotesHistory;
+
+actions!(collab, [CopyLink, ShowNotesHistory, ShowNotesAuthors]);
 
 pub fn init(cx: &mut AppContext) {
     register_followable_item::<ChannelView>(cx)
@@ -170,11 +175,25 @@ impl ChannelView {
             editor.set_custom_context_menu(move |_, position, cx| {
                 let this = this.clone();
                 Some(ui::ContextMenu::build(cx, move |menu, _| {
+                    let copy_link = this.clone();
+                    let show_authors = this.clone();
+                    let show_history = this.clone();
                     menu.entry("Copy link to section", None, move |cx| {
-                        this.update(cx, |this, cx| {
-                            this.copy_link_for_position(position.clone(), cx)
-                        })
-                        .ok();
+                        copy_link
+                            .update(cx, |this, cx| {
+                                this.copy_link_for_position(position.clone(), cx)
+                            })
+                            .ok();
+                    })
+                    .entry("Who wrote this?", None, move |cx| {
+                        show_authors
+                            .update(cx, |this, cx| this.show_authors(&ShowNotesAuthors, cx))
+                            .ok();
+                    })
+                    .entry("Show version history", None, move |cx| {
+                        show_history
+                            .update(cx, |this, cx| this.show_history(&ShowNotesHistory, cx))
+                            .ok();
                     })
                 }))
             });
@@ -276,6 +295,71 @@ impl ChannelView {
             .ok();
     }
 
+    fn show_history(&mut self, _: &ShowNotesHistory, cx: &mut ViewContext<Self>) {
+        let channel_buffer = self.channel_buffer.clone();
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let weak_workspace = cx.view().downgrade();
+                workspace.toggle_modal(cx, |cx| {
+                    ChannelNotesHistory::new(weak_workspace, channel_buffer, cx)
+                });
+            })
+            .ok();
+    }
+
+    /// Shows who wrote the selected text, or the character before the cursor, as
+    /// recorded in the operations of the notes' current epoch and the ones before it.
+    fn show_authors(&mut self, _: &ShowNotesAuthors, cx: &mut ViewContext<Self>) {
+        let selection = self.editor.update(cx, |editor, cx| {
+            editor.selections.newest::<usize>(cx).range()
+        });
+        let selection = if selection.is_empty() {
+            selection.start.saturating_sub(1)..selection.start.max(1)
+        } else {
+            selection
+        };
+        let buffer = self.channel_buffer.read(cx).buffer();
+        let authorship = self
+            .channel_buffer
+            .update(cx, |channel_buffer, cx| channel_buffer.authorship(cx));
+        cx.spawn(|this, mut cx| async move {
+            let message = match authorship.await {
+                Ok(ranges) => buffer.update(&mut cx, |buffer, _| {
+                    let mut logins = Vec::new();
+                    for (range, author) in ranges {
+                        let start = range.start.to_offset(buffer);
+                        let end = range.end.to_offset(buffer);
+                        if start >= selection.end || end <= selection.start {
+                            continue;
+                        }
+                        if let Some(author) = author {
+                            let login = format!("@{}", author.github_login);
+                            if !logins.contains(&login) {
+                                logins.push(login);
+                            }
+                        }
+                    }
+                    match logins.as_slice() {
+                        [] => "No author is recorded for this text".to_string(),
+                        [login] => format!("Written by {login}"),
+                        [logins @ .., last] => {
+                            format!("Written by {} and {last}", logins.join(", "))
+                        }
+                    }
+                })?,
+                Err(error) => format!("Couldn't determine who wrote this: {error}"),
+            };
+            this.update(&mut cx, |this, cx| {
+                this.workspace
+                    .update(cx, |workspace, cx| {
+                        workspace.show_toast(Toast::new(0, message), cx);
+                    })
+                    .ok();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub fn channel(&self, cx: &AppContext) -> Option<Arc<Channel>> {
         self.channel_buffer.read(cx).channel(cx)
     }
@@ -339,6 +423,8 @@ impl Render for ChannelView {
         div()
             .size_full()
             .on_action(cx.listener(Self::copy_link))
+            .on_action(cx.listener(Self::show_history))
+            .on_action(cx.listener(Self::show_authors))
             .child(self.editor.clone())
     }
 }
@@ -0,0 +1,218 @@
+use crate::notification_panel::format_timestamp;
+use channel::{ChannelBuffer, ChannelNotesVersion};
+use editor::{Editor, MultiBuffer};
+use gpui::{
+    rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model, Render, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, BufferId};
+use picker::{Picker, PickerDelegate};
+use similar::TextDiff;
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+/// The versions of a channel's notes that were saved when everyone stopped editing
+/// them, which can be compared with the current notes or restored.
+pub struct ChannelNotesHistory {
+    picker: View<Picker<ChannelNotesHistoryDelegate>>,
+}
+
+impl FocusableView for ChannelNotesHistory {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ChannelNotesHistory {}
+impl ModalView for ChannelNotesHistory {}
+
+impl Render for ChannelNotesHistory {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl ChannelNotesHistory {
+    pub fn new(
+        workspace: WeakView<Workspace>,
+        channel_buffer: Model<ChannelBuffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let history = channel_buffer.update(cx, |buffer, cx| buffer.history(cx));
+        let delegate = ChannelNotesHistoryDelegate {
+            history_view: cx.view().downgrade(),
+            workspace,
+            channel_buffer,
+            versions: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            now: OffsetDateTime::now_utc(),
+            timezone: cx.local_timezone(),
+        };
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx).max_height(vh(0.75, cx)));
+        let weak_picker = picker.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let versions = history.await?;
+            weak_picker.update(&mut cx, |picker, cx| {
+                picker.delegate.versions = versions;
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+        Self { picker }
+    }
+}
+
+pub struct ChannelNotesHistoryDelegate {
+    history_view: WeakView<ChannelNotesHistory>,
+    workspace: WeakView<Workspace>,
+    channel_buffer: Model<ChannelBuffer>,
+    versions: Vec<ChannelNotesVersion>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    now: OffsetDateTime,
+    timezone: UtcOffset,
+}
+
+impl ChannelNotesHistoryDelegate {
+    fn label(&self, version: &ChannelNotesVersion) -> String {
+        let timestamp = format_timestamp(version.created_at, self.now, self.timezone);
+        if version.authors.is_empty() {
+            timestamp
+        } else {
+            let authors = version
+                .authors
+                .iter()
+                .map(|author| format!("@{}", author.github_login))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{authors} · {timestamp}")
+        }
+    }
+
+    /// Replaces the current notes with the given version, as a new edit that
+    /// collaborators receive like any other.
+    fn restore(&self, version: &ChannelNotesVersion, cx: &mut ViewContext<Picker<Self>>) {
+        let buffer = self.channel_buffer.read(cx).buffer();
+        let text = self.channel_buffer.update(cx, |channel_buffer, cx| {
+            channel_buffer.version_text(version.epoch, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let text = text.await?;
+            let diff = buffer
+                .update(&mut cx, |buffer, cx| buffer.diff(text, cx))?
+                .await;
+            buffer.update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_diff(&self, version: &ChannelNotesVersion, cx: &mut ViewContext<Picker<Self>>) {
+        let workspace = self.workspace.clone();
+        let label = self.label(version);
+        let channel_buffer = self.channel_buffer.read(cx);
+        let title = channel_buffer
+            .channel(cx)
+            .map(|channel| format!("#{} (history)", channel.name))
+            .unwrap_or_else(|| "Channel notes (history)".to_string());
+        let buffer = channel_buffer.buffer();
+        let text = self.channel_buffer.update(cx, |channel_buffer, cx| {
+            channel_buffer.version_text(version.epoch, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let text = text.await?;
+            let current_text = buffer.update(&mut cx, |buffer, _| buffer.text())?;
+            let diff_text = TextDiff::from_lines(text.as_str(), current_text.as_str())
+                .unified_diff()
+                .header(&label, "Current")
+                .to_string();
+            workspace.update(&mut cx, |workspace, cx| {
+                let diff_buffer =
+                    cx.new_model(|_| Buffer::new(0, BufferId::new(1).unwrap(), diff_text));
+                let multibuffer =
+                    cx.new_model(|cx| MultiBuffer::singleton(diff_buffer, cx).with_title(title));
+                let editor = cx.new_view(|cx| {
+                    let mut editor = Editor::for_multibuffer(multibuffer, None, cx);
+                    editor.set_read_only(true);
+                    editor
+                });
+                workspace.add_item(Box::new(editor), cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for ChannelNotesHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select a version to restore, or cmd-enter to compare it...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .versions
+            .iter()
+            .enumerate()
+            .filter(|(_, version)| self.label(version).to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(version) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.versions.get(*ix))
+        {
+            if secondary {
+                self.open_diff(version, cx);
+            } else {
+                self.restore(version, cx);
+            }
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.history_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let version = &self.versions[*self.matches.get(ix)?];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(Label::new(self.label(version))),
+        )
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{collab_panel, ChatPanelSettings};
 use anyhow::Result;
 use call::{room, ActiveCall};
-use channel::{ChannelChat, ChannelChatEvent, ChannelMessage, ChannelMessageId, ChannelStore};
-use client::Client;
+use channel::{
+    ChannelChat, ChannelChatEvent, ChannelId, ChannelMessage, ChannelMessageId,
+    ChannelMessageReaction, ChannelStore, MessageSearchQuery, MessageSearchResult,
+};
+use client::{Client, UserStore};
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use editor::Editor;
+use editor::{Editor, EditorEvent};
 use gpui::{
     actions, div, list, prelude::*, px, Action, AppContext, AsyncWindowContext, CursorStyle,
     DismissEvent, ElementId, EventEmitter, FocusHandle, FocusableView, FontStyle, FontWeight,
@@ -13,8 +16,9 @@ use gpui::{
     Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
 use language::LanguageRegistry;
-use menu::Confirm;
+use menu::{Cancel, Confirm};
 use message_editor::MessageEditor;
+use message_search::ParsedSearchQuery;
 use project::Fs;
 use rich_text::RichText;
 use serde::{Deserialize, Serialize};
@@ -32,9 +36,12 @@ use workspace::{
 };
 
 mod message_editor;
+mod message_search;
 
 const MESSAGE_LOADING_THRESHOLD: usize = 50;
 const CHAT_PANEL_KEY: &'static str = "ChatPanel";
+const REACTION_EMOJIS: &[&str] = &["👍", "❤️", "😄", "🎉", "👀"];
+const SEARCH_DEBOUNCE_INTERVAL: Duration = Duration::from_millis(200);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
@@ -48,6 +55,7 @@ pub fn init(cx: &mut AppContext) {
 pub struct ChatPanel {
     client: Arc<Client>,
     channel_store: Model<ChannelStore>,
+    user_store: Model<UserStore>,
     languages: Arc<LanguageRegistry>,
     message_list: ListState,
     active_chat: Option<(Model<ChannelChat>, Subscription)>,
@@ -63,6 +71,16 @@ pub struct ChatPanel {
     focus_handle: FocusHandle,
     open_context_menu: Option<(u64, Subscription)>,
     highlighted_message: Option<(u64, Task<()>)>,
+    open_thread: Option<u64>,
+    search: Option<MessageSearch>,
+}
+
+/// The state of a search through the messages of every channel the user can access.
+struct MessageSearch {
+    editor: View<Editor>,
+    results: Vec<MessageSearchResult>,
+    pending_search: Task<Option<()>>,
+    _subscription: Subscription,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -70,13 +88,23 @@ struct SerializedChatPanel {
     width: Option<Pixels>,
 }
 
-actions!(chat_panel, [ToggleFocus, CloseReplyPreview]);
+actions!(
+    chat_panel,
+    [
+        ToggleFocus,
+        CloseReplyPreview,
+        CancelEdit,
+        CloseThread,
+        ToggleSearch
+    ]
+);
 
 impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
         let fs = workspace.app_state().fs.clone();
         let client = workspace.app_state().client.clone();
         let channel_store = ChannelStore::global(cx);
+        let user_store = workspace.app_state().user_store.clone();
         let languages = workspace.app_state().languages.clone();
 
         let input_editor = cx.new_view(|cx| {
@@ -112,6 +140,7 @@ impl ChatPanel {
                 fs,
                 client,
                 channel_store,
+                user_store,
                 languages,
                 message_list,
                 active_chat: Default::default(),
@@ -126,6 +155,8 @@ impl ChatPanel {
                 focus_handle: cx.focus_handle(),
                 open_context_menu: None,
                 highlighted_message: None,
+                open_thread: None,
+                search: None,
             };
 
             if let Some(channel_id) = ActiveCall::global(cx)
@@ -239,8 +270,10 @@ impl ChatPanel {
                 self.message_editor.update(cx, |editor, cx| {
                     editor.set_channel(channel_id, channel_name, cx);
                     editor.clear_reply_to_message_id();
+                    editor.cancel_editing_message(cx);
                 });
             };
+            self.open_thread = None;
             let subscription = cx.subscribe(&chat, Self::channel_did_change);
             self.active_chat = Some((chat, subscription));
             self.acknowledge_last_message(cx);
@@ -250,7 +283,7 @@ impl ChatPanel {
 
     fn channel_did_change(
         &mut self,
-        _: Model<ChannelChat>,
+        chat: Model<ChannelChat>,
         event: &ChannelChatEvent,
         cx: &mut ViewContext<Self>,
     ) {
@@ -259,9 +292,16 @@ impl ChatPanel {
                 old_range,
                 new_count,
             } => {
+                // Messages that replace ones with the same id have been edited, or
+                // reacted to, so their rendered bodies are stale.
+                let new_range = old_range.start..old_range.start + new_count;
+                for message in chat.read(cx).messages_in_range(new_range) {
+                    self.markdown_data.remove(&message.id);
+                }
                 self.message_list.splice(old_range.clone(), *new_count);
                 if self.active {
                     self.acknowledge_last_message(cx);
+                    self.acknowledge_open_thread(cx);
                 }
             }
             ChannelChatEvent::NewMessage {
@@ -278,6 +318,12 @@ impl ChatPanel {
         cx.notify();
     }
 
+    fn acknowledge_open_thread(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(((chat, _), thread_id)) = self.active_chat.as_ref().zip(self.open_thread) {
+            chat.update(cx, |chat, _| chat.acknowledge_thread(thread_id));
+        }
+    }
+
     fn acknowledge_last_message(&mut self, cx: &mut ViewContext<Self>) {
         if self.active && self.is_scrolled_to_bottom {
             if let Some((chat, _)) = &self.active_chat {
@@ -383,34 +429,45 @@ impl ChatPanel {
 
     fn render_message(&mut self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let active_chat = &self.active_chat.as_ref().unwrap().0;
-        let (message, is_continuation_from_previous, is_admin) =
-            active_chat.update(cx, |active_chat, cx| {
-                let is_admin = self
-                    .channel_store
-                    .read(cx)
-                    .is_channel_admin(active_chat.channel_id);
-
-                let last_message = active_chat.message(ix.saturating_sub(1));
-                let this_message = active_chat.message(ix).clone();
-
-                let duration_since_last_message = this_message.timestamp - last_message.timestamp;
-                let is_continuation_from_previous = last_message.sender.id
-                    == this_message.sender.id
-                    && last_message.id != this_message.id
-                    && duration_since_last_message < Duration::from_secs(5 * 60);
-
-                if let ChannelMessageId::Saved(id) = this_message.id {
-                    if this_message
-                        .mentions
-                        .iter()
-                        .any(|(_, user_id)| Some(*user_id) == self.client.user_id())
-                    {
-                        active_chat.acknowledge_message(id);
-                    }
+        let (message, is_continuation_from_previous) = active_chat.update(cx, |active_chat, _| {
+            let last_message = active_chat.message(ix.saturating_sub(1));
+            let this_message = active_chat.message(ix).clone();
+
+            let duration_since_last_message = this_message.timestamp - last_message.timestamp;
+            let is_continuation_from_previous = last_message.sender.id == this_message.sender.id
+                && last_message.id != this_message.id
+                && duration_since_last_message < Duration::from_secs(5 * 60);
+
+            if let ChannelMessageId::Saved(id) = this_message.id {
+                if this_message
+                    .mentions
+                    .iter()
+                    .any(|(_, user_id)| Some(*user_id) == self.client.user_id())
+                {
+                    active_chat.acknowledge_message(id);
                 }
+            }
 
-                (this_message, is_continuation_from_previous, is_admin)
-            });
+            (this_message, is_continuation_from_previous)
+        });
+
+        self.render_message_content(message, is_continuation_from_previous, false, cx)
+    }
+
+    /// Renders a message, either in the channel's timeline, or in the thread that's
+    /// open, where the message that each reply responds to is left out.
+    fn render_message_content(
+        &mut self,
+        message: ChannelMessage,
+        is_continuation_from_previous: bool,
+        in_thread: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let active_chat = self.active_chat.as_ref().unwrap().0.clone();
+        let is_admin = self
+            .channel_store
+            .read(cx)
+            .is_channel_admin(active_chat.read(cx).channel_id);
 
         let _is_pending = message.is_pending();
 
@@ -435,6 +492,7 @@ impl ChatPanel {
 
         let reply_to_message = message
             .reply_to_message_id
+            .filter(|_| !in_thread)
             .map(|id| active_chat.read(cx).find_loaded_message(id))
             .flatten()
             .cloned();
@@ -442,6 +500,17 @@ impl ChatPanel {
         let replied_to_you =
             reply_to_message.as_ref().map(|m| m.sender.id) == self.client.user_id();
 
+        let (reply_count, has_unread_replies) = match message_id {
+            Some(message_id) if !in_thread => {
+                let chat = active_chat.read(cx);
+                (
+                    chat.reply_count(message_id),
+                    chat.thread_has_unread_replies(message_id),
+                )
+            }
+            _ => (0, false),
+        };
+
         let is_highlighted_message = self
             .highlighted_message
             .as_ref()
@@ -487,7 +556,9 @@ impl ChatPanel {
                     )
                 })
                 .when(
-                    message.reply_to_message_id.is_some() && reply_to_message.is_none(),
+                    !in_thread
+                        && message.reply_to_message_id.is_some()
+                        && reply_to_message.is_none(),
                     |this| {
                         const MESSAGE_DELETED: &str = "Message has been deleted";
 
@@ -536,6 +607,44 @@ impl ChatPanel {
                             .id(element_id)
                             .group("")
                             .child(text.element("body".into(), cx))
+                            .when(message.edited_at.is_some(), |el| {
+                                el.child(
+                                    Label::new("(edited)")
+                                        .size(LabelSize::XSmall)
+                                        .color(Color::Muted),
+                                )
+                            })
+                            .when_some(
+                                message_id.filter(|_| !message.reactions.is_empty()),
+                                |el, message_id| {
+                                    el.child(self.render_reactions(
+                                        message_id,
+                                        &message.reactions,
+                                        cx,
+                                    ))
+                                },
+                            )
+                            .when_some(message_id.filter(|_| reply_count > 0), |el, message_id| {
+                                let label = if reply_count == 1 {
+                                    "1 reply".to_string()
+                                } else {
+                                    format!("{reply_count} replies")
+                                };
+                                el.child(
+                                    h_flex().child(
+                                        Button::new("replies", label)
+                                            .label_size(LabelSize::Small)
+                                            .color(if has_unread_replies {
+                                                Color::Accent
+                                            } else {
+                                                Color::Muted
+                                            })
+                                            .on_click(cx.listener(move |this, _, cx| {
+                                                this.open_thread(message_id, cx)
+                                            })),
+                                    ),
+                                )
+                            })
                             .child(
                                 div()
                                     .absolute()
@@ -557,7 +666,9 @@ impl ChatPanel {
                                                     Some(Self::render_message_menu(
                                                         &this,
                                                         message_id,
+                                                        belongs_to_user,
                                                         can_delete_message,
+                                                        in_thread,
                                                         cx,
                                                     ))
                                                 }),
@@ -569,6 +680,52 @@ impl ChatPanel {
         )
     }
 
+    fn render_reactions(
+        &self,
+        message_id: u64,
+        reactions: &[ChannelMessageReaction],
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let current_user_id = self.client.user_id();
+        h_flex()
+            .gap_1()
+            .flex_wrap()
+            .children(reactions.iter().enumerate().map(|(ix, reaction)| {
+                let reacted =
+                    current_user_id.map_or(false, |user_id| reaction.user_ids.contains(&user_id));
+                let emoji = reaction.emoji.clone();
+                Button::new(
+                    ("reaction", ix),
+                    format!("{} {}", reaction.emoji, reaction.user_ids.len()),
+                )
+                .label_size(LabelSize::Small)
+                .style(ButtonStyle::Filled)
+                .selected(reacted)
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.toggle_reaction(message_id, emoji.clone(), cx)
+                }))
+            }))
+    }
+
+    fn render_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let chat = self.active_chat.as_ref().unwrap().0.read(cx);
+        let messages = chat
+            .find_loaded_message(thread_id)
+            .into_iter()
+            .chain(chat.thread_replies(thread_id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut children = Vec::with_capacity(messages.len());
+        for message in messages {
+            children.push(self.render_message_content(message, false, true, cx));
+        }
+        v_flex()
+            .id(("thread", thread_id))
+            .size_full()
+            .overflow_y_scroll()
+            .children(children)
+    }
+
     fn has_open_menu(&self, message_id: Option<u64>) -> bool {
         match self.open_context_menu.as_ref() {
             Some((id, _)) => Some(*id) == message_id,
@@ -579,22 +736,49 @@ impl ChatPanel {
     fn render_message_menu(
         this: &View<Self>,
         message_id: u64,
+        can_edit_message: bool,
         can_delete_message: bool,
+        in_thread: bool,
         cx: &mut WindowContext,
     ) -> View<ContextMenu> {
         let menu = {
-            ContextMenu::build(cx, move |menu, cx| {
-                menu.entry(
-                    "Reply to message",
-                    None,
-                    cx.handler_for(&this, move |this, cx| {
-                        this.message_editor.update(cx, |editor, cx| {
-                            editor.set_reply_to_message_id(message_id);
-                            editor.focus_handle(cx).focus(cx);
-                        })
-                    }),
-                )
-                .when(can_delete_message, move |menu| {
+            ContextMenu::build(cx, move |mut menu, cx| {
+                if !in_thread {
+                    menu = menu
+                        .entry(
+                            "Reply to message",
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.message_editor.update(cx, |editor, cx| {
+                                    editor.cancel_editing_message(cx);
+                                    editor.set_reply_to_message_id(message_id);
+                                    editor.focus_handle(cx).focus(cx);
+                                })
+                            }),
+                        )
+                        .entry(
+                            "Reply in thread",
+                            None,
+                            cx.handler_for(&this, move |this, cx| this.open_thread(message_id, cx)),
+                        );
+                }
+                for emoji in REACTION_EMOJIS {
+                    menu = menu.entry(
+                        format!("React with {emoji}"),
+                        None,
+                        cx.handler_for(&this, move |this, cx| {
+                            this.toggle_reaction(message_id, emoji.to_string(), cx)
+                        }),
+                    );
+                }
+                if can_edit_message {
+                    menu = menu.entry(
+                        "Edit message",
+                        None,
+                        cx.handler_for(&this, move |this, cx| this.edit_message(message_id, cx)),
+                    );
+                }
+                menu.when(can_delete_message, move |menu| {
                     menu.entry(
                         "Delete message",
                         None,
@@ -631,10 +815,23 @@ impl ChatPanel {
 
     fn send(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
-            let message = self
-                .message_editor
-                .update(cx, |editor, cx| editor.take_message(cx));
+            let (mut message, edit_message_id) = self.message_editor.update(cx, |editor, cx| {
+                (editor.take_message(cx), editor.take_edit_message_id())
+            });
+
+            if let Some(message_id) = edit_message_id {
+                if let Some(task) = chat
+                    .update(cx, |chat, cx| chat.update_message(message_id, message, cx))
+                    .log_err()
+                {
+                    task.detach_and_log_err(cx);
+                }
+                return;
+            }
 
+            if message.reply_to_message_id.is_none() {
+                message.reply_to_message_id = self.open_thread;
+            }
             if let Some(task) = chat
                 .update(cx, |chat, cx| chat.send_message(message, cx))
                 .log_err()
@@ -644,6 +841,79 @@ impl ChatPanel {
         }
     }
 
+    fn edit_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
+        let Some(body) = self
+            .active_chat()
+            .and_then(|chat| Some(chat.read(cx).find_loaded_message(id)?.body.clone()))
+        else {
+            return;
+        };
+        self.message_editor.update(cx, |editor, cx| {
+            editor.start_editing_message(id, &body, cx);
+            editor.focus_handle(cx).focus(cx);
+        });
+    }
+
+    fn cancel_edit(&mut self, _: &CancelEdit, cx: &mut ViewContext<Self>) {
+        self.message_editor
+            .update(cx, |editor, cx| editor.cancel_editing_message(cx));
+    }
+
+    /// Adds the current user's reaction to a message, or takes it back if they've
+    /// already reacted with the same emoji.
+    fn toggle_reaction(&mut self, id: u64, emoji: String, cx: &mut ViewContext<Self>) {
+        let Some((chat, _)) = self.active_chat.as_ref() else {
+            return;
+        };
+        let Some(user_id) = self.client.user_id() else {
+            return;
+        };
+        chat.update(cx, |chat, cx| {
+            let reacted = chat.find_loaded_message(id).map_or(false, |message| {
+                message
+                    .reactions
+                    .iter()
+                    .any(|reaction| reaction.emoji == emoji && reaction.user_ids.contains(&user_id))
+            });
+            if reacted {
+                chat.remove_reaction(id, emoji, cx)
+            } else {
+                chat.add_reaction(id, emoji, cx)
+            }
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Shows the replies to a message in place of the channel's timeline, loading
+    /// the channel's history back to the message first.
+    fn open_thread(&mut self, thread_id: u64, cx: &mut ViewContext<Self>) {
+        let Some(chat) = self.active_chat() else {
+            return;
+        };
+        self.open_thread = Some(thread_id);
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.focus_handle(cx).focus(cx);
+        });
+        cx.notify();
+        cx.spawn(|this, mut cx| async move {
+            ChannelChat::load_history_since_message(chat, thread_id, (*cx).clone()).await;
+            this.update(&mut cx, |this, cx| {
+                if this.active {
+                    this.acknowledge_open_thread(cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn close_thread(&mut self, _: &CloseThread, cx: &mut ViewContext<Self>) {
+        self.open_thread = None;
+        cx.notify();
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -720,21 +990,249 @@ impl ChatPanel {
         })
     }
 
+    fn toggle_search(&mut self, _: &ToggleSearch, cx: &mut ViewContext<Self>) {
+        if self.search.take().is_none() {
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::single_line(cx);
+                editor.set_placeholder_text("Search messages (from:, in:, before:, after:)", cx);
+                editor
+            });
+            let subscription = cx.subscribe(&editor, |this, _, event, cx| {
+                if let EditorEvent::Edited = event {
+                    this.search(cx);
+                }
+            });
+            cx.focus_view(&editor);
+            self.search = Some(MessageSearch {
+                editor,
+                results: Vec::new(),
+                pending_search: Task::ready(None),
+                _subscription: subscription,
+            });
+        } else {
+            cx.focus_self();
+        }
+        cx.notify();
+    }
+
+    fn dismiss_search(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.search.is_some() {
+            self.toggle_search(&ToggleSearch, cx);
+        }
+    }
+
+    fn search(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(search) = self.search.as_ref() else {
+            return;
+        };
+        let query = ParsedSearchQuery::parse(&search.editor.read(cx).text(cx));
+        let channel_id = match query.channel_name.as_deref() {
+            Some(name) => self
+                .channel_store
+                .read(cx)
+                .channels()
+                .find(|channel| channel.name.as_ref() == name)
+                .map(|channel| Some(channel.id)),
+            None => Some(None),
+        };
+        let Some(channel_id) = channel_id.filter(|_| !query.text.is_empty()) else {
+            self.set_search_results(Vec::new(), cx);
+            return;
+        };
+
+        let timezone = self.local_timezone;
+        let sent_after = query
+            .sent_after
+            .and_then(|date| date.next_day())
+            .map(|date| date.midnight().assume_offset(timezone));
+        let sent_before = query
+            .sent_before
+            .map(|date| date.midnight().assume_offset(timezone));
+        let search_users = query.sender_login.clone().map(|login| {
+            self.user_store
+                .update(cx, |store, cx| store.fuzzy_search_users(login, cx))
+        });
+        let task = cx.spawn(|this, mut cx| {
+            async move {
+                cx.background_executor()
+                    .timer(SEARCH_DEBOUNCE_INTERVAL)
+                    .await;
+                let sender_id = if let Some(search_users) = search_users {
+                    let login = query.sender_login.unwrap_or_default();
+                    let sender = search_users
+                        .await?
+                        .into_iter()
+                        .find(|user| user.github_login == login);
+                    let Some(sender) = sender else {
+                        return this
+                            .update(&mut cx, |this, cx| this.set_search_results(Vec::new(), cx));
+                    };
+                    Some(sender.id)
+                } else {
+                    None
+                };
+
+                let results = this
+                    .update(&mut cx, |this, cx| {
+                        this.channel_store.update(cx, |store, cx| {
+                            store.search_messages(
+                                MessageSearchQuery {
+                                    query: query.text,
+                                    channel_id,
+                                    sender_id,
+                                    sent_after,
+                                    sent_before,
+                                },
+                                cx,
+                            )
+                        })
+                    })?
+                    .await?;
+                this.update(&mut cx, |this, cx| this.set_search_results(results, cx))
+            }
+            .log_err()
+        });
+        if let Some(search) = self.search.as_mut() {
+            search.pending_search = task;
+        }
+    }
+
+    fn set_search_results(
+        &mut self,
+        results: Vec<MessageSearchResult>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(search) = self.search.as_mut() {
+            search.results = results;
+            search.pending_search = Task::ready(None);
+            cx.notify();
+        }
+    }
+
+    fn open_search_result(
+        &mut self,
+        channel_id: ChannelId,
+        message_id: u64,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.search = None;
+        self.select_channel(channel_id, Some(message_id), cx)
+            .detach_and_log_err(cx);
+        cx.focus_self();
+        cx.notify();
+    }
+
+    fn render_search(
+        &self,
+        search: &MessageSearch,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let has_query = !search.editor.read(cx).text(cx).trim().is_empty();
+        let results = search
+            .results
+            .iter()
+            .filter_map(|result| {
+                let ChannelMessageId::Saved(message_id) = result.message.id else {
+                    return None;
+                };
+                let channel_id = result.channel_id;
+                let channel_name = self
+                    .channel_store
+                    .read(cx)
+                    .channel_for_id(channel_id)
+                    .map(|channel| channel.name.clone())
+                    .unwrap_or_default();
+                Some(
+                    v_flex()
+                        .id(("search-result", message_id))
+                        .w_full()
+                        .px_1()
+                        .py_0p5()
+                        .rounded_md()
+                        .cursor(CursorStyle::PointingHand)
+                        .hover(|style| style.bg(cx.theme().colors().element_hover))
+                        .child(
+                            h_flex()
+                                .gap_1()
+                                .child(
+                                    Label::new(format!("#{}", channel_name))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Accent),
+                                )
+                                .child(
+                                    Label::new(result.message.sender.github_login.clone())
+                                        .size(LabelSize::Small),
+                                )
+                                .child(
+                                    Label::new(format_timestamp(
+                                        now,
+                                        result.message.timestamp,
+                                        self.local_timezone,
+                                    ))
+                                    .size(LabelSize::XSmall)
+                                    .color(Color::Muted),
+                                ),
+                        )
+                        .child(Label::new(result.message.body.clone()).size(LabelSize::Small))
+                        .on_click(cx.listener(move |this, _, cx| {
+                            this.open_search_result(channel_id, message_id, cx)
+                        })),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .key_context("MessageSearch")
+            .on_action(cx.listener(Self::dismiss_search))
+            .size_full()
+            .child(
+                div()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(search.editor.clone()),
+            )
+            .child(
+                v_flex()
+                    .id("search-results")
+                    .flex_grow()
+                    .overflow_y_scroll()
+                    .py_1()
+                    .gap_1()
+                    .children(results)
+                    .when(has_query && search.results.is_empty(), |el| {
+                        el.child(
+                            Label::new("No messages found.")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+    }
+
     fn close_reply_preview(&mut self, _: &CloseReplyPreview, cx: &mut ViewContext<Self>) {
-        self.message_editor
-            .update(cx, |editor, _| editor.clear_reply_to_message_id());
+        self.message_editor.update(cx, |editor, cx| {
+            editor.clear_reply_to_message_id();
+            editor.cancel_editing_message(cx);
+        });
     }
 }
 
 impl Render for ChatPanel {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
         let reply_to_message_id = self.message_editor.read(cx).reply_to_message_id();
+        let is_editing_message = self.message_editor.read(cx).edit_message_id().is_some();
+        let open_thread = self.open_thread.filter(|_| self.active_chat.is_some());
+        let is_searching = self.search.is_some();
 
         v_flex()
             .key_context("ChatPanel")
             .track_focus(&self.focus_handle)
             .size_full()
             .on_action(cx.listener(Self::send))
+            .on_action(cx.listener(Self::close_thread))
+            .on_action(cx.listener(Self::toggle_search))
             .child(
                 h_flex().z_index(1).child(
                     TabBar::new("chat_header").child(
@@ -742,19 +1240,54 @@ impl Render for ChatPanel {
                             .w_full()
                             .h(rems(ui::Tab::CONTAINER_HEIGHT_IN_REMS))
                             .px_2()
-                            .child(Label::new(
-                                self.active_chat
-                                    .as_ref()
-                                    .and_then(|c| {
-                                        Some(format!("#{}", c.0.read(cx).channel(cx)?.name))
+                            .gap_1()
+                            .when(open_thread.is_some(), |el| {
+                                el.child(
+                                    IconButton::new("close-thread", IconName::ArrowLeft)
+                                        .shape(ui::IconButtonShape::Square)
+                                        .tooltip(|cx| {
+                                            Tooltip::for_action("Back to chat", &CloseThread, cx)
+                                        })
+                                        .on_click(cx.listener(|this, _, cx| {
+                                            this.close_thread(&CloseThread, cx)
+                                        })),
+                                )
+                            })
+                            .child(
+                                div().flex_1().child(Label::new(
+                                    self.active_chat
+                                        .as_ref()
+                                        .and_then(|c| {
+                                            let name = &c.0.read(cx).channel(cx)?.name;
+                                            Some(if open_thread.is_some() {
+                                                format!("Thread in #{}", name)
+                                            } else {
+                                                format!("#{}", name)
+                                            })
+                                        })
+                                        .unwrap_or("Chat".to_string()),
+                                )),
+                            )
+                            .child(
+                                IconButton::new("toggle-search", IconName::MagnifyingGlass)
+                                    .shape(ui::IconButtonShape::Square)
+                                    .selected(is_searching)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action("Search messages", &ToggleSearch, cx)
                                     })
-                                    .unwrap_or("Chat".to_string()),
-                            )),
+                                    .on_click(cx.listener(|this, _, cx| {
+                                        this.toggle_search(&ToggleSearch, cx)
+                                    })),
+                            ),
                     ),
                 ),
             )
             .child(div().flex_grow().px_2().map(|this| {
-                if self.active_chat.is_some() {
+                if let Some(search) = self.search.as_ref() {
+                    this.child(self.render_search(search, cx))
+                } else if let Some(thread_id) = open_thread {
+                    this.child(self.render_thread(thread_id, cx))
+                } else if self.active_chat.is_some() {
                     this.child(list(self.message_list.clone()).size_full())
                 } else {
                     this.child(
@@ -830,19 +1363,48 @@ impl Render for ChatPanel {
                     )
                 })
             })
+            .when(is_editing_message, |el| {
+                el.child(
+                    h_flex()
+                        .when(!self.is_scrolled_to_bottom, |el| {
+                            el.border_t_1().border_color(cx.theme().colors().border)
+                        })
+                        .w_full()
+                        .justify_between()
+                        .py_1()
+                        .px_2()
+                        .bg(cx.theme().colors().background)
+                        .child(
+                            Label::new("Editing message")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                        .child(
+                            IconButton::new("cancel-edit", IconName::Close)
+                                .shape(ui::IconButtonShape::Square)
+                                .tooltip(|cx| Tooltip::for_action("Cancel edit", &CancelEdit, cx))
+                                .on_click(cx.listener(move |_, _, cx| {
+                                    cx.dispatch_action(CancelEdit.boxed_clone())
+                                })),
+                        ),
+                )
+            })
             .children(
                 Some(
                     h_flex()
                         .key_context("MessageEditor")
                         .on_action(cx.listener(ChatPanel::close_reply_preview))
+                        .on_action(cx.listener(ChatPanel::cancel_edit))
                         .when(
-                            !self.is_scrolled_to_bottom && reply_to_message_id.is_none(),
+                            !self.is_scrolled_to_bottom
+                                && reply_to_message_id.is_none()
+                                && !is_editing_message,
                             |el| el.border_t_1().border_color(cx.theme().colors().border),
                         )
                         .p_2()
                         .map(|el| el.child(self.message_editor.clone())),
                 )
-                .filter(|_| self.active_chat.is_some()),
+                .filter(|_| self.active_chat.is_some() && !is_searching),
             )
             .into_any()
     }
@@ -850,7 +1412,9 @@ impl Render for ChatPanel {
 
 impl FocusableView for ChatPanel {
     fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
-        if self.active_chat.is_some() {
+        if let Some(search) = self.search.as_ref() {
+            search.editor.focus_handle(cx)
+        } else if self.active_chat.is_some() {
             self.message_editor.read(cx).focus_handle(cx)
         } else {
             self.focus_handle.clone()
@@ -983,6 +1547,8 @@ mod tests {
             nonce: 5,
             mentions: vec![(ranges[0].clone(), 101), (ranges[1].clone(), 102)],
             reply_to_message_id: None,
+            edited_at: None,
+            reactions: Vec::new(),
         };
 
         let message = ChatPanel::render_markdown_with_mentions(&language_registry, 102, &message);
@@ -35,6 +35,7 @@ pub struct MessageEditor {
     mentions_task: Option<Task<()>>,
     channel_id: Option<ChannelId>,
     reply_to_message_id: Option<u64>,
+    edit_message_id: Option<u64>,
 }
 
 struct MessageEditorCompletionProvider(WeakView<MessageEditor>);
@@ -114,6 +115,7 @@ impl MessageEditor {
             mentions: Vec::new(),
             mentions_task: None,
             reply_to_message_id: None,
+            edit_message_id: None,
         }
     }
 
@@ -129,6 +131,34 @@ impl MessageEditor {
         self.reply_to_message_id = None;
     }
 
+    pub fn edit_message_id(&self) -> Option<u64> {
+        self.edit_message_id
+    }
+
+    /// Replaces the editor's contents with the body of a message that's being edited.
+    pub fn start_editing_message(
+        &mut self,
+        message_id: u64,
+        body: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.edit_message_id = Some(message_id);
+        self.reply_to_message_id = None;
+        self.editor
+            .update(cx, |editor, cx| editor.set_text(body, cx));
+    }
+
+    pub fn cancel_editing_message(&mut self, cx: &mut ViewContext<Self>) {
+        if self.edit_message_id.take().is_some() {
+            self.editor.update(cx, |editor, cx| editor.clear(cx));
+            self.mentions.clear();
+        }
+    }
+
+    pub fn take_edit_message_id(&mut self) -> Option<u64> {
+        self.edit_message_id.take()
+    }
+
     pub fn set_channel(
         &mut self,
         channel_id: u64,
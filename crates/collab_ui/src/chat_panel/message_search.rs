@@ -0,0 +1,71 @@
+use time::{Date, Month};
+
+/// A query typed into the chat panel's message search. Besides the words to search
+/// for, it can contain `from:<login>`, `in:<channel>`, `after:<yyyy-mm-dd>` and
+/// `before:<yyyy-mm-dd>` filters.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ParsedSearchQuery {
+    pub text: String,
+    pub sender_login: Option<String>,
+    pub channel_name: Option<String>,
+    pub sent_after: Option<Date>,
+    pub sent_before: Option<Date>,
+}
+
+impl ParsedSearchQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut result = Self::default();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            if let Some(login) = word.strip_prefix("from:") {
+                result.sender_login = Some(login.trim_start_matches('@').to_string());
+            } else if let Some(channel) = word.strip_prefix("in:") {
+                result.channel_name = Some(channel.trim_start_matches('#').to_string());
+            } else if let Some(date) = word.strip_prefix("after:").and_then(parse_date) {
+                result.sent_after = Some(date);
+            } else if let Some(date) = word.strip_prefix("before:").and_then(parse_date) {
+                result.sent_before = Some(date);
+            } else {
+                words.push(word);
+            }
+        }
+        result.text = words.join(" ");
+        result
+    }
+}
+
+fn parse_date(date: &str) -> Option<Date> {
+    let mut components = date.splitn(3, '-');
+    let year = components.next()?.parse().ok()?;
+    let month = Month::try_from(components.next()?.parse::<u8>().ok()?).ok()?;
+    let day = components.next()?.parse().ok()?;
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_query() {
+        assert_eq!(
+            ParsedSearchQuery::parse("deploy  failed from:@nathan in:#zed after:2024-01-31"),
+            ParsedSearchQuery {
+                text: "deploy failed".into(),
+                sender_login: Some("nathan".into()),
+                channel_name: Some("zed".into()),
+                sent_after: Some(Date::from_calendar_date(2024, Month::January, 31).unwrap()),
+                sent_before: None,
+            }
+        );
+
+        // Filters with invalid dates are searched for as words.
+        assert_eq!(
+            ParsedSearchQuery::parse("before:2024-02-30 release"),
+            ParsedSearchQuery {
+                text: "before:2024-02-30 release".into(),
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -30,7 +30,7 @@ use rpc::{
 use serde_derive::{Deserialize, Serialize};
 use settings::Settings;
 use smallvec::SmallVec;
-use std::{mem, sync::Arc};
+use std::{mem, path::PathBuf, sync::Arc};
 use theme::{ActiveTheme, ThemeSettings};
 use ui::{
     prelude::*, tooltip_container, Avatar, AvatarAvailabilityIndicator, Button, Color, ContextMenu,
@@ -40,7 +40,7 @@ use util::{maybe, ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
     notifications::{DetachAndPromptErr, NotifyResultExt, NotifyTaskExt},
-    OpenChannelNotes, Workspace,
+    project_link_for_path, OpenChannelNotes, Workspace,
 };
 
 actions!(
@@ -1154,6 +1154,25 @@ impl CollabPanel {
                         cx.handler_for(&this, move |this, cx| this.rename_channel(channel_id, cx)),
                     );
 
+                if self.current_project_root(cx).is_some() {
+                    context_menu = context_menu
+                        .separator()
+                        .entry(
+                            "Link Current Project",
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.link_current_project(channel_id, cx)
+                            }),
+                        )
+                        .entry(
+                            "Unlink Current Project",
+                            None,
+                            cx.handler_for(&this, move |this, cx| {
+                                this.unlink_current_project(channel_id, cx)
+                            }),
+                        );
+                }
+
                 if let Some(channel_name) = clipboard_channel_name {
                     context_menu = context_menu.separator().entry(
                         format!("Move '#{}' here", channel_name),
@@ -1652,6 +1671,67 @@ impl CollabPanel {
             });
     }
 
+    fn current_project_root(&self, cx: &AppContext) -> Option<PathBuf> {
+        let workspace = self.workspace.upgrade()?;
+        let project = workspace.read(cx).project().read(cx);
+        if !project.is_local() {
+            return None;
+        }
+        let worktree = project.visible_worktrees(cx).next()?;
+        Some(worktree.read(cx).abs_path().to_path_buf())
+    }
+
+    fn link_current_project(&mut self, channel_id: ChannelId, cx: &mut ViewContext<Self>) {
+        let Some(abs_path) = self.current_project_root(cx) else {
+            return;
+        };
+        let fs = self.fs.clone();
+        let channel_store = self.channel_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let (remote_url, path) = project_link_for_path(fs.as_ref(), &abs_path)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("{} has no origin remote", abs_path.display()))?;
+            channel_store
+                .update(&mut cx, |channel_store, cx| {
+                    channel_store.link_project(channel_id, remote_url, path, cx)
+                })?
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to link project", cx, |_, _| None);
+    }
+
+    fn unlink_current_project(&mut self, channel_id: ChannelId, cx: &mut ViewContext<Self>) {
+        let Some(abs_path) = self.current_project_root(cx) else {
+            return;
+        };
+        let fs = self.fs.clone();
+        let channel_store = self.channel_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let (remote_url, path) = project_link_for_path(fs.as_ref(), &abs_path)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("{} has no origin remote", abs_path.display()))?;
+            let links = channel_store
+                .update(&mut cx, |channel_store, cx| {
+                    channel_store.project_links(channel_id, cx)
+                })?
+                .await?;
+            let link = links
+                .into_iter()
+                .find(|link| link.remote_url == remote_url && link.path == path)
+                .ok_or_else(|| {
+                    anyhow::anyhow!("the current project isn't linked to this channel")
+                })?;
+            channel_store
+                .update(&mut cx, |channel_store, cx| {
+                    channel_store.unlink_project(link.id, cx)
+                })?
+                .await?;
+            anyhow::Ok(())
+        })
+        .detach_and_prompt_err("Failed to unlink project", cx, |_, _| None);
+    }
+
     fn start_move_channel(&mut self, channel_id: ChannelId, _cx: &mut ViewContext<Self>) {
         self.channel_clipboard = Some(ChannelMoveClipboard { channel_id });
     }
@@ -6,6 +6,7 @@ mod face_pile;
 pub mod notification_panel;
 pub mod notifications;
 mod panel_settings;
+pub mod review_panel;
 
 use std::{rc::Rc, sync::Arc};
 
@@ -17,7 +18,7 @@ use gpui::{
     WindowContext, WindowKind, WindowOptions,
 };
 pub use panel_settings::{
-    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings,
+    ChatPanelSettings, CollaborationPanelSettings, NotificationPanelSettings, ReviewPanelSettings,
 };
 use settings::Settings;
 use workspace::{notifications::DetachAndPromptErr, AppState};
@@ -31,6 +32,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     CollaborationPanelSettings::register(cx);
     ChatPanelSettings::register(cx);
     NotificationPanelSettings::register(cx);
+    ReviewPanelSettings::register(cx);
 
     vcs_menu::init(cx);
     collab_titlebar_item::init(cx);
@@ -38,6 +40,7 @@ pub fn init(app_state: &Arc<AppState>, cx: &mut AppContext) {
     channel_view::init(cx);
     chat_panel::init(cx);
     notification_panel::init(cx);
+    review_panel::init(cx);
     notifications::init(&app_state, cx);
 }
 
@@ -758,7 +758,7 @@ impl Render for NotificationToast {
 
 impl EventEmitter<DismissEvent> for NotificationToast {}
 
-fn format_timestamp(
+pub(crate) fn format_timestamp(
     mut timestamp: OffsetDateTime,
     mut now: OffsetDateTime,
     local_timezone: UtcOffset,
@@ -0,0 +1,283 @@
+use crate::{notification_panel::format_timestamp, ReviewPanelSettings};
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, Action, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Render,
+    Subscription, View, WeakView,
+};
+use language::{ToOffset, ToPoint};
+use project::{
+    review_threads::{ReviewThread, ReviewThreadId},
+    Fs, Project,
+};
+use settings::Settings;
+use std::sync::Arc;
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+actions!(review_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<ReviewPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// A panel listing the review threads of the current project, from which they can be
+/// opened in an editor.
+pub struct ReviewPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    show_resolved: bool,
+    local_timezone: UtcOffset,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ReviewPanel {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let weak_workspace = cx.view().downgrade();
+        cx.new_view(|cx| {
+            let subscriptions = vec![cx.subscribe(&project, |_, _, event, cx| {
+                if let project::Event::ReviewThreadUpdated(_) = event {
+                    cx.notify();
+                }
+            })];
+            Self {
+                workspace: weak_workspace,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                show_resolved: false,
+                local_timezone: cx.local_timezone(),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    fn open_thread(&mut self, id: ReviewThreadId, cx: &mut ViewContext<Self>) {
+        let Some(thread) = self.project.read(cx).review_thread(id).cloned() else {
+            return;
+        };
+        let workspace = self.workspace.clone();
+        let buffer = self.project.update(cx, |project, cx| {
+            project.open_buffer_by_id(thread.buffer_id, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let buffer = buffer.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let editor = workspace.open_project_item::<Editor>(buffer.clone(), cx);
+                let snapshot = buffer.read(cx).snapshot();
+                let range =
+                    thread.range.start.to_offset(&snapshot)..thread.range.end.to_offset(&snapshot);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([range])
+                    });
+                });
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_thread(
+        &self,
+        thread: &ReviewThread,
+        now: OffsetDateTime,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let location = thread
+            .project_path
+            .as_ref()
+            .map(|project_path| {
+                let path = project_path.path.to_string_lossy().to_string();
+                match project.buffer_for_id(thread.buffer_id) {
+                    Some(buffer) => {
+                        let row = thread.range.start.to_point(buffer.read(cx)).row;
+                        format!("{path}:{}", row + 1)
+                    }
+                    None => path,
+                }
+            })
+            .unwrap_or_else(|| "untitled".to_string());
+        let first_comment = thread.comments.first();
+        let author = first_comment
+            .and_then(|comment| comment.author_id)
+            .and_then(|id| project.user_store().read(cx).get_cached_user(id))
+            .map(|user| format!("@{}", user.github_login))
+            .unwrap_or_else(|| "Unknown user".to_string());
+        let body = first_comment
+            .and_then(|comment| comment.body.lines().next())
+            .unwrap_or_default();
+        let updated_at = thread
+            .comments
+            .last()
+            .map(|comment| {
+                format_timestamp(
+                    OffsetDateTime::from(comment.timestamp),
+                    now,
+                    self.local_timezone,
+                )
+            })
+            .unwrap_or_default();
+        let details = match thread.comments.len() {
+            0 | 1 => format!("{author}: {body} · {updated_at}"),
+            count => format!("{author}: {body} · {} replies · {updated_at}", count - 1),
+        };
+        let id = thread.id;
+
+        ListItem::new(("review-thread", id))
+            .spacing(ListItemSpacing::Sparse)
+            .start_slot(
+                Icon::new(IconName::MessageBubbles).color(if thread.resolved {
+                    Color::Muted
+                } else {
+                    Color::Accent
+                }),
+            )
+            .on_click(cx.listener(move |this, _, cx| this.open_thread(id, cx)))
+            .child(
+                v_flex().child(Label::new(location)).child(
+                    Label::new(details)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+    }
+}
+
+impl Render for ReviewPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let now = OffsetDateTime::now_utc();
+        let threads = self
+            .project
+            .read(cx)
+            .review_threads()
+            .filter(|thread| self.show_resolved || !thread.resolved)
+            .cloned()
+            .collect::<Vec<_>>();
+        let empty_message = threads.is_empty().then(|| {
+            if self.show_resolved {
+                "No one has commented on this project."
+            } else {
+                "There are no open review threads. Add one with the editor's \"Add Review Comment\" action."
+            }
+        });
+
+        v_flex()
+            .key_context("ReviewPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Review Threads"))
+                    .child(
+                        Button::new("show_resolved", "Show Resolved")
+                            .label_size(LabelSize::Small)
+                            .selected(self.show_resolved)
+                            .on_click(cx.listener(|this, _, cx| {
+                                this.show_resolved = !this.show_resolved;
+                                cx.notify();
+                            })),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("review_threads")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        empty_message.map(|message| {
+                            div().p_2().child(Label::new(message).color(Color::Muted))
+                        }),
+                    )
+                    .children(
+                        threads
+                            .iter()
+                            .map(|thread| self.render_thread(thread, now, cx)),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReviewPanel {}
+
+impl FocusableView for ReviewPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for ReviewPanel {
+    fn persistent_name() -> &'static str {
+        "ReviewPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        ReviewPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<ReviewPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| ReviewPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        ReviewPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::Quote)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Review Panel")
+    }
+
+    fn icon_label(&self, cx: &WindowContext) -> Option<String> {
+        let count = self
+            .project
+            .read(cx)
+            .review_threads()
+            .filter(|thread| !thread.resolved)
+            .count();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,194 @@
+use collections::HashSet;
+use language::{Diagnostic, DiagnosticSeverity};
+use lsp::LanguageServerId;
+use project::ProjectPath;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::paths::PathMatcher;
+
+/// How the groups of diagnostics in the project diagnostics view are arranged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupBy {
+    /// List every file's diagnostics together, in path order.
+    #[default]
+    File,
+    /// List errors first, then warnings, and so on.
+    Severity,
+    /// List diagnostics with the same code together.
+    Code,
+}
+
+impl GroupBy {
+    pub const ALL: [Self; 3] = [Self::File, Self::Severity, Self::Code];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::File => "File",
+            Self::Severity => "Severity",
+            Self::Code => "Code",
+        }
+    }
+}
+
+/// The section of the project diagnostics view that a group of diagnostics is listed in.
+/// Sections are ordered before the paths within them.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Section {
+    All,
+    Severity(DiagnosticSeverity),
+    /// Diagnostics without a code are listed after those with one.
+    Code {
+        missing: bool,
+        code: String,
+    },
+}
+
+impl Section {
+    pub fn for_diagnostic(diagnostic: &Diagnostic, group_by: GroupBy) -> Self {
+        match group_by {
+            GroupBy::File => Self::All,
+            GroupBy::Severity => Self::Severity(diagnostic.severity),
+            GroupBy::Code => Self::Code {
+                missing: diagnostic.code.is_none(),
+                code: diagnostic.code.clone().unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// Restricts which diagnostics are shown in the project diagnostics view.
+#[derive(Clone, Debug)]
+pub(crate) struct DiagnosticFilter {
+    /// The least severe diagnostics to show.
+    pub max_severity: DiagnosticSeverity,
+    pub excluded_sources: HashSet<String>,
+    pub excluded_language_servers: HashSet<LanguageServerId>,
+    pub path_matchers: Vec<PathMatcher>,
+    /// Only show diagnostics whose message contains this text, ignoring case.
+    pub message_query: String,
+    /// Only show diagnostics in this file.
+    pub path: Option<ProjectPath>,
+}
+
+impl DiagnosticFilter {
+    pub fn new(include_warnings: bool) -> Self {
+        Self {
+            max_severity: if include_warnings {
+                DiagnosticSeverity::WARNING
+            } else {
+                DiagnosticSeverity::ERROR
+            },
+            excluded_sources: HashSet::default(),
+            excluded_language_servers: HashSet::default(),
+            path_matchers: Vec::new(),
+            message_query: String::new(),
+            path: None,
+        }
+    }
+
+    pub fn matches_path(&self, path: &ProjectPath) -> bool {
+        if self
+            .path
+            .as_ref()
+            .map_or(false, |filter_path| filter_path != path)
+        {
+            return false;
+        }
+        self.path_matchers.is_empty()
+            || self
+                .path_matchers
+                .iter()
+                .any(|matcher| matcher.is_match(&path.path))
+    }
+
+    pub fn matches(&self, language_server_id: LanguageServerId, diagnostic: &Diagnostic) -> bool {
+        if diagnostic.severity > self.max_severity
+            || self.excluded_language_servers.contains(&language_server_id)
+        {
+            return false;
+        }
+        if let Some(source) = &diagnostic.source {
+            if self.excluded_sources.contains(source) {
+                return false;
+            }
+        }
+        self.message_query.is_empty()
+            || diagnostic
+                .message
+                .to_lowercase()
+                .contains(&self.message_query.to_lowercase())
+    }
+
+    /// Parses a comma-separated list of globs, ignoring the ones that are invalid.
+    pub fn parse_path_matchers(text: &str) -> Vec<PathMatcher> {
+        text.split(',')
+            .map(str::trim)
+            .filter(|glob| !glob.is_empty())
+            .filter_map(|glob| PathMatcher::new(glob).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use project::WorktreeId;
+    use std::{path::Path, sync::Arc};
+
+    #[test]
+    fn test_diagnostic_filter() {
+        let diagnostic = |severity, source: &str, message: &str| Diagnostic {
+            severity,
+            source: Some(source.to_string()),
+            message: message.to_string(),
+            ..Default::default()
+        };
+        let server_1 = LanguageServerId(1);
+        let server_2 = LanguageServerId(2);
+
+        let mut filter = DiagnosticFilter::new(false);
+        assert!(filter.matches(
+            server_1,
+            &diagnostic(DiagnosticSeverity::ERROR, "rustc", "a")
+        ));
+        assert!(!filter.matches(
+            server_1,
+            &diagnostic(DiagnosticSeverity::WARNING, "rustc", "a")
+        ));
+
+        filter.max_severity = DiagnosticSeverity::HINT;
+        filter.excluded_sources.insert("clippy".to_string());
+        filter.excluded_language_servers.insert(server_2);
+        filter.message_query = "Unused".to_string();
+        assert!(filter.matches(
+            server_1,
+            &diagnostic(DiagnosticSeverity::HINT, "rustc", "unused variable")
+        ));
+        assert!(!filter.matches(
+            server_1,
+            &diagnostic(DiagnosticSeverity::WARNING, "clippy", "unused variable")
+        ));
+        assert!(!filter.matches(
+            server_2,
+            &diagnostic(DiagnosticSeverity::ERROR, "rustc", "unused variable")
+        ));
+        assert!(!filter.matches(
+            server_1,
+            &diagnostic(DiagnosticSeverity::ERROR, "rustc", "mismatched types")
+        ));
+
+        let path = |path: &str| ProjectPath {
+            worktree_id: WorktreeId::from_usize(0),
+            path: Arc::from(Path::new(path)),
+        };
+        filter.path_matchers = DiagnosticFilter::parse_path_matchers("src/**/*.rs, , tests/*");
+        assert!(filter.matches_path(&path("src/a/b.rs")));
+        assert!(filter.matches_path(&path("tests/c.rs")));
+        assert!(!filter.matches_path(&path("benches/d.rs")));
+
+        filter.path = Some(path("src/a/b.rs"));
+        assert!(filter.matches_path(&path("src/a/b.rs")));
+        assert!(!filter.matches_path(&path("tests/c.rs")));
+    }
+}
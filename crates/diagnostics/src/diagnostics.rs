@@ -1,9 +1,13 @@
+mod diagnostic_filter;
+mod filter_bar;
 pub mod items;
 mod project_diagnostics_settings;
 mod toolbar_controls;
 
-use anyhow::{Context as _, Result};
-use collections::{HashMap, HashSet};
+use anyhow::{anyhow, Context as _, Result};
+use collections::{BTreeSet, HashMap, HashSet};
+pub use diagnostic_filter::GroupBy;
+use diagnostic_filter::{DiagnosticFilter, Section};
 use editor::{
     diagnostic_block_renderer,
     display_map::{BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock},
@@ -11,6 +15,7 @@ use editor::{
     scroll::Autoscroll,
     Editor, EditorEvent, ExcerptId, ExcerptRange, MultiBuffer, ToOffset,
 };
+pub use filter_bar::DiagnosticsFilterBar;
 use futures::future::try_join_all;
 use gpui::{
     actions, div, svg, AnyElement, AnyView, AppContext, Context, EventEmitter, FocusHandle,
@@ -25,6 +30,7 @@ use language::{
 use lsp::LanguageServerId;
 use project::{DiagnosticSummary, Project, ProjectPath};
 use project_diagnostics_settings::ProjectDiagnosticsSettings;
+use rpc::proto::{self, PeerId};
 use settings::Settings;
 use std::{
     any::{Any, TypeId},
@@ -39,8 +45,8 @@ pub use toolbar_controls::ToolbarControls;
 use ui::{h_flex, prelude::*, Icon, IconName, Label};
 use util::TryFutureExt;
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
-    ItemNavHistory, Pane, ToolbarItemLocation, Workspace,
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle},
+    ItemNavHistory, Pane, ToolbarItemLocation, ViewId, Workspace,
 };
 
 actions!(diagnostics, [Deploy, ToggleWarnings]);
@@ -49,6 +55,7 @@ const CONTEXT_LINE_COUNT: u32 = 1;
 
 pub fn init(cx: &mut AppContext) {
     ProjectDiagnosticsSettings::register(cx);
+    workspace::register_followable_item::<ProjectDiagnosticsEditor>(cx);
     cx.observe_new_views(ProjectDiagnosticsEditor::register)
         .detach();
 }
@@ -63,11 +70,20 @@ struct ProjectDiagnosticsEditor {
     path_states: Vec<PathState>,
     paths_to_update: HashMap<LanguageServerId, HashSet<ProjectPath>>,
     current_diagnostics: HashMap<LanguageServerId, HashSet<ProjectPath>>,
-    include_warnings: bool,
+    filter: DiagnosticFilter,
+    group_by: GroupBy,
+    /// The sources of all diagnostics seen so far, which can be excluded by the filter.
+    sources: BTreeSet<String>,
+    path_filter_editor: View<Editor>,
+    message_filter_editor: View<Editor>,
+    remote_id: Option<ViewId>,
+    /// A scroll position received from the leader that the excerpts don't contain yet.
+    pending_scroll_position: Option<proto::BufferScrollPosition>,
     _subscriptions: Vec<Subscription>,
 }
 
 struct PathState {
+    section: Section,
     path: ProjectPath,
     diagnostic_groups: Vec<DiagnosticGroupState>,
 }
@@ -171,8 +187,28 @@ impl ProjectDiagnosticsEditor {
                 }
             });
 
+        let path_filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Include files, e.g. src/**/*.rs", cx);
+            editor
+        });
+        let message_filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter messages", cx);
+            editor
+        });
+        let filter_editor_subscriptions =
+            [&path_filter_editor, &message_filter_editor].map(|editor| {
+                cx.subscribe(editor, |this, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.update_text_filters(cx);
+                    }
+                })
+            });
+
         let project = project_handle.read(cx);
         let summary = project.diagnostic_summary(false, cx);
+        let settings = ProjectDiagnosticsSettings::get_global(cx);
         let mut this = Self {
             project: project_handle,
             summary,
@@ -182,14 +218,21 @@ impl ProjectDiagnosticsEditor {
             editor,
             path_states: Default::default(),
             paths_to_update: HashMap::default(),
-            include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
+            filter: DiagnosticFilter::new(settings.include_warnings),
+            group_by: settings.group_by,
+            sources: BTreeSet::default(),
+            path_filter_editor,
+            message_filter_editor,
             current_diagnostics: HashMap::default(),
+            remote_id: None,
+            pending_scroll_position: None,
             _subscriptions: vec![
                 project_event_subscription,
                 editor_event_subscription,
                 focus_in_subscription,
             ],
         };
+        this._subscriptions.extend(filter_editor_subscriptions);
         this.update_excerpts(None, cx);
         this
     }
@@ -206,8 +249,76 @@ impl ProjectDiagnosticsEditor {
         }
     }
 
+    fn include_warnings(&self) -> bool {
+        self.filter.max_severity >= DiagnosticSeverity::WARNING
+    }
+
     fn toggle_warnings(&mut self, _: &ToggleWarnings, cx: &mut ViewContext<Self>) {
-        self.include_warnings = !self.include_warnings;
+        self.filter.max_severity = if self.include_warnings() {
+            DiagnosticSeverity::ERROR
+        } else {
+            DiagnosticSeverity::WARNING
+        };
+        self.refilter(cx);
+    }
+
+    fn set_max_severity(&mut self, max_severity: DiagnosticSeverity, cx: &mut ViewContext<Self>) {
+        self.filter.max_severity = max_severity;
+        self.refilter(cx);
+    }
+
+    fn toggle_source(&mut self, source: &str, cx: &mut ViewContext<Self>) {
+        if !self.filter.excluded_sources.remove(source) {
+            self.filter.excluded_sources.insert(source.to_string());
+        }
+        self.refilter(cx);
+    }
+
+    fn toggle_language_server(&mut self, server_id: LanguageServerId, cx: &mut ViewContext<Self>) {
+        if !self.filter.excluded_language_servers.remove(&server_id) {
+            self.filter.excluded_language_servers.insert(server_id);
+        }
+        self.refilter(cx);
+    }
+
+    fn set_group_by(&mut self, group_by: GroupBy, cx: &mut ViewContext<Self>) {
+        if self.group_by != group_by {
+            self.group_by = group_by;
+            self.refilter(cx);
+        }
+    }
+
+    /// Restricts the diagnostics to the file under the cursor in the active editor, or shows
+    /// every file again. When this view is active, that's the file of the excerpt under its
+    /// own cursor.
+    fn toggle_current_file_only(&mut self, cx: &mut ViewContext<Self>) {
+        self.filter.path = if self.filter.path.is_some() {
+            None
+        } else {
+            let Some(workspace) = self.workspace.upgrade() else {
+                return;
+            };
+            let Some(path) = workspace
+                .read(cx)
+                .active_item(cx)
+                .and_then(|item| active_editor_path(&item.act_as::<Editor>(cx)?, cx))
+            else {
+                return;
+            };
+            Some(path)
+        };
+        self.refilter(cx);
+    }
+
+    fn update_text_filters(&mut self, cx: &mut ViewContext<Self>) {
+        let path_filter = self.path_filter_editor.read(cx).text(cx);
+        self.filter.path_matchers = DiagnosticFilter::parse_path_matchers(&path_filter);
+        self.filter.message_query = self.message_filter_editor.read(cx).text(cx);
+        self.refilter(cx);
+    }
+
+    /// Rechecks every path with diagnostics after the filter or grouping changed.
+    fn refilter(&mut self, cx: &mut ViewContext<Self>) {
         self.paths_to_update = self.current_diagnostics.clone();
         self.update_excerpts(None, cx);
         cx.notify();
@@ -326,14 +437,59 @@ impl ProjectDiagnosticsEditor {
         buffer: Model<Buffer>,
         cx: &mut ViewContext<Self>,
     ) {
-        let was_empty = self.path_states.is_empty();
         let snapshot = buffer.read(cx).snapshot();
-        let path_ix = match self.path_states.binary_search_by_key(&&path, |e| &e.path) {
+        let groups = snapshot.diagnostic_groups(language_server_id);
+        self.sources.extend(
+            groups
+                .iter()
+                .filter_map(|(_, group)| group.entries[group.primary_ix].diagnostic.source.clone()),
+        );
+
+        // Update the sections that the path's diagnostics were listed in before, as well as
+        // those they belong in now.
+        let mut sections = self
+            .path_states
+            .iter()
+            .filter(|state| state.path == path)
+            .map(|state| state.section.clone())
+            .collect::<BTreeSet<_>>();
+        if self.filter.matches_path(&path) {
+            sections.extend(groups.iter().map(|(_, group)| {
+                Section::for_diagnostic(&group.entries[group.primary_ix].diagnostic, self.group_by)
+            }));
+        }
+        for section in sections {
+            self.populate_section_excerpts(
+                section,
+                path.clone(),
+                language_server_id,
+                &buffer,
+                &snapshot,
+                cx,
+            );
+        }
+    }
+
+    fn populate_section_excerpts(
+        &mut self,
+        section: Section,
+        path: ProjectPath,
+        language_server_id: Option<LanguageServerId>,
+        buffer: &Model<Buffer>,
+        snapshot: &language::BufferSnapshot,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let was_empty = self.path_states.is_empty();
+        let path_ix = match self
+            .path_states
+            .binary_search_by(|probe| (&probe.section, &probe.path).cmp(&(&section, &path)))
+        {
             Ok(ix) => ix,
             Err(ix) => {
                 self.path_states.insert(
                     ix,
                     PathState {
+                        section: section.clone(),
                         path: path.clone(),
                         diagnostic_groups: Default::default(),
                     },
@@ -358,18 +514,19 @@ impl ProjectDiagnosticsEditor {
         let mut blocks_to_add = Vec::new();
         let mut blocks_to_remove = HashSet::default();
         let mut first_excerpt_id = None;
-        let max_severity = if self.include_warnings {
-            DiagnosticSeverity::WARNING
-        } else {
-            DiagnosticSeverity::ERROR
-        };
+        let filter = &self.filter;
+        let path_matches = filter.matches_path(&path);
+        let group_by = self.group_by;
         let excerpts_snapshot = self.excerpts.update(cx, |excerpts, excerpts_cx| {
             let mut old_groups = path_state.diagnostic_groups.iter().enumerate().peekable();
             let mut new_groups = snapshot
                 .diagnostic_groups(language_server_id)
                 .into_iter()
-                .filter(|(_, group)| {
-                    group.entries[group.primary_ix].diagnostic.severity <= max_severity
+                .filter(|(language_server_id, group)| {
+                    let primary = &group.entries[group.primary_ix].diagnostic;
+                    path_matches
+                        && filter.matches(*language_server_id, primary)
+                        && Section::for_diagnostic(primary, group_by) == section
                 })
                 .peekable();
             loop {
@@ -390,7 +547,7 @@ impl ProjectDiagnosticsEditor {
                     (Some((_, old_group)), Some((_, new_group))) => {
                         let old_primary = &old_group.primary_diagnostic;
                         let new_primary = &new_group.entries[new_group.primary_ix];
-                        match compare_diagnostics(old_primary, new_primary, &snapshot) {
+                        match compare_diagnostics(old_primary, new_primary, snapshot) {
                             Ordering::Less => {
                                 if language_server_id
                                     .map_or(true, |id| id == old_group.language_server_id)
@@ -421,7 +578,7 @@ impl ProjectDiagnosticsEditor {
                     let mut pending_range: Option<(Range<Point>, usize)> = None;
                     let mut is_first_excerpt_for_group = true;
                     for (ix, entry) in group.entries.iter().map(Some).chain([None]).enumerate() {
-                        let resolved_entry = entry.map(|e| e.resolve::<Point>(&snapshot));
+                        let resolved_entry = entry.map(|e| e.resolve::<Point>(snapshot));
                         if let Some((range, start_ix)) = &mut pending_range {
                             if let Some(entry) = resolved_entry.as_ref() {
                                 if entry.range.start.row
@@ -546,8 +703,8 @@ impl ProjectDiagnosticsEditor {
             let range_b = &b.primary_diagnostic.range;
             range_a
                 .start
-                .cmp(&range_b.start, &snapshot)
-                .then_with(|| range_a.end.cmp(&range_b.end, &snapshot))
+                .cmp(&range_b.start, snapshot)
+                .then_with(|| range_a.end.cmp(&range_b.end, snapshot))
         });
 
         if path_state.diagnostic_groups.is_empty() {
@@ -614,8 +771,61 @@ impl ProjectDiagnosticsEditor {
             let focus_handle = self.editor.focus_handle(cx);
             cx.focus(&focus_handle);
         }
+        self.scroll_to_leader_position(cx);
         cx.notify();
     }
+
+    /// Shows the same severities and filters as the leader and scrolls to where the leader
+    /// is looking.
+    fn apply_leader_state(
+        &mut self,
+        include_warnings: Option<bool>,
+        path_filter: Option<String>,
+        message_filter: Option<String>,
+        scroll_position: Option<proto::BufferScrollPosition>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(include_warnings) = include_warnings {
+            if include_warnings != self.include_warnings() {
+                self.set_max_severity(
+                    if include_warnings {
+                        DiagnosticSeverity::WARNING
+                    } else {
+                        DiagnosticSeverity::ERROR
+                    },
+                    cx,
+                );
+            }
+        }
+        // Editing the filters refilters the diagnostics.
+        for (filter_editor, filter) in [
+            (self.path_filter_editor.clone(), path_filter),
+            (self.message_filter_editor.clone(), message_filter),
+        ] {
+            if let Some(filter) = filter {
+                filter_editor.update(cx, |editor, cx| {
+                    if editor.text(cx) != filter {
+                        editor.set_text(filter, cx);
+                    }
+                });
+            }
+        }
+        if let Some(scroll_position) = scroll_position {
+            self.pending_scroll_position = Some(scroll_position);
+            self.scroll_to_leader_position(cx);
+        }
+    }
+
+    fn scroll_to_leader_position(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(position) = self.pending_scroll_position.take() {
+            let scrolled = self.editor.update(cx, |editor, cx| {
+                editor.set_buffer_scroll_position_remote(&position, cx)
+            });
+            if !scrolled {
+                self.pending_scroll_position = Some(position);
+            }
+        }
+    }
 }
 
 impl FocusableView for ProjectDiagnosticsEditor {
@@ -795,6 +1005,132 @@ impl Item for ProjectDiagnosticsEditor {
     }
 }
 
+impl FollowableItem for ProjectDiagnosticsEditor {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::Diagnostics(
+            proto::view::Diagnostics {
+                include_warnings: self.include_warnings(),
+                scroll_position: Some(self.editor.read(cx).buffer_scroll_position()),
+                path_filter: self.path_filter_editor.read(cx).text(cx),
+                message_filter: self.message_filter_editor.read(cx).text(cx),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::Diagnostics(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Diagnostics(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing_view = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        let view = existing_view.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let workspace = workspace.downgrade();
+            cx.new_view(|cx| {
+                let mut view = Self::new(project, workspace, cx);
+                view.remote_id = Some(remote_id);
+                view
+            })
+        });
+        view.update(cx, |view, cx| {
+            view.apply_leader_state(
+                Some(state.include_warnings),
+                Some(state.path_filter),
+                Some(state.message_filter),
+                state.scroll_position,
+                cx,
+            )
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(event: &EditorEvent) -> Option<FollowEvent> {
+        match event {
+            EditorEvent::Edited
+            | EditorEvent::ScrollPositionChanged {
+                local: true,
+                autoscroll: false,
+            } => Some(FollowEvent::Unfollow),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &EditorEvent,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let update = update
+            .get_or_insert_with(|| proto::update_view::Variant::Diagnostics(Default::default()));
+        let proto::update_view::Variant::Diagnostics(update) = update else {
+            return false;
+        };
+
+        match event {
+            // The excerpts change whenever the severities or paths being shown do.
+            EditorEvent::ExcerptsAdded { .. } | EditorEvent::ExcerptsRemoved { .. } => {
+                update.include_warnings = Some(self.include_warnings());
+                update.path_filter = Some(self.path_filter_editor.read(cx).text(cx));
+                update.message_filter = Some(self.message_filter_editor.read(cx).text(cx));
+                true
+            }
+            EditorEvent::ScrollPositionChanged { .. } => {
+                update.scroll_position = Some(self.editor.read(cx).buffer_scroll_position());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::Diagnostics(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-diagnostics update for project diagnostics"
+            )));
+        };
+        self.apply_leader_state(
+            message.include_warnings,
+            message.path_filter,
+            message.message_filter,
+            message.scroll_position,
+            cx,
+        );
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        });
+    }
+}
+
 fn diagnostic_header_renderer(diagnostic: Diagnostic) -> RenderBlock {
     let (message, code_ranges) = highlight_diagnostic_message(&diagnostic);
     let message: SharedString = message.into();
@@ -862,6 +1198,21 @@ fn diagnostic_header_renderer(diagnostic: Diagnostic) -> RenderBlock {
     })
 }
 
+/// Returns the path of the file containing the newest cursor of the given editor.
+fn active_editor_path(editor: &View<Editor>, cx: &AppContext) -> Option<ProjectPath> {
+    let editor = editor.read(cx);
+    let head = editor.selections.newest_anchor().head();
+    let (buffer, _) = editor
+        .buffer()
+        .read(cx)
+        .text_anchor_for_position(head, cx)?;
+    let file = project::File::from_dyn(buffer.read(cx).file())?;
+    Some(ProjectPath {
+        worktree_id: file.worktree_id(cx),
+        path: file.path.clone(),
+    })
+}
+
 fn compare_diagnostics<L: language::ToOffset, R: language::ToOffset>(
     lhs: &DiagnosticEntry<L>,
     rhs: &DiagnosticEntry<R>,
@@ -892,6 +1243,7 @@ mod tests {
     use project::FakeFs;
     use serde_json::json;
     use settings::SettingsStore;
+    use std::{cell::RefCell, rc::Rc};
     use unindent::Unindent as _;
 
     #[gpui::test]
@@ -1569,6 +1921,269 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_diagnostics_grouping(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/test",
+            json!({
+                "a.rs": "a1();\na2();\na3();\na4();\na5();\n",
+                "b.rs": "b1();\nb2();\nb3();\nb4();\nb5();\n",
+            }),
+        )
+        .await;
+
+        let language_server_id = LanguageServerId(0);
+        let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+
+        let view = window.build_view(cx, |cx| {
+            ProjectDiagnosticsEditor::new(project.clone(), workspace.downgrade(), cx)
+        });
+
+        // Each file has a warning on its first line and an error on its last.
+        project.update(cx, |project, cx| {
+            project.disk_based_diagnostics_started(language_server_id, cx);
+            for (path, name) in [("/test/a.rs", "a"), ("/test/b.rs", "b")] {
+                let entry = |row, severity, message: String, group_id| DiagnosticEntry {
+                    range: Unclipped(PointUtf16::new(row, 0))..Unclipped(PointUtf16::new(row, 1)),
+                    diagnostic: Diagnostic {
+                        message,
+                        severity,
+                        is_primary: true,
+                        is_disk_based: true,
+                        group_id,
+                        ..Default::default()
+                    },
+                };
+                project
+                    .update_diagnostic_entries(
+                        language_server_id,
+                        PathBuf::from(path),
+                        None,
+                        vec![
+                            entry(0, DiagnosticSeverity::WARNING, format!("{name} warning"), 0),
+                            entry(4, DiagnosticSeverity::ERROR, format!("{name} error"), 1),
+                        ],
+                        cx,
+                    )
+                    .unwrap();
+            }
+            project.disk_based_diagnostics_finished(language_server_id, cx);
+        });
+        cx.executor().run_until_parked();
+
+        let sections = |view: &ProjectDiagnosticsEditor| {
+            view.path_states
+                .iter()
+                .map(|state| {
+                    (
+                        state.section.clone(),
+                        state.path.path.to_string_lossy().into_owned(),
+                        state.diagnostic_groups.len(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let headers = |view: &ProjectDiagnosticsEditor,
+                       cx: &mut ViewContext<ProjectDiagnosticsEditor>| {
+            editor_blocks(&view.editor, cx)
+                .into_iter()
+                .map(|(_, name)| name)
+                .filter(|name| name != "collapsed context")
+                .collect::<Vec<_>>()
+        };
+
+        // By default, each file lists all of its diagnostics under one header.
+        view.update(cx, |view, cx| {
+            assert_eq!(
+                sections(view),
+                [
+                    (Section::All, "a.rs".to_string(), 2),
+                    (Section::All, "b.rs".to_string(), 2),
+                ]
+            );
+            assert_eq!(
+                headers(view, cx),
+                [
+                    "path header block",
+                    "diagnostic header",
+                    "diagnostic header",
+                    "path header block",
+                    "diagnostic header",
+                    "diagnostic header",
+                ]
+            );
+        });
+
+        // Grouped by severity, every file's errors are listed before any warnings.
+        view.update(cx, |view, cx| view.set_group_by(GroupBy::Severity, cx));
+        cx.executor().run_until_parked();
+        view.update(cx, |view, cx| {
+            assert_eq!(
+                sections(view),
+                [
+                    (
+                        Section::Severity(DiagnosticSeverity::ERROR),
+                        "a.rs".to_string(),
+                        1
+                    ),
+                    (
+                        Section::Severity(DiagnosticSeverity::ERROR),
+                        "b.rs".to_string(),
+                        1
+                    ),
+                    (
+                        Section::Severity(DiagnosticSeverity::WARNING),
+                        "a.rs".to_string(),
+                        1
+                    ),
+                    (
+                        Section::Severity(DiagnosticSeverity::WARNING),
+                        "b.rs".to_string(),
+                        1
+                    ),
+                ]
+            );
+            assert_eq!(
+                headers(view, cx),
+                [
+                    "path header block",
+                    "diagnostic header",
+                    "path header block",
+                    "diagnostic header",
+                    "path header block",
+                    "diagnostic header",
+                    "path header block",
+                    "diagnostic header",
+                ]
+            );
+        });
+    }
+
+    #[gpui::test]
+    async fn test_following_diagnostics(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/test", json!({ "a.rs": "a();", "b.rs": "b();" }))
+            .await;
+        let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let workspace = window.root(cx).unwrap();
+
+        project.update(cx, |project, cx| {
+            for (path, severity) in [
+                ("/test/a.rs", DiagnosticSeverity::ERROR),
+                ("/test/b.rs", DiagnosticSeverity::WARNING),
+            ] {
+                project
+                    .update_diagnostic_entries(
+                        LanguageServerId(0),
+                        PathBuf::from(path),
+                        None,
+                        vec![DiagnosticEntry {
+                            range: Unclipped(PointUtf16::new(0, 0))
+                                ..Unclipped(PointUtf16::new(0, 1)),
+                            diagnostic: Diagnostic {
+                                message: "unknown function".to_string(),
+                                severity,
+                                is_primary: true,
+                                is_disk_based: true,
+                                group_id: 0,
+                                ..Default::default()
+                            },
+                        }],
+                        cx,
+                    )
+                    .unwrap();
+            }
+        });
+
+        let leader = window.build_view(cx, |cx| {
+            ProjectDiagnosticsEditor::new(project.clone(), workspace.downgrade(), cx)
+        });
+        leader.update(cx, |leader, cx| {
+            leader.set_max_severity(DiagnosticSeverity::WARNING, cx);
+            leader
+                .path_filter_editor
+                .update(cx, |editor, cx| editor.set_text("a.rs", cx));
+        });
+        cx.run_until_parked();
+
+        // The follower starts out with the leader's severities and filters.
+        let state = cx.update(|cx| leader.read(cx).to_state_proto(cx));
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        let follower = cx
+            .update(|cx| {
+                ProjectDiagnosticsEditor::from_state_proto(
+                    pane,
+                    workspace.clone(),
+                    ViewId {
+                        creator: PeerId::default(),
+                        id: 1,
+                    },
+                    &mut state.clone(),
+                    cx,
+                )
+            })
+            .unwrap()
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        follower.update(cx, |follower, cx| {
+            assert!(follower.include_warnings());
+            assert_eq!(follower.path_filter_editor.read(cx).text(cx), "a.rs");
+            assert_eq!(paths(follower), ["a.rs"]);
+        });
+
+        // Changing the filters changes the excerpts, which sends the new filters along.
+        let update = Rc::new(RefCell::new(None));
+        cx.update(|cx| {
+            let update = update.clone();
+            cx.subscribe(&leader, move |leader, event: &EditorEvent, cx| {
+                leader
+                    .read(cx)
+                    .add_event_to_update_proto(event, &mut update.borrow_mut(), cx);
+            })
+            .detach();
+        });
+        leader.update(cx, |leader, cx| {
+            leader
+                .path_filter_editor
+                .update(cx, |editor, cx| editor.set_text("", cx));
+            leader
+                .message_filter_editor
+                .update(cx, |editor, cx| editor.set_text("unknown", cx));
+        });
+        cx.run_until_parked();
+        let update = update.take().unwrap();
+        follower
+            .update(cx, |follower, cx| {
+                follower.apply_update_proto(&project, update, cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        follower.update(cx, |follower, cx| {
+            assert_eq!(follower.path_filter_editor.read(cx).text(cx), "");
+            assert_eq!(follower.message_filter_editor.read(cx).text(cx), "unknown");
+            assert_eq!(paths(follower), ["a.rs", "b.rs"]);
+        });
+
+        fn paths(view: &ProjectDiagnosticsEditor) -> Vec<String> {
+            view.path_states
+                .iter()
+                .map(|state| state.path.path.to_string_lossy().into_owned())
+                .collect()
+        }
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
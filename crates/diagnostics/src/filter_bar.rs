@@ -0,0 +1,293 @@
+use crate::{GroupBy, ProjectDiagnosticsEditor};
+use gpui::{
+    AnchorCorner, EventEmitter, ParentElement, Render, Subscription, View, ViewContext,
+    WindowContext,
+};
+use language::DiagnosticSeverity;
+use lsp::LanguageServerId;
+use ui::{popover_menu, prelude::*, ContextMenu, IconButton, IconName, Tooltip};
+use workspace::{item::ItemHandle, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
+
+const SEVERITIES: [(DiagnosticSeverity, &str); 4] = [
+    (DiagnosticSeverity::ERROR, "Errors"),
+    (DiagnosticSeverity::WARNING, "Errors and Warnings"),
+    (DiagnosticSeverity::INFORMATION, "Errors, Warnings and Info"),
+    (DiagnosticSeverity::HINT, "All Diagnostics"),
+];
+
+/// A toolbar row for filtering and grouping the diagnostics in the project diagnostics view.
+pub struct DiagnosticsFilterBar {
+    diagnostics: Option<View<ProjectDiagnosticsEditor>>,
+    _observe_diagnostics: Option<Subscription>,
+}
+
+impl Default for DiagnosticsFilterBar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DiagnosticsFilterBar {
+    pub fn new() -> Self {
+        Self {
+            diagnostics: None,
+            _observe_diagnostics: None,
+        }
+    }
+
+    fn build_severity_menu(
+        diagnostics: View<ProjectDiagnosticsEditor>,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let max_severity = diagnostics.read(cx).filter.max_severity;
+        ContextMenu::build(cx, move |mut menu, _| {
+            for (severity, label) in SEVERITIES {
+                let diagnostics = diagnostics.clone();
+                menu = menu.custom_entry(
+                    move |_| checked_entry(label.into(), severity == max_severity),
+                    move |cx| {
+                        diagnostics.update(cx, |diagnostics, cx| {
+                            diagnostics.set_max_severity(severity, cx)
+                        })
+                    },
+                );
+            }
+            menu
+        })
+    }
+
+    fn build_source_menu(
+        diagnostics: View<ProjectDiagnosticsEditor>,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let sources = diagnostics
+            .read(cx)
+            .sources
+            .iter()
+            .map(|source| {
+                let excluded = diagnostics
+                    .read(cx)
+                    .filter
+                    .excluded_sources
+                    .contains(source);
+                (source.clone(), excluded)
+            })
+            .collect::<Vec<_>>();
+        ContextMenu::build(cx, move |mut menu, _| {
+            if sources.is_empty() {
+                return menu.header("No Sources");
+            }
+            for (source, excluded) in sources {
+                let diagnostics = diagnostics.clone();
+                let label = SharedString::from(source.clone());
+                menu = menu.custom_entry(
+                    move |_| checked_entry(label.clone(), !excluded),
+                    move |cx| {
+                        diagnostics
+                            .update(cx, |diagnostics, cx| diagnostics.toggle_source(&source, cx))
+                    },
+                );
+            }
+            menu
+        })
+    }
+
+    fn build_language_server_menu(
+        diagnostics: View<ProjectDiagnosticsEditor>,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let this = diagnostics.read(cx);
+        let mut server_ids = this
+            .current_diagnostics
+            .keys()
+            .chain(&this.filter.excluded_language_servers)
+            .copied()
+            .collect::<Vec<LanguageServerId>>();
+        server_ids.sort();
+        server_ids.dedup();
+        let project = this.project.read(cx);
+        let servers = server_ids
+            .into_iter()
+            .map(|server_id| {
+                let name = project
+                    .language_server_for_id(server_id)
+                    .map(|server| SharedString::from(server.name().to_string()))
+                    .unwrap_or_else(|| format!("Server {}", server_id.0).into());
+                let excluded = this.filter.excluded_language_servers.contains(&server_id);
+                (server_id, name, excluded)
+            })
+            .collect::<Vec<_>>();
+        ContextMenu::build(cx, move |mut menu, _| {
+            if servers.is_empty() {
+                return menu.header("No Language Servers");
+            }
+            for (server_id, name, excluded) in servers {
+                let diagnostics = diagnostics.clone();
+                menu = menu.custom_entry(
+                    move |_| checked_entry(name.clone(), !excluded),
+                    move |cx| {
+                        diagnostics.update(cx, |diagnostics, cx| {
+                            diagnostics.toggle_language_server(server_id, cx)
+                        })
+                    },
+                );
+            }
+            menu
+        })
+    }
+
+    fn build_group_by_menu(
+        diagnostics: View<ProjectDiagnosticsEditor>,
+        cx: &mut WindowContext,
+    ) -> View<ContextMenu> {
+        let current_group_by = diagnostics.read(cx).group_by;
+        ContextMenu::build(cx, move |mut menu, _| {
+            for group_by in GroupBy::ALL {
+                let diagnostics = diagnostics.clone();
+                menu = menu.custom_entry(
+                    move |_| checked_entry(group_by.label().into(), group_by == current_group_by),
+                    move |cx| {
+                        diagnostics
+                            .update(cx, |diagnostics, cx| diagnostics.set_group_by(group_by, cx))
+                    },
+                );
+            }
+            menu
+        })
+    }
+}
+
+fn checked_entry(label: SharedString, checked: bool) -> AnyElement {
+    h_flex()
+        .gap_1()
+        .child(div().w_4().when(checked, |this| {
+            this.child(Icon::new(IconName::Check).size(IconSize::Small))
+        }))
+        .child(Label::new(label))
+        .into_any_element()
+}
+
+impl Render for DiagnosticsFilterBar {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some(diagnostics) = self.diagnostics.clone() else {
+            return div();
+        };
+        let this = diagnostics.read(cx);
+        let severity_label = SEVERITIES
+            .iter()
+            .find(|(severity, _)| *severity == this.filter.max_severity)
+            .map_or("Errors", |(_, label)| *label);
+        let excluded_count =
+            this.filter.excluded_sources.len() + this.filter.excluded_language_servers.len();
+        let current_file_only = this.filter.path.is_some();
+        let current_file_tooltip = match &this.filter.path {
+            Some(path) => format!("Showing only {}", path.path.display()),
+            None => "Show only the current file".to_string(),
+        };
+
+        let input = |editor: View<editor::Editor>| {
+            h_flex()
+                .w_64()
+                .px_2()
+                .py_1()
+                .bg(cx.theme().colors().editor_background)
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .rounded_lg()
+                .child(editor)
+        };
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .child(input(this.path_filter_editor.clone()))
+            .child(input(this.message_filter_editor.clone()))
+            .child(
+                popover_menu("diagnostics-severity-filter")
+                    .menu({
+                        let diagnostics = diagnostics.clone();
+                        move |cx| Some(Self::build_severity_menu(diagnostics.clone(), cx))
+                    })
+                    .anchor(AnchorCorner::TopLeft)
+                    .trigger(Button::new(
+                        "diagnostics-severity-filter-button",
+                        severity_label,
+                    )),
+            )
+            .child(
+                popover_menu("diagnostics-source-filter")
+                    .menu({
+                        let diagnostics = diagnostics.clone();
+                        move |cx| Some(Self::build_source_menu(diagnostics.clone(), cx))
+                    })
+                    .anchor(AnchorCorner::TopLeft)
+                    .trigger(Button::new("diagnostics-source-filter-button", "Sources")),
+            )
+            .child(
+                popover_menu("diagnostics-language-server-filter")
+                    .menu({
+                        let diagnostics = diagnostics.clone();
+                        move |cx| Some(Self::build_language_server_menu(diagnostics.clone(), cx))
+                    })
+                    .anchor(AnchorCorner::TopLeft)
+                    .trigger(Button::new(
+                        "diagnostics-language-server-filter-button",
+                        "Language Servers",
+                    )),
+            )
+            .child(
+                popover_menu("diagnostics-group-by")
+                    .menu({
+                        let diagnostics = diagnostics.clone();
+                        move |cx| Some(Self::build_group_by_menu(diagnostics.clone(), cx))
+                    })
+                    .anchor(AnchorCorner::TopLeft)
+                    .trigger(Button::new(
+                        "diagnostics-group-by-button",
+                        format!("Group by {}", this.group_by.label()),
+                    )),
+            )
+            .child(
+                IconButton::new("diagnostics-current-file-only", IconName::File)
+                    .selected(current_file_only)
+                    .tooltip(move |cx| Tooltip::text(current_file_tooltip.clone(), cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(diagnostics) = &this.diagnostics {
+                            diagnostics.update(cx, |diagnostics, cx| {
+                                diagnostics.toggle_current_file_only(cx)
+                            });
+                        }
+                    })),
+            )
+            .when(excluded_count > 0, |this| {
+                this.child(
+                    Label::new(format!("{excluded_count} hidden"))
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+            })
+    }
+}
+
+impl EventEmitter<ToolbarItemEvent> for DiagnosticsFilterBar {}
+
+impl ToolbarItemView for DiagnosticsFilterBar {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) -> ToolbarItemLocation {
+        self.diagnostics =
+            active_pane_item.and_then(|item| item.downcast::<ProjectDiagnosticsEditor>());
+        self._observe_diagnostics = self
+            .diagnostics
+            .as_ref()
+            .map(|diagnostics| cx.observe(diagnostics, |_, _, cx| cx.notify()));
+        cx.notify();
+        if self.diagnostics.is_some() {
+            ToolbarItemLocation::Secondary
+        } else {
+            ToolbarItemLocation::Hidden
+        }
+    }
+}
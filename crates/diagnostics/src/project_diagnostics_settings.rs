@@ -1,9 +1,11 @@
+use crate::diagnostic_filter::GroupBy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Debug)]
 pub struct ProjectDiagnosticsSettings {
     pub include_warnings: bool,
+    pub group_by: GroupBy,
 }
 
 /// Diagnostics configuration.
@@ -13,6 +15,10 @@ pub struct ProjectDiagnosticsSettingsContent {
     ///
     /// Default: true
     include_warnings: Option<bool>,
+    /// How to arrange diagnostics by default: by "file", "severity" or "code".
+    ///
+    /// Default: file
+    group_by: Option<GroupBy>,
 }
 
 impl settings::Settings for ProjectDiagnosticsSettings {
@@ -14,7 +14,7 @@ impl Render for ToolbarControls {
             .editor
             .as_ref()
             .and_then(|editor| editor.upgrade())
-            .map(|editor| editor.read(cx).include_warnings)
+            .map(|editor| editor.read(cx).include_warnings())
             .unwrap_or(false);
 
         let tooltip = if include_warnings {
@@ -119,11 +119,13 @@ impl_actions!(
 gpui::actions!(
     editor,
     [
+        AddReviewComment,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
         Cancel,
         ConfirmRename,
+        ConfirmReviewComment,
         ContextMenuFirst,
         ContextMenuLast,
         ContextMenuNext,
@@ -169,6 +171,7 @@ gpui::actions!(
         HalfPageUp,
         Hover,
         Indent,
+        InspectHighlights,
         JoinLines,
         LineDown,
         LineUp,
@@ -312,11 +312,10 @@ impl DisplayMap {
     }
 
     fn tab_size(buffer: &Model<MultiBuffer>, cx: &mut ModelContext<Self>) -> NonZeroU32 {
-        let language = buffer
-            .read(cx)
-            .as_singleton()
-            .and_then(|buffer| buffer.read(cx).language());
-        language_settings(language.as_deref(), None, cx).tab_size
+        let buffer = buffer.read(cx).as_singleton().map(|buffer| buffer.read(cx));
+        let language = buffer.and_then(|buffer| buffer.language());
+        let file = buffer.and_then(|buffer| buffer.file());
+        language_settings(language, file, cx).tab_size
     }
 
     #[cfg(test)]
@@ -895,6 +894,43 @@ impl DisplaySnapshot {
         }
     }
 
+    /// Returns the text highlights with a range touching the given position.
+    pub(crate) fn text_highlights_at(
+        &self,
+        position: Anchor,
+    ) -> Vec<(Option<TypeId>, HighlightStyle)> {
+        let buffer = &self.buffer_snapshot;
+        self.text_highlights
+            .iter()
+            .filter_map(|(type_id, highlights)| {
+                let (style, ranges) = highlights.as_ref();
+                ranges
+                    .iter()
+                    .any(|range| {
+                        range.start.cmp(&position, buffer).is_le()
+                            && range.end.cmp(&position, buffer).is_ge()
+                    })
+                    .then_some((*type_id, *style))
+            })
+            .collect()
+    }
+
+    /// Returns the highlights of the inlays inserted at the given position.
+    pub(crate) fn inlay_highlights_at(&self, position: Anchor) -> Vec<(TypeId, HighlightStyle)> {
+        let buffer = &self.buffer_snapshot;
+        self.inlay_highlights
+            .iter()
+            .flat_map(|(type_id, highlights)| {
+                highlights
+                    .values()
+                    .filter(move |(_, highlight)| {
+                        highlight.inlay_position.cmp(&position, buffer).is_eq()
+                    })
+                    .map(move |(style, _)| (*type_id, *style))
+            })
+            .collect()
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn text_highlight_ranges<Tag: ?Sized + 'static>(
         &self,
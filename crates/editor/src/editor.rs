@@ -18,16 +18,20 @@ pub mod display_map;
 mod editor_settings;
 mod element;
 mod inlay_hint_cache;
+mod inline_diagnostics;
 
 mod debounced_delay;
 mod git;
+mod highlight_inspector;
 mod highlight_matching_bracket;
 mod hover_links;
 mod hover_popover;
 pub mod items;
+pub mod large_file_indicator;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
+mod review_threads;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
@@ -64,9 +68,13 @@ use gpui::{
     UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler, VisualContext,
     WeakView, WhiteSpace, WindowContext,
 };
+use highlight_inspector::{
+    hide_highlight_inspector, refresh_highlight_inspector, HighlightInspector,
+};
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
+use inline_diagnostics::InlineDiagnostics;
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
 use language::{char_kind, CharKind};
@@ -90,6 +98,7 @@ use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
 use project::{FormatTrigger, Location, Project, ProjectPath, ProjectTransaction};
 use rand::prelude::*;
+use review_threads::ReviewThreadBlocks;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
@@ -203,6 +212,7 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Diagnostic(usize),
 }
 
 impl InlayId {
@@ -210,6 +220,7 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Diagnostic(id) => *id,
         }
     }
 }
@@ -382,6 +393,10 @@ pub struct Editor {
     highlighted_rows: Option<Range<u32>>,
     background_highlights: BTreeMap<TypeId, BackgroundHighlight>,
     inlay_background_highlights: TreeMap<Option<TypeId>, InlayBackgroundHighlight>,
+    highlight_inspector: Option<HighlightInspector>,
+    /// The type names of the highlights set on this editor, which are only recorded while
+    /// the highlight inspector is open.
+    highlight_type_names: Option<HashMap<TypeId, &'static str>>,
     nav_history: Option<ItemNavHistory>,
     context_menu: RwLock<Option<ContextMenu>>,
     mouse_context_menu: Option<MouseContextMenu>,
@@ -392,6 +407,7 @@ pub struct Editor {
     code_actions_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
     pending_rename: Option<RenameState>,
+    review_threads: ReviewThreadBlocks,
     searchable: bool,
     cursor_shape: CursorShape,
     collapse_matches: bool,
@@ -408,6 +424,7 @@ pub struct Editor {
     hovered_link_state: Option<HoveredLinkState>,
     copilot_state: CopilotState,
     inlay_hint_cache: InlayHintCache,
+    inline_diagnostics: InlineDiagnostics,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -1458,8 +1475,15 @@ impl Editor {
                     }));
                 }
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
-                    if let project::Event::RefreshInlayHints = event {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    match event {
+                        project::Event::RefreshInlayHints => {
+                            editor
+                                .refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                        }
+                        project::Event::ReviewThreadUpdated(_) => {
+                            editor.refresh_review_threads(cx);
+                        }
+                        _ => {}
                     };
                 }));
             }
@@ -1506,17 +1530,21 @@ impl Editor {
             highlighted_rows: None,
             background_highlights: Default::default(),
             inlay_background_highlights: Default::default(),
+            highlight_inspector: None,
+            highlight_type_names: None,
             nav_history: None,
             context_menu: RwLock::new(None),
             mouse_context_menu: None,
             completion_tasks: Default::default(),
             next_completion_id: 0,
             completion_documentation_pre_resolve_debounce: DebouncedDelay::new(),
+            inline_diagnostics: Default::default(),
             next_inlay_id: 0,
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
             pending_rename: Default::default(),
+            review_threads: Default::default(),
             searchable: true,
             cursor_shape: Default::default(),
             autoindent_mode: Some(AutoindentMode::EachLine),
@@ -1564,6 +1592,8 @@ impl Editor {
         };
 
         this._subscriptions.extend(project_subscriptions);
+        this.refresh_review_threads(cx);
+        this.refresh_inline_diagnostics(cx);
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
@@ -1589,6 +1619,9 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.review_threads.is_composing() {
+            key_context.add("review_comment");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
@@ -1926,6 +1959,9 @@ impl Editor {
             }
 
             hide_hover(self, cx);
+            if self.highlight_inspector.is_some() {
+                refresh_highlight_inspector(self, cx);
+            }
 
             if old_cursor_position.to_display_point(&display_map).row()
                 != new_cursor_position.to_display_point(&display_map).row()
@@ -2339,10 +2375,18 @@ impl Editor {
             return;
         }
 
+        if self.take_review_comment_composer(cx).is_some() {
+            return;
+        }
+
         if hide_hover(self, cx) {
             return;
         }
 
+        if hide_highlight_inspector(self, cx) {
+            return;
+        }
+
         if self.hide_context_menu(cx).is_some() {
             return;
         }
@@ -8568,6 +8612,7 @@ impl Editor {
                 .summary_for_anchor::<usize>(&range.end);
         }
 
+        self.record_highlight_type_name::<T>();
         self.background_highlights
             .insert(TypeId::of::<T>(), (color_fetcher, ranges));
         cx.notify();
@@ -8580,6 +8625,7 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         // TODO: no actual highlights happen for inlays currently, find a way to do that
+        self.record_highlight_type_name::<T>();
         self.inlay_background_highlights
             .insert(Some(TypeId::of::<T>()), (color_fetcher, ranges));
         cx.notify();
@@ -8799,6 +8845,7 @@ impl Editor {
         style: HighlightStyle,
         cx: &mut ViewContext<Self>,
     ) {
+        self.record_highlight_type_name::<T>();
         self.display_map.update(cx, |map, _| {
             map.highlight_text(TypeId::of::<T>(), ranges, style)
         });
@@ -8811,12 +8858,19 @@ impl Editor {
         style: HighlightStyle,
         cx: &mut ViewContext<Self>,
     ) {
+        self.record_highlight_type_name::<T>();
         self.display_map.update(cx, |map, _| {
             map.highlight_inlays(TypeId::of::<T>(), highlights, style)
         });
         cx.notify();
     }
 
+    fn record_highlight_type_name<T: 'static>(&mut self) {
+        if let Some(highlight_type_names) = &mut self.highlight_type_names {
+            highlight_type_names.insert(TypeId::of::<T>(), std::any::type_name::<T>());
+        }
+    }
+
     pub fn text_highlights<'a, T: 'static>(
         &'a self,
         cx: &'a AppContext,
@@ -8904,13 +8958,16 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                self.refresh_inline_diagnostics(cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                self.refresh_inline_diagnostics(cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
             multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
             multi_buffer::Event::LanguageChanged => {
+                self.invalidate_inline_diagnostics(cx);
                 cx.emit(EditorEvent::Reparsed);
                 cx.notify();
             }
@@ -8923,6 +8980,7 @@ impl Editor {
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
+                self.refresh_inline_diagnostics(cx);
             }
             _ => {}
         };
@@ -8942,6 +9000,7 @@ impl Editor {
             )),
             cx,
         );
+        self.invalidate_inline_diagnostics(cx);
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
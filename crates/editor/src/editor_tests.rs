@@ -12,11 +12,14 @@ use futures::StreamExt;
 use gpui::{div, TestAppContext, VisualTestContext, WindowBounds, WindowOptions};
 use indoc::indoc;
 use language::{
-    language_settings::{AllLanguageSettings, AllLanguageSettingsContent, LanguageSettingsContent},
+    language_settings::{
+        AllLanguageSettings, AllLanguageSettingsContent, DiagnosticSeverityFilter,
+        InlineDiagnosticsSettings, LanguageSettingsContent,
+    },
     BracketPairConfig,
     Capability::ReadWrite,
-    FakeLspAdapter, LanguageConfig, LanguageConfigOverride, LanguageMatcher, LanguageRegistry,
-    Override, Point,
+    DiagnosticEntry, DiagnosticSet, FakeLspAdapter, LanguageConfig, LanguageConfigOverride,
+    LanguageMatcher, LanguageRegistry, Override, Point, PointUtf16,
 };
 use parking_lot::Mutex;
 use project::project_settings::{LspSettings, ProjectSettings};
@@ -7177,6 +7180,77 @@ async fn go_to_prev_overlapping_diagnostic(
     "});
 }
 
+#[gpui::test]
+fn test_inline_diagnostics(cx: &mut TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.inline_diagnostics = Some(InlineDiagnosticsSettings {
+            enabled: true,
+            min_severity: DiagnosticSeverityFilter::Warning,
+        });
+    });
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(6, 6, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = editor.update(cx, |editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+        buffer.update(cx, |buffer, cx| {
+            let diagnostic = |row, column, severity, message: &str, is_primary| DiagnosticEntry {
+                range: PointUtf16::new(row, column)..PointUtf16::new(row, column + 1),
+                diagnostic: Diagnostic {
+                    severity,
+                    message: message.to_string(),
+                    is_primary,
+                    ..Default::default()
+                },
+            };
+            let set = DiagnosticSet::new(
+                [
+                    diagnostic(0, 0, DiagnosticSeverity::WARNING, "a warning", true),
+                    diagnostic(0, 2, DiagnosticSeverity::ERROR, "an error\nmore", true),
+                    diagnostic(2, 0, DiagnosticSeverity::HINT, "a hint", true),
+                    diagnostic(3, 0, DiagnosticSeverity::ERROR, "secondary", false),
+                    diagnostic(4, 0, DiagnosticSeverity::WARNING, "another warning", true),
+                ],
+                &buffer.text_snapshot(),
+            );
+            buffer.update_diagnostics(LanguageServerId(0), set, cx);
+        });
+    });
+    _ = editor.update(cx, |editor, cx| {
+        // Only the first line of the most severe primary diagnostic on each line is shown.
+        assert_eq!(
+            editor.display_text(cx),
+            "aaaaaa    an error\nbbbbbb\ncccccc\ndddddd\neeeeee    another warning\nffffff"
+        );
+    });
+
+    // Messages are truncated to the columns left visible after the end of their line.
+    cx.simulate_window_resize(editor.into(), size(px(200.), px(200.)));
+    cx.run_until_parked();
+    _ = editor.update(cx, |editor, cx| {
+        let columns = editor.visible_column_count().unwrap() as usize;
+        let message =
+            util::truncate_and_trailoff("another warning", columns.saturating_sub(10).max(10));
+        assert!(message.ends_with('…'));
+        assert_eq!(
+            editor.display_text(cx),
+            format!("aaaaaa    an error\nbbbbbb\ncccccc\ndddddd\neeeeee    {message}\nffffff")
+        );
+    });
+
+    update_test_language_settings(cx, |settings| {
+        settings.defaults.inline_diagnostics = Some(InlineDiagnosticsSettings {
+            enabled: false,
+            min_severity: DiagnosticSeverityFilter::Warning,
+        });
+    });
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.display_text(cx), sample_text(6, 6, 'a'));
+    });
+}
+
 #[gpui::test]
 async fn go_to_hunk(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8101,6 +8175,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "some other init value": false
                 })),
+                ..Default::default()
             },
         );
     });
@@ -8118,6 +8193,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
+                ..Default::default()
             },
         );
     });
@@ -8135,6 +8211,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
                 initialization_options: Some(json!({
                     "anotherInitValue": false
                 })),
+                ..Default::default()
             },
         );
     });
@@ -8150,6 +8227,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             language_server_name.into(),
             LspSettings {
                 initialization_options: None,
+                ..Default::default()
             },
         );
     });
@@ -273,6 +273,9 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, crate::highlight_inspector::inspect_highlights);
+        register_action(view, cx, Editor::add_review_comment);
+        register_action(view, cx, Editor::confirm_review_comment);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
@@ -1967,6 +1970,7 @@ impl EditorElement {
                 editor.set_visible_line_count((bounds.size.height / line_height).into(), cx);
 
                 let editor_width = text_width - gutter_dimensions.margin - overscroll.width - em_width;
+                editor.set_visible_column_count((editor_width / em_advance).into(), cx);
                 let wrap_width = match editor.soft_wrap_mode(cx) {
                     SoftWrap::None => (MAX_LINE_LEN / 2) as f32 * em_advance,
                     SoftWrap::EditorWidth => editor_width,
@@ -2290,6 +2294,8 @@ impl EditorElement {
 
             let hover = if context_menu.is_some() {
                 None
+            } else if let Some(highlight_inspector) = editor.highlight_inspector.as_ref() {
+                highlight_inspector.render(&snapshot, visible_rows, max_size, cx)
             } else {
                 editor.hover_state.render(
                 &snapshot,
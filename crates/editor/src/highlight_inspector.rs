@@ -0,0 +1,398 @@
+use std::{any::TypeId, ops::Range};
+
+use gpui::{
+    div, AnyElement, HighlightStyle, Hsla, InteractiveElement, IntoElement, ParentElement, Pixels,
+    SharedString, Size, StatefulInteractiveElement, Styled, ViewContext,
+};
+use language::Point;
+use multi_buffer::Anchor;
+use ui::{prelude::*, Label, LabelSize};
+
+use crate::{display_map::ToDisplayPoint, DisplayPoint, Editor, EditorSnapshot, InspectHighlights};
+
+/// A popover describing where the highlighting of the text under the cursor comes from:
+/// the syntax layers containing it, the tree-sitter captures matched on it, and the
+/// editor highlights overlapping it. It is shown like a hover popover, and follows the
+/// cursor until it's dismissed.
+pub(crate) struct HighlightInspector {
+    anchor: Anchor,
+    position: Point,
+    layers: Vec<InspectedLayer>,
+    captures: Vec<InspectedCapture>,
+    editor_highlights: Vec<InspectedEditorHighlight>,
+    inlays: Vec<SharedString>,
+}
+
+struct InspectedLayer {
+    language: SharedString,
+    depth: usize,
+    range: Range<Point>,
+}
+
+struct InspectedCapture {
+    language: Option<SharedString>,
+    depth: usize,
+    capture_name: SharedString,
+    node_kind: &'static str,
+    theme_key: Option<SharedString>,
+    style: Option<HighlightStyle>,
+}
+
+struct InspectedEditorHighlight {
+    kind: &'static str,
+    name: SharedString,
+    color: Option<Hsla>,
+    style: Option<HighlightStyle>,
+}
+
+/// Toggles the highlight inspector. While it's open, the editor records the type names of
+/// the highlights that are set on it, so that they can be shown.
+pub fn inspect_highlights(
+    editor: &mut Editor,
+    _: &InspectHighlights,
+    cx: &mut ViewContext<Editor>,
+) {
+    if !hide_highlight_inspector(editor, cx) {
+        editor.highlight_type_names = Some(Default::default());
+        refresh_highlight_inspector(editor, cx);
+    }
+}
+
+/// Closes the highlight inspector, returning whether it was open.
+pub(crate) fn hide_highlight_inspector(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
+    editor.highlight_type_names = None;
+    let was_open = editor.highlight_inspector.take().is_some();
+    if was_open {
+        cx.notify();
+    }
+    was_open
+}
+
+/// Inspects the highlights under the newest cursor.
+pub(crate) fn refresh_highlight_inspector(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let head = editor.selections.newest::<usize>(cx).head();
+    let snapshot = editor.snapshot(cx);
+    let multi_buffer = &snapshot.buffer_snapshot;
+    let position = multi_buffer.anchor_before(head);
+    let Some((buffer, offset)) = multi_buffer.point_to_buffer_offset(head) else {
+        return;
+    };
+
+    let layers = buffer
+        .syntax_layers_at(offset)
+        .map(|layer| {
+            let node = layer.node();
+            InspectedLayer {
+                language: layer.language.name().to_string().into(),
+                depth: layer.depth(),
+                range: Point::new(
+                    node.start_position().row as u32,
+                    node.start_position().column as u32,
+                )
+                    ..Point::new(
+                        node.end_position().row as u32,
+                        node.end_position().column as u32,
+                    ),
+            }
+        })
+        .collect();
+
+    let syntax_theme = cx.theme().syntax().clone();
+    let captures = buffer
+        .highlight_captures_at(offset)
+        .into_iter()
+        .map(|capture| InspectedCapture {
+            language: capture
+                .language
+                .map(|language| language.name().to_string().into()),
+            depth: capture.depth,
+            capture_name: capture.capture_name.into(),
+            node_kind: capture.node_kind,
+            theme_key: capture
+                .highlight_id
+                .name(&syntax_theme)
+                .map(|name| name.to_string().into()),
+            style: capture.highlight_id.style(&syntax_theme),
+        })
+        .collect();
+
+    let type_name = |type_id: Option<TypeId>| -> SharedString {
+        // Highlights that were set before the inspector was opened can't be named.
+        let type_name = type_id
+            .and_then(|type_id| editor.highlight_type_names.as_ref()?.get(&type_id).copied())
+            .unwrap_or("unknown");
+        let short_name = type_name.rsplit("::").next().unwrap_or(type_name);
+        short_name.to_string().into()
+    };
+
+    let colors = cx.theme().colors();
+    let mut editor_highlights = Vec::new();
+    for (type_id, (color_fetcher, ranges)) in &editor.background_highlights {
+        if ranges.iter().any(|range| {
+            range.start.cmp(&position, multi_buffer).is_le()
+                && range.end.cmp(&position, multi_buffer).is_ge()
+        }) {
+            editor_highlights.push(InspectedEditorHighlight {
+                kind: "background",
+                name: type_name(Some(*type_id)),
+                color: Some(color_fetcher(colors)),
+                style: None,
+            });
+        }
+    }
+    for (type_id, (color_fetcher, highlights)) in editor.inlay_background_highlights.iter() {
+        if highlights.iter().any(|highlight| {
+            highlight
+                .inlay_position
+                .cmp(&position, multi_buffer)
+                .is_eq()
+        }) {
+            editor_highlights.push(InspectedEditorHighlight {
+                kind: "inlay background",
+                name: type_name(*type_id),
+                color: Some(color_fetcher(colors)),
+                style: None,
+            });
+        }
+    }
+    for (type_id, style) in snapshot.display_snapshot.text_highlights_at(position) {
+        editor_highlights.push(InspectedEditorHighlight {
+            kind: "text",
+            name: type_name(type_id),
+            color: None,
+            style: Some(style),
+        });
+    }
+    for (type_id, style) in snapshot.display_snapshot.inlay_highlights_at(position) {
+        editor_highlights.push(InspectedEditorHighlight {
+            kind: "inlay",
+            name: type_name(Some(type_id)),
+            color: None,
+            style: Some(style),
+        });
+    }
+
+    let inlays = editor
+        .display_map
+        .read(cx)
+        .current_inlays()
+        .filter(|inlay| inlay.position.cmp(&position, multi_buffer).is_eq())
+        .map(|inlay| inlay.text.to_string().into())
+        .collect();
+
+    editor.highlight_inspector = Some(HighlightInspector {
+        anchor: position,
+        position: buffer.offset_to_point(offset),
+        layers,
+        captures,
+        editor_highlights,
+        inlays,
+    });
+    cx.notify();
+}
+
+impl HighlightInspector {
+    /// Renders the inspector as a hover popover, if the inspected position is visible.
+    pub(crate) fn render(
+        &self,
+        snapshot: &EditorSnapshot,
+        visible_rows: Range<u32>,
+        max_size: Size<Pixels>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<(DisplayPoint, Vec<AnyElement>)> {
+        let point = self.anchor.to_display_point(&snapshot.display_snapshot);
+        if !visible_rows.contains(&point.row()) {
+            return None;
+        }
+
+        let element = v_flex()
+            .id("highlight_inspector")
+            .elevation_2(cx)
+            .p_2()
+            .gap_1()
+            .max_w(max_size.width)
+            .max_h(max_size.height)
+            .overflow_y_scroll()
+            // Prevent a mouse move on the popover from being propagated to the editor.
+            .on_mouse_move(|_, cx| cx.stop_propagation())
+            .child(
+                Label::new(format!(
+                    "Highlights at {}:{}",
+                    self.position.row + 1,
+                    self.position.column + 1
+                ))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+            )
+            .child(section_header("Syntax Layers"))
+            .when(self.layers.is_empty(), |this| this.child(empty_label()))
+            .children(self.layers.iter().map(|layer| {
+                row(format!(
+                    "{} (depth {}) [{}:{} - {}:{}]",
+                    layer.language,
+                    layer.depth,
+                    layer.range.start.row + 1,
+                    layer.range.start.column + 1,
+                    layer.range.end.row + 1,
+                    layer.range.end.column + 1,
+                ))
+            }))
+            .child(section_header("Captures"))
+            .when(self.captures.is_empty(), |this| this.child(empty_label()))
+            .children(self.captures.iter().map(|capture| {
+                let language = capture.language.as_ref().map_or("", |name| name.as_ref());
+                h_flex()
+                    .gap_2()
+                    .child(row(format!(
+                        "@{} on {} ({} depth {})",
+                        capture.capture_name, capture.node_kind, language, capture.depth
+                    )))
+                    .child(
+                        Label::new(match &capture.theme_key {
+                            Some(theme_key) => format!("→ {theme_key}"),
+                            None => "→ no theme key".to_string(),
+                        })
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                    )
+                    .children(capture.style.as_ref().map(render_style))
+            }))
+            .child(section_header("Editor Highlights"))
+            .when(self.editor_highlights.is_empty(), |this| {
+                this.child(empty_label())
+            })
+            .children(self.editor_highlights.iter().map(|highlight| {
+                h_flex()
+                    .gap_2()
+                    .child(row(format!("{} ({})", highlight.name, highlight.kind)))
+                    .children(highlight.color.map(render_color))
+                    .children(highlight.style.as_ref().map(render_style))
+            }))
+            .when(!self.inlays.is_empty(), |this| {
+                this.child(section_header("Inlays"))
+                    .children(self.inlays.iter().map(|text| row(text.clone())))
+            })
+            .into_any_element();
+
+        Some((point, vec![element]))
+    }
+}
+
+fn section_header(title: &'static str) -> impl IntoElement {
+    Label::new(title)
+        .size(LabelSize::Small)
+        .color(Color::Accent)
+}
+
+fn empty_label() -> impl IntoElement {
+    Label::new("None")
+        .size(LabelSize::Small)
+        .color(Color::Disabled)
+}
+
+fn row(text: impl Into<SharedString>) -> impl IntoElement {
+    Label::new(text).size(LabelSize::Small)
+}
+
+fn render_color(color: Hsla) -> impl IntoElement {
+    h_flex()
+        .gap_1()
+        .child(div().size_3().rounded_sm().bg(color))
+        .child(
+            Label::new(format!("#{:08x}", u32::from(color.to_rgb())))
+                .size(LabelSize::Small)
+                .color(Color::Muted),
+        )
+}
+
+fn render_style(style: &HighlightStyle) -> impl IntoElement {
+    let mut attributes = Vec::new();
+    if let Some(font_weight) = style.font_weight {
+        attributes.push(format!("weight {}", font_weight.0));
+    }
+    if let Some(font_style) = style.font_style {
+        attributes.push(format!("{font_style:?}").to_lowercase());
+    }
+    if style.underline.is_some() {
+        attributes.push("underline".to_string());
+    }
+    if style.strikethrough.is_some() {
+        attributes.push("strikethrough".to_string());
+    }
+    if let Some(fade_out) = style.fade_out {
+        attributes.push(format!("fade {fade_out}"));
+    }
+
+    h_flex()
+        .gap_1()
+        .children(style.color.map(render_color))
+        .children(style.background_color.map(|color| {
+            h_flex()
+                .gap_1()
+                .child(Label::new("bg").size(LabelSize::Small).color(Color::Muted))
+                .child(render_color(color))
+        }))
+        .when(!attributes.is_empty(), |this| {
+            this.child(
+                Label::new(attributes.join(", "))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext, Cancel};
+
+    #[gpui::test]
+    async fn test_highlight_inspector(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+        enum BeforeOpening {}
+        enum WhileOpen {}
+
+        cx.set_state("one ˇtwo three");
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let range = snapshot.anchor_before(4)..snapshot.anchor_after(7);
+            editor.highlight_background::<BeforeOpening>(vec![range], |_| Hsla::default(), cx);
+            assert!(editor.highlight_type_names.is_none());
+
+            // The inspector is shown as a popover rather than as a context menu.
+            inspect_highlights(editor, &InspectHighlights, cx);
+            assert!(editor.context_menu.read().is_none());
+            let inspector = editor.highlight_inspector.as_ref().unwrap();
+            assert_eq!(inspector.position, Point::new(0, 4));
+            let names = inspector
+                .editor_highlights
+                .iter()
+                .map(|highlight| highlight.name.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["unknown"]);
+
+            // Highlights set while the inspector is open are named.
+            let range = snapshot.anchor_before(8)..snapshot.anchor_after(13);
+            editor.highlight_background::<WhileOpen>(vec![range], |_| Hsla::default(), cx);
+        });
+
+        // The inspector follows the cursor.
+        cx.set_selections_state("one two thˇree");
+        cx.update_editor(|editor, cx| {
+            let inspector = editor.highlight_inspector.as_ref().unwrap();
+            assert_eq!(inspector.position, Point::new(0, 10));
+            let names = inspector
+                .editor_highlights
+                .iter()
+                .map(|highlight| highlight.name.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(names, ["WhileOpen"]);
+
+            // Dismissing the inspector stops recording the highlights' names.
+            editor.cancel(&Cancel, cx);
+            assert!(editor.highlight_inspector.is_none());
+            assert!(editor.highlight_type_names.is_none());
+        });
+    }
+}
@@ -0,0 +1,186 @@
+//! Diagnostic messages shown as inlays after the end of the lines they start on, when
+//! enabled through the `inline_diagnostics` language setting.
+
+use collections::{BTreeMap, HashMap, HashSet};
+use gpui::{HighlightStyle, ViewContext};
+use language::{language_settings::language_settings, Diagnostic, Point};
+use lsp::DiagnosticSeverity;
+use theme::ActiveTheme;
+use util::post_inc;
+
+use crate::{
+    diagnostic_style, display_map::Inlay, hover_links::InlayHighlight, Editor, EditorMode,
+    ExcerptId,
+};
+
+/// Separates an inline diagnostic from the end of its line.
+const INLINE_DIAGNOSTIC_GAP: &str = "    ";
+/// Inline diagnostic messages are truncated to fit in the editor, but never to fewer
+/// characters than this.
+const MIN_INLINE_DIAGNOSTIC_CHARS: usize = 10;
+
+// Inlays are highlighted per type, so each severity's color needs its own type.
+enum InlineErrorHighlight {}
+enum InlineWarningHighlight {}
+enum InlineInfoHighlight {}
+
+#[derive(Default)]
+pub(crate) struct InlineDiagnostics {
+    excerpts: HashMap<ExcerptId, ExcerptInlineDiagnostics>,
+}
+
+struct ExcerptInlineDiagnostics {
+    /// The diagnostics update of the excerpt's buffer that the inlays were created for,
+    /// or `None` if they need to be recreated regardless.
+    diagnostics_update_count: Option<usize>,
+    inlays: Vec<(InlayHighlight, DiagnosticSeverity)>,
+}
+
+impl Editor {
+    /// Recreates the inline diagnostics of every excerpt, for when their settings or the
+    /// space available to them changed.
+    pub(crate) fn invalidate_inline_diagnostics(&mut self, cx: &mut ViewContext<Self>) {
+        for excerpt in self.inline_diagnostics.excerpts.values_mut() {
+            excerpt.diagnostics_update_count = None;
+        }
+        self.refresh_inline_diagnostics(cx);
+    }
+
+    /// Shows the message of the most severe primary diagnostic starting on each line, for the
+    /// excerpts whose settings enable them. Only the excerpts that were added, or whose
+    /// buffer's diagnostics changed since the last refresh, are recomputed.
+    pub(crate) fn refresh_inline_diagnostics(&mut self, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let excerpts = if self.mode == EditorMode::Full {
+            snapshot.excerpts().collect::<Vec<_>>()
+        } else {
+            Vec::new()
+        };
+
+        let mut to_remove = Vec::new();
+        let excerpt_ids = excerpts
+            .iter()
+            .map(|(excerpt_id, _, _)| *excerpt_id)
+            .collect::<HashSet<_>>();
+        self.inline_diagnostics
+            .excerpts
+            .retain(|excerpt_id, excerpt| {
+                let retain = excerpt_ids.contains(excerpt_id);
+                if !retain {
+                    to_remove.extend(excerpt.inlays.iter().map(|(highlight, _)| highlight.inlay));
+                }
+                retain
+            });
+
+        let max_columns = self.visible_column_count().map(|columns| columns as u32);
+        let mut to_insert = Vec::new();
+        for (excerpt_id, buffer, range) in excerpts {
+            let diagnostics_update_count = Some(buffer.diagnostics_update_count());
+            let excerpt = self
+                .inline_diagnostics
+                .excerpts
+                .entry(excerpt_id)
+                .or_insert_with(|| ExcerptInlineDiagnostics {
+                    diagnostics_update_count: None,
+                    inlays: Vec::new(),
+                });
+            if excerpt.diagnostics_update_count == diagnostics_update_count {
+                continue;
+            }
+            excerpt.diagnostics_update_count = diagnostics_update_count;
+            to_remove.extend(
+                excerpt
+                    .inlays
+                    .drain(..)
+                    .map(|(highlight, _)| highlight.inlay),
+            );
+
+            let settings =
+                language_settings(buffer.language(), buffer.file(), cx).inline_diagnostics;
+            if !settings.enabled {
+                continue;
+            }
+
+            let mut diagnostics_by_row = BTreeMap::<u32, Diagnostic>::default();
+            for entry in buffer.diagnostics_in_range::<_, Point>(range.context, false) {
+                let diagnostic = entry.diagnostic;
+                if !diagnostic.is_primary || !settings.min_severity.includes(diagnostic.severity) {
+                    continue;
+                }
+                let most_severe = diagnostics_by_row
+                    .entry(entry.range.start.row)
+                    .or_insert_with(|| diagnostic.clone());
+                if diagnostic.severity < most_severe.severity {
+                    *most_severe = diagnostic;
+                }
+            }
+
+            for (row, diagnostic) in diagnostics_by_row {
+                let Some(message) = diagnostic.message.lines().next() else {
+                    continue;
+                };
+                let message = message.trim();
+                let line_len = buffer.line_len(row);
+                // Keep the message within the columns left visible after the end of its line.
+                let message = match max_columns {
+                    Some(max_columns) => {
+                        let available_columns = max_columns
+                            .saturating_sub(line_len + INLINE_DIAGNOSTIC_GAP.len() as u32);
+                        util::truncate_and_trailoff(
+                            message,
+                            (available_columns as usize).max(MIN_INLINE_DIAGNOSTIC_CHARS),
+                        )
+                    }
+                    None => message.to_string(),
+                };
+                let text = format!("{INLINE_DIAGNOSTIC_GAP}{message}");
+                let line_end = buffer.anchor_after(Point::new(row, line_len));
+                let position = snapshot.anchor_in_excerpt(excerpt_id, line_end);
+                let inlay = Inlay::diagnostic(post_inc(&mut self.next_inlay_id), position, text);
+                let highlight = InlayHighlight {
+                    inlay: inlay.id,
+                    inlay_position: inlay.position,
+                    range: 0..inlay.text.len(),
+                };
+                excerpt.inlays.push((highlight, diagnostic.severity));
+                to_insert.push(inlay);
+            }
+        }
+
+        if to_remove.is_empty() && to_insert.is_empty() {
+            return;
+        }
+        self.display_map.update(cx, |display_map, cx| {
+            display_map.splice_inlays(to_remove, to_insert, cx)
+        });
+
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        let mut infos = Vec::new();
+        for excerpt in self.inline_diagnostics.excerpts.values() {
+            for (highlight, severity) in &excerpt.inlays {
+                let highlight = highlight.clone();
+                match *severity {
+                    DiagnosticSeverity::ERROR => errors.push(highlight),
+                    DiagnosticSeverity::WARNING => warnings.push(highlight),
+                    _ => infos.push(highlight),
+                }
+            }
+        }
+
+        let status = cx.theme().status();
+        let style = |severity| HighlightStyle {
+            color: Some(diagnostic_style(severity, true, status)),
+            ..Default::default()
+        };
+        let error_style = style(DiagnosticSeverity::ERROR);
+        let warning_style = style(DiagnosticSeverity::WARNING);
+        let info_style = style(DiagnosticSeverity::INFORMATION);
+        self.clear_highlights::<InlineErrorHighlight>(cx);
+        self.clear_highlights::<InlineWarningHighlight>(cx);
+        self.clear_highlights::<InlineInfoHighlight>(cx);
+        self.highlight_inlays::<InlineErrorHighlight>(errors, error_style, cx);
+        self.highlight_inlays::<InlineWarningHighlight>(warnings, warning_style, cx);
+        self.highlight_inlays::<InlineInfoHighlight>(infos, info_style, cx);
+    }
+}
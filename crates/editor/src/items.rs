@@ -310,6 +310,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -319,7 +320,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("received a non-editor update for an editor")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -331,6 +334,61 @@ impl FollowableItem for Editor {
     }
 }
 
+impl Editor {
+    /// Describes the scroll position relative to the buffer at the top of the editor, so that
+    /// followers can restore it even if their excerpts of that buffer have different ids.
+    pub fn buffer_scroll_position(&self) -> proto::BufferScrollPosition {
+        let scroll_anchor = self.scroll_manager.anchor();
+        proto::BufferScrollPosition {
+            buffer_id: scroll_anchor.anchor.buffer_id.map(Into::into),
+            anchor: Some(serialize_text_anchor(&scroll_anchor.anchor.text_anchor)),
+            x: scroll_anchor.offset.x,
+            y: scroll_anchor.offset.y,
+        }
+    }
+
+    /// Scrolls to a position received from a leader, returning `false` if none of this
+    /// editor's excerpts contain it yet.
+    pub fn set_buffer_scroll_position_remote(
+        &mut self,
+        position: &proto::BufferScrollPosition,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        let anchor = if let Some(buffer_id) = position.buffer_id {
+            let Some(text_anchor) = position
+                .anchor
+                .clone()
+                .and_then(language::proto::deserialize_anchor)
+            else {
+                return false;
+            };
+            let snapshot = self.buffer.read(cx).snapshot(cx);
+            let excerpt_id = snapshot.excerpts().find_map(|(excerpt_id, buffer, range)| {
+                let contains_anchor = u64::from(buffer.remote_id()) == buffer_id
+                    && buffer.can_resolve(&text_anchor)
+                    && range.context.start.cmp(&text_anchor, buffer).is_le()
+                    && range.context.end.cmp(&text_anchor, buffer).is_ge();
+                contains_anchor.then_some(excerpt_id)
+            });
+            match excerpt_id {
+                Some(excerpt_id) => snapshot.anchor_in_excerpt(excerpt_id, text_anchor),
+                None => return false,
+            }
+        } else {
+            Anchor::min()
+        };
+
+        self.set_scroll_anchor_remote(
+            ScrollAnchor {
+                anchor,
+                offset: point(position.x, position.y),
+            },
+            cx,
+        );
+        true
+    }
+}
+
 async fn update_editor_from_message(
     this: WeakView<Editor>,
     project: Model<Project>,
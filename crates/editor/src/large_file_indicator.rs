@@ -0,0 +1,113 @@
+use crate::Editor;
+use gpui::{AnchorCorner, Model, Render, Subscription, View};
+use language::Buffer;
+use project::{large_files::LargeFileFeature, Project};
+use ui::{popover_menu, prelude::*, ContextMenu, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView};
+
+/// A status bar item shown for buffers opened in large-file mode, explaining which
+/// features were turned off and allowing each of them to be turned back on.
+pub struct LargeFileIndicator {
+    active_buffer: Option<(Model<Project>, Model<Buffer>)>,
+    _observe_project: Option<Subscription>,
+}
+
+impl Default for LargeFileIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LargeFileIndicator {
+    pub fn new() -> Self {
+        Self {
+            active_buffer: None,
+            _observe_project: None,
+        }
+    }
+
+    fn build_menu(
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        cx: &mut WindowContext,
+    ) -> Option<View<ContextMenu>> {
+        let large_file = project.read(cx).large_file(buffer.read(cx))?.clone();
+        Some(ContextMenu::build(cx, move |mut menu, _| {
+            menu = menu.header("Large-File Mode");
+            for feature in LargeFileFeature::ALL {
+                let enabled = large_file.is_enabled(feature);
+                let label = if enabled {
+                    format!("Disable {}", feature.label())
+                } else {
+                    format!("Enable {}", feature.label())
+                };
+                let project = project.clone();
+                let buffer = buffer.clone();
+                menu = menu.entry(label, None, move |cx| {
+                    project.update(cx, |project, cx| {
+                        project.set_large_file_feature_enabled(&buffer, feature, !enabled, cx)
+                    })
+                });
+            }
+            menu
+        }))
+    }
+}
+
+impl Render for LargeFileIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let Some((project, buffer)) = self.active_buffer.clone() else {
+            return div();
+        };
+        let Some(large_file) = project.read(cx).large_file(buffer.read(cx)) else {
+            return div();
+        };
+
+        let size = format!("{:.1} MB", large_file.len as f64 / 1_000_000.);
+        let disabled_features = large_file
+            .disabled_features()
+            .map(|feature| feature.label())
+            .collect::<Vec<_>>();
+        let tooltip = if disabled_features.is_empty() {
+            format!("This {size} file was opened in large-file mode. All features are enabled.")
+        } else {
+            format!(
+                "This {size} file was opened in large-file mode. Disabled: {}.",
+                disabled_features.join(", ")
+            )
+        };
+
+        div().child(
+            popover_menu("large-file")
+                .menu(move |cx| Self::build_menu(project.clone(), buffer.clone(), cx))
+                .anchor(AnchorCorner::BottomRight)
+                .trigger(
+                    Button::new("large-file-indicator", "Large File")
+                        .label_size(LabelSize::Small)
+                        .tooltip(move |cx| Tooltip::text(tooltip.clone(), cx)),
+                ),
+        )
+    }
+}
+
+impl StatusItemView for LargeFileIndicator {
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.active_buffer = active_pane_item
+            .and_then(|item| item.act_as::<Editor>(cx))
+            .and_then(|editor| {
+                let editor = editor.read(cx);
+                let buffer = editor.buffer().read(cx).as_singleton()?;
+                Some((editor.project.clone()?, buffer))
+            });
+        self._observe_project = self
+            .active_buffer
+            .as_ref()
+            .map(|(project, _)| cx.observe(project, |_, _, cx| cx.notify()));
+
+        cx.notify();
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    DisplayPoint, Editor, EditorMode, FindAllReferences, GoToDefinition, GoToTypeDefinition,
-    Rename, RevealInFinder, SelectMode, ToggleCodeActions,
+    AddReviewComment, DisplayPoint, Editor, EditorMode, FindAllReferences, GoToDefinition,
+    GoToTypeDefinition, Rename, RevealInFinder, SelectMode, ToggleCodeActions,
 };
 use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
 
@@ -55,6 +55,7 @@ pub fn deploy_context_menu(
                         deployed_from_indicator: false,
                     }),
                 )
+                .action("Add Review Comment", Box::new(AddReviewComment))
                 .separator()
                 .action("Reveal in Finder", Box::new(RevealInFinder))
         })
@@ -0,0 +1,414 @@
+//! Review threads that collaborators attach to ranges of a project's buffers. Each
+//! thread is shown in a block below the line on which its range ends, with an icon in
+//! the gutter that expands the block from its first comment to the whole thread.
+
+use std::{ops::Range, sync::Arc};
+
+use collections::{HashMap, HashSet};
+use gpui::{AnyElement, AppContext, Model, View};
+use language::{Buffer, Point};
+use project::review_threads::{ReviewThread, ReviewThreadId};
+use ui::{prelude::*, Tooltip};
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    AddReviewComment, Anchor, ConfirmReviewComment, Editor, EditorMode,
+};
+
+#[derive(Default)]
+pub(crate) struct ReviewThreadBlocks {
+    blocks: HashMap<ReviewThreadId, BlockId>,
+    expanded: HashSet<ReviewThreadId>,
+    composer: Option<ReviewCommentComposer>,
+}
+
+impl ReviewThreadBlocks {
+    pub(crate) fn is_composing(&self) -> bool {
+        self.composer.is_some()
+    }
+}
+
+/// An inline editor in which a comment is written, either to start a thread or to
+/// reply to one.
+pub(crate) struct ReviewCommentComposer {
+    target: ComposerTarget,
+    editor: View<Editor>,
+    block_id: BlockId,
+}
+
+enum ComposerTarget {
+    NewThread {
+        buffer: Model<Buffer>,
+        range: Range<text::Anchor>,
+    },
+    Reply(ReviewThreadId),
+}
+
+impl Editor {
+    /// Starts writing a comment on the selected text, or on the cursor's line if
+    /// nothing is selected.
+    pub fn add_review_comment(&mut self, _: &AddReviewComment, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full || self.project.is_none() {
+            return;
+        }
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut range = self.selections.newest::<Point>(cx).range();
+        if range.is_empty() {
+            let row = range.start.row;
+            range = Point::new(row, 0)..Point::new(row, snapshot.line_len(row));
+        }
+        let multibuffer = self.buffer.read(cx);
+        let Some((buffer, start)) = multibuffer.text_anchor_for_position(range.start, cx) else {
+            return;
+        };
+        let Some((end_buffer, end)) = multibuffer.text_anchor_for_position(range.end, cx) else {
+            return;
+        };
+        if buffer != end_buffer {
+            return;
+        }
+
+        self.show_review_comment_composer(
+            snapshot.anchor_before(range.end),
+            ComposerTarget::NewThread {
+                buffer,
+                range: start..end,
+            },
+            "Add a review comment…",
+            cx,
+        );
+    }
+
+    pub fn confirm_review_comment(&mut self, _: &ConfirmReviewComment, cx: &mut ViewContext<Self>) {
+        let Some(composer) = self.take_review_comment_composer(cx) else {
+            return;
+        };
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let body = composer.editor.read(cx).text(cx);
+        if body.trim().is_empty() {
+            return;
+        }
+
+        match composer.target {
+            ComposerTarget::NewThread { buffer, range } => project
+                .update(cx, |project, cx| {
+                    project.create_review_thread(&buffer, range, body, cx)
+                })
+                .detach_and_log_err(cx),
+            ComposerTarget::Reply(id) => project
+                .update(cx, |project, cx| {
+                    project.reply_to_review_thread(id, body, cx)
+                })
+                .detach_and_log_err(cx),
+        }
+    }
+
+    pub(crate) fn take_review_comment_composer(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<ReviewCommentComposer> {
+        let composer = self.review_threads.composer.take()?;
+        if composer.editor.focus_handle(cx).is_focused(cx) {
+            cx.focus(&self.focus_handle);
+        }
+        self.remove_blocks([composer.block_id].into_iter().collect(), None, cx);
+        Some(composer)
+    }
+
+    fn reply_to_review_thread(&mut self, id: ReviewThreadId, cx: &mut ViewContext<Self>) {
+        let Some(position) = self.review_thread_position(id, cx) else {
+            return;
+        };
+        self.review_threads.expanded.insert(id);
+        self.refresh_review_threads(cx);
+        self.show_review_comment_composer(position, ComposerTarget::Reply(id), "Reply…", cx);
+    }
+
+    fn toggle_review_thread(&mut self, id: ReviewThreadId, cx: &mut ViewContext<Self>) {
+        if !self.review_threads.expanded.remove(&id) {
+            self.review_threads.expanded.insert(id);
+        }
+        self.refresh_review_threads(cx);
+    }
+
+    fn set_review_thread_resolved(
+        &mut self,
+        id: ReviewThreadId,
+        resolved: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(project) = self.project.clone() {
+            project
+                .update(cx, |project, cx| {
+                    project.set_review_thread_resolved(id, resolved, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn show_review_comment_composer(
+        &mut self,
+        position: Anchor,
+        target: ComposerTarget,
+        placeholder: &'static str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.take_review_comment_composer(cx);
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(placeholder, cx);
+            editor
+        });
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                style: BlockStyle::Flex,
+                position,
+                height: 1,
+                render: Arc::new({
+                    let editor = editor.clone();
+                    move |cx: &mut BlockContext| {
+                        h_flex()
+                            .w_full()
+                            .pl(cx.gutter_width)
+                            .pr_4()
+                            .child(div().flex_1().child(editor.clone()))
+                            .into_any_element()
+                    }
+                }),
+                disposition: BlockDisposition::Below,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        cx.focus_view(&editor);
+        self.review_threads.composer = Some(ReviewCommentComposer {
+            target,
+            editor,
+            block_id,
+        });
+        cx.notify();
+    }
+
+    /// Replaces the blocks of the review threads with ones showing their current state.
+    pub(crate) fn refresh_review_threads(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+
+        let old_block_ids = self
+            .review_threads
+            .blocks
+            .drain()
+            .map(|(_, block_id)| block_id)
+            .collect::<HashSet<_>>();
+        if !old_block_ids.is_empty() {
+            self.remove_blocks(old_block_ids, None, cx);
+        }
+
+        let mut thread_ids = Vec::new();
+        let mut blocks = Vec::new();
+        let project = project.read(cx);
+        let user_store = project.user_store();
+        for thread in project.review_threads() {
+            let Some(position) = self.review_thread_anchor(thread, cx) else {
+                continue;
+            };
+            let block = ReviewThreadBlock {
+                id: thread.id,
+                resolved: thread.resolved,
+                expanded: self.review_threads.expanded.contains(&thread.id),
+                comments: thread
+                    .comments
+                    .iter()
+                    .map(|comment| {
+                        let author = comment
+                            .author_id
+                            .and_then(|id| user_store.read(cx).get_cached_user(id))
+                            .map(|user| format!("@{}", user.github_login))
+                            .unwrap_or_else(|| "Unknown user".to_string());
+                        (author.into(), comment.body.clone().into())
+                    })
+                    .collect(),
+            };
+            thread_ids.push(thread.id);
+            blocks.push(BlockProperties {
+                style: BlockStyle::Flex,
+                position,
+                height: block.height(),
+                render: Arc::new(move |cx: &mut BlockContext| block.render(cx)),
+                disposition: BlockDisposition::Below,
+            });
+        }
+
+        if !blocks.is_empty() {
+            let block_ids = self.insert_blocks(blocks, None, cx);
+            self.review_threads.blocks = thread_ids.into_iter().zip(block_ids).collect();
+        }
+    }
+
+    fn review_thread_position(&self, id: ReviewThreadId, cx: &AppContext) -> Option<Anchor> {
+        let project = self.project.as_ref()?.read(cx);
+        self.review_thread_anchor(project.review_thread(id)?, cx)
+    }
+
+    /// The position in this editor's buffer at which a thread's range ends, if the
+    /// buffer shows it.
+    fn review_thread_anchor(&self, thread: &ReviewThread, cx: &AppContext) -> Option<Anchor> {
+        let multibuffer = self.buffer.read(cx);
+        let buffer = multibuffer.buffer(thread.buffer_id)?;
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let position = thread.range.end;
+        let (excerpt_id, _) = multibuffer
+            .excerpts_for_buffer(&buffer, cx)
+            .into_iter()
+            .find(|(_, range)| {
+                range.context.start.cmp(&position, &buffer_snapshot).is_le()
+                    && range.context.end.cmp(&position, &buffer_snapshot).is_ge()
+            })?;
+        Some(multibuffer.read(cx).anchor_in_excerpt(excerpt_id, position))
+    }
+}
+
+struct ReviewThreadBlock {
+    id: ReviewThreadId,
+    resolved: bool,
+    expanded: bool,
+    comments: Vec<(SharedString, SharedString)>,
+}
+
+impl ReviewThreadBlock {
+    fn height(&self) -> u8 {
+        if !self.expanded {
+            return 1;
+        }
+        let height = 1 + self
+            .comments
+            .iter()
+            .map(|(_, body)| 1 + body.lines().count().max(1))
+            .sum::<usize>();
+        height.min(u8::MAX as usize) as u8
+    }
+
+    fn render(&self, cx: &mut BlockContext) -> AnyElement {
+        let id = self.id;
+        let resolved = self.resolved;
+        let editor = cx.view.clone();
+        let line_height = cx.line_height;
+        let summary = if self.expanded {
+            match self.comments.len() {
+                1 => "1 comment".to_string(),
+                count => format!("{count} comments"),
+            }
+        } else {
+            let (author, body) = self.comments.first().cloned().unwrap_or_default();
+            let first_line = body.lines().next().unwrap_or_default().to_string();
+            match self.comments.len() {
+                0 | 1 => format!("{author}: {first_line}"),
+                count => format!("{author}: {first_line} ({} replies)", count - 1),
+            }
+        };
+
+        v_flex()
+            .id(("review-thread", id))
+            .w_full()
+            .child(
+                h_flex()
+                    .h(line_height)
+                    .gap_2()
+                    .child(
+                        h_flex()
+                            .w(cx.gutter_width)
+                            .justify_end()
+                            .pr(cx.gutter_padding)
+                            .child(
+                                IconButton::new(
+                                    ("toggle-review-thread", id),
+                                    IconName::MessageBubbles,
+                                )
+                                .icon_size(IconSize::Small)
+                                .icon_color(if resolved {
+                                    Color::Muted
+                                } else {
+                                    Color::Accent
+                                })
+                                .selected(self.expanded)
+                                .tooltip(|cx| Tooltip::text("Toggle review thread", cx))
+                                .on_click({
+                                    let editor = editor.clone();
+                                    move |_, cx| {
+                                        editor.update(cx, |editor, cx| {
+                                            editor.toggle_review_thread(id, cx)
+                                        })
+                                    }
+                                }),
+                            ),
+                    )
+                    .child(
+                        Label::new(summary)
+                            .size(LabelSize::Small)
+                            .color(if resolved {
+                                Color::Muted
+                            } else {
+                                Color::Default
+                            }),
+                    )
+                    .when(resolved, |this| {
+                        this.child(
+                            Label::new("Resolved")
+                                .size(LabelSize::Small)
+                                .color(Color::Success),
+                        )
+                    })
+                    .child(
+                        Button::new(("reply-to-review-thread", id), "Reply")
+                            .label_size(LabelSize::Small)
+                            .on_click({
+                                let editor = editor.clone();
+                                move |_, cx| {
+                                    editor.update(cx, |editor, cx| {
+                                        editor.reply_to_review_thread(id, cx)
+                                    })
+                                }
+                            }),
+                    )
+                    .child(
+                        Button::new(
+                            ("resolve-review-thread", id),
+                            if resolved { "Unresolve" } else { "Resolve" },
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(move |_, cx| {
+                            editor.update(cx, |editor, cx| {
+                                editor.set_review_thread_resolved(id, !resolved, cx)
+                            })
+                        }),
+                    ),
+            )
+            .when(self.expanded, |this| {
+                this.children(self.comments.iter().map(|(author, body)| {
+                    v_flex()
+                        .pl(cx.gutter_width)
+                        .child(
+                            div().h(line_height).child(
+                                Label::new(author.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                        )
+                        .children(
+                            body.lines().map(|line| {
+                                div().h(line_height).child(Label::new(line.to_string()))
+                            }),
+                        )
+                }))
+            })
+            .into_any_element()
+    }
+}
@@ -137,6 +137,7 @@ pub struct ScrollManager {
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: bool,
     visible_line_count: Option<f32>,
+    visible_column_count: Option<f32>,
 }
 
 impl ScrollManager {
@@ -151,6 +152,7 @@ impl ScrollManager {
             dragging_scrollbar: false,
             last_autoscroll: None,
             visible_line_count: None,
+            visible_column_count: None,
         }
     }
 
@@ -329,6 +331,31 @@ impl Editor {
         }
     }
 
+    /// The number of characters that fit in a line of the editor.
+    pub fn visible_column_count(&self) -> Option<f32> {
+        self.scroll_manager.visible_column_count
+    }
+
+    pub(crate) fn set_visible_column_count(&mut self, columns: f32, cx: &mut ViewContext<Self>) {
+        let previous_columns = self
+            .scroll_manager
+            .visible_column_count
+            .replace(columns)
+            .map(f32::floor);
+        if previous_columns != Some(columns.floor()) {
+            // Inline diagnostics are truncated to the visible columns, but can't be updated
+            // while the editor is being laid out.
+            cx.spawn(|editor, mut cx| async move {
+                editor
+                    .update(&mut cx, |editor, cx| {
+                        editor.invalidate_inline_diagnostics(cx)
+                    })
+                    .ok()
+            })
+            .detach()
+        }
+    }
+
     pub fn apply_scroll_delta(
         &mut self,
         scroll_delta: gpui::Point<f32>,
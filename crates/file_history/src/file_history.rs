@@ -0,0 +1,355 @@
+use editor::{Editor, EditorMode, MultiBuffer};
+use gpui::{
+    actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{language_settings::SoftWrap, Buffer};
+use picker::{Picker, PickerDelegate};
+use project::{
+    file_history::{FileSnapshot, SnapshotReason, FILE_HISTORY},
+    Project,
+};
+use similar::{ChangeTag, TextDiff};
+use std::{path::PathBuf, sync::Arc};
+use time::{OffsetDateTime, UtcOffset};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(file_history, [Toggle]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(FileHistoryView::register).detach();
+}
+
+pub fn toggle(editor: View<Editor>, _: &Toggle, cx: &mut WindowContext) {
+    let Some(buffer) = editor.read(cx).buffer().read(cx).as_singleton() else {
+        return;
+    };
+    let Some(abs_path) = buffer
+        .read(cx)
+        .file()
+        .and_then(|file| Some(file.as_local()?.abs_path(cx)))
+    else {
+        return;
+    };
+
+    if let Some(workspace) = editor.read(cx).workspace() {
+        workspace.update(cx, |workspace, cx| {
+            let weak_workspace = cx.view().downgrade();
+            let project = workspace.project().clone();
+            workspace.toggle_modal(cx, |cx| {
+                FileHistoryView::new(weak_workspace, project, buffer, abs_path, cx)
+            });
+        })
+    }
+}
+
+/// A timeline of the snapshots recorded for the file of the active editor.
+pub struct FileHistoryView {
+    picker: View<Picker<FileHistoryDelegate>>,
+}
+
+impl FocusableView for FileHistoryView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for FileHistoryView {}
+impl ModalView for FileHistoryView {}
+
+impl Render for FileHistoryView {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FileHistoryView {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        if editor.mode() == EditorMode::Full {
+            let handle = cx.view().downgrade();
+            editor.register_action(move |action, cx| {
+                if let Some(editor) = handle.upgrade() {
+                    toggle(editor, action, cx);
+                }
+            });
+        }
+    }
+
+    fn new(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        buffer: Model<Buffer>,
+        abs_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = FileHistoryDelegate {
+            file_history_view: cx.view().downgrade(),
+            workspace,
+            project,
+            buffer,
+            entries: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            now: OffsetDateTime::now_utc(),
+            timezone: cx.local_timezone(),
+        };
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx).max_height(vh(0.75, cx)));
+        Self::load_entries(picker.downgrade(), abs_path, cx);
+        Self { picker }
+    }
+
+    fn load_entries(
+        picker: WeakView<Picker<FileHistoryDelegate>>,
+        abs_path: PathBuf,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(current_text) = picker
+            .upgrade()
+            .map(|picker| picker.read(cx).delegate.buffer.read(cx).text())
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let entries = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut entries = Vec::new();
+                    for snapshot in FILE_HISTORY.snapshots(&abs_path)? {
+                        let Some(content) = FILE_HISTORY.snapshot_content(snapshot.id)? else {
+                            continue;
+                        };
+                        let (insertions, deletions) = line_stats(&content, &current_text);
+                        entries.push(HistoryEntry {
+                            snapshot,
+                            content: content.into(),
+                            insertions,
+                            deletions,
+                        });
+                    }
+                    anyhow::Ok(entries)
+                })
+                .await?;
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.entries = entries;
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+struct HistoryEntry {
+    snapshot: FileSnapshot,
+    content: Arc<str>,
+    /// The number of lines the current buffer adds relative to the snapshot.
+    insertions: usize,
+    /// The number of lines the current buffer removes relative to the snapshot.
+    deletions: usize,
+}
+
+pub struct FileHistoryDelegate {
+    file_history_view: WeakView<FileHistoryView>,
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    buffer: Model<Buffer>,
+    entries: Vec<HistoryEntry>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    now: OffsetDateTime,
+    timezone: UtcOffset,
+}
+
+impl FileHistoryDelegate {
+    fn label(&self, entry: &HistoryEntry) -> String {
+        let reason = match entry.snapshot.reason {
+            SnapshotReason::Save => "Saved",
+            SnapshotReason::Reload => "Before reload",
+        };
+        let timestamp = OffsetDateTime::from(entry.snapshot.timestamp);
+        format!(
+            "{reason} {}",
+            format_timestamp(self.now, timestamp, self.timezone)
+        )
+    }
+
+    fn restore(&self, entry: &HistoryEntry, cx: &mut ViewContext<Picker<Self>>) {
+        let buffer = self.buffer.clone();
+        let diff = buffer.read(cx).diff(entry.content.to_string(), cx);
+        cx.spawn(|_, mut cx| async move {
+            let diff = diff.await;
+            buffer.update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))?;
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_diff(&self, entry: &HistoryEntry, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let project = self.project.clone();
+        let buffer = self.buffer.read(cx);
+        let current_text = buffer.text();
+        let file_name = buffer
+            .file()
+            .map(|file| file.file_name(cx).to_string_lossy().to_string())
+            .unwrap_or_default();
+        let diff_text = TextDiff::from_lines(entry.content.as_ref(), current_text.as_str())
+            .unified_diff()
+            .header(&self.label(entry), "Current")
+            .to_string();
+
+        let Some(diff_buffer) = project
+            .update(cx, |project, cx| {
+                project.create_buffer(&diff_text, None, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let multibuffer = cx.new_model(|cx| {
+            MultiBuffer::singleton(diff_buffer, cx).with_title(format!("{file_name} (history)"))
+        });
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_multibuffer(multibuffer, Some(project), cx);
+            editor.set_read_only(true);
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item(Box::new(editor), cx);
+        });
+    }
+}
+
+impl PickerDelegate for FileHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select a snapshot to restore, or cmd-enter to compare it...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, _: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let query = query.to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.label(entry).to_lowercase().contains(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = 0;
+        Task::ready(())
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .and_then(|ix| self.entries.get(*ix))
+        {
+            if secondary {
+                self.open_diff(entry, cx);
+            } else {
+                self.restore(entry, cx);
+            }
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.file_history_view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.entries[*self.matches.get(ix)?];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    h_flex()
+                        .w_full()
+                        .justify_between()
+                        .child(Label::new(self.label(entry)))
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    Label::new(format!("+{}", entry.insertions))
+                                        .color(Color::Created),
+                                )
+                                .child(
+                                    Label::new(format!("-{}", entry.deletions))
+                                        .color(Color::Deleted),
+                                ),
+                        ),
+                ),
+        )
+    }
+}
+
+fn line_stats(old_text: &str, new_text: &str) -> (usize, usize) {
+    let mut insertions = 0;
+    let mut deletions = 0;
+    for change in TextDiff::from_lines(old_text, new_text).iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => insertions += 1,
+            ChangeTag::Delete => deletions += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+    (insertions, deletions)
+}
+
+fn format_timestamp(
+    reference: OffsetDateTime,
+    timestamp: OffsetDateTime,
+    timezone: UtcOffset,
+) -> String {
+    let timestamp_local = timestamp.to_offset(timezone);
+    let formatted_time = format!(
+        "{:02}:{:02}:{:02}",
+        timestamp_local.hour(),
+        timestamp_local.minute(),
+        timestamp_local.second()
+    );
+
+    let reference_local_date = reference.to_offset(timezone).date();
+    let timestamp_local_date = timestamp_local.date();
+    if timestamp_local_date == reference_local_date {
+        return format!("today at {formatted_time}");
+    }
+    if reference_local_date.previous_day() == Some(timestamp_local_date) {
+        return format!("yesterday at {formatted_time}");
+    }
+
+    format!(
+        "{:02}/{:02}/{} at {formatted_time}",
+        timestamp_local_date.month() as u32,
+        timestamp_local_date.day(),
+        timestamp_local_date.year()
+    )
+}
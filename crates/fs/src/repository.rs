@@ -27,6 +27,8 @@ pub trait GitRepository: Send {
 
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
+    /// Returns the URLs of all of the repository's remotes.
+    fn remote_urls(&self) -> Vec<String>;
     fn branch_name(&self) -> Option<String>;
 
     /// Returns the SHA of the current HEAD.
@@ -97,6 +99,17 @@ impl GitRepository for LibGitRepository {
         remote.url().map(|url| url.to_string())
     }
 
+    fn remote_urls(&self) -> Vec<String> {
+        let Some(names) = self.remotes().log_err() else {
+            return Vec::new();
+        };
+        names
+            .iter()
+            .flatten()
+            .filter_map(|name| GitRepository::remote_url(self, name))
+            .collect()
+    }
+
     fn branch_name(&self) -> Option<String> {
         let head = self.head().log_err()?;
         let branch = String::from_utf8_lossy(head.shorthand_bytes());
@@ -253,6 +266,7 @@ pub struct FakeGitRepositoryState {
     pub index_contents: HashMap<PathBuf, String>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub branch_name: Option<String>,
+    pub remote_urls: HashMap<String, String>,
 }
 
 impl FakeGitRepository {
@@ -269,8 +283,16 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
-    fn remote_url(&self, _name: &str) -> Option<String> {
-        None
+    fn remote_url(&self, name: &str) -> Option<String> {
+        let state = self.state.lock();
+        state.remote_urls.get(name).cloned()
+    }
+
+    fn remote_urls(&self) -> Vec<String> {
+        let state = self.state.lock();
+        let mut remotes = state.remote_urls.iter().collect::<Vec<_>>();
+        remotes.sort();
+        remotes.into_iter().map(|(_, url)| url.clone()).collect()
     }
 
     fn branch_name(&self) -> Option<String> {
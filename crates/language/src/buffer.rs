@@ -97,6 +97,7 @@ pub struct Buffer {
     pending_autoindent: Option<Task<()>>,
     sync_parse_timeout: Duration,
     syntax_map: Mutex<SyntaxMap>,
+    syntax_enabled: bool,
     parsing_in_background: bool,
     parse_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
@@ -329,6 +330,8 @@ pub enum Event {
     Saved,
     /// The buffer's file was changed on disk.
     FileHandleChanged,
+    /// The buffer is about to be reloaded from disk.
+    Reloading,
     /// The buffer was reloaded.
     Reloaded,
     /// The buffer's diff_base changed.
@@ -497,6 +500,23 @@ pub struct Chunk<'a> {
     pub is_tab: bool,
 }
 
+/// A tree-sitter highlight capture containing a position in a buffer.
+#[derive(Clone, Debug)]
+pub struct HighlightCapture {
+    /// The language of the syntax layer that the capture was matched in.
+    pub language: Option<Arc<Language>>,
+    /// The depth of the syntax layer, which increases with each level of injection.
+    pub depth: usize,
+    /// The name of the capture in the highlights query, such as `function.method`.
+    pub capture_name: String,
+    /// The kind of the captured syntax node.
+    pub node_kind: &'static str,
+    /// The byte range of the captured syntax node.
+    pub range: Range<usize>,
+    /// The theme highlight that the capture resolves to.
+    pub highlight_id: HighlightId,
+}
+
 /// A set of edits to a given version of a buffer, computed asynchronously.
 pub struct Diff {
     pub(crate) base_version: clock::Global,
@@ -682,6 +702,7 @@ impl Buffer {
             file,
             capability,
             syntax_map: Mutex::new(SyntaxMap::new()),
+            syntax_enabled: true,
             parsing_in_background: false,
             parse_count: 0,
             sync_parse_timeout: Duration::from_millis(1),
@@ -765,6 +786,28 @@ impl Buffer {
         cx.emit(Event::LanguageChanged);
     }
 
+    /// Whether the buffer's text is parsed with its language's grammar.
+    pub fn syntax_enabled(&self) -> bool {
+        self.syntax_enabled
+    }
+
+    /// Enables or disables parsing the buffer. A buffer whose syntax is disabled keeps its
+    /// language, but has no syntax tree, so it isn't highlighted or auto-indented.
+    pub fn set_syntax_enabled(&mut self, enabled: bool, cx: &mut ModelContext<Self>) {
+        if self.syntax_enabled == enabled {
+            return;
+        }
+        self.syntax_enabled = enabled;
+        if enabled {
+            self.reparse(cx);
+        } else {
+            self.parse_count += 1;
+            self.syntax_map.lock().clear();
+            cx.emit(Event::Reparsed);
+            cx.notify();
+        }
+    }
+
     /// Assign a language registry to the buffer. This allows the buffer to retrieve
     /// other languages if parts of the buffer are written in different languages.
     pub fn set_language_registry(&mut self, language_registry: Arc<LanguageRegistry>) {
@@ -801,6 +844,7 @@ impl Buffer {
     ) -> oneshot::Receiver<Option<Transaction>> {
         let (tx, rx) = futures::channel::oneshot::channel();
         let prev_version = self.text.version();
+        cx.emit(Event::Reloading);
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
@@ -922,6 +966,14 @@ impl Buffer {
         cx.emit(Event::DiffBaseChanged);
     }
 
+    /// Removes the diff base, along with the hunks that were computed against it.
+    pub fn clear_diff_base(&mut self, cx: &mut ModelContext<Self>) {
+        self.diff_base = None;
+        self.git_diff = git::diff::BufferDiff::new();
+        self.git_diff_update_count += 1;
+        cx.emit(Event::DiffBaseChanged);
+    }
+
     /// Recomputes the Git diff status.
     pub fn git_diff_recalc(&mut self, cx: &mut ModelContext<Self>) -> Option<Task<()>> {
         let diff_base = self.diff_base.clone()?; // TODO: Make this an Arc
@@ -1025,7 +1077,7 @@ impl Buffer {
     /// for the same buffer, we only initiate a new parse if we are not already
     /// parsing in the background.
     pub fn reparse(&mut self, cx: &mut ModelContext<Self>) {
-        if self.parsing_in_background {
+        if self.parsing_in_background || !self.syntax_enabled {
             return;
         }
         let language = if let Some(language) = self.language.clone() {
@@ -1479,6 +1531,14 @@ impl Buffer {
         self.edit([(offset..len, "\n")], None, cx);
     }
 
+    /// Changes the line ending that the buffer is written with when it's saved.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut ModelContext<Self>) {
+        if self.line_ending() != line_ending {
+            self.text.set_line_ending(line_ending);
+            cx.notify();
+        }
+    }
+
     /// Applies a diff to the buffer. If the buffer has changed since the given diff was
     /// calculated, then adjust the diff to account for those changes, and discard any
     /// parts of the diff that conflict with those changes.
@@ -2493,11 +2553,68 @@ impl BufferSnapshot {
     }
 
     fn syntax_layer_at<D: ToOffset>(&self, position: D) -> Option<SyntaxLayer> {
+        self.syntax_layers_at(position).last()
+    }
+
+    /// Iterates over every [`SyntaxLayer`] containing the given location, from the
+    /// outermost to the most deeply injected one.
+    pub fn syntax_layers_at<D: ToOffset>(
+        &self,
+        position: D,
+    ) -> impl Iterator<Item = SyntaxLayer> + '_ {
         let offset = position.to_offset(self);
         self.syntax
             .layers_for_range(offset..offset, &self.text)
-            .filter(|l| l.node().end_byte() > offset)
-            .last()
+            .filter(move |l| l.node().end_byte() > offset)
+    }
+
+    /// Returns the highlight captures whose nodes contain the given location, in the
+    /// order in which they are applied, so that the last one determines the style.
+    pub fn highlight_captures_at<D: ToOffset>(&self, position: D) -> Vec<HighlightCapture> {
+        let offset = position.to_offset(self);
+        let mut captures = self.syntax.captures(
+            offset..(offset + 1).min(self.len()),
+            &self.text,
+            |grammar| grammar.highlights_query.as_ref(),
+        );
+        let grammars = captures.grammars().to_vec();
+        let highlight_maps = grammars
+            .iter()
+            .map(|grammar| grammar.highlight_map())
+            .collect::<Vec<_>>();
+        let languages = grammars
+            .iter()
+            .map(|grammar| {
+                self.syntax_layers_at(offset)
+                    .find(|layer| {
+                        layer
+                            .language
+                            .grammar()
+                            .map_or(false, |layer_grammar| layer_grammar.id() == grammar.id())
+                    })
+                    .map(|layer| layer.language.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let mut result = Vec::new();
+        while let Some(capture) = captures.peek() {
+            let range = capture.node.byte_range();
+            let grammar = grammars[capture.grammar_index];
+            if let Some(query) = grammar.highlights_query.as_ref() {
+                if range.start <= offset && offset < range.end {
+                    result.push(HighlightCapture {
+                        language: languages[capture.grammar_index].clone(),
+                        depth: capture.depth,
+                        capture_name: query.capture_names()[capture.index as usize].to_string(),
+                        node_kind: capture.node.kind(),
+                        range,
+                        highlight_id: highlight_maps[capture.grammar_index].get(capture.index),
+                    });
+                }
+            }
+            captures.advance();
+        }
+        result
     }
 
     /// Returns the [Language] at the given location.
@@ -1930,6 +1930,54 @@ fn test_language_scope_at_with_combined_injections(cx: &mut AppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_highlight_captures_at(cx: &mut gpui::TestAppContext) {
+    let language = rust_lang()
+        .with_highlights_query(
+            r#"
+            (identifier) @variable
+            (function_item name: (identifier) @function)
+            "#,
+        )
+        .unwrap();
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(
+            0,
+            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+            "fn main() { x }",
+        )
+        .with_language(Arc::new(language), cx)
+    });
+    cx.executor().run_until_parked();
+
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    let capture_names = |offset: usize| {
+        snapshot
+            .highlight_captures_at(offset)
+            .into_iter()
+            .map(|capture| (capture.capture_name, capture.node_kind, capture.range))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        capture_names(4),
+        [
+            ("variable".to_string(), "identifier", 3..7),
+            ("function".to_string(), "identifier", 3..7)
+        ]
+    );
+    assert_eq!(
+        capture_names(12),
+        [("variable".to_string(), "identifier", 12..13)]
+    );
+    assert!(capture_names(0).is_empty());
+
+    let layers = snapshot
+        .syntax_layers_at(4)
+        .map(|layer| (layer.language.name(), layer.depth()))
+        .collect::<Vec<_>>();
+    assert_eq!(layers, [("Rust".into(), 0)]);
+}
+
 #[gpui::test]
 fn test_serialization(cx: &mut gpui::AppContext) {
     let mut now = Instant::now();
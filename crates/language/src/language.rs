@@ -38,6 +38,7 @@ use serde_json::Value;
 use std::{
     any::Any,
     cell::RefCell,
+    ffi::OsString,
     fmt::Debug,
     hash::Hash,
     mem,
@@ -58,8 +59,8 @@ pub use buffer::Operation;
 pub use buffer::*;
 pub use diagnostic_set::DiagnosticEntry;
 pub use language_registry::{
-    LanguageQueries, LanguageRegistry, LanguageServerBinaryStatus, PendingLanguageServer,
-    QUERY_FILENAME_PREFIXES,
+    LanguageQueries, LanguageRegistry, LanguageServerBinaryOverride, LanguageServerBinaryStatus,
+    PendingLanguageServer, QUERY_FILENAME_PREFIXES,
 };
 pub use lsp::LanguageServerId;
 pub use outline::{Outline, OutlineItem};
@@ -353,6 +354,12 @@ pub trait LspAdapter: 'static + Send + Sync {
         Default::default()
     }
 
+    /// Returns the arguments to launch the language server with when the settings
+    /// configure the path of its binary, but not its arguments.
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        Vec::new()
+    }
+
     fn prettier_plugins(&self) -> &[&'static str] {
         &[]
     }
@@ -15,7 +15,7 @@ use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use std::{
     borrow::Cow,
-    ffi::OsStr,
+    ffi::{OsStr, OsString},
     ops::Not,
     path::{Path, PathBuf},
     sync::Arc,
@@ -65,6 +65,40 @@ pub struct PendingLanguageServer {
     pub container_dir: Option<Arc<Path>>,
 }
 
+/// Changes to how a language server is launched, as configured in the settings.
+#[derive(Clone, Debug, Default)]
+pub struct LanguageServerBinaryOverride {
+    /// A binary to launch instead of the one the adapter installs or locates.
+    pub path: Option<PathBuf>,
+    /// The arguments to launch the binary with, instead of the adapter's.
+    pub arguments: Option<Vec<OsString>>,
+    /// Environment variables to set for the server, in addition to the adapter's.
+    pub env: Option<HashMap<String, String>>,
+}
+
+impl LanguageServerBinaryOverride {
+    /// The binary to launch when the settings configure its path, with the adapter's
+    /// default arguments unless the settings override those too.
+    fn configured_binary(&self, default_arguments: Vec<OsString>) -> Option<LanguageServerBinary> {
+        let binary = LanguageServerBinary {
+            path: self.path.clone()?,
+            arguments: default_arguments,
+            env: None,
+        };
+        Some(self.clone().apply(binary))
+    }
+
+    fn apply(self, mut binary: LanguageServerBinary) -> LanguageServerBinary {
+        if let Some(arguments) = self.arguments {
+            binary.arguments = arguments;
+        }
+        if let Some(env) = self.env {
+            binary.env.get_or_insert_with(HashMap::default).extend(env);
+        }
+        binary
+    }
+}
+
 #[derive(Clone)]
 struct AvailableLanguage {
     id: LanguageId,
@@ -261,6 +295,21 @@ impl LanguageRegistry {
         self.state.write().add(language);
     }
 
+    /// Loads the source of the tree-sitter queries of the language with the given name,
+    /// so that they can be inspected.
+    pub fn load_language_queries(&self, name: &str) -> Result<LanguageQueries> {
+        let load = self
+            .state
+            .read()
+            .available_languages
+            .iter()
+            .rfind(|language| language.name.as_ref() == name)
+            .map(|language| language.load.clone())
+            .ok_or_else(|| anyhow!("no queries found for language {name}"))?;
+        let (_, queries) = load()?;
+        Ok(queries)
+    }
+
     pub fn subscribe(&self) -> watch::Receiver<()> {
         self.state.read().subscription.1.clone()
     }
@@ -495,6 +544,7 @@ impl LanguageRegistry {
         stderr_capture: Arc<Mutex<Option<String>>>,
         language: Arc<Language>,
         adapter: Arc<CachedLspAdapter>,
+        binary_override: LanguageServerBinaryOverride,
         root_path: Arc<Path>,
         delegate: Arc<dyn LspAdapterDelegate>,
         cx: &mut AppContext,
@@ -554,38 +604,45 @@ impl LanguageRegistry {
         let adapter = adapter.clone();
         let login_shell_env_loaded = self.login_shell_env_loaded.clone();
         let lsp_binary_statuses = self.lsp_binary_status_tx.clone();
+        let has_installed_binary = binary_override.path.is_none();
 
         let task = {
             let container_dir = container_dir.clone();
             cx.spawn(move |mut cx| async move {
                 login_shell_env_loaded.await;
 
-                let entry = this
-                    .lsp_binary_paths
-                    .lock()
-                    .entry(adapter.name.clone())
-                    .or_insert_with(|| {
-                        let adapter = adapter.clone();
-                        let language = language.clone();
-                        let delegate = delegate.clone();
-                        cx.spawn(|cx| {
-                            get_binary(
-                                adapter,
-                                language,
-                                delegate,
-                                container_dir,
-                                lsp_binary_statuses,
-                                cx,
-                            )
-                            .map_err(Arc::new)
+                let configured_binary =
+                    binary_override.configured_binary(adapter.adapter.default_binary_arguments());
+                let binary = if let Some(binary) = configured_binary {
+                    binary
+                } else {
+                    let entry = this
+                        .lsp_binary_paths
+                        .lock()
+                        .entry(adapter.name.clone())
+                        .or_insert_with(|| {
+                            let adapter = adapter.clone();
+                            let language = language.clone();
+                            let delegate = delegate.clone();
+                            cx.spawn(|cx| {
+                                get_binary(
+                                    adapter,
+                                    language,
+                                    delegate,
+                                    container_dir,
+                                    lsp_binary_statuses,
+                                    cx,
+                                )
+                                .map_err(Arc::new)
+                            })
+                            .shared()
                         })
-                        .shared()
-                    })
-                    .clone();
+                        .clone();
 
-                let binary = match entry.await {
-                    Ok(binary) => binary,
-                    Err(err) => anyhow::bail!("{err}"),
+                    match entry.await {
+                        Ok(binary) => binary_override.apply(binary),
+                        Err(err) => anyhow::bail!("{err}"),
+                    }
                 };
 
                 if let Some(task) = adapter.will_start_server(&delegate, &mut cx) {
@@ -606,7 +663,9 @@ impl LanguageRegistry {
         Some(PendingLanguageServer {
             server_id,
             task,
-            container_dir: Some(container_dir),
+            // Binaries configured in the settings aren't installed by Zed, so they
+            // can't be reinstalled either.
+            container_dir: has_installed_binary.then_some(container_dir),
         })
     }
 
@@ -794,3 +853,63 @@ async fn fetch_latest_binary(
 
     Ok(binary)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_server_binary_override() {
+        let adapter_binary = || LanguageServerBinary {
+            path: PathBuf::from("/installed/server"),
+            arguments: vec!["--stdio".into()],
+            env: Some([("A".to_string(), "1".to_string())].into_iter().collect()),
+        };
+
+        // Without a path, the installed binary is launched with the configured
+        // arguments and the configured environment variables added to its own.
+        let binary_override = LanguageServerBinaryOverride {
+            path: None,
+            arguments: Some(vec!["--verbose".into()]),
+            env: Some([("B".to_string(), "2".to_string())].into_iter().collect()),
+        };
+        assert!(binary_override
+            .configured_binary(vec!["--stdio".into()])
+            .is_none());
+        let binary = binary_override.apply(adapter_binary());
+        assert_eq!(binary.path, PathBuf::from("/installed/server"));
+        assert_eq!(binary.arguments, [OsString::from("--verbose")]);
+        let mut env = binary.env.unwrap().into_iter().collect::<Vec<_>>();
+        env.sort();
+        assert_eq!(
+            env,
+            [
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "2".to_string())
+            ]
+        );
+
+        // A configured path keeps the adapter's default arguments, unless they're
+        // configured too.
+        let binary_override = LanguageServerBinaryOverride {
+            path: Some(PathBuf::from("/usr/bin/server")),
+            arguments: None,
+            env: None,
+        };
+        let binary = binary_override
+            .configured_binary(vec!["--stdio".into()])
+            .unwrap();
+        assert_eq!(binary.path, PathBuf::from("/usr/bin/server"));
+        assert_eq!(binary.arguments, [OsString::from("--stdio")]);
+        assert_eq!(binary.env, None);
+
+        let binary_override = LanguageServerBinaryOverride {
+            arguments: Some(Vec::new()),
+            ..binary_override
+        };
+        let binary = binary_override
+            .configured_binary(vec!["--stdio".into()])
+            .unwrap();
+        assert!(binary.arguments.is_empty());
+    }
+}
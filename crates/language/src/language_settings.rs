@@ -1,6 +1,6 @@
 //! Provides `language`-related settings.
 
-use crate::{File, Language};
+use crate::{File, Language, LineEnding};
 use anyhow::Result;
 use collections::{HashMap, HashSet};
 use globset::GlobMatcher;
@@ -10,7 +10,10 @@ use schemars::{
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use settings::Settings;
+use settings::{
+    editorconfig::{EditorConfigProperties, EndOfLine, IndentStyle, MaxLineLength},
+    Settings, SettingsStore,
+};
 use std::{num::NonZeroU32, path::Path, sync::Arc};
 
 /// Initializes the language settings.
@@ -19,6 +22,9 @@ pub fn init(cx: &mut AppContext) {
 }
 
 /// Returns the settings for the specified language from the provided file.
+///
+/// The properties of any `.editorconfig` files that apply to the file take precedence
+/// over the settings from `settings.json` files.
 pub fn language_settings<'a>(
     language: Option<&Arc<Language>>,
     file: Option<&Arc<dyn File>>,
@@ -28,13 +34,22 @@ pub fn language_settings<'a>(
     all_language_settings(file, cx).language(language_name.as_deref())
 }
 
-/// Returns the settings for all languages from the provided file.
+/// Returns the settings for all languages from the provided file, merged with the
+/// properties of any `.editorconfig` files that apply to it.
 pub fn all_language_settings<'a>(
     file: Option<&Arc<dyn File>>,
     cx: &'a AppContext,
 ) -> &'a AllLanguageSettings {
     let location = file.map(|f| (f.worktree_id(), f.path().as_ref()));
-    AllLanguageSettings::get(location, cx)
+    let settings = AllLanguageSettings::get(location, cx);
+    let Some(file) = file else {
+        return settings;
+    };
+    cx.global::<SettingsStore>()
+        .editorconfig_value(file.worktree_id(), file.path(), |properties| {
+            settings.with_editorconfig_properties(properties)
+        })
+        .unwrap_or(settings)
 }
 
 /// The settings for all languages.
@@ -91,10 +106,19 @@ pub struct LanguageSettings {
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
+    /// Settings for showing diagnostic messages at the end of their lines.
+    pub inline_diagnostics: InlineDiagnosticsSettings,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
     /// Which code actions to run on save
     pub code_actions_on_format: HashMap<String, bool>,
+    /// The order in which to prefer language servers for each feature, when the
+    /// language has several.
+    pub language_server_preferences: HashMap<LanguageServerFeature, Vec<Arc<str>>>,
+    /// The line ending to convert a buffer to when saving it. This can only be set
+    /// by an `.editorconfig` file.
+    #[serde(skip)]
+    pub line_ending: Option<LineEnding>,
 }
 
 /// The settings for [GitHub Copilot](https://github.com/features/copilot).
@@ -212,6 +236,9 @@ pub struct LanguageSettingsContent {
     /// Inlay hint related settings.
     #[serde(default)]
     pub inlay_hints: Option<InlayHintSettings>,
+    /// Settings for showing diagnostic messages at the end of their lines.
+    #[serde(default)]
+    pub inline_diagnostics: Option<InlineDiagnosticsSettings>,
     /// Whether to automatically type closing characters for you. For example,
     /// when you type (, Zed will automatically add a closing ) at the correct position.
     ///
@@ -222,6 +249,15 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
     pub code_actions_on_format: Option<HashMap<String, bool>>,
+
+    /// The order in which to prefer language servers for each feature, when the
+    /// language has several, such as `{ "formatting": ["ruff", "pyright"] }`.
+    /// Features that take a single server's result use the first listed server that
+    /// supports them, and features that merge results from every server list the
+    /// preferred server's results first. Unlisted servers follow in their default order.
+    ///
+    /// Default: {}
+    pub language_server_preferences: Option<HashMap<LanguageServerFeature, Vec<Arc<str>>>>,
 }
 
 /// The contents of the GitHub Copilot settings.
@@ -240,6 +276,31 @@ pub struct FeaturesContent {
     pub copilot: Option<bool>,
 }
 
+/// A feature provided by language servers, for which the preferred servers can be
+/// configured.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerFeature {
+    /// Completions, which are merged from every server.
+    Completions,
+    /// Code actions, which are merged from every server.
+    CodeActions,
+    /// Hover information.
+    Hover,
+    /// Going to definitions and type definitions.
+    Definition,
+    /// Finding references.
+    References,
+    /// Highlighting the other occurrences of a symbol.
+    DocumentHighlights,
+    /// Renaming symbols.
+    Rename,
+    /// Formatting buffers, including formatting as you type.
+    Formatting,
+    /// Inlay hints.
+    InlayHints,
+}
+
 /// Controls the soft-wrapping behavior in the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -350,6 +411,54 @@ impl InlayHintSettings {
     }
 }
 
+/// The settings for diagnostics shown at the end of their lines.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnosticsSettings {
+    /// Whether to show the message of the most severe diagnostic on each line
+    /// after the end of that line.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub enabled: bool,
+    /// The least severe diagnostics to show inline.
+    ///
+    /// Default: warning
+    #[serde(default = "default_inline_diagnostics_min_severity")]
+    pub min_severity: DiagnosticSeverityFilter,
+}
+
+fn default_inline_diagnostics_min_severity() -> DiagnosticSeverityFilter {
+    DiagnosticSeverityFilter::Warning
+}
+
+/// A minimum severity, which diagnostics must meet to be shown.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverityFilter {
+    /// Only show errors.
+    Error,
+    /// Show errors and warnings.
+    Warning,
+    /// Show errors, warnings and information.
+    Info,
+    /// Show all diagnostics.
+    Hint,
+}
+
+impl DiagnosticSeverityFilter {
+    /// Returns whether diagnostics of the given severity meet this minimum.
+    pub fn includes(&self, severity: lsp::DiagnosticSeverity) -> bool {
+        let min_severity = match self {
+            Self::Error => lsp::DiagnosticSeverity::ERROR,
+            Self::Warning => lsp::DiagnosticSeverity::WARNING,
+            Self::Info => lsp::DiagnosticSeverity::INFORMATION,
+            Self::Hint => lsp::DiagnosticSeverity::HINT,
+        };
+        // More severe diagnostics have lower values.
+        severity <= min_severity
+    }
+}
+
 impl AllLanguageSettings {
     /// Returns the [`LanguageSettings`] for the language with the specified name.
     pub fn language<'a>(&'a self, language_name: Option<&str>) -> &'a LanguageSettings {
@@ -361,6 +470,17 @@ impl AllLanguageSettings {
         &self.defaults
     }
 
+    /// Returns a copy of these settings, with the given EditorConfig properties applied to
+    /// every language.
+    fn with_editorconfig_properties(&self, properties: &EditorConfigProperties) -> Self {
+        let mut settings = self.clone();
+        merge_editorconfig_properties(&mut settings.defaults, properties);
+        for language_settings in settings.languages.values_mut() {
+            merge_editorconfig_properties(language_settings, properties);
+        }
+        settings
+    }
+
     /// Returns whether GitHub Copilot is enabled for the given path.
     pub fn copilot_enabled_for_path(&self, path: &Path) -> bool {
         !self
@@ -550,6 +670,34 @@ impl settings::Settings for AllLanguageSettings {
     }
 }
 
+fn merge_editorconfig_properties(
+    settings: &mut LanguageSettings,
+    properties: &EditorConfigProperties,
+) {
+    if let Some(indent_style) = properties.indent_style {
+        settings.hard_tabs = indent_style == IndentStyle::Tab;
+    }
+    if let Some(tab_size) = properties.tab_size() {
+        settings.tab_size = tab_size;
+    }
+    match properties.end_of_line {
+        Some(EndOfLine::Lf) => settings.line_ending = Some(LineEnding::Unix),
+        Some(EndOfLine::CrLf) => settings.line_ending = Some(LineEnding::Windows),
+        // Buffers can't use lone carriage returns as line endings.
+        Some(EndOfLine::Cr) | None => {}
+    }
+    if let Some(trim_trailing_whitespace) = properties.trim_trailing_whitespace {
+        settings.remove_trailing_whitespace_on_save = trim_trailing_whitespace;
+    }
+    if let Some(insert_final_newline) = properties.insert_final_newline {
+        settings.ensure_final_newline_on_save = insert_final_newline;
+    }
+    if let Some(MaxLineLength::Columns(columns)) = properties.max_line_length {
+        settings.preferred_line_length = columns;
+    }
+    // Buffers are always saved as UTF-8, so `charset` isn't applied.
+}
+
 fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent) {
     merge(&mut settings.tab_size, src.tab_size);
     merge(&mut settings.hard_tabs, src.hard_tabs);
@@ -561,6 +709,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.code_actions_on_format,
         src.code_actions_on_format.clone(),
     );
+    merge(
+        &mut settings.language_server_preferences,
+        src.language_server_preferences.clone(),
+    );
 
     merge(
         &mut settings.preferred_line_length,
@@ -591,6 +743,7 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(&mut settings.inline_diagnostics, src.inline_diagnostics);
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
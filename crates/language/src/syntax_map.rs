@@ -1459,6 +1459,11 @@ impl<'a> SyntaxLayer<'a> {
         }
     }
 
+    /// Returns how deeply this layer is injected, which is zero for the buffer's own language.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
     /// Returns the root node for this layer.
     pub fn node(&self) -> Node<'a> {
         self.tree
@@ -0,0 +1,477 @@
+use std::{sync::Arc, time::Duration};
+
+use collections::HashSet;
+use gpui::{
+    actions, Action, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, Subscription, Task, View,
+};
+use language::LanguageServerName;
+use project::{Fs, LanguageServerDetails, LanguageServerLifecycle, Project, WorktreeId};
+use settings::{Settings, SettingsStore};
+use sysinfo::{Pid, PidExt, ProcessExt, System, SystemExt};
+use ui::{prelude::*, Indicator, ListItem};
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::{
+    language_server_panel_settings::LanguageServerPanelSettings, lsp_log::OpenLanguageServerLogs,
+};
+
+const PROCESS_REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+actions!(language_server_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    LanguageServerPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            if !workspace.project().read(cx).is_remote() {
+                workspace.toggle_panel_focus::<LanguageServerPanel>(cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// A panel listing the language servers of every worktree, with controls to stop,
+/// start, restart and disable them.
+pub struct LanguageServerPanel {
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    width: Option<Pixels>,
+    active: bool,
+    expanded_servers: HashSet<(WorktreeId, LanguageServerName)>,
+    system: System,
+    _refresh_processes: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl LanguageServerPanel {
+    pub fn new(workspace: &Workspace, cx: &mut WindowContext) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let subscriptions = vec![
+                cx.observe(&project, |_, _, cx| cx.notify()),
+                cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+            ];
+            let refresh_processes = cx.spawn(|this, mut cx| async move {
+                loop {
+                    cx.background_executor()
+                        .timer(PROCESS_REFRESH_INTERVAL)
+                        .await;
+                    let updated = this.update(&mut cx, |this, cx| {
+                        if this.active {
+                            this.refresh_processes(cx);
+                            cx.notify();
+                        }
+                    });
+                    if updated.is_err() {
+                        break;
+                    }
+                }
+            });
+            Self {
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                width: None,
+                active: false,
+                expanded_servers: HashSet::default(),
+                system: System::new(),
+                _refresh_processes: refresh_processes,
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    fn refresh_processes(&mut self, cx: &mut ViewContext<Self>) {
+        let process_ids = self
+            .project
+            .read(cx)
+            .language_server_details(cx)
+            .into_iter()
+            .filter_map(|details| details.process_id)
+            .collect::<Vec<_>>();
+        for process_id in process_ids {
+            self.system.refresh_process(Pid::from_u32(process_id));
+        }
+    }
+
+    fn toggle_expanded(
+        &mut self,
+        key: (WorktreeId, LanguageServerName),
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self.expanded_servers.remove(&key) {
+            self.expanded_servers.insert(key);
+        }
+        cx.notify();
+    }
+
+    fn render_server(
+        &self,
+        ix: usize,
+        details: &LanguageServerDetails,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let worktree_name = project
+            .worktree_for_id(details.worktree_id, cx)
+            .map(|worktree| worktree.read(cx).root_name().to_string())
+            .unwrap_or_default();
+        let key = (details.worktree_id, details.name.clone());
+        let is_expanded = self.expanded_servers.contains(&key);
+        let (status_color, status_label) = match details.lifecycle {
+            LanguageServerLifecycle::Starting => (Color::Warning, "Starting"),
+            LanguageServerLifecycle::Running => (Color::Success, "Running"),
+            LanguageServerLifecycle::Stopped => (Color::Muted, "Stopped"),
+            LanguageServerLifecycle::Disabled => (Color::Disabled, "Disabled"),
+        };
+
+        v_flex()
+            .id(ix)
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(
+                ListItem::new(("server", ix))
+                    .toggle(is_expanded)
+                    .on_toggle(cx.listener({
+                        let key = key.clone();
+                        move |this, _, cx| this.toggle_expanded(key.clone(), cx)
+                    }))
+                    .on_click(cx.listener(move |this, _, cx| this.toggle_expanded(key.clone(), cx)))
+                    .start_slot(Indicator::dot().color(status_color))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(details.name.0.to_string()))
+                            .child(
+                                Label::new(format!("{worktree_name} · {status_label}"))
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            ),
+                    ),
+            )
+            .child(self.render_controls(ix, details, cx))
+            .when(is_expanded, |this| {
+                this.children(self.render_details(details, cx))
+            })
+    }
+
+    fn render_controls(
+        &self,
+        ix: usize,
+        details: &LanguageServerDetails,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let worktree_id = details.worktree_id;
+        let name = details.name.clone();
+        let is_disabled = details.lifecycle == LanguageServerLifecycle::Disabled;
+        let is_stopped = matches!(
+            details.lifecycle,
+            LanguageServerLifecycle::Stopped | LanguageServerLifecycle::Disabled
+        );
+
+        h_flex()
+            .gap_1()
+            .map(|this| {
+                if is_stopped {
+                    this.child(
+                        Button::new(("start", ix), "Start")
+                            .label_size(LabelSize::Small)
+                            .disabled(is_disabled)
+                            .on_click(cx.listener({
+                                let name = name.clone();
+                                move |this, _, cx| {
+                                    this.project.update(cx, |project, cx| {
+                                        project.start_language_server_for_worktree(
+                                            worktree_id,
+                                            name.clone(),
+                                            cx,
+                                        )
+                                    });
+                                }
+                            })),
+                    )
+                } else {
+                    this.child(
+                        Button::new(("stop", ix), "Stop")
+                            .label_size(LabelSize::Small)
+                            .on_click(cx.listener({
+                                let name = name.clone();
+                                move |this, _, cx| {
+                                    this.project
+                                        .update(cx, |project, cx| {
+                                            project.stop_language_server_for_worktree(
+                                                worktree_id,
+                                                name.clone(),
+                                                cx,
+                                            )
+                                        })
+                                        .detach();
+                                }
+                            })),
+                    )
+                }
+            })
+            .child(
+                Button::new(("restart", ix), "Restart")
+                    .label_size(LabelSize::Small)
+                    .disabled(is_disabled)
+                    .on_click(cx.listener({
+                        let name = name.clone();
+                        move |this, _, cx| {
+                            this.project
+                                .update(cx, |project, cx| {
+                                    project.restart_language_server_for_worktree(
+                                        worktree_id,
+                                        name.clone(),
+                                        cx,
+                                    )
+                                })
+                                .detach();
+                        }
+                    })),
+            )
+            .child(
+                Button::new(
+                    ("disable", ix),
+                    if is_disabled {
+                        "Enable for This Project"
+                    } else {
+                        "Disable for This Project"
+                    },
+                )
+                .label_size(LabelSize::Small)
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.project
+                        .update(cx, |project, cx| {
+                            project.set_language_server_disabled(name.clone(), !is_disabled, cx)
+                        })
+                        .detach_and_log_err(cx);
+                })),
+            )
+    }
+
+    fn render_details(
+        &self,
+        details: &LanguageServerDetails,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<AnyElement> {
+        let mut rows = Vec::new();
+        if let Some(id) = details.id {
+            rows.push(detail_row("ID", id.0.to_string()));
+        }
+        if let Some(binary_path) = &details.binary_path {
+            rows.push(detail_row("Binary", binary_path.display().to_string()));
+        }
+        if let Some(version) = &details.version {
+            rows.push(detail_row("Version", version.clone()));
+        }
+        if let Some(process_id) = details.process_id {
+            rows.push(detail_row("Process", process_id.to_string()));
+            if let Some(process) = self.system.process(Pid::from_u32(process_id)) {
+                rows.push(detail_row(
+                    "Memory",
+                    format!("{:.1} MB", process.memory() as f64 / 1_000_000.),
+                ));
+                rows.push(detail_row("CPU", format!("{:.1}%", process.cpu_usage())));
+            }
+        }
+        if let Some(capabilities) = &details.capabilities {
+            let capabilities = capability_names(capabilities);
+            rows.push(section_label("Capabilities"));
+            if capabilities.is_empty() {
+                rows.push(empty_label());
+            }
+            rows.extend(
+                capabilities
+                    .into_iter()
+                    .map(|name| Label::new(name).size(LabelSize::Small).into_any_element()),
+            );
+        }
+        if details.id.is_some() {
+            rows.push(section_label("Buffers"));
+            if details.buffers.is_empty() {
+                rows.push(empty_label());
+            }
+            for buffer in &details.buffers {
+                let path = buffer
+                    .read(cx)
+                    .file()
+                    .map(|file| file.full_path(cx).display().to_string())
+                    .unwrap_or_else(|| "untitled".to_string());
+                rows.push(Label::new(path).size(LabelSize::Small).into_any_element());
+            }
+        }
+        rows
+    }
+}
+
+/// The names of the capabilities that the server enabled, in the casing of the protocol.
+fn capability_names(capabilities: &lsp::ServerCapabilities) -> Vec<String> {
+    let Ok(serde_json::Value::Object(capabilities)) = serde_json::to_value(capabilities) else {
+        return Vec::new();
+    };
+    capabilities
+        .into_iter()
+        .filter(|(_, value)| {
+            !matches!(
+                value,
+                serde_json::Value::Null | serde_json::Value::Bool(false)
+            )
+        })
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn detail_row(name: &'static str, value: String) -> AnyElement {
+    h_flex()
+        .gap_2()
+        .child(Label::new(name).size(LabelSize::Small).color(Color::Muted))
+        .child(Label::new(value).size(LabelSize::Small))
+        .into_any_element()
+}
+
+fn section_label(name: &'static str) -> AnyElement {
+    Label::new(name)
+        .size(LabelSize::Small)
+        .color(Color::Muted)
+        .into_any_element()
+}
+
+fn empty_label() -> AnyElement {
+    Label::new("None")
+        .size(LabelSize::Small)
+        .color(Color::Disabled)
+        .into_any_element()
+}
+
+impl Render for LanguageServerPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let project = self.project.read(cx);
+        let servers = if project.is_remote() {
+            Vec::new()
+        } else {
+            project.language_server_details(cx)
+        };
+        let empty_message = if project.is_remote() {
+            Some("Language servers are managed by the host of this project.")
+        } else if servers.is_empty() {
+            Some("No language servers have been started.")
+        } else {
+            None
+        };
+
+        v_flex()
+            .key_context("LanguageServerPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().panel_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(Label::new("Language Servers"))
+                    .child(
+                        Button::new("open_logs", "Logs")
+                            .label_size(LabelSize::Small)
+                            .on_click(|_, cx| {
+                                cx.dispatch_action(OpenLanguageServerLogs.boxed_clone())
+                            }),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("language_servers")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(
+                        empty_message.map(|message| {
+                            div().p_2().child(Label::new(message).color(Color::Muted))
+                        }),
+                    )
+                    .children(
+                        servers
+                            .iter()
+                            .enumerate()
+                            .map(|(ix, details)| self.render_server(ix, details, cx)),
+                    ),
+            )
+    }
+}
+
+impl EventEmitter<PanelEvent> for LanguageServerPanel {}
+
+impl FocusableView for LanguageServerPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for LanguageServerPanel {
+    fn persistent_name() -> &'static str {
+        "LanguageServerPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        LanguageServerPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<LanguageServerPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| LanguageServerPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        cx.notify();
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.active = active;
+        if active {
+            self.refresh_processes(cx);
+            cx.notify();
+        }
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        // Guests can't control the host's language servers, so the panel is only
+        // offered for local projects.
+        let show_button = LanguageServerPanelSettings::get_global(cx).button
+            && !self.project.read(cx).is_remote();
+        show_button.then_some(IconName::Bolt)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Language Server Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -1,4 +1,7 @@
+mod language_server_panel;
+mod language_server_panel_settings;
 mod lsp_log;
+mod query_playground;
 mod syntax_tree_view;
 
 #[cfg(test)]
@@ -6,10 +9,14 @@ mod lsp_log_tests;
 
 use gpui::AppContext;
 
+pub use language_server_panel::LanguageServerPanel;
 pub use lsp_log::{LogStore, LspLogToolbarItemView, LspLogView};
+pub use query_playground::QueryPlaygroundView;
 pub use syntax_tree_view::{SyntaxTreeToolbarItemView, SyntaxTreeView};
 
 pub fn init(cx: &mut AppContext) {
     lsp_log::init(cx);
+    language_server_panel::init(cx);
     syntax_tree_view::init(cx);
+    query_playground::init(cx);
 }
@@ -0,0 +1,693 @@
+use crate::syntax_tree_view::format_point_range;
+use anyhow::anyhow;
+use editor::{scroll::Autoscroll, Anchor, Editor, EditorEvent, ExcerptId};
+use gpui::{
+    actions, div, rems, uniform_list, AnyElement, AppContext, Div, EventEmitter, FocusHandle,
+    FocusableView, Hsla, InteractiveElement, IntoElement, Model, MouseButton, MouseDownEvent,
+    MouseMoveEvent, ParentElement, Render, SharedString, Styled, Subscription, Task,
+    UniformListScrollHandle, View, ViewContext, VisualContext, WeakView, WindowContext,
+};
+use language::{
+    Buffer, BufferSnapshot, Language, LanguageQueries, LanguageRegistry, QUERY_FILENAME_PREFIXES,
+};
+use std::{borrow::Cow, cmp::Reverse, mem, ops::Range, sync::Arc, time::Duration};
+use theme::ActiveTheme;
+use tree_sitter::{Point, Query, QueryCursor};
+use ui::{h_flex, popover_menu, v_flex, Button, Color, ContextMenu, Label, LabelCommon, LabelSize};
+use workspace::{
+    item::{Item, ItemHandle},
+    SplitDirection, Workspace,
+};
+
+actions!(debug, [OpenQueryPlayground]);
+
+/// The most captures that are listed, to keep broad queries on large buffers responsive.
+const MAX_CAPTURES: usize = 10_000;
+
+/// How long to wait for the query or the buffer to stop changing before running the query.
+const QUERY_DEBOUNCE: Duration = Duration::from_millis(100);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &OpenQueryPlayground, cx| {
+            let active_item = workspace.active_item(cx);
+            let workspace_handle = workspace.weak_handle();
+            let languages = workspace.project().read(cx).languages().clone();
+            let query_playground = cx.new_view(|cx| {
+                QueryPlaygroundView::new(workspace_handle, languages, active_item, cx)
+            });
+            workspace.split_item(SplitDirection::Right, Box::new(query_playground), cx)
+        });
+    })
+    .detach();
+}
+
+/// Runs a tree-sitter query against the buffer of the active editor, highlighting
+/// and listing its captures.
+pub struct QueryPlaygroundView {
+    workspace_handle: WeakView<Workspace>,
+    languages: Arc<LanguageRegistry>,
+    query_editor: View<Editor>,
+    editor: Option<EditorState>,
+    captures: Vec<CaptureEntry>,
+    captures_truncated: bool,
+    query_error: Option<String>,
+    pending_query: Task<()>,
+    list_scroll_handle: UniformListScrollHandle,
+    selected_capture_ix: Option<usize>,
+    hovered_capture_ix: Option<usize>,
+    _query_editor_subscription: Subscription,
+}
+
+struct EditorState {
+    editor: View<Editor>,
+    active_buffer: Option<(Model<Buffer>, ExcerptId)>,
+    /// The language at the cursor, whose syntax layers are queried. It differs from the
+    /// buffer's language when the cursor is in injected code.
+    active_language: Option<Arc<Language>>,
+    _subscription: Subscription,
+}
+
+struct CaptureEntry {
+    name: SharedString,
+    pattern_ix: usize,
+    node_kind: &'static str,
+    range: Range<usize>,
+    point_range: Range<Point>,
+}
+
+/// Highlights the node of the capture under the mouse.
+struct HoveredCapture;
+
+impl QueryPlaygroundView {
+    pub fn new(
+        workspace_handle: WeakView<Workspace>,
+        languages: Arc<LanguageRegistry>,
+        active_item: Option<Box<dyn ItemHandle>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let query_editor = cx.new_view(|cx| {
+            let mut editor = Editor::multi_line(cx);
+            editor.set_placeholder_text("(function_item name: (identifier) @name)", cx);
+            editor
+        });
+        let query_editor_subscription = cx.subscribe(&query_editor, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.run_query(cx);
+            }
+        });
+
+        let mut this = Self {
+            workspace_handle: workspace_handle.clone(),
+            languages,
+            query_editor,
+            editor: None,
+            captures: Vec::new(),
+            captures_truncated: false,
+            query_error: None,
+            pending_query: Task::ready(()),
+            list_scroll_handle: UniformListScrollHandle::new(),
+            selected_capture_ix: None,
+            hovered_capture_ix: None,
+            _query_editor_subscription: query_editor_subscription,
+        };
+
+        this.workspace_updated(active_item, cx);
+        if let Some(workspace) = workspace_handle.upgrade() {
+            cx.observe(&workspace, |this, workspace, cx| {
+                this.workspace_updated(workspace.read(cx).active_item(cx), cx);
+            })
+            .detach();
+        }
+
+        this
+    }
+
+    fn workspace_updated(
+        &mut self,
+        active_item: Option<Box<dyn ItemHandle>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(item) = active_item {
+            if item.item_id() != cx.entity_id() {
+                if let Some(editor) = item.act_as::<Editor>(cx) {
+                    self.set_editor(editor, cx);
+                }
+            }
+        }
+    }
+
+    fn set_editor(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        if let Some(state) = &self.editor {
+            if state.editor == editor {
+                return;
+            }
+            state.editor.update(cx, |editor, cx| {
+                editor.clear_background_highlights::<Self>(cx);
+                editor.clear_background_highlights::<HoveredCapture>(cx);
+            });
+        }
+
+        let subscription = cx.subscribe(&editor, |this, _, event, cx| {
+            let did_reparse = match event {
+                EditorEvent::Reparsed => true,
+                EditorEvent::SelectionsChanged { .. } => false,
+                _ => return,
+            };
+            this.editor_updated(did_reparse, cx);
+        });
+
+        self.editor = Some(EditorState {
+            editor,
+            active_buffer: None,
+            active_language: None,
+            _subscription: subscription,
+        });
+        self.editor_updated(true, cx);
+    }
+
+    fn editor_updated(&mut self, did_reparse: bool, cx: &mut ViewContext<Self>) -> Option<()> {
+        // Query the buffer of the excerpt that contains the cursor.
+        let editor_state = self.editor.as_mut()?;
+        let editor = editor_state.editor.read(cx);
+        let selection_range = editor.selections.newest::<usize>(cx).range();
+        let (buffer, range, excerpt_id) = editor
+            .buffer()
+            .read(cx)
+            .range_to_buffer_ranges(selection_range, cx)
+            .pop()?;
+        let active_language = buffer.read(cx).language_at(range.start);
+        let active_buffer = Some((buffer, excerpt_id));
+        let language_changed = match (&editor_state.active_language, &active_language) {
+            (Some(old), Some(new)) => !Arc::ptr_eq(old, new),
+            (old, new) => old.is_some() != new.is_some(),
+        };
+        if did_reparse || language_changed || editor_state.active_buffer != active_buffer {
+            editor_state.active_buffer = active_buffer;
+            editor_state.active_language = active_language;
+            self.run_query(cx);
+        }
+        Some(())
+    }
+
+    /// Runs the query on the background once the query and the buffer stop changing.
+    fn run_query(&mut self, cx: &mut ViewContext<Self>) {
+        self.pending_query = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(QUERY_DEBOUNCE).await;
+            let Some((target, source)) = this
+                .update(&mut cx, |this, cx| {
+                    let target = this
+                        .active_buffer_snapshot(cx)
+                        .zip(this.active_language().cloned());
+                    (target, this.query_editor.read(cx).text(cx))
+                })
+                .ok()
+            else {
+                return;
+            };
+            let result = match target {
+                Some((snapshot, language)) => {
+                    cx.background_executor()
+                        .spawn(async move { query_captures(&snapshot, &language, &source) })
+                        .await
+                }
+                None => Ok((Vec::new(), false)),
+            };
+            this.update(&mut cx, |this, cx| this.set_query_result(result, cx))
+                .ok();
+        });
+    }
+
+    fn set_query_result(
+        &mut self,
+        result: Result<(Vec<CaptureEntry>, bool), String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.selected_capture_ix = None;
+        self.hovered_capture_ix = None;
+        match result {
+            Ok((captures, truncated)) => {
+                self.captures = captures;
+                self.captures_truncated = truncated;
+                self.query_error = None;
+            }
+            Err(error) => {
+                self.captures.clear();
+                self.captures_truncated = false;
+                self.query_error = Some(error);
+            }
+        }
+
+        let ranges = self.anchor_ranges(
+            self.captures.iter().map(|capture| capture.range.clone()),
+            cx,
+        );
+        if let Some(editor_state) = &self.editor {
+            editor_state.editor.update(cx, |editor, cx| {
+                editor.clear_background_highlights::<HoveredCapture>(cx);
+                editor.highlight_background::<Self>(
+                    ranges,
+                    |theme| theme.editor_document_highlight_read_background,
+                    cx,
+                );
+            });
+        }
+        cx.notify();
+    }
+
+    fn active_buffer_snapshot(&self, cx: &AppContext) -> Option<BufferSnapshot> {
+        let (buffer, _) = self.editor.as_ref()?.active_buffer.as_ref()?;
+        Some(buffer.read(cx).snapshot())
+    }
+
+    fn active_language(&self) -> Option<&Arc<Language>> {
+        self.editor.as_ref()?.active_language.as_ref()
+    }
+
+    /// Converts byte ranges in the active buffer into anchor ranges in the editor.
+    fn anchor_ranges(
+        &self,
+        ranges: impl Iterator<Item = Range<usize>>,
+        cx: &AppContext,
+    ) -> Vec<Range<Anchor>> {
+        let Some(editor_state) = &self.editor else {
+            return Vec::new();
+        };
+        let Some((buffer, excerpt_id)) = &editor_state.active_buffer else {
+            return Vec::new();
+        };
+        let buffer = buffer.read(cx);
+        let multibuffer = editor_state.editor.read(cx).buffer().read(cx).snapshot(cx);
+        ranges
+            .map(|range| {
+                multibuffer.anchor_in_excerpt(*excerpt_id, buffer.anchor_before(range.start))
+                    ..multibuffer.anchor_in_excerpt(*excerpt_id, buffer.anchor_after(range.end))
+            })
+            .collect()
+    }
+
+    fn load_language_query(
+        &mut self,
+        query_name: &'static str,
+        query: fn(&mut LanguageQueries) -> &mut Option<Cow<'static, str>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(language) = self.active_language().cloned() else {
+            return;
+        };
+        let languages = self.languages.clone();
+        cx.spawn(|this, mut cx| async move {
+            let language_name = language.name();
+            let source = cx
+                .background_executor()
+                .spawn(async move {
+                    let mut queries = languages.load_language_queries(&language_name)?;
+                    query(&mut queries)
+                        .take()
+                        .ok_or_else(|| anyhow!("{language_name} has no {query_name}.scm"))
+                })
+                .await;
+            this.update(&mut cx, |this, cx| match source {
+                Ok(source) => this.query_editor.update(cx, |editor, cx| {
+                    editor.set_text(source.into_owned(), cx);
+                }),
+                Err(error) => {
+                    this.query_error = Some(error.to_string());
+                    cx.notify();
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn update_editor_with_range_for_capture_ix(
+        &self,
+        capture_ix: usize,
+        cx: &mut ViewContext<Self>,
+        f: impl FnOnce(&mut Editor, Range<Anchor>, &mut ViewContext<Editor>),
+    ) -> Option<()> {
+        let editor_state = self.editor.as_ref()?;
+        let capture = self.captures.get(capture_ix)?;
+        let range = self
+            .anchor_ranges([capture.range.clone()].into_iter(), cx)
+            .pop()?;
+        editor_state
+            .editor
+            .update(cx, |editor, cx| f(editor, range, cx));
+        Some(())
+    }
+
+    fn render_capture(&self, capture_ix: usize, cx: &mut ViewContext<Self>) -> Div {
+        let colors = cx.theme().colors();
+        let capture = &self.captures[capture_ix];
+        h_flex()
+            .gap_2()
+            .px_2()
+            .child(Label::new(format!("@{}", capture.name)).color(Color::Info))
+            .child(Label::new(capture.node_kind))
+            .child(Label::new(format_point_range(capture.point_range.clone())).color(Color::Muted))
+            .child(
+                Label::new(format!("pattern {}", capture.pattern_ix))
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .text_bg(if Some(capture_ix) == self.selected_capture_ix {
+                colors.element_selected
+            } else {
+                Hsla::default()
+            })
+            .hover(|style| style.bg(colors.element_hover))
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(move |this, _: &MouseDownEvent, cx| {
+                    this.selected_capture_ix = Some(capture_ix);
+                    this.update_editor_with_range_for_capture_ix(
+                        capture_ix,
+                        cx,
+                        |editor, mut range, cx| {
+                            // Put the cursor at the beginning of the node.
+                            mem::swap(&mut range.start, &mut range.end);
+                            editor.change_selections(
+                                Some(Autoscroll::newest()),
+                                cx,
+                                |selections| selections.select_ranges(vec![range]),
+                            );
+                        },
+                    );
+                    cx.notify();
+                }),
+            )
+            .on_mouse_move(cx.listener(move |this, _: &MouseMoveEvent, cx| {
+                if this.hovered_capture_ix != Some(capture_ix) {
+                    this.hovered_capture_ix = Some(capture_ix);
+                    this.update_editor_with_range_for_capture_ix(
+                        capture_ix,
+                        cx,
+                        |editor, range, cx| {
+                            editor.highlight_background::<HoveredCapture>(
+                                vec![range],
+                                |theme| theme.editor_document_highlight_write_background,
+                                cx,
+                            );
+                        },
+                    );
+                    cx.notify();
+                }
+            }))
+    }
+}
+
+/// Runs a query against every syntax layer of the given language, returning the captures
+/// in the order of their nodes, and whether there were more than [`MAX_CAPTURES`].
+fn query_captures(
+    snapshot: &BufferSnapshot,
+    language: &Arc<Language>,
+    source: &str,
+) -> Result<(Vec<CaptureEntry>, bool), String> {
+    if source.trim().is_empty() {
+        return Ok((Vec::new(), false));
+    }
+    let grammar = language
+        .grammar()
+        .ok_or_else(|| format!("{} has no grammar", language.name()))?;
+    let query = Query::new(&grammar.ts_language, source).map_err(|error| error.to_string())?;
+
+    let text = snapshot.text();
+    let mut cursor = QueryCursor::new();
+    let mut captures = Vec::new();
+    'layers: for layer in snapshot
+        .syntax_layers()
+        .filter(|layer| Arc::ptr_eq(layer.language, language))
+    {
+        for query_match in cursor.matches(&query, layer.node(), text.as_bytes()) {
+            for capture in query_match.captures {
+                captures.push(CaptureEntry {
+                    name: query.capture_names()[capture.index as usize]
+                        .to_string()
+                        .into(),
+                    pattern_ix: query_match.pattern_index,
+                    node_kind: capture.node.kind(),
+                    range: capture.node.byte_range(),
+                    point_range: capture.node.start_position()..capture.node.end_position(),
+                });
+                // Find one more capture than is listed, to tell whether any were left out.
+                if captures.len() > MAX_CAPTURES {
+                    break 'layers;
+                }
+            }
+        }
+    }
+    let truncated = captures.len() > MAX_CAPTURES;
+    captures.truncate(MAX_CAPTURES);
+    captures.sort_by_key(|capture| (capture.range.start, Reverse(capture.range.end)));
+    Ok((captures, truncated))
+}
+
+impl Render for QueryPlaygroundView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let colors = cx.theme().colors();
+        let language_name = self
+            .active_language()
+            .map_or(SharedString::from("No Language"), |language| {
+                language.name().to_string().into()
+            });
+        let status = match &self.query_error {
+            Some(error) => Label::new(error.clone()).color(Color::Error),
+            None if self.captures_truncated => {
+                Label::new(format!("First {MAX_CAPTURES} captures")).color(Color::Muted)
+            }
+            None => Label::new(format!("{} captures", self.captures.len())).color(Color::Muted),
+        };
+
+        let view = cx.view().clone();
+        v_flex()
+            .size_full()
+            .bg(colors.editor_background)
+            .child(
+                h_flex()
+                    .p_2()
+                    .justify_between()
+                    .child(Label::new(language_name))
+                    .child(
+                        popover_menu("query-playground-load-query")
+                            .trigger(Button::new("load-query", "Load Language Query"))
+                            .menu(move |cx| {
+                                ContextMenu::build(cx, |mut menu, cx| {
+                                    for &(query_name, query) in QUERY_FILENAME_PREFIXES {
+                                        menu = menu.entry(
+                                            format!("{query_name}.scm"),
+                                            None,
+                                            cx.handler_for(&view, move |view, cx| {
+                                                view.load_language_query(query_name, query, cx);
+                                            }),
+                                        );
+                                    }
+                                    menu
+                                })
+                                .into()
+                            }),
+                    ),
+            )
+            .child(
+                div()
+                    .h(rems(12.))
+                    .p_2()
+                    .border_y_1()
+                    .border_color(colors.border)
+                    .child(self.query_editor.clone()),
+            )
+            .child(div().px_2().py_1().child(status.size(LabelSize::Small)))
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "QueryPlaygroundCaptures",
+                    self.captures.len(),
+                    |this, range, cx| {
+                        range
+                            .map(|capture_ix| this.render_capture(capture_ix, cx))
+                            .collect()
+                    },
+                )
+                .flex_1()
+                .track_scroll(self.list_scroll_handle.clone()),
+            )
+    }
+}
+
+impl EventEmitter<()> for QueryPlaygroundView {}
+
+impl FocusableView for QueryPlaygroundView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.query_editor.focus_handle(cx)
+    }
+}
+
+impl Item for QueryPlaygroundView {
+    type Event = ();
+
+    fn to_item_events(_: &Self::Event, _: impl FnMut(workspace::item::ItemEvent)) {}
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext<'_>) -> AnyElement {
+        Label::new("Query Playground")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        None
+    }
+
+    fn clone_on_split(
+        &self,
+        _: workspace::WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        let query = self.query_editor.read(cx).text(cx);
+        Some(cx.new_view(|cx| {
+            let mut clone = Self::new(
+                self.workspace_handle.clone(),
+                self.languages.clone(),
+                None,
+                cx,
+            );
+            if let Some(editor) = &self.editor {
+                clone.set_editor(editor.editor.clone(), cx);
+            }
+            clone
+                .query_editor
+                .update(cx, |editor, cx| editor.set_text(query, cx));
+            clone
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context as _, TestAppContext};
+    use language::{BufferId, LanguageConfig, LanguageMatcher};
+    use unindent::Unindent as _;
+
+    #[gpui::test]
+    async fn test_query_captures_in_injected_languages(cx: &mut TestAppContext) {
+        let rust = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                matcher: LanguageMatcher {
+                    path_suffixes: vec!["rs".into()],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::language()),
+        ));
+        let markdown = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Markdown".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["md".into()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_markdown::language()),
+            )
+            .with_injection_query(
+                r#"
+                (fenced_code_block
+                    (info_string
+                        (language) @language)
+                    (code_fence_content) @content)
+                "#,
+            )
+            .unwrap(),
+        );
+        let registry = Arc::new(LanguageRegistry::test());
+        registry.add(rust.clone());
+        registry.add(markdown.clone());
+
+        let text = "
+            # Example
+
+            ```rust
+            fn one() {}
+            ```
+
+            ```rust
+            fn two() {}
+            ```
+        "
+        .unindent();
+        let buffer = cx.new_model(|cx| {
+            let mut buffer = Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text);
+            buffer.set_language_registry(registry);
+            buffer.set_language(Some(markdown.clone()), cx);
+            buffer
+        });
+        cx.executor().run_until_parked();
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+
+        // Queries run against every layer of the injected language.
+        let (captures, truncated) =
+            query_captures(&snapshot, &rust, "(function_item name: (identifier) @name)").unwrap();
+        assert!(!truncated);
+        assert_eq!(
+            captures
+                .iter()
+                .map(|capture| (
+                    capture.name.as_ref(),
+                    snapshot
+                        .text_for_range(capture.range.clone())
+                        .collect::<String>()
+                ))
+                .collect::<Vec<_>>(),
+            [("name", "one".to_string()), ("name", "two".to_string())]
+        );
+
+        // Queries are compiled for the language they run against.
+        assert!(query_captures(&snapshot, &markdown, "(function_item) @item").is_err());
+        let (captures, _) =
+            query_captures(&snapshot, &markdown, "(fenced_code_block) @block").unwrap();
+        assert_eq!(captures.len(), 2);
+    }
+
+    #[gpui::test]
+    async fn test_query_captures_limit(cx: &mut TestAppContext) {
+        let rust = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                ..Default::default()
+            },
+            Some(language::tree_sitter_rust::language()),
+        ));
+        let build_snapshot = |statements: usize, cx: &mut TestAppContext| {
+            let text = format!("fn f() {{ {} }}", "a; ".repeat(statements));
+            let buffer = cx.new_model(|cx| {
+                Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
+                    .with_language(rust.clone(), cx)
+            });
+            cx.executor().run_until_parked();
+            buffer.read_with(cx, |buffer, _| buffer.snapshot())
+        };
+
+        // Exactly as many captures as are listed aren't reported as truncated.
+        let snapshot = build_snapshot(MAX_CAPTURES - 1, cx);
+        let (captures, truncated) = query_captures(&snapshot, &rust, "(identifier) @id").unwrap();
+        assert_eq!(captures.len(), MAX_CAPTURES);
+        assert!(!truncated);
+
+        let snapshot = build_snapshot(MAX_CAPTURES, cx);
+        let (captures, truncated) = query_captures(&snapshot, &rust, "(identifier) @id").unwrap();
+        assert_eq!(captures.len(), MAX_CAPTURES);
+        assert!(truncated);
+    }
+}
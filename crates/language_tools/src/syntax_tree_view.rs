@@ -8,7 +8,7 @@ use gpui::{
 use language::{Buffer, OwnedSyntaxLayer};
 use std::{mem, ops::Range};
 use theme::ActiveTheme;
-use tree_sitter::{Node, TreeCursor};
+use tree_sitter::{Node, Point, TreeCursor};
 use ui::{h_flex, popover_menu, ButtonLike, Color, ContextMenu, Label, LabelCommon, PopoverMenu};
 use workspace::{
     item::{Item, ItemHandle},
@@ -485,8 +485,11 @@ impl SyntaxTreeToolbarItemView {
 }
 
 fn format_node_range(node: Node) -> String {
-    let start = node.start_position();
-    let end = node.end_position();
+    format_point_range(node.start_position()..node.end_position())
+}
+
+pub(crate) fn format_point_range(range: Range<Point>) -> String {
+    let Range { start, end } = range;
     format!(
         "[{}:{} - {}:{}]",
         start.row + 1,
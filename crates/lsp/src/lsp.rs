@@ -54,6 +54,10 @@ pub enum IoKind {
 pub struct LanguageServerBinary {
     pub path: PathBuf,
     pub arguments: Vec<OsString>,
+    /// Environment variables to set for the language server process, in addition to
+    /// the ones inherited from Zed.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
 }
 
 /// A running language server process.
@@ -72,6 +76,9 @@ pub struct LanguageServer {
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
     output_done_rx: Mutex<Option<barrier::Receiver>>,
     root_path: PathBuf,
+    binary_path: Option<PathBuf>,
+    version: Option<String>,
+    process_id: Option<u32>,
     _server: Option<Mutex<Child>>,
 }
 
@@ -181,11 +188,13 @@ impl LanguageServer {
         let mut server = process::Command::new(&binary.path)
             .current_dir(working_dir)
             .args(binary.arguments)
+            .envs(binary.env.unwrap_or_default())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .kill_on_drop(true)
             .spawn()?;
+        let process_id = server.id();
 
         let stdin = server.stdin.take().unwrap();
         let stdout = server.stdout.take().unwrap();
@@ -219,6 +228,8 @@ impl LanguageServer {
         if let Some(name) = binary.path.file_name() {
             server.name = name.to_string_lossy().to_string();
         }
+        server.binary_path = Some(binary.path);
+        server.process_id = Some(process_id);
 
         Ok(server)
     }
@@ -302,6 +313,9 @@ impl LanguageServer {
             io_tasks: Mutex::new(Some((input_task, output_task))),
             output_done_rx: Mutex::new(Some(output_done_rx)),
             root_path: root_path.to_path_buf(),
+            binary_path: None,
+            version: None,
+            process_id: None,
             _server: server.map(|server| Mutex::new(server)),
         }
     }
@@ -501,7 +515,7 @@ impl LanguageServer {
                         refresh_support: Some(true),
                     }),
                     diagnostic: Some(DiagnosticWorkspaceClientCapabilities {
-                        refresh_support: None,
+                        refresh_support: Some(true),
                     }),
                     workspace_edit: Some(WorkspaceEditClientCapabilities {
                         resource_operations: Some(vec![
@@ -619,6 +633,7 @@ impl LanguageServer {
             let response = self.request::<request::Initialize>(params).await?;
             if let Some(info) = response.server_info {
                 self.name = info.name;
+                self.version = info.version;
             }
             self.capabilities = response.capabilities;
 
@@ -835,6 +850,21 @@ impl LanguageServer {
         self.server_id
     }
 
+    /// Get the path of the binary that the language server process was started from.
+    pub fn binary_path(&self) -> Option<&Path> {
+        self.binary_path.as_deref()
+    }
+
+    /// Get the version that the language server reported when it was initialized.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Get the operating system id of the language server process.
+    pub fn process_id(&self) -> Option<u32> {
+        self.process_id
+    }
+
     /// Get the root path of the project the language server is running against.
     pub fn root_path(&self) -> &PathBuf {
         &self.root_path
@@ -1432,7 +1432,7 @@ impl MultiBuffer {
             }
 
             //
-            language::Event::Operation(_) => return,
+            language::Event::Operation(_) | language::Event::Reloading => return,
         });
     }
 
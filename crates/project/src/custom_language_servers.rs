@@ -0,0 +1,198 @@
+//! Language servers that are defined in the `lsp` settings rather than by a language's
+//! adapters, and the binary overrides that settings apply to every language server.
+
+use std::{any::Any, ffi::OsString, path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use collections::HashMap;
+use futures::FutureExt;
+use gpui::AppContext;
+use language::{
+    CachedLspAdapter, Language, LanguageServerBinaryOverride, LanguageServerName, LspAdapter,
+    LspAdapterDelegate,
+};
+use lsp::LanguageServerBinary;
+use parking_lot::Mutex;
+use settings::Settings;
+
+use crate::{
+    project_settings::{LspSettings, ProjectSettings},
+    Project, WorktreeId,
+};
+
+/// An adapter for a language server defined in settings. It's always launched with the
+/// binary configured in its settings, so it never downloads or locates one itself.
+struct CustomLspAdapter {
+    name: LanguageServerName,
+    language_ids: HashMap<String, String>,
+}
+
+impl CustomLspAdapter {
+    fn new(name: &str, settings: &LspSettings) -> Self {
+        let mut language_ids = settings.language_ids.clone();
+        for language in &settings.languages {
+            language_ids
+                .entry(language.to_string())
+                .or_insert_with(|| language.to_lowercase());
+        }
+        Self {
+            name: LanguageServerName(name.into()),
+            language_ids,
+        }
+    }
+}
+
+/// The adapters created for the language servers defined in settings, which are reused
+/// for as long as the settings they were created from don't change.
+#[derive(Default)]
+pub(crate) struct CustomLspAdapters(Mutex<Vec<Arc<CachedLspAdapter>>>);
+
+impl CustomLspAdapters {
+    fn get_or_create(&self, name: &str, settings: &LspSettings) -> Arc<CachedLspAdapter> {
+        let adapter = CustomLspAdapter::new(name, settings);
+        let mut adapters = self.0.lock();
+        if let Some(cached) = adapters.iter().find(|cached| {
+            cached.name == adapter.name && cached.language_ids == adapter.language_ids
+        }) {
+            return cached.clone();
+        }
+        let cached = CachedLspAdapter::new(Arc::new(adapter))
+            .now_or_never()
+            .expect("creating a cached adapter doesn't wait on anything");
+        adapters.push(cached.clone());
+        cached
+    }
+}
+
+#[async_trait]
+impl LspAdapter for CustomLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        self.name.clone()
+    }
+
+    fn short_name(&self) -> &'static str {
+        "custom"
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        Err(anyhow!(
+            "no binary path configured for language server {:?}",
+            self.name.0
+        ))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        Err(anyhow!(
+            "no binary path configured for language server {:?}",
+            self.name.0
+        ))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn language_ids(&self) -> HashMap<String, String> {
+        self.language_ids.clone()
+    }
+}
+
+impl Project {
+    /// The adapters of the language servers to run for a language in a worktree: the
+    /// language's own adapters, followed by the servers defined for it in settings.
+    pub(crate) fn lsp_adapters_for_language(
+        &self,
+        worktree_id: WorktreeId,
+        language: &Arc<Language>,
+        cx: &AppContext,
+    ) -> Vec<Arc<CachedLspAdapter>> {
+        let mut adapters = language.lsp_adapters().to_vec();
+        let settings = lsp_settings_for_worktree(worktree_id, cx);
+        let mut custom_servers = settings
+            .iter()
+            .filter(|(_, settings)| {
+                settings.is_custom_server()
+                    && settings
+                        .languages
+                        .iter()
+                        .any(|name| name.as_ref() == language.name().as_ref())
+            })
+            .collect::<Vec<_>>();
+        custom_servers.sort_by(|(a, _), (b, _)| a.cmp(b));
+        for (name, settings) in custom_servers {
+            if adapters.iter().any(|adapter| adapter.name.0 == *name) {
+                continue;
+            }
+            adapters.push(self.custom_lsp_adapters.get_or_create(name, settings));
+        }
+        adapters
+    }
+
+    /// The binary that settings configure for a language server in a worktree.
+    pub(crate) fn language_server_binary_override(
+        &self,
+        worktree_id: WorktreeId,
+        name: &LanguageServerName,
+        cx: &AppContext,
+    ) -> LanguageServerBinaryOverride {
+        let settings = lsp_settings_for_worktree(worktree_id, cx);
+        let Some(binary) = settings
+            .get(&name.0)
+            .and_then(|settings| settings.binary.as_ref())
+        else {
+            return LanguageServerBinaryOverride::default();
+        };
+        LanguageServerBinaryOverride {
+            path: binary.path.as_ref().map(PathBuf::from),
+            arguments: binary
+                .arguments
+                .as_ref()
+                .map(|arguments| arguments.iter().map(OsString::from).collect()),
+            env: binary.env.clone(),
+        }
+    }
+}
+
+/// The `lsp` settings of a worktree. The binaries that language servers are launched with,
+/// and the servers defined in settings, are only taken from the user's settings: a
+/// project's own settings can't make opening it run the executables it names.
+pub(crate) fn lsp_settings_for_worktree(
+    worktree_id: WorktreeId,
+    cx: &AppContext,
+) -> HashMap<Arc<str>, LspSettings> {
+    let location = (worktree_id.to_proto() as usize, std::path::Path::new(""));
+    let user_settings = &ProjectSettings::get_global(cx).lsp;
+    let mut settings = ProjectSettings::get(Some(location), cx).lsp.clone();
+    for (name, settings) in &mut settings {
+        let user_settings = user_settings.get(name);
+        settings.binary = user_settings.and_then(|settings| settings.binary.clone());
+        settings.languages = user_settings
+            .map(|settings| settings.languages.clone())
+            .unwrap_or_default();
+        settings.language_ids = user_settings
+            .map(|settings| settings.language_ids.clone())
+            .unwrap_or_default();
+    }
+    settings
+}
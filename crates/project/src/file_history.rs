@@ -0,0 +1,329 @@
+//! A local history of file contents, which are snapshotted whenever a file is saved or
+//! reloaded from disk, so that they can be restored after the file was overwritten by
+//! another program.
+
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+use gpui::{Model, ModelContext};
+use language::Buffer;
+use settings::Settings;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use util::ResultExt;
+
+use crate::{
+    project_settings::{FileHistorySettings, ProjectSettings},
+    File, Project,
+};
+
+const DEFAULT_MAX_SNAPSHOTS_PER_FILE: usize = 50;
+const DEFAULT_MAX_AGE_DAYS: u64 = 30;
+const DEFAULT_MAX_FILE_SIZE: u64 = 1_000_000;
+
+define_connection!(
+    // Current schema shape using pseudo-rust syntax:
+    // file_history(
+    //   id: i64,
+    //   abs_path: PathBuf,
+    //   timestamp: i64, // Seconds since the Unix epoch
+    //   reason: String,
+    //   content: String,
+    // )
+    pub static ref FILE_HISTORY: FileHistoryDb<()> =
+        &[sql!(
+            CREATE TABLE file_history(
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                abs_path BLOB NOT NULL,
+                timestamp INTEGER NOT NULL,
+                reason TEXT NOT NULL,
+                content TEXT NOT NULL
+            ) STRICT;
+            CREATE INDEX file_history_by_path ON file_history(abs_path, id);
+        )];
+);
+
+/// Why a snapshot of a file was taken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SnapshotReason {
+    /// The file was saved with these contents.
+    Save,
+    /// The buffer had these contents before it was reloaded from disk.
+    Reload,
+}
+
+impl SnapshotReason {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Save => "save",
+            Self::Reload => "reload",
+        }
+    }
+
+    fn from_str(reason: &str) -> Option<Self> {
+        match reason {
+            "save" => Some(Self::Save),
+            "reload" => Some(Self::Reload),
+            _ => None,
+        }
+    }
+}
+
+/// A snapshot in a file's history. Its contents are loaded separately.
+#[derive(Clone, Debug)]
+pub struct FileSnapshot {
+    pub id: i64,
+    pub timestamp: SystemTime,
+    pub reason: SnapshotReason,
+}
+
+impl FileHistoryDb {
+    query! {
+        async fn insert_snapshot(abs_path: PathBuf, timestamp: i64, reason: String, content: String) -> Result<()> {
+            INSERT INTO file_history
+                (abs_path, timestamp, reason, content)
+            VALUES
+                (?1, ?2, ?3, ?4)
+        }
+    }
+
+    query! {
+        async fn delete_old_snapshots(abs_path: PathBuf, min_timestamp: i64, max_count: usize) -> Result<()> {
+            DELETE FROM file_history
+            WHERE abs_path = ?1 AND (
+                timestamp < ?2 OR
+                id NOT IN (
+                    SELECT id FROM file_history
+                    WHERE abs_path = ?1
+                    ORDER BY id DESC
+                    LIMIT ?3
+                )
+            )
+        }
+    }
+
+    query! {
+        fn latest_content(abs_path: PathBuf) -> Result<Option<String>> {
+            SELECT content FROM file_history
+            WHERE abs_path = ?
+            ORDER BY id DESC
+            LIMIT 1
+        }
+    }
+
+    query! {
+        fn snapshot_rows(abs_path: PathBuf) -> Result<Vec<(i64, i64, String)>> {
+            SELECT id, timestamp, reason FROM file_history
+            WHERE abs_path = ?
+            ORDER BY id DESC
+        }
+    }
+
+    query! {
+        pub fn snapshot_content(id: i64) -> Result<Option<String>> {
+            SELECT content FROM file_history
+            WHERE id = ?
+        }
+    }
+
+    /// Returns the snapshots of the file at the given path, from the newest to the oldest.
+    pub fn snapshots(&self, abs_path: &Path) -> Result<Vec<FileSnapshot>> {
+        Ok(self
+            .snapshot_rows(abs_path.to_path_buf())?
+            .into_iter()
+            .filter_map(|(id, timestamp, reason)| {
+                Some(FileSnapshot {
+                    id,
+                    timestamp: UNIX_EPOCH + Duration::from_secs(timestamp.try_into().ok()?),
+                    reason: SnapshotReason::from_str(&reason)?,
+                })
+            })
+            .collect())
+    }
+
+    /// Records a snapshot of a file, unless its contents are the same as in the previous
+    /// snapshot, then deletes the file's snapshots that exceed the retention limits.
+    pub async fn record_snapshot(
+        &self,
+        abs_path: PathBuf,
+        reason: SnapshotReason,
+        content: String,
+        timestamp: SystemTime,
+        settings: FileHistorySettings,
+    ) -> Result<()> {
+        if self.latest_content(abs_path.clone())?.as_deref() != Some(content.as_str()) {
+            self.insert_snapshot(
+                abs_path.clone(),
+                unix_seconds(timestamp),
+                reason.as_str().to_string(),
+                content,
+            )
+            .await?;
+        }
+
+        let max_age_days = settings.max_age_days.unwrap_or(DEFAULT_MAX_AGE_DAYS);
+        let max_age = Duration::from_secs(max_age_days * 24 * 60 * 60);
+        let min_timestamp = timestamp.checked_sub(max_age).unwrap_or(UNIX_EPOCH);
+        let max_count = settings
+            .max_snapshots_per_file
+            .unwrap_or(DEFAULT_MAX_SNAPSHOTS_PER_FILE);
+        self.delete_old_snapshots(abs_path, unix_seconds(min_timestamp), max_count)
+            .await
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+impl Project {
+    pub(crate) fn record_file_snapshot(
+        &self,
+        buffer: &Model<Buffer>,
+        reason: SnapshotReason,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = buffer.read(cx);
+        let Some(file) = File::from_dyn(buffer.file()) else {
+            return;
+        };
+        if !file.is_local() {
+            return;
+        }
+        let settings = ProjectSettings::get(
+            Some((file.worktree.entity_id().as_u64() as usize, &file.path)),
+            cx,
+        )
+        .file_history;
+        if !settings.enabled.unwrap_or(true) {
+            return;
+        }
+        let max_file_size = settings.max_file_size.unwrap_or(DEFAULT_MAX_FILE_SIZE);
+        if buffer.len() as u64 > max_file_size {
+            return;
+        }
+
+        let abs_path = file.abs_path(cx);
+        let content = buffer.text();
+        let timestamp = SystemTime::now();
+        cx.background_executor()
+            .spawn(async move {
+                FILE_HISTORY
+                    .record_snapshot(abs_path, reason, content, timestamp, settings)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_file_history() {
+        let db = FileHistoryDb(db::open_test_db("test_file_history").await);
+        let settings = FileHistorySettings {
+            enabled: Some(true),
+            max_snapshots_per_file: Some(2),
+            max_age_days: Some(1),
+            max_file_size: None,
+        };
+        let path = Path::new("/dir/a.txt");
+        let now = SystemTime::now();
+        let seconds = |seconds| Duration::from_secs(seconds);
+
+        db.record_snapshot(
+            path.into(),
+            SnapshotReason::Save,
+            "one".into(),
+            now - seconds(3),
+            settings,
+        )
+        .await
+        .unwrap();
+        // Unchanged contents aren't recorded again.
+        db.record_snapshot(
+            path.into(),
+            SnapshotReason::Reload,
+            "one".into(),
+            now - seconds(2),
+            settings,
+        )
+        .await
+        .unwrap();
+        db.record_snapshot(
+            path.into(),
+            SnapshotReason::Reload,
+            "two".into(),
+            now - seconds(1),
+            settings,
+        )
+        .await
+        .unwrap();
+        db.record_snapshot(
+            "/dir/b.txt".into(),
+            SnapshotReason::Save,
+            "other".into(),
+            now,
+            settings,
+        )
+        .await
+        .unwrap();
+
+        let snapshots = db.snapshots(path).unwrap();
+        assert_eq!(
+            snapshots
+                .iter()
+                .map(|snapshot| snapshot.reason)
+                .collect::<Vec<_>>(),
+            [SnapshotReason::Reload, SnapshotReason::Save]
+        );
+        assert_eq!(
+            db.snapshot_content(snapshots[0].id).unwrap().as_deref(),
+            Some("two")
+        );
+        assert_eq!(
+            db.snapshot_content(snapshots[1].id).unwrap().as_deref(),
+            Some("one")
+        );
+
+        // Only the newest snapshots are kept.
+        db.record_snapshot(
+            path.into(),
+            SnapshotReason::Save,
+            "three".into(),
+            now,
+            settings,
+        )
+        .await
+        .unwrap();
+        let snapshots = db.snapshots(path).unwrap();
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(
+            db.snapshot_content(snapshots[1].id).unwrap().as_deref(),
+            Some("two")
+        );
+
+        // Snapshots older than the maximum age are deleted.
+        db.record_snapshot(
+            path.into(),
+            SnapshotReason::Save,
+            "four".into(),
+            now + seconds(2 * 24 * 60 * 60),
+            settings,
+        )
+        .await
+        .unwrap();
+        let snapshots = db.snapshots(path).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(
+            db.snapshot_content(snapshots[0].id).unwrap().as_deref(),
+            Some("four")
+        );
+        assert_eq!(db.snapshots(Path::new("/dir/b.txt")).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,203 @@
+use collections::HashSet;
+use futures::{
+    channel::mpsc::{self, UnboundedSender},
+    StreamExt,
+};
+use gpui::{Model, ModelContext};
+use language::Buffer;
+
+use crate::{Event, File, Project, ProjectPath};
+
+/// A feature that is turned off for buffers opened in large-file mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LargeFileFeature {
+    SyntaxHighlighting,
+    LanguageServers,
+    GitDiff,
+    InlayHints,
+}
+
+impl LargeFileFeature {
+    pub const ALL: [Self; 4] = [
+        Self::SyntaxHighlighting,
+        Self::LanguageServers,
+        Self::GitDiff,
+        Self::InlayHints,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::SyntaxHighlighting => "Syntax Highlighting",
+            Self::LanguageServers => "Language Servers",
+            Self::GitDiff => "Git Diff",
+            Self::InlayHints => "Inlay Hints",
+        }
+    }
+}
+
+/// A buffer that was opened in large-file mode.
+#[derive(Clone, Debug)]
+pub struct LargeFile {
+    /// The size of the file when it was opened, in bytes.
+    pub len: u64,
+    enabled_features: HashSet<LargeFileFeature>,
+}
+
+impl LargeFile {
+    fn new(len: u64) -> Self {
+        Self {
+            len,
+            enabled_features: HashSet::default(),
+        }
+    }
+
+    pub fn is_enabled(&self, feature: LargeFileFeature) -> bool {
+        self.enabled_features.contains(&feature)
+    }
+
+    pub fn disabled_features(&self) -> impl Iterator<Item = LargeFileFeature> + '_ {
+        LargeFileFeature::ALL
+            .into_iter()
+            .filter(|feature| !self.is_enabled(*feature))
+    }
+}
+
+/// The progress of loading a file in large-file mode.
+#[derive(Clone, Copy, Debug)]
+pub struct LargeFileLoad {
+    pub len: u64,
+    pub loaded: u64,
+}
+
+impl Project {
+    /// Returns the large-file state of the given buffer, if it was opened in large-file mode.
+    pub fn large_file(&self, buffer: &Buffer) -> Option<&LargeFile> {
+        self.large_files.get(&buffer.remote_id())
+    }
+
+    /// Returns the files that are being loaded in large-file mode.
+    pub fn large_file_loads(&self) -> impl Iterator<Item = (&ProjectPath, &LargeFileLoad)> {
+        self.large_file_loads.iter()
+    }
+
+    pub(crate) fn is_large_file_feature_disabled(
+        &self,
+        buffer: &Buffer,
+        feature: LargeFileFeature,
+    ) -> bool {
+        self.large_file(buffer)
+            .map_or(false, |large_file| !large_file.is_enabled(feature))
+    }
+
+    /// Starts tracking the progress of loading a large file, returning the sender that the
+    /// number of bytes loaded so far should be sent to.
+    pub(crate) fn start_large_file_load(
+        &mut self,
+        path: ProjectPath,
+        len: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> UnboundedSender<u64> {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded();
+        self.large_file_loads
+            .insert(path.clone(), LargeFileLoad { len, loaded: 0 });
+        cx.notify();
+        cx.spawn(|this, mut cx| async move {
+            while let Some(loaded) = progress_rx.next().await {
+                this.update(&mut cx, |this, cx| {
+                    if let Some(load) = this.large_file_loads.get_mut(&path) {
+                        load.loaded = loaded;
+                        cx.notify();
+                    }
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach();
+        progress_tx
+    }
+
+    pub(crate) fn finish_large_file_load(
+        &mut self,
+        path: &ProjectPath,
+        buffer: Option<&Model<Buffer>>,
+        len: u64,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.large_file_loads.remove(path);
+        if let Some(buffer) = buffer {
+            let buffer_id = buffer.read(cx).remote_id();
+            self.large_files.insert(buffer_id, LargeFile::new(len));
+            buffer.update(cx, |buffer, cx| buffer.set_syntax_enabled(false, cx));
+        }
+        cx.notify();
+    }
+
+    /// Turns a feature that large-file mode disabled back on for the given buffer, or off again.
+    pub fn set_large_file_feature_enabled(
+        &mut self,
+        buffer: &Model<Buffer>,
+        feature: LargeFileFeature,
+        enabled: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(large_file) = self.large_file(buffer.read(cx)) else {
+            return;
+        };
+        if large_file.is_enabled(feature) == enabled {
+            return;
+        }
+
+        // Language servers are only notified about buffers that have the feature enabled, so
+        // the buffer must be closed with them before it is disabled.
+        if feature == LargeFileFeature::LanguageServers && !enabled {
+            if let Some(file) = File::from_dyn(buffer.read(cx).file()).cloned() {
+                self.unregister_buffer_from_language_servers(buffer, &file, cx);
+            }
+        }
+
+        let buffer_id = buffer.read(cx).remote_id();
+        if let Some(large_file) = self.large_files.get_mut(&buffer_id) {
+            if enabled {
+                large_file.enabled_features.insert(feature);
+            } else {
+                large_file.enabled_features.remove(&feature);
+            }
+        }
+
+        match feature {
+            LargeFileFeature::SyntaxHighlighting => {
+                buffer.update(cx, |buffer, cx| buffer.set_syntax_enabled(enabled, cx));
+            }
+            LargeFileFeature::LanguageServers => {
+                if enabled {
+                    let language = buffer.read(cx).language().cloned();
+                    let worktree =
+                        File::from_dyn(buffer.read(cx).file()).map(|file| file.worktree.clone());
+                    if let Some((language, worktree)) = language.zip(worktree) {
+                        self.start_language_servers(&worktree, language, cx);
+                    }
+                    self.register_buffer_with_language_servers(buffer, cx);
+                    // Copilot keeps the buffer until it is released, even if the
+                    // feature is turned off again.
+                    self.register_buffer_with_copilot(buffer, cx);
+                }
+                cx.emit(Event::RefreshInlayHints);
+            }
+            LargeFileFeature::GitDiff => {
+                if enabled {
+                    let file = File::from_dyn(buffer.read(cx).file())
+                        .filter(|file| file.worktree.read(cx).is_local())
+                        .map(|file| (file.worktree.clone(), file.path.clone()));
+                    if let Some((worktree, path)) = file {
+                        self.reload_diff_bases(worktree, vec![(buffer.clone(), path)], cx)
+                            .detach_and_log_err(cx);
+                    }
+                } else {
+                    buffer.update(cx, |buffer, cx| buffer.clear_diff_base(cx));
+                }
+            }
+            LargeFileFeature::InlayHints => cx.emit(Event::RefreshInlayHints),
+        }
+        cx.notify();
+    }
+}
@@ -9,7 +9,7 @@ use client::proto::{self, PeerId};
 use futures::future;
 use gpui::{AppContext, AsyncAppContext, Model};
 use language::{
-    language_settings::{language_settings, InlayHintKind},
+    language_settings::{language_settings, InlayHintKind, LanguageServerFeature},
     point_from_lsp, point_to_lsp, prepare_completion_documentation,
     proto::{deserialize_anchor, deserialize_version, serialize_anchor, serialize_version},
     range_from_lsp, range_to_lsp, Anchor, Bias, Buffer, BufferSnapshot, CachedLspAdapter, CharKind,
@@ -42,6 +42,12 @@ pub trait LspCommand: 'static + Sized + Send {
         true
     }
 
+    /// The feature that this request serves, which decides the language server it's
+    /// sent to when a buffer has several.
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        None
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -155,6 +161,10 @@ impl LspCommand for PrepareRename {
     type LspRequest = lsp::request::PrepareRenameRequest;
     type ProtoRequest = proto::PrepareRename;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Rename)
+    }
+
     fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
         if let Some(lsp::OneOf::Right(rename)) = &capabilities.rename_provider {
             rename.prepare_provider == Some(true)
@@ -286,6 +296,10 @@ impl LspCommand for PerformRename {
     type LspRequest = lsp::request::Rename;
     type ProtoRequest = proto::PerformRename;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Rename)
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -405,6 +419,10 @@ impl LspCommand for GetDefinition {
     type LspRequest = lsp::request::GotoDefinition;
     type ProtoRequest = proto::GetDefinition;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Definition)
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -498,6 +516,10 @@ impl LspCommand for GetTypeDefinition {
     type LspRequest = lsp::request::GotoTypeDefinition;
     type ProtoRequest = proto::GetTypeDefinition;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Definition)
+    }
+
     fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
         match &capabilities.type_definition_provider {
             None => false,
@@ -797,6 +819,10 @@ impl LspCommand for GetReferences {
     type LspRequest = lsp::request::References;
     type ProtoRequest = proto::GetReferences;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::References)
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -960,6 +986,10 @@ impl LspCommand for GetDocumentHighlights {
     type LspRequest = lsp::request::DocumentHighlightRequest;
     type ProtoRequest = proto::GetDocumentHighlights;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::DocumentHighlights)
+    }
+
     fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
         capabilities.document_highlight_provider.is_some()
     }
@@ -1111,6 +1141,10 @@ impl LspCommand for GetHover {
     type LspRequest = lsp::request::HoverRequest;
     type ProtoRequest = proto::GetHover;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Hover)
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -1327,6 +1361,10 @@ impl LspCommand for GetCompletions {
     type LspRequest = lsp::request::Completion;
     type ProtoRequest = proto::GetCompletions;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Completions)
+    }
+
     fn to_lsp(
         &self,
         path: &Path,
@@ -1575,6 +1613,10 @@ impl LspCommand for GetCodeActions {
     type LspRequest = lsp::request::CodeActionRequest;
     type ProtoRequest = proto::GetCodeActions;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::CodeActions)
+    }
+
     fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
         match &capabilities.code_action_provider {
             None => false,
@@ -1720,6 +1762,10 @@ impl LspCommand for OnTypeFormatting {
     type LspRequest = lsp::request::OnTypeFormatting;
     type ProtoRequest = proto::OnTypeFormatting;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::Formatting)
+    }
+
     fn check_capabilities(&self, server_capabilities: &lsp::ServerCapabilities) -> bool {
         let Some(on_type_formatting_options) =
             &server_capabilities.document_on_type_formatting_provider
@@ -2226,6 +2272,10 @@ impl LspCommand for InlayHints {
     type LspRequest = lsp::InlayHintRequest;
     type ProtoRequest = proto::InlayHints;
 
+    fn feature(&self) -> Option<LanguageServerFeature> {
+        Some(LanguageServerFeature::InlayHints)
+    }
+
     fn check_capabilities(&self, server_capabilities: &lsp::ServerCapabilities) -> bool {
         let Some(inlay_hint_provider) = &server_capabilities.inlay_hint_provider else {
             return false;
@@ -0,0 +1,345 @@
+//! Inspecting the language servers of a project, and stopping, starting and disabling
+//! them on demand.
+
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use futures::future::try_join_all;
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::{Buffer, Language, LanguageServerName};
+use lsp::LanguageServerId;
+use settings::SettingsStore;
+use util::paths::LOCAL_SETTINGS_RELATIVE_PATH;
+
+use crate::{
+    custom_language_servers::lsp_settings_for_worktree, project_settings::ProjectSettings,
+    LanguageServerState, Project, WorktreeId,
+};
+
+/// Where a language server is in its lifecycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LanguageServerLifecycle {
+    /// The server is being installed or initialized.
+    Starting,
+    /// The server is initialized and handling requests.
+    Running,
+    /// The server was stopped, and won't be started for the worktree until it's
+    /// started or restarted explicitly.
+    Stopped,
+    /// The server was disabled in the worktree's settings.
+    Disabled,
+}
+
+/// The state of a language server for one of the project's worktrees.
+#[derive(Clone)]
+pub struct LanguageServerDetails {
+    pub name: LanguageServerName,
+    pub worktree_id: WorktreeId,
+    /// The id of the server, unless it's stopped.
+    pub id: Option<LanguageServerId>,
+    pub lifecycle: LanguageServerLifecycle,
+    pub binary_path: Option<PathBuf>,
+    pub version: Option<String>,
+    pub process_id: Option<u32>,
+    /// The capabilities negotiated with the server, once it's running.
+    pub capabilities: Option<lsp::ServerCapabilities>,
+    /// The open buffers that the server was notified about.
+    pub buffers: Vec<Model<Buffer>>,
+}
+
+impl Project {
+    /// Describes the language servers of every worktree, including the ones that were
+    /// stopped or disabled.
+    pub fn language_server_details(&self, cx: &AppContext) -> Vec<LanguageServerDetails> {
+        let mut details = Vec::new();
+        for ((worktree_id, name), server_id) in &self.language_server_ids {
+            let server = match self.language_servers.get(server_id) {
+                Some(LanguageServerState::Running { server, .. }) => Some(server),
+                Some(LanguageServerState::Starting(_)) => None,
+                None => continue,
+            };
+            details.push(LanguageServerDetails {
+                name: name.clone(),
+                worktree_id: *worktree_id,
+                id: Some(*server_id),
+                lifecycle: if server.is_some() {
+                    LanguageServerLifecycle::Running
+                } else {
+                    LanguageServerLifecycle::Starting
+                },
+                binary_path: server
+                    .and_then(|server| server.binary_path())
+                    .map(|path| path.to_path_buf()),
+                version: server
+                    .and_then(|server| server.version())
+                    .map(|version| version.to_string()),
+                process_id: server.and_then(|server| server.process_id()),
+                capabilities: server.map(|server| server.capabilities().clone()),
+                buffers: self.buffers_for_language_server(*server_id, cx),
+            });
+        }
+        for key in self.stopped_language_servers.keys() {
+            let (worktree_id, name) = key;
+            details.push(LanguageServerDetails {
+                name: name.clone(),
+                worktree_id: *worktree_id,
+                id: None,
+                lifecycle: if self.disabled_language_servers.contains(key) {
+                    LanguageServerLifecycle::Disabled
+                } else {
+                    LanguageServerLifecycle::Stopped
+                },
+                binary_path: None,
+                version: None,
+                process_id: None,
+                capabilities: None,
+                buffers: Vec::new(),
+            });
+        }
+        details.sort_by(|a, b| {
+            a.worktree_id
+                .cmp(&b.worktree_id)
+                .then_with(|| a.name.0.cmp(&b.name.0))
+        });
+        details
+    }
+
+    /// Whether the worktree's settings disable the given language server.
+    pub fn is_language_server_disabled(
+        &self,
+        worktree_id: WorktreeId,
+        name: &LanguageServerName,
+        cx: &AppContext,
+    ) -> bool {
+        lsp_settings_for_worktree(worktree_id, cx)
+            .get(&name.0)
+            .and_then(|settings| settings.enabled)
+            == Some(false)
+    }
+
+    /// Stops the given language server of a worktree, along with the other worktrees
+    /// it serves. The server won't be started for them again until it's started or
+    /// restarted explicitly.
+    pub fn stop_language_server_for_worktree(
+        &mut self,
+        worktree_id: WorktreeId,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let (_, task) = self.stop_language_server_keys(worktree_id, name, cx);
+        task
+    }
+
+    /// Starts a stopped language server for a worktree.
+    pub fn start_language_server_for_worktree(
+        &mut self,
+        worktree_id: WorktreeId,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let key = (worktree_id, name);
+        if self.disabled_language_servers.contains(&key) {
+            return;
+        }
+        let Some(language) = self.language_for_language_server(&key, cx) else {
+            return;
+        };
+        let Some(adapter) = self
+            .lsp_adapters_for_language(worktree_id, &language, cx)
+            .into_iter()
+            .find(|adapter| adapter.name == key.1)
+        else {
+            return;
+        };
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+
+        self.stopped_language_servers.remove(&key);
+        self.start_language_server(&worktree, adapter, language, cx);
+        cx.notify();
+    }
+
+    /// Stops the given language server of a worktree, and starts it again for every
+    /// worktree it served.
+    pub fn restart_language_server_for_worktree(
+        &mut self,
+        worktree_id: WorktreeId,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        if self
+            .disabled_language_servers
+            .contains(&(worktree_id, name.clone()))
+        {
+            return Task::ready(());
+        }
+        let (stopped_keys, stop) = self.stop_language_server_keys(worktree_id, name, cx);
+        cx.spawn(move |this, mut cx| async move {
+            stop.await;
+            this.update(&mut cx, |this, cx| {
+                for (worktree_id, name) in stopped_keys {
+                    this.start_language_server_for_worktree(worktree_id, name, cx);
+                }
+            })
+            .ok();
+        })
+    }
+
+    /// Disables a language server in the settings of every worktree in the project, or
+    /// enables it again. The servers are stopped or started once the worktrees reload
+    /// their settings.
+    pub fn set_language_server_disabled(
+        &mut self,
+        name: LanguageServerName,
+        disabled: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if self.is_remote() {
+            return Task::ready(Err(anyhow!(
+                "language servers can only be disabled by the host of the project"
+            )));
+        }
+
+        let fs = self.fs.clone();
+        let settings_paths = self
+            .visible_worktrees(cx)
+            .filter_map(|worktree| {
+                let worktree = worktree.read(cx);
+                worktree
+                    .root_entry()?
+                    .is_dir()
+                    .then(|| worktree.abs_path().join(&*LOCAL_SETTINGS_RELATIVE_PATH))
+            })
+            .collect::<Vec<_>>();
+        cx.spawn(move |_, cx| async move {
+            try_join_all(settings_paths.into_iter().map(|settings_path| {
+                let fs = fs.clone();
+                let name = name.clone();
+                let cx = cx.clone();
+                async move {
+                    let old_text = if fs.is_file(&settings_path).await {
+                        fs.load(&settings_path).await?
+                    } else {
+                        settings::initial_local_settings_content().to_string()
+                    };
+                    let new_text = cx.read_global(|store: &SettingsStore, _| {
+                        store.new_text_for_update::<ProjectSettings>(old_text, |settings| {
+                            let settings = settings.lsp.entry(name.0.clone()).or_default();
+                            settings.enabled = if disabled { Some(false) } else { None };
+                        })
+                    })?;
+                    if let Some(settings_dir) = settings_path.parent() {
+                        fs.create_dir(settings_dir).await?;
+                    }
+                    fs.atomic_write(settings_path.clone(), new_text)
+                        .await
+                        .with_context(|| format!("failed to write settings to {settings_path:?}"))
+                }
+            }))
+            .await?;
+            Ok(())
+        })
+    }
+
+    /// Stops the language servers that the worktrees' settings disable, and starts the
+    /// ones that they no longer disable.
+    pub(crate) fn sync_disabled_language_servers(&mut self, cx: &mut ModelContext<Self>) {
+        let newly_disabled = self
+            .language_server_ids
+            .keys()
+            .filter(|(worktree_id, name)| self.is_language_server_disabled(*worktree_id, name, cx))
+            .cloned()
+            .collect::<Vec<_>>();
+        for (worktree_id, name) in newly_disabled {
+            self.disabled_language_servers
+                .insert((worktree_id, name.clone()));
+            self.stop_language_server_keys(worktree_id, name, cx)
+                .1
+                .detach();
+        }
+
+        let newly_enabled = self
+            .disabled_language_servers
+            .iter()
+            .filter(|(worktree_id, name)| !self.is_language_server_disabled(*worktree_id, name, cx))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in newly_enabled {
+            self.disabled_language_servers.remove(&key);
+            self.start_language_server_for_worktree(key.0, key.1, cx);
+        }
+    }
+
+    fn stop_language_server_keys(
+        &mut self,
+        worktree_id: WorktreeId,
+        name: LanguageServerName,
+        cx: &mut ModelContext<Self>,
+    ) -> (Vec<(WorktreeId, LanguageServerName)>, Task<()>) {
+        let key = (worktree_id, name);
+        let Some(language) = self.language_for_language_server(&key, cx) else {
+            return (Vec::new(), Task::ready(()));
+        };
+
+        // Mark the keys as stopped before the server is shut down, so that opening a
+        // buffer in the meantime doesn't start it again.
+        let server_id = self.language_server_ids.get(&key).copied();
+        let mut stopped_keys = self
+            .language_server_ids
+            .iter()
+            .filter(|(_, id)| Some(**id) == server_id)
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        if stopped_keys.is_empty() {
+            stopped_keys.push(key.clone());
+        }
+        for key in &stopped_keys {
+            self.stopped_language_servers
+                .insert(key.clone(), language.clone());
+        }
+        cx.notify();
+
+        let stop = self.stop_language_server(key.0, key.1, cx);
+        let task = cx.spawn(|_, _| async move {
+            stop.await;
+        });
+        (stopped_keys, task)
+    }
+
+    fn language_for_language_server(
+        &self,
+        key: &(WorktreeId, LanguageServerName),
+        cx: &AppContext,
+    ) -> Option<Arc<Language>> {
+        if let Some(language) = self.stopped_language_servers.get(key) {
+            return Some(language.clone());
+        }
+        let server_id = self.language_server_ids.get(key);
+        if let Some(LanguageServerState::Running { language, .. }) =
+            server_id.and_then(|server_id| self.language_servers.get(server_id))
+        {
+            return Some(language.clone());
+        }
+        self.languages.to_vec().into_iter().find(|language| {
+            self.lsp_adapters_for_language(key.0, language, cx)
+                .iter()
+                .any(|adapter| adapter.name == key.1)
+        })
+    }
+
+    fn buffers_for_language_server(
+        &self,
+        server_id: LanguageServerId,
+        cx: &AppContext,
+    ) -> Vec<Model<Buffer>> {
+        self.opened_buffers
+            .values()
+            .filter_map(|buffer| {
+                let buffer = buffer.upgrade()?;
+                let snapshots = self.buffer_snapshots.get(&buffer.read(cx).remote_id())?;
+                snapshots.contains_key(&server_id).then_some(buffer)
+            })
+            .collect()
+    }
+}
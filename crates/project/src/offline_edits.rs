@@ -0,0 +1,253 @@
+//! Edits that a guest made to a remote project's buffers while it was disconnected
+//! from the host. When the connection is lost for longer than the room is willing to
+//! wait, the project keeps the buffers holding those edits, so their operations can
+//! be replayed into the project once the guest joins it again, or saved to disk if it
+//! is closed instead.
+
+use std::{
+    mem,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use client::proto;
+use collections::HashSet;
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::{proto::split_operations, Buffer};
+
+use crate::{Item as _, Project, ProjectPath};
+
+#[derive(Default)]
+pub(crate) struct OfflineEdits {
+    buffers: Vec<Model<Buffer>>,
+}
+
+/// The outcome of replaying a disconnected project's edits into a rejoined one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OfflineEditsReport {
+    /// Buffers whose offline edits were sent to the host.
+    pub replayed: Vec<ProjectPath>,
+    /// Buffers that need the user's attention.
+    pub conflicts: Vec<OfflineEditConflict>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OfflineEditConflict {
+    /// The host edited the buffer while the guest was away. The offline edits were
+    /// merged with the host's, so the result may need reviewing.
+    HostDiverged(ProjectPath),
+    /// The host closed the buffer, so its history no longer contains the edits the
+    /// offline ones were based on.
+    HostClosed(ProjectPath),
+    /// The file was deleted from the host's project.
+    FileRemoved(ProjectPath),
+    /// The replica id the edits were made with now belongs to another collaborator,
+    /// so replaying them would clash with that collaborator's edits.
+    ReplicaReassigned(ProjectPath),
+}
+
+impl OfflineEditConflict {
+    pub fn path(&self) -> &ProjectPath {
+        match self {
+            OfflineEditConflict::HostDiverged(path)
+            | OfflineEditConflict::HostClosed(path)
+            | OfflineEditConflict::FileRemoved(path)
+            | OfflineEditConflict::ReplicaReassigned(path) => path,
+        }
+    }
+}
+
+impl Project {
+    /// Whether this project was disconnected from its host while holding edits that
+    /// may not have reached it.
+    pub fn has_offline_edits(&self) -> bool {
+        !self.offline_edits.buffers.is_empty()
+    }
+
+    pub(crate) fn stash_offline_edits(&mut self, cx: &mut AppContext) {
+        self.offline_edits.buffers = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| buffer.upgrade())
+            .filter(|buffer| buffer.read(cx).is_dirty())
+            .collect();
+    }
+
+    /// Writes the edits that this disconnected project couldn't deliver to files in
+    /// `dir`, laid out like the project's worktrees, so that the project can be closed
+    /// without losing them. Returns the paths of the files that were written.
+    pub fn save_offline_edits(
+        &mut self,
+        dir: &Path,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<PathBuf>>> {
+        let files = self
+            .offline_edits
+            .buffers
+            .iter()
+            .filter_map(|buffer| {
+                let buffer = buffer.read(cx);
+                let path = dir.join(buffer.file()?.full_path(cx));
+                Some((path, buffer.as_rope().clone(), buffer.line_ending()))
+            })
+            .collect::<Vec<_>>();
+
+        let fs = self.fs.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut paths = Vec::new();
+            for (path, text, line_ending) in files {
+                if let Some(parent) = path.parent() {
+                    fs.create_dir(parent).await?;
+                }
+                fs.save(&path, &text, line_ending).await?;
+                paths.push(path);
+            }
+            this.update(&mut cx, |this, _| this.offline_edits.buffers.clear())?;
+            Ok(paths)
+        })
+    }
+
+    /// Sends the edits that `offline_project` couldn't deliver before it was
+    /// disconnected to the host of this project, which must be the same project
+    /// joined again. Buffers that can't take the edits are reported as conflicts and
+    /// left untouched.
+    pub fn replay_offline_edits(
+        &mut self,
+        offline_project: Model<Project>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<OfflineEditsReport>> {
+        let project_id = match self.remote_id() {
+            Some(project_id) if self.is_remote() && !self.is_disconnected() => project_id,
+            _ => {
+                return Task::ready(Err(anyhow!(
+                    "offline edits can only be replayed into a connected remote project"
+                )))
+            }
+        };
+
+        let offline_buffers = offline_project.update(cx, |offline_project, _| {
+            if offline_project.remote_id() == Some(project_id) {
+                Ok(mem::take(&mut offline_project.offline_edits.buffers))
+            } else {
+                Err(anyhow!("offline edits belong to a different project"))
+            }
+        });
+        let offline_buffers = match offline_buffers {
+            Ok(offline_buffers) => offline_buffers,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let replica_id = self.replica_id();
+        let replica_ids_in_use = self
+            .collaborators
+            .values()
+            .map(|collaborator| collaborator.replica_id)
+            .collect::<HashSet<_>>();
+        let mut offline_buffers = offline_buffers
+            .into_iter()
+            .filter_map(|buffer| {
+                let offline_replica_id = buffer.read(cx).replica_id();
+                let replica_reassigned = offline_replica_id != replica_id
+                    && replica_ids_in_use.contains(&offline_replica_id);
+                let path = buffer.read(cx).project_path(cx)?;
+                Some((buffer, path, replica_reassigned))
+            })
+            .collect::<Vec<_>>();
+        offline_buffers.sort_by(|(_, a, _), (_, b, _)| a.cmp(b));
+
+        let client = self.client.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut report = OfflineEditsReport::default();
+            for (offline_buffer, path, replica_reassigned) in offline_buffers {
+                if replica_reassigned {
+                    report
+                        .conflicts
+                        .push(OfflineEditConflict::ReplicaReassigned(path));
+                    continue;
+                }
+
+                // The host still has the buffer open if it kept the same id, even if the
+                // file was renamed since. Otherwise the file was closed or removed.
+                let open_buffer = this.update(&mut cx, |this, cx| {
+                    let buffer_id = offline_buffer.read(cx).remote_id();
+                    this.open_buffer_by_id(buffer_id, cx)
+                })?;
+                let Ok(buffer) = open_buffer.await else {
+                    let open_buffer =
+                        this.update(&mut cx, |this, cx| this.open_buffer(path.clone(), cx))?;
+                    let conflict = if open_buffer.await.is_ok() {
+                        OfflineEditConflict::HostClosed(path)
+                    } else {
+                        OfflineEditConflict::FileRemoved(path)
+                    };
+                    report.conflicts.push(conflict);
+                    continue;
+                };
+
+                let (buffer_id, path, is_deleted, host_version) =
+                    buffer.update(&mut cx, |buffer, cx| {
+                        (
+                            buffer.remote_id(),
+                            buffer.project_path(cx).unwrap_or(path),
+                            buffer.file().map_or(false, |file| file.is_deleted()),
+                            buffer.version(),
+                        )
+                    })?;
+                if is_deleted {
+                    report
+                        .conflicts
+                        .push(OfflineEditConflict::FileRemoved(path));
+                    continue;
+                }
+                let offline_version =
+                    offline_buffer.update(&mut cx, |buffer, _| buffer.version())?;
+
+                // Selections and diagnostics are stale by now, so only the text
+                // operations that the host hasn't seen are replayed.
+                let operations = offline_buffer
+                    .update(&mut cx, |buffer, cx| {
+                        buffer.serialize_ops(Some(host_version.clone()), cx)
+                    })?
+                    .await
+                    .into_iter()
+                    .filter(|operation| {
+                        matches!(
+                            operation.variant,
+                            Some(proto::operation::Variant::Edit(_))
+                                | Some(proto::operation::Variant::Undo(_))
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                if operations.is_empty() {
+                    continue;
+                }
+
+                buffer.update(&mut cx, |buffer, cx| {
+                    let operations = operations
+                        .iter()
+                        .cloned()
+                        .map(language::proto::deserialize_operation)
+                        .collect::<Result<Vec<_>>>()?;
+                    buffer.apply_ops(operations, cx)
+                })??;
+                for chunk in split_operations(operations) {
+                    client
+                        .request(proto::UpdateBuffer {
+                            project_id,
+                            buffer_id: buffer_id.into(),
+                            operations: chunk,
+                        })
+                        .await?;
+                }
+
+                if !offline_version.observed_all(&host_version) {
+                    report
+                        .conflicts
+                        .push(OfflineEditConflict::HostDiverged(path.clone()));
+                }
+                report.replayed.push(path);
+            }
+            Ok(report)
+        })
+    }
+}
@@ -1,9 +1,16 @@
+mod custom_language_servers;
 pub mod debounced_delay;
+pub mod file_history;
 mod ignore;
+pub mod large_files;
 pub mod lsp_command;
 pub mod lsp_ext_command;
+mod lsp_lifecycle;
+pub mod offline_edits;
 mod prettier_support;
 pub mod project_settings;
+mod pull_diagnostics;
+pub mod review_threads;
 pub mod search;
 pub mod terminals;
 pub mod worktree;
@@ -18,7 +25,9 @@ use client::{proto, Client, Collaborator, TypedEnvelope, UserStore};
 use clock::ReplicaId;
 use collections::{hash_map, BTreeMap, HashMap, HashSet, VecDeque};
 use copilot::Copilot;
+use custom_language_servers::{lsp_settings_for_worktree, CustomLspAdapters};
 use debounced_delay::DebouncedDelay;
+use file_history::SnapshotReason;
 use futures::{
     channel::mpsc::{self, UnboundedReceiver},
     future::{try_join_all, Shared},
@@ -32,7 +41,9 @@ use gpui::{
 };
 use itertools::Itertools;
 use language::{
-    language_settings::{language_settings, FormatOnSave, Formatter, InlayHintKind},
+    language_settings::{
+        language_settings, FormatOnSave, Formatter, InlayHintKind, LanguageServerFeature,
+    },
     markdown, point_to_lsp,
     proto::{
         deserialize_anchor, deserialize_fingerprint, deserialize_line_ending, deserialize_version,
@@ -44,6 +55,7 @@ use language::{
     LocalFile, LspAdapterDelegate, OffsetRangeExt, Operation, Patch, PendingLanguageServer,
     PointUtf16, TextBufferSnapshot, ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
+use large_files::{LargeFile, LargeFileFeature, LargeFileLoad};
 use log::error;
 use lsp::{
     DiagnosticSeverity, DiagnosticTag, DidChangeWatchedFilesRegistrationOptions,
@@ -52,15 +64,20 @@ use lsp::{
 };
 use lsp_command::*;
 use node_runtime::NodeRuntime;
+use offline_edits::OfflineEdits;
 use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
 use project_settings::{LspSettings, ProjectSettings};
 use rand::prelude::*;
+use review_threads::{ReviewThreadId, ReviewThreads};
 use rpc::{ErrorCode, ErrorExt};
 use search::SearchQuery;
 use serde::Serialize;
-use settings::{Settings, SettingsStore};
+use settings::{
+    editorconfig::{EditorConfig, EDITORCONFIG_FILE_NAME},
+    Settings, SettingsStore,
+};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
@@ -68,6 +85,7 @@ use smol::lock::Semaphore;
 use std::{
     cmp::{self, Ordering},
     convert::TryInto,
+    ffi::OsStr,
     hash::Hash,
     mem,
     num::NonZeroU32,
@@ -81,7 +99,7 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use terminals::Terminals;
+use terminals::{SharedTerminalId, Terminals};
 use text::{Anchor, BufferId};
 use util::{
     debug_panic, defer, http::HttpClient, merge_json_value_into,
@@ -89,6 +107,7 @@ use util::{
 };
 
 pub use fs::*;
+pub use lsp_lifecycle::{LanguageServerDetails, LanguageServerLifecycle};
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
 pub use worktree::*;
@@ -110,6 +129,8 @@ pub struct Project {
     language_servers: HashMap<LanguageServerId, LanguageServerState>,
     language_server_ids: HashMap<(WorktreeId, LanguageServerName), LanguageServerId>,
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
+    stopped_language_servers: HashMap<(WorktreeId, LanguageServerName), Arc<Language>>,
+    disabled_language_servers: HashSet<(WorktreeId, LanguageServerName)>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
     client: Arc<client::Client>,
     next_entry_id: Arc<AtomicUsize>,
@@ -142,13 +163,19 @@ pub struct Project {
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay,
+    large_files: HashMap<BufferId, LargeFile>,
+    large_file_loads: HashMap<ProjectPath, LargeFileLoad>,
     nonce: u128,
     _maintain_buffer_languages: Task<()>,
     _maintain_workspace_config: Task<Result<()>>,
     terminals: Terminals,
+    review_threads: ReviewThreads,
+    offline_edits: OfflineEdits,
     copilot_lsp_subscription: Option<gpui::Subscription>,
     copilot_log_subscription: Option<lsp::Subscription>,
-    current_lsp_settings: HashMap<Arc<str>, LspSettings>,
+    /// The `lsp` settings that each worktree's language servers were last started with.
+    current_lsp_settings: HashMap<WorktreeId, HashMap<Arc<str>, LspSettings>>,
+    custom_lsp_adapters: CustomLspAdapters,
     node: Option<Arc<dyn NodeRuntime>>,
     default_prettier: DefaultPrettier,
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
@@ -272,6 +299,8 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    ReviewThreadUpdated(ReviewThreadId),
+    RemoteTerminalShared(SharedTerminalId),
 }
 
 pub enum LanguageServerState {
@@ -283,6 +312,7 @@ pub enum LanguageServerState {
         server: Arc<LanguageServer>,
         watched_paths: HashMap<WorktreeId, GlobSet>,
         simulate_disk_based_diagnostics_completion: Option<Task<()>>,
+        pulled_diagnostics: pull_diagnostics::PulledDiagnostics,
     },
 }
 
@@ -582,7 +612,7 @@ impl Project {
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_synchronize_buffers);
         client.add_model_request_handler(Self::handle_format_buffers);
-        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
+        client.add_model_request_handler(Self::handle_get_code_actions);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
@@ -598,6 +628,14 @@ impl Project {
         client.add_model_request_handler(Self::handle_open_buffer_by_path);
         client.add_model_request_handler(Self::handle_save_buffer);
         client.add_model_message_handler(Self::handle_update_diff_base);
+        client.add_model_request_handler(Self::handle_get_review_threads);
+        client.add_model_request_handler(Self::handle_create_review_thread);
+        client.add_model_request_handler(Self::handle_reply_to_review_thread);
+        client.add_model_request_handler(Self::handle_set_review_thread_resolved);
+        client.add_model_message_handler(Self::handle_update_review_thread);
+        client.add_model_message_handler(Self::handle_update_shared_terminal);
+        client.add_model_message_handler(Self::handle_unshare_terminal);
+        client.add_model_request_handler(Self::handle_terminal_input);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
     }
 
@@ -649,18 +687,23 @@ impl Project {
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
+                stopped_language_servers: HashMap::default(),
+                disabled_language_servers: HashSet::default(),
                 language_server_statuses: Default::default(),
                 last_workspace_edits_by_language_server: Default::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
+                large_files: Default::default(),
+                large_file_loads: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                review_threads: Default::default(),
+                offline_edits: Default::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
-                current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
+                current_lsp_settings: HashMap::default(),
+                custom_lsp_adapters: CustomLspAdapters::default(),
                 node: Some(node),
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
@@ -737,6 +780,8 @@ impl Project {
                 supplementary_language_servers: HashMap::default(),
                 language_servers: Default::default(),
                 language_server_ids: HashMap::default(),
+                stopped_language_servers: HashMap::default(),
+                disabled_language_servers: HashSet::default(),
                 language_server_statuses: response
                     .payload
                     .language_servers
@@ -758,14 +803,17 @@ impl Project {
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
+                large_files: Default::default(),
+                large_file_loads: Default::default(),
                 buffer_snapshots: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
-                terminals: Terminals {
-                    local_handles: Vec::new(),
-                },
+                terminals: Terminals::default(),
+                review_threads: Default::default(),
+                offline_edits: Default::default(),
                 copilot_lsp_subscription,
                 copilot_log_subscription: None,
-                current_lsp_settings: ProjectSettings::get_global(cx).lsp.clone(),
+                current_lsp_settings: HashMap::default(),
+                custom_lsp_adapters: CustomLspAdapters::default(),
                 node: None,
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
@@ -792,6 +840,7 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             this.set_collaborators_from_proto(response.payload.collaborators, cx)?;
             this.client_subscriptions.push(subscription);
+            this.fetch_review_threads(cx);
             anyhow::Ok(())
         })??;
 
@@ -869,6 +918,8 @@ impl Project {
     }
 
     fn on_settings_changed(&mut self, cx: &mut ModelContext<Self>) {
+        self.sync_disabled_language_servers(cx);
+
         let mut language_servers_to_start = Vec::new();
         let mut language_formatters_to_check = Vec::new();
         for buffer in self.opened_buffers.values() {
@@ -897,38 +948,57 @@ impl Project {
         let mut language_servers_to_restart = Vec::new();
         let languages = self.languages.to_vec();
 
-        let new_lsp_settings = ProjectSettings::get_global(cx).lsp.clone();
-        let current_lsp_settings = &self.current_lsp_settings;
-        for (worktree_id, started_lsp_name) in self.language_server_ids.keys() {
-            let language = languages.iter().find_map(|l| {
-                let adapter = l
-                    .lsp_adapters()
-                    .iter()
-                    .find(|adapter| &adapter.name == started_lsp_name)?;
-                Some((l, adapter))
-            });
+        // Each worktree can configure its language servers differently, so they're
+        // compared with the settings they were started with in their own worktree.
+        let mut new_lsp_settings = HashMap::default();
+        for ((worktree_id, started_lsp_name), server_id) in &self.language_server_ids {
+            let worktree_lsp_settings: &HashMap<Arc<str>, LspSettings> = new_lsp_settings
+                .entry(*worktree_id)
+                .or_insert_with(|| lsp_settings_for_worktree(*worktree_id, cx));
+            let language = match self.language_servers.get(server_id) {
+                Some(LanguageServerState::Running {
+                    language, adapter, ..
+                }) => Some((language, adapter)),
+                _ => languages.iter().find_map(|l| {
+                    let adapter = l
+                        .lsp_adapters()
+                        .iter()
+                        .find(|adapter| &adapter.name == started_lsp_name)?;
+                    Some((l, adapter))
+                }),
+            };
             if let Some((language, adapter)) = language {
                 let worktree = self.worktree_for_id(*worktree_id, cx);
                 let file = worktree.as_ref().and_then(|tree| {
                     tree.update(cx, |tree, cx| tree.root_file(cx).map(|f| f as _))
                 });
-                if !language_settings(Some(language), file.as_ref(), cx).enable_language_server {
+                // Servers defined in settings stop once they're no longer configured
+                // for the language.
+                let custom_server_removed = language
+                    .lsp_adapters()
+                    .iter()
+                    .all(|adapter| &adapter.name != started_lsp_name)
+                    && !worktree_lsp_settings
+                        .get(&started_lsp_name.0)
+                        .map_or(false, |settings| {
+                            settings.is_custom_server()
+                                && settings
+                                    .languages
+                                    .iter()
+                                    .any(|name| name.as_ref() == language.name().as_ref())
+                        });
+                if custom_server_removed
+                    || !language_settings(Some(language), file.as_ref(), cx).enable_language_server
+                {
                     language_servers_to_stop.push((*worktree_id, started_lsp_name.clone()));
                 } else if let Some(worktree) = worktree {
                     let server_name = &adapter.name.0;
-                    match (
-                        current_lsp_settings.get(server_name),
-                        new_lsp_settings.get(server_name),
-                    ) {
-                        (None, None) => {}
-                        (Some(_), None) | (None, Some(_)) => {
-                            language_servers_to_restart.push((worktree, Arc::clone(language)));
-                        }
-                        (Some(current_lsp_settings), Some(new_lsp_settings)) => {
-                            if current_lsp_settings != new_lsp_settings {
-                                language_servers_to_restart.push((worktree, Arc::clone(language)));
-                            }
-                        }
+                    let current_lsp_settings = self
+                        .current_lsp_settings
+                        .get(worktree_id)
+                        .and_then(|settings| settings.get(server_name));
+                    if current_lsp_settings != worktree_lsp_settings.get(server_name) {
+                        language_servers_to_restart.push((worktree, Arc::clone(language)));
                     }
                 }
             }
@@ -1381,7 +1451,8 @@ impl Project {
         let store = cx.global::<SettingsStore>();
         for worktree in self.worktrees() {
             let worktree_id = worktree.read(cx).id().to_proto();
-            for (path, content) in store.local_settings(worktree.entity_id().as_u64() as usize) {
+            let root_id = worktree.entity_id().as_u64() as usize;
+            for (path, content) in store.local_settings(root_id) {
                 self.client
                     .send(proto::UpdateWorktreeSettings {
                         project_id,
@@ -1391,6 +1462,26 @@ impl Project {
                     })
                     .log_err();
             }
+            let editorconfigs = store
+                .local_editorconfigs(root_id)
+                .map(|(directory, content)| (directory.join(EDITORCONFIG_FILE_NAME), content))
+                .chain(
+                    store
+                        .parent_editorconfigs(root_id)
+                        .map(|(root_path, content)| {
+                            (parent_editorconfig_proto_path(&root_path), content)
+                        }),
+                );
+            for (path, content) in editorconfigs {
+                self.client
+                    .send(proto::UpdateWorktreeSettings {
+                        project_id,
+                        worktree_id,
+                        path: path.to_string_lossy().into(),
+                        content: Some(content.to_string()),
+                    })
+                    .log_err();
+            }
         }
 
         let (updates_tx, mut updates_rx) = mpsc::unbounded();
@@ -1496,6 +1587,8 @@ impl Project {
         self.shared_buffers.clear();
         self.set_collaborators_from_proto(message.collaborators, cx)?;
         self.metadata_changed(cx);
+        // Guests may have missed terminal updates while the host was disconnected.
+        self.resend_shared_terminals(cx);
         Ok(())
     }
 
@@ -1555,6 +1648,7 @@ impl Project {
             self.collaborators.clear();
             self.shared_buffers.clear();
             self.client_subscriptions.clear();
+            self.unshare_terminals();
 
             for worktree_handle in self.worktrees.iter_mut() {
                 if let WorktreeHandle::Strong(worktree) = worktree_handle {
@@ -1623,6 +1717,8 @@ impl Project {
             *sharing_has_stopped = true;
 
             self.collaborators.clear();
+            self.close_remote_terminals(cx);
+            self.stash_offline_edits(cx);
 
             for worktree in &self.worktrees {
                 if let Some(worktree) = worktree.upgrade() {
@@ -1798,14 +1894,50 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Model<Buffer>>> {
         let buffer_id = self.next_buffer_id.next();
-        let load_buffer = worktree.update(cx, |worktree, cx| {
-            let worktree = worktree.as_local_mut().unwrap();
-            worktree.load_buffer(buffer_id, path, cx)
-        });
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.clone(),
+        };
+        let abs_path = worktree.read(cx).as_local().unwrap().absolutize(path);
+        let large_file_settings = ProjectSettings::get(
+            Some((worktree.entity_id().as_u64() as usize, path.as_ref())),
+            cx,
+        )
+        .large_file;
+        let fs = self.fs.clone();
+        let worktree = worktree.clone();
         cx.spawn(move |this, mut cx| async move {
-            let buffer = load_buffer.await?;
-            this.update(&mut cx, |this, cx| this.register_buffer(&buffer, cx))??;
-            Ok(buffer)
+            let len = fs
+                .metadata(&abs_path?)
+                .await?
+                .map_or(0, |metadata| metadata.len);
+            let is_large_file = large_file_settings.is_large(len);
+            let large_file_progress = if is_large_file {
+                Some(this.update(&mut cx, |this, cx| {
+                    this.start_large_file_load(project_path.clone(), len, cx)
+                })?)
+            } else {
+                None
+            };
+
+            let load_buffer = worktree.update(&mut cx, |worktree, cx| {
+                let worktree = worktree.as_local_mut().unwrap();
+                worktree.load_buffer(buffer_id, &project_path.path, large_file_progress, cx)
+            })?;
+            let buffer = load_buffer.await;
+            this.update(&mut cx, |this, cx| {
+                if is_large_file {
+                    this.finish_large_file_load(&project_path, buffer.as_ref().ok(), len, cx);
+                }
+                let buffer = buffer?;
+                if is_large_file && large_file_settings.read_only.unwrap_or(false) {
+                    buffer.update(cx, |buffer, cx| {
+                        buffer.set_capability(Capability::ReadOnly, cx)
+                    });
+                }
+                this.register_buffer(&buffer, cx)?;
+                Ok(buffer)
+            })?
         })
     }
 
@@ -2045,8 +2177,12 @@ impl Project {
 
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
-        self.register_buffer_with_copilot(buffer, cx);
+        if !self.is_large_file_feature_disabled(buffer.read(cx), LargeFileFeature::LanguageServers)
+        {
+            self.register_buffer_with_copilot(buffer, cx);
+        }
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.large_files.remove(&buffer.remote_id());
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2076,6 +2212,9 @@ impl Project {
     ) {
         let buffer = buffer_handle.read(cx);
         let buffer_id = buffer.remote_id();
+        if self.is_large_file_feature_disabled(buffer, LargeFileFeature::LanguageServers) {
+            return;
+        }
 
         if let Some(file) = File::from_dyn(buffer.file()) {
             if !file.is_local() {
@@ -2097,7 +2236,7 @@ impl Project {
             }
 
             if let Some(language) = language {
-                for adapter in language.lsp_adapters() {
+                for adapter in self.lsp_adapters_for_language(worktree_id, &language, cx) {
                     let language_id = adapter.language_ids.get(language.name().as_ref()).cloned();
                     let server = self
                         .language_server_ids
@@ -2148,6 +2287,7 @@ impl Project {
                         .entry(buffer_id)
                         .or_default()
                         .insert(server.server_id(), vec![snapshot]);
+                    self.pull_document_diagnostics(server.server_id(), uri.clone(), Some(0), cx);
                 }
             }
         }
@@ -2168,9 +2308,14 @@ impl Project {
             let worktree_id = old_file.worktree_id(cx);
             let ids = &self.language_server_ids;
 
-            let language = buffer.language().cloned();
-            let adapters = language.iter().flat_map(|language| language.lsp_adapters());
-            for &server_id in adapters.flat_map(|a| ids.get(&(worktree_id, a.name.clone()))) {
+            let adapters = buffer
+                .language()
+                .map(|language| self.lsp_adapters_for_language(worktree_id, language, cx))
+                .unwrap_or_default();
+            for &server_id in adapters
+                .iter()
+                .flat_map(|a| ids.get(&(worktree_id, a.name.clone())))
+            {
                 buffer.update_diagnostics(server_id, Default::default(), cx);
             }
 
@@ -2425,7 +2570,13 @@ impl Project {
                 }
             }
 
+            BufferEvent::Reloading => {
+                self.record_file_snapshot(&buffer, SnapshotReason::Reload, cx);
+            }
+
             BufferEvent::Saved => {
+                self.record_file_snapshot(&buffer, SnapshotReason::Save, cx);
+
                 let file = File::from_dyn(buffer.read(cx).file())?;
                 let worktree_id = file.worktree_id(cx);
                 let abs_path = file.as_local()?.abs_path(cx);
@@ -2448,6 +2599,7 @@ impl Project {
 
                 let language_server_ids = self.language_server_ids_for_buffer(buffer.read(cx), cx);
                 for language_server_id in language_server_ids {
+                    self.pull_workspace_diagnostics(language_server_id, cx);
                     if let Some(LanguageServerState::Running {
                         adapter,
                         simulate_disk_based_diagnostics_completion,
@@ -2517,6 +2669,10 @@ impl Project {
             _ => {}
         }
 
+        if let BufferEvent::Edited { .. } = event {
+            self.pull_buffer_diagnostics(&buffer, cx);
+        }
+
         None
     }
 
@@ -2525,6 +2681,9 @@ impl Project {
         buffer: &Model<Buffer>,
         cx: &mut ModelContext<Self>,
     ) {
+        if self.is_large_file_feature_disabled(buffer.read(cx), LargeFileFeature::GitDiff) {
+            return;
+        }
         self.buffers_needing_diff.insert(buffer.downgrade());
         let first_insertion = self.buffers_needing_diff.len() == 1;
 
@@ -2758,7 +2917,12 @@ impl Project {
         };
         if let Some(file) = buffer_file {
             let worktree = file.worktree.clone();
-            if worktree.read(cx).is_local() {
+            if worktree.read(cx).is_local()
+                && !self.is_large_file_feature_disabled(
+                    buffer.read(cx),
+                    LargeFileFeature::LanguageServers,
+                )
+            {
                 self.start_language_servers(&worktree, new_language, cx);
             }
         }
@@ -2776,8 +2940,9 @@ impl Project {
             return;
         }
 
-        for adapter in language.lsp_adapters() {
-            self.start_language_server(worktree, adapter.clone(), language.clone(), cx);
+        let worktree_id = worktree.read(cx).id();
+        for adapter in self.lsp_adapters_for_language(worktree_id, &language, cx) {
+            self.start_language_server(worktree, adapter, language.clone(), cx);
         }
     }
 
@@ -2796,15 +2961,27 @@ impl Project {
         let worktree_id = worktree.id();
         let worktree_path = worktree.abs_path();
         let key = (worktree_id, adapter.name.clone());
-        if self.language_server_ids.contains_key(&key) {
+        if self.language_server_ids.contains_key(&key)
+            || self.stopped_language_servers.contains_key(&key)
+        {
+            return;
+        }
+        if self.is_language_server_disabled(worktree_id, &adapter.name, cx) {
+            self.stopped_language_servers.insert(key.clone(), language);
+            self.disabled_language_servers.insert(key);
+            cx.notify();
             return;
         }
 
+        let binary_override = self.language_server_binary_override(worktree_id, &adapter.name, cx);
+        self.current_lsp_settings
+            .insert(worktree_id, lsp_settings_for_worktree(worktree_id, cx));
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let pending_server = match self.languages.create_pending_language_server(
             stderr_capture.clone(),
             language.clone(),
             adapter.clone(),
+            binary_override,
             Arc::clone(&worktree_path),
             ProjectLspAdapterDelegate::new(self, cx),
             cx,
@@ -3131,6 +3308,21 @@ impl Project {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |project, cx| {
+                            project.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -3238,6 +3430,7 @@ impl Project {
                 watched_paths: Default::default(),
                 server: language_server.clone(),
                 simulate_disk_based_diagnostics_completion: None,
+                pulled_diagnostics: Default::default(),
             },
         );
 
@@ -3277,7 +3470,12 @@ impl Project {
                 };
 
                 if file.worktree.read(cx).id() != key.0
-                    || !language.lsp_adapters().iter().any(|a| a.name == key.1)
+                    || !self
+                        .lsp_adapters_for_language(key.0, language, cx)
+                        .iter()
+                        .any(|a| a.name == key.1)
+                    || self
+                        .is_large_file_feature_disabled(buffer, LargeFileFeature::LanguageServers)
                 {
                     continue;
                 }
@@ -3332,6 +3530,7 @@ impl Project {
             }
         }
 
+        self.refresh_pulled_diagnostics(server_id, cx);
         cx.notify();
         Ok(())
     }
@@ -3441,9 +3640,13 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) {
         let worktree_id = worktree.read(cx).id();
+        let adapters = self.lsp_adapters_for_language(worktree_id, &language, cx);
+        for adapter in &adapters {
+            self.stopped_language_servers
+                .remove(&(worktree_id, adapter.name.clone()));
+        }
 
-        let stop_tasks = language
-            .lsp_adapters()
+        let stop_tasks = adapters
             .iter()
             .map(|adapter| {
                 let stop_task = self.stop_language_server(worktree_id, adapter.name.clone(), cx);
@@ -4151,7 +4354,7 @@ impl Project {
                     let file = File::from_dyn(buffer.file())?;
                     let buffer_abs_path = file.as_local().map(|f| f.abs_path(cx));
                     let (adapter, server) = self
-                        .primary_language_server_for_buffer(buffer, cx)
+                        .formatting_language_server_for_buffer(buffer, cx)
                         .map(|(a, s)| (Some(a.clone()), Some(s.clone())))
                         .unwrap_or((None, None));
                     Some((buffer_handle, buffer_abs_path, adapter, server))
@@ -4193,6 +4396,7 @@ impl Project {
 
                     let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
                     let ensure_final_newline = settings.ensure_final_newline_on_save;
+                    let line_ending = settings.line_ending;
                     let tab_size = settings.tab_size;
 
                     // First, format buffer's whitespace according to the settings.
@@ -4214,6 +4418,9 @@ impl Project {
                         if ensure_final_newline {
                             buffer.ensure_final_newline(cx);
                         }
+                        if let Some(line_ending) = line_ending {
+                            buffer.set_line_ending(line_ending, cx);
+                        }
                         buffer.end_transaction(cx)
                     })?;
 
@@ -4455,6 +4662,30 @@ impl Project {
         }
     }
 
+    /// The most preferred language server of a buffer that can format it.
+    fn formatting_language_server_for_buffer(
+        &self,
+        buffer: &Buffer,
+        cx: &AppContext,
+    ) -> Option<(&Arc<CachedLspAdapter>, &Arc<LanguageServer>)> {
+        let servers =
+            self.language_servers_for_buffer_feature(buffer, LanguageServerFeature::Formatting, cx);
+        let first = servers.first().copied();
+        servers
+            .into_iter()
+            .find(|(_, server)| {
+                let capabilities = server.capabilities();
+                matches!(
+                    capabilities.document_formatting_provider.as_ref(),
+                    Some(p) if *p != OneOf::Left(false)
+                ) || matches!(
+                    capabilities.document_range_formatting_provider.as_ref(),
+                    Some(p) if *p != OneOf::Left(false)
+                )
+            })
+            .or(first)
+    }
+
     async fn format_via_lsp(
         this: &WeakModel<Self>,
         buffer: &Model<Buffer>,
@@ -4915,7 +5146,12 @@ impl Project {
             let scope = snapshot.language_scope_at(offset);
 
             let server_ids: Vec<_> = self
-                .language_servers_for_buffer(buffer.read(cx), cx)
+                .language_servers_for_buffer_feature(
+                    buffer.read(cx),
+                    LanguageServerFeature::Completions,
+                    cx,
+                )
+                .into_iter()
                 .filter(|(_, server)| server.capabilities().completion_provider.is_some())
                 .filter(|(adapter, _)| {
                     scope
@@ -5246,12 +5482,57 @@ impl Project {
         range: Range<Anchor>,
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<CodeAction>>> {
-        self.request_lsp(
-            buffer_handle.clone(),
-            LanguageServerToQuery::Primary,
-            GetCodeActions { range, kinds: None },
-            cx,
-        )
+        if self.is_local() {
+            let request = GetCodeActions {
+                range: range.clone(),
+                kinds: None,
+            };
+            let server_ids: Vec<_> = self
+                .language_servers_for_buffer_feature(
+                    buffer_handle.read(cx),
+                    LanguageServerFeature::CodeActions,
+                    cx,
+                )
+                .into_iter()
+                .filter(|(_, server)| request.check_capabilities(server.capabilities()))
+                .map(|(_, server)| server.server_id())
+                .collect();
+
+            let buffer = buffer_handle.clone();
+            cx.spawn(move |this, mut cx| async move {
+                let mut tasks = Vec::with_capacity(server_ids.len());
+                this.update(&mut cx, |this, cx| {
+                    for server_id in server_ids {
+                        tasks.push(this.request_lsp(
+                            buffer.clone(),
+                            LanguageServerToQuery::Other(server_id),
+                            GetCodeActions {
+                                range: range.clone(),
+                                kinds: None,
+                            },
+                            cx,
+                        ));
+                    }
+                })?;
+
+                let mut actions = Vec::new();
+                for task in tasks {
+                    if let Some(new_actions) = task.await.log_err() {
+                        actions.extend(new_actions);
+                    }
+                }
+
+                Ok(actions)
+            })
+        } else {
+            // The host merges the actions of all of its language servers.
+            self.request_lsp(
+                buffer_handle.clone(),
+                LanguageServerToQuery::Primary,
+                GetCodeActions { range, kinds: None },
+                cx,
+            )
+        }
     }
 
     pub fn code_actions<T: Clone + ToOffset>(
@@ -5836,6 +6117,9 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<anyhow::Result<Vec<InlayHint>>> {
         let buffer = buffer_handle.read(cx);
+        if self.is_large_file_feature_disabled(buffer, LargeFileFeature::InlayHints) {
+            return Task::ready(Ok(Vec::new()));
+        }
         let range_start = range.start;
         let range_end = range.end;
         let buffer_id = buffer.remote_id().into();
@@ -6415,7 +6699,15 @@ impl Project {
         if self.is_local() {
             let language_server = match server {
                 LanguageServerToQuery::Primary => {
-                    match self.primary_language_server_for_buffer(buffer, cx) {
+                    let server = match request.feature() {
+                        // Route the request to the most preferred server that supports it.
+                        Some(feature) => self
+                            .language_servers_for_buffer_feature(buffer, feature, cx)
+                            .into_iter()
+                            .find(|(_, server)| request.check_capabilities(server.capabilities())),
+                        None => self.primary_language_server_for_buffer(buffer, cx),
+                    };
+                    match server {
                         Some((_, server)) => Some(Arc::clone(server)),
                         None => return Task::ready(Ok(Default::default())),
                     }
@@ -6718,6 +7010,7 @@ impl Project {
     fn add_worktree(&mut self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
         cx.observe(worktree, |_, _, cx| cx.notify()).detach();
         if worktree.read(cx).is_local() {
+            self.watch_parent_editorconfigs(worktree, cx);
             cx.subscribe(worktree, |this, worktree, event, cx| match event {
                 worktree::Event::UpdatedEntries(changes) => {
                     this.update_local_worktree_buffers(&worktree, changes, cx);
@@ -6984,6 +7277,9 @@ impl Project {
             .values()
             .filter_map(|buffer| {
                 let buffer = buffer.upgrade()?;
+                if self.is_large_file_feature_disabled(buffer.read(cx), LargeFileFeature::GitDiff) {
+                    return None;
+                }
                 let file = File::from_dyn(buffer.read(cx).file())?;
                 if file.worktree != worktree_handle {
                     return None;
@@ -7000,22 +7296,46 @@ impl Project {
             return;
         }
 
+        cx.spawn(move |this, mut cx| async move {
+            // Wait for all of the buffers to load, skipping large files without Git diffs.
+            let future_buffers = future_buffers.collect::<Vec<_>>().await;
+            this.update(&mut cx, |this, cx| {
+                let buffers = future_buffers
+                    .into_iter()
+                    .flatten()
+                    .filter(|(buffer, _)| {
+                        !this.is_large_file_feature_disabled(
+                            buffer.read(cx),
+                            LargeFileFeature::GitDiff,
+                        )
+                    })
+                    .chain(current_buffers)
+                    .collect();
+                this.reload_diff_bases(worktree_handle, buffers, cx)
+            })?
+            .await
+        })
+        .detach();
+    }
+
+    /// Reloads the diff base of each of the given buffers from the index of the Git
+    /// repository containing it.
+    pub(crate) fn reload_diff_bases(
+        &self,
+        worktree_handle: Model<Worktree>,
+        buffers: Vec<(Model<Buffer>, Arc<Path>)>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
         let remote_id = self.remote_id();
         let client = self.client.clone();
         cx.spawn(move |_, mut cx| async move {
-            // Wait for all of the buffers to load.
-            let future_buffers = future_buffers.collect::<Vec<_>>().await;
-
-            // Reload the diff base for every buffer whose containing git repository has changed.
             let snapshot =
                 worktree_handle.update(&mut cx, |tree, _| tree.as_local().unwrap().snapshot())?;
             let diff_bases_by_buffer = cx
                 .background_executor()
                 .spawn(async move {
-                    future_buffers
+                    buffers
                         .into_iter()
-                        .filter_map(|e| e)
-                        .chain(current_buffers)
                         .filter_map(|(buffer, path)| {
                             let (work_directory, repo) =
                                 snapshot.repository_and_work_directory_for_path(&path)?;
@@ -7047,7 +7367,6 @@ impl Project {
 
             anyhow::Ok(())
         })
-        .detach();
     }
 
     fn update_local_worktree_settings(
@@ -7062,8 +7381,24 @@ impl Project {
         let remote_worktree_id = worktree.id();
 
         let mut settings_contents = Vec::new();
+        let mut editorconfig_contents = Vec::new();
         for (path, _, change) in changes.iter() {
-            if path.ends_with(&*LOCAL_SETTINGS_RELATIVE_PATH) {
+            if path.file_name() == Some(OsStr::new(EDITORCONFIG_FILE_NAME)) {
+                let directory: Arc<Path> = Arc::from(path.parent().unwrap_or(Path::new("")));
+                let fs = self.fs.clone();
+                let removed = *change == PathChange::Removed;
+                let abs_path = worktree.absolutize(path);
+                editorconfig_contents.push(async move {
+                    (
+                        directory,
+                        if removed {
+                            None
+                        } else {
+                            Some(async move { fs.load(&abs_path?).await }.await)
+                        },
+                    )
+                });
+            } else if path.ends_with(&*LOCAL_SETTINGS_RELATIVE_PATH) {
                 let settings_dir = Arc::from(
                     path.ancestors()
                         .nth(LOCAL_SETTINGS_RELATIVE_PATH.components().count())
@@ -7085,7 +7420,7 @@ impl Project {
             }
         }
 
-        if settings_contents.is_empty() {
+        if settings_contents.is_empty() && editorconfig_contents.is_empty() {
             return;
         }
 
@@ -7093,8 +7428,34 @@ impl Project {
         cx.spawn(move |_, cx| async move {
             let settings_contents: Vec<(Arc<Path>, _)> =
                 futures::future::join_all(settings_contents).await;
+            let editorconfig_contents: Vec<(Arc<Path>, _)> =
+                futures::future::join_all(editorconfig_contents).await;
             cx.update(|cx| {
                 cx.update_global::<SettingsStore, _>(|store, cx| {
+                    for (directory, file_content) in editorconfig_contents {
+                        let file_content = file_content.and_then(|content| content.log_err());
+                        store
+                            .set_local_editorconfig(
+                                worktree_id.as_u64() as usize,
+                                directory.clone(),
+                                file_content.as_deref(),
+                            )
+                            .log_err();
+                        if let Some(remote_id) = project_id {
+                            client
+                                .send(proto::UpdateWorktreeSettings {
+                                    project_id: remote_id,
+                                    worktree_id: remote_worktree_id.to_proto(),
+                                    path: directory
+                                        .join(EDITORCONFIG_FILE_NAME)
+                                        .to_string_lossy()
+                                        .into_owned(),
+                                    content: file_content,
+                                })
+                                .log_err();
+                        }
+                    }
+
                     for (directory, file_content) in settings_contents {
                         let file_content = file_content.and_then(|content| content.log_err());
                         store
@@ -7123,6 +7484,90 @@ impl Project {
         .detach();
     }
 
+    /// Loads the EditorConfig files in the directories above a local worktree's root, up to
+    /// the first one that is marked as the root, and reloads them whenever one of them changes
+    /// for as long as the worktree exists.
+    fn watch_parent_editorconfigs(&self, worktree: &Model<Worktree>, cx: &mut ModelContext<Self>) {
+        let root_id = worktree.entity_id().as_u64() as usize;
+        let remote_worktree_id = worktree.read(cx).id();
+        let abs_path = worktree.read(cx).abs_path();
+        let worktree = worktree.downgrade();
+        let fs = self.fs.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let mut loaded_editorconfigs = BTreeMap::<Arc<Path>, String>::new();
+            loop {
+                let editorconfigs = load_parent_editorconfigs(fs.as_ref(), &abs_path).await;
+                if worktree.upgrade().is_none() {
+                    return;
+                }
+
+                let mut changes = Vec::new();
+                for root_path in loaded_editorconfigs.keys() {
+                    if !editorconfigs.contains_key(root_path) {
+                        changes.push((root_path.clone(), None));
+                    }
+                }
+                for (root_path, content) in &editorconfigs {
+                    if loaded_editorconfigs.get(root_path) != Some(content) {
+                        changes.push((root_path.clone(), Some(content.clone())));
+                    }
+                }
+                if !changes.is_empty() {
+                    let updated = this.update(&mut cx, |this, cx| {
+                        let project_id = this.remote_id();
+                        let client = this.client.clone();
+                        cx.update_global::<SettingsStore, _>(|store, _| {
+                            for (root_path, content) in changes {
+                                store
+                                    .set_parent_editorconfig(
+                                        root_id,
+                                        root_path.clone(),
+                                        content.as_deref(),
+                                    )
+                                    .log_err();
+                                if let Some(project_id) = project_id {
+                                    client
+                                        .send(proto::UpdateWorktreeSettings {
+                                            project_id,
+                                            worktree_id: remote_worktree_id.to_proto(),
+                                            path: parent_editorconfig_proto_path(&root_path)
+                                                .to_string_lossy()
+                                                .into_owned(),
+                                            content,
+                                        })
+                                        .log_err();
+                                }
+                            }
+                        });
+                    });
+                    if updated.is_err() {
+                        return;
+                    }
+                }
+                loaded_editorconfigs = editorconfigs;
+                if loaded_editorconfigs.is_empty() {
+                    return;
+                }
+
+                let mut events = futures::stream::select_all(
+                    futures::future::join_all(loaded_editorconfigs.keys().map(|root_path| {
+                        let editorconfig_path = parent_editorconfig_abs_path(&abs_path, root_path);
+                        let fs = fs.clone();
+                        async move {
+                            fs.watch(&editorconfig_path, Duration::from_millis(100))
+                                .await
+                        }
+                    }))
+                    .await,
+                );
+                if events.next().await.is_none() {
+                    return;
+                }
+            }
+        })
+        .detach();
+    }
+
     pub fn set_active_path(&mut self, entry: Option<ProjectPath>, cx: &mut ModelContext<Self>) {
         let new_active_entry = entry.and_then(|project_path| {
             let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
@@ -7270,6 +7715,7 @@ impl Project {
             cx.emit(Event::CollaboratorJoined(collaborator.peer_id));
             this.collaborators
                 .insert(collaborator.peer_id, collaborator);
+            this.resend_shared_terminals(cx);
             cx.notify();
         })?;
 
@@ -7308,6 +7754,9 @@ impl Project {
             if let Some(buffers) = buffers {
                 this.shared_buffers.insert(new_peer_id, buffers);
             }
+            this.replace_terminal_writer(old_peer_id, Some(new_peer_id));
+            // The guest may have missed terminal updates while it was disconnected.
+            this.resend_shared_terminals(cx);
 
             if is_host {
                 this.opened_buffers
@@ -7348,6 +7797,7 @@ impl Project {
                 }
             }
             this.shared_buffers.remove(&peer_id);
+            this.replace_terminal_writer(peer_id, None);
 
             cx.emit(Event::CollaboratorLeft(peer_id));
             cx.notify();
@@ -7397,15 +7847,36 @@ impl Project {
         this.update(&mut cx, |this, cx| {
             let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
             if let Some(worktree) = this.worktree_for_id(worktree_id, cx) {
+                let root_id = worktree.entity_id().as_u64() as usize;
+                let path = PathBuf::from(&envelope.payload.path);
+                let content = envelope.payload.content.as_deref();
                 cx.update_global::<SettingsStore, _>(|store, cx| {
-                    store
-                        .set_local_settings(
-                            worktree.entity_id().as_u64() as usize,
-                            PathBuf::from(&envelope.payload.path).into(),
-                            envelope.payload.content.as_ref().map(String::as_str),
-                            cx,
-                        )
-                        .log_err();
+                    if path.file_name() == Some(OsStr::new(EDITORCONFIG_FILE_NAME)) {
+                        let directory = path.parent().unwrap_or(Path::new(""));
+                        let parent_count = directory
+                            .components()
+                            .take_while(|component| *component == Component::ParentDir)
+                            .count();
+                        if parent_count == 0 {
+                            store
+                                .set_local_editorconfig(root_id, directory.into(), content)
+                                .log_err();
+                        } else {
+                            let abs_path = worktree.read(cx).abs_path();
+                            let components = abs_path.components().collect::<Vec<_>>();
+                            let root_path = components
+                                [components.len().saturating_sub(parent_count)..]
+                                .iter()
+                                .collect::<PathBuf>();
+                            store
+                                .set_parent_editorconfig(root_id, root_path.into(), content)
+                                .log_err();
+                        }
+                    } else {
+                        store
+                            .set_local_settings(root_id, path.into(), content, cx)
+                            .log_err();
+                    }
                 });
             }
             Ok(())
@@ -8283,6 +8754,46 @@ impl Project {
         })?
     }
 
+    async fn handle_get_code_actions(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetCodeActions>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetCodeActionsResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let buffer_id = GetCodeActions::buffer_id_from_proto(&envelope.payload)?;
+        let buffer_handle = this.update(&mut cx, |this, _cx| {
+            this.opened_buffers
+                .get(&buffer_id)
+                .and_then(|buffer| buffer.upgrade())
+                .ok_or_else(|| anyhow!("unknown buffer id {}", buffer_id))
+        })??;
+        let request = GetCodeActions::from_proto(
+            envelope.payload,
+            this.clone(),
+            buffer_handle.clone(),
+            cx.clone(),
+        )
+        .await?;
+        let buffer_version = buffer_handle.update(&mut cx, |buffer, _| buffer.version())?;
+        // Unlike other requests, code actions come from every language server that
+        // offers them, just as they do for the host.
+        let actions = this
+            .update(&mut cx, |this, cx| {
+                this.code_actions_impl(&buffer_handle, request.range, cx)
+            })?
+            .await?;
+        this.update(&mut cx, |this, cx| {
+            Ok(GetCodeActions::response_to_proto(
+                actions,
+                this,
+                sender_id,
+                &buffer_version,
+                cx,
+            ))
+        })?
+    }
+
     async fn handle_get_project_symbols(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::GetProjectSymbols>,
@@ -9046,6 +9557,29 @@ impl Project {
         self.language_servers_for_buffer(buffer, cx).next()
     }
 
+    /// The language servers of a buffer, ordered by the preference that the buffer's
+    /// language settings configure for the given feature.
+    pub fn language_servers_for_buffer_feature(
+        &self,
+        buffer: &Buffer,
+        feature: LanguageServerFeature,
+        cx: &AppContext,
+    ) -> Vec<(&Arc<CachedLspAdapter>, &Arc<LanguageServer>)> {
+        let mut servers = self
+            .language_servers_for_buffer(buffer, cx)
+            .collect::<Vec<_>>();
+        let settings = language_settings(buffer.language(), buffer.file(), cx);
+        if let Some(preferences) = settings.language_server_preferences.get(&feature) {
+            servers.sort_by_key(|(adapter, _)| {
+                preferences
+                    .iter()
+                    .position(|name| name.as_ref() == adapter.name.0.as_ref())
+                    .unwrap_or(preferences.len())
+            });
+        }
+        servers
+    }
+
     pub fn language_server_for_buffer(
         &self,
         buffer: &Buffer,
@@ -9061,10 +9595,12 @@ impl Project {
         buffer: &Buffer,
         cx: &AppContext,
     ) -> Vec<LanguageServerId> {
+        if self.is_large_file_feature_disabled(buffer, LargeFileFeature::LanguageServers) {
+            return Vec::new();
+        }
         if let Some((file, language)) = File::from_dyn(buffer.file()).zip(buffer.language()) {
             let worktree_id = file.worktree_id(cx);
-            language
-                .lsp_adapters()
+            self.lsp_adapters_for_language(worktree_id, language, cx)
                 .iter()
                 .flat_map(|adapter| {
                     let key = (worktree_id, adapter.name.clone());
@@ -9309,6 +9845,49 @@ fn resolve_path(base: &Path, path: &Path) -> PathBuf {
     result
 }
 
+/// Loads the EditorConfig files in the directories above the given worktree root, up to the
+/// first one that is marked as the root, keyed by the path from their directory to the root.
+async fn load_parent_editorconfigs(fs: &dyn Fs, abs_path: &Path) -> BTreeMap<Arc<Path>, String> {
+    let mut editorconfigs = BTreeMap::new();
+    for directory in abs_path.ancestors().skip(1) {
+        let editorconfig_path = directory.join(EDITORCONFIG_FILE_NAME);
+        if !fs.is_file(&editorconfig_path).await {
+            continue;
+        }
+        let Some(content) = fs.load(&editorconfig_path).await.log_err() else {
+            continue;
+        };
+        let Ok(root_path) = abs_path.strip_prefix(directory) else {
+            continue;
+        };
+        let is_root = EditorConfig::parse(&content).map_or(false, |config| config.root);
+        editorconfigs.insert(Arc::from(root_path), content);
+        if is_root {
+            break;
+        }
+    }
+    editorconfigs
+}
+
+/// Returns the absolute path of the EditorConfig file at the given path above a worktree's root.
+fn parent_editorconfig_abs_path(abs_path: &Path, root_path: &Path) -> PathBuf {
+    let mut directory = abs_path;
+    for _ in root_path.components() {
+        directory = directory.parent().unwrap_or(directory);
+    }
+    directory.join(EDITORCONFIG_FILE_NAME)
+}
+
+/// Returns the path that's sent to guests for an EditorConfig file above a worktree's root,
+/// which has a `..` component for each directory between the file and the root.
+fn parent_editorconfig_proto_path(root_path: &Path) -> PathBuf {
+    root_path
+        .components()
+        .map(|_| Component::ParentDir)
+        .chain([Component::Normal(OsStr::new(EDITORCONFIG_FILE_NAME))])
+        .collect()
+}
+
 impl Item for Buffer {
     fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
         File::from_dyn(self.file()).and_then(|file| file.project_entry_id(cx))
@@ -10,9 +10,12 @@ pub struct ProjectSettings {
     /// Configuration for language servers.
     ///
     /// The following settings can be overridden for specific language servers:
+    /// - binary
     /// - initialization_options
+    /// - enabled
     /// To override settings for a language, add an entry for that language server's
-    /// name to the lsp value.
+    /// name to the lsp value. Entries that list `languages` define a new language
+    /// server for those languages.
     /// Default: null
     #[serde(default)]
     pub lsp: HashMap<Arc<str>, LspSettings>,
@@ -21,6 +24,14 @@ pub struct ProjectSettings {
     #[serde(default)]
     pub git: GitSettings,
 
+    /// Configuration for opening large files
+    #[serde(default)]
+    pub large_file: LargeFileSettings,
+
+    /// Configuration for the local history of file contents
+    #[serde(default)]
+    pub file_history: FileHistorySettings,
+
     /// Completely ignore files matching globs from `file_scan_exclusions`
     ///
     /// Default: [
@@ -50,6 +61,49 @@ pub struct GitSettings {
     pub gutter_debounce: Option<u64>,
 }
 
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LargeFileSettings {
+    /// Files larger than this many bytes are opened in large-file mode.
+    /// Set to 0 to open every file normally.
+    ///
+    /// Default: 50000000
+    pub threshold: Option<u64>,
+    /// Whether files opened in large-file mode are read-only.
+    ///
+    /// Default: false
+    pub read_only: Option<bool>,
+}
+
+impl LargeFileSettings {
+    /// Whether a file of the given size should be opened in large-file mode.
+    pub fn is_large(&self, len: u64) -> bool {
+        match self.threshold {
+            Some(0) | None => false,
+            Some(threshold) => len > threshold,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct FileHistorySettings {
+    /// Whether to snapshot the contents of files when they are saved or reloaded.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// The most snapshots to keep for each file.
+    ///
+    /// Default: 50
+    pub max_snapshots_per_file: Option<usize>,
+    /// Snapshots older than this many days are deleted.
+    ///
+    /// Default: 30
+    pub max_age_days: Option<u64>,
+    /// Files larger than this many bytes aren't snapshotted.
+    ///
+    /// Default: 1000000
+    pub max_file_size: Option<u64>,
+}
+
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GitGutterSetting {
@@ -60,10 +114,46 @@ pub enum GitGutterSetting {
     Hide,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct LspSettings {
+    /// How to launch the language server, instead of the binary that Zed installs or
+    /// locates for it. Only read from the user's settings.
+    pub binary: Option<BinarySettings>,
     pub initialization_options: Option<serde_json::Value>,
+    /// The languages to start the language server for. Setting this defines a new
+    /// language server, which is launched with the configured binary. Only read from the
+    /// user's settings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub languages: Vec<Arc<str>>,
+    /// The language identifiers to send to the language server for each language,
+    /// such as `{ "C++": "cpp" }`. Languages without one use their lowercased name. Only
+    /// read from the user's settings.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub language_ids: HashMap<String, String>,
+    /// Whether to start the language server. Disabling it in a project's settings stops
+    /// it for that project.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct BinarySettings {
+    /// The path of the language server binary.
+    pub path: Option<String>,
+    /// The arguments to pass to the binary.
+    pub arguments: Option<Vec<String>>,
+    /// Environment variables to set for the language server process.
+    pub env: Option<HashMap<String, String>>,
+}
+
+impl LspSettings {
+    /// Whether the settings define a language server of their own, rather than
+    /// configuring one of a language's built-in servers.
+    pub fn is_custom_server(&self) -> bool {
+        !self.languages.is_empty()
+    }
 }
 
 impl Settings for ProjectSettings {
@@ -1,9 +1,12 @@
-use crate::{Event, *};
+use crate::{
+    large_files::LargeFileFeature, project_settings::BinarySettings,
+    review_threads::MAX_REVIEW_COMMENT_LEN, Event, *,
+};
 use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::AppContext;
 use language::{
-    language_settings::{AllLanguageSettings, LanguageSettingsContent},
+    language_settings::{AllLanguageSettings, LanguageServerFeature, LanguageSettingsContent},
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
@@ -11,7 +14,7 @@ use lsp::Url;
 use parking_lot::Mutex;
 use pretty_assertions::assert_eq;
 use serde_json::json;
-use std::{os, task::Poll};
+use std::{ffi::OsString, os, task::Poll};
 use unindent::Unindent as _;
 use util::{assert_set_eq, paths::PathMatcher, test::temp_tree};
 
@@ -142,6 +145,214 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_editorconfig_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".editorconfig": "root = true\n[the-root/a/*.md]\nmax_line_length = 40\n",
+            "the-root": {
+                ".editorconfig": "[*]\nindent_size = 3\n[*.md]\ntrim_trailing_whitespace = false\n",
+                ".zed": {
+                    "settings.json": r#"{ "tab_size": 8, "hard_tabs": true }"#
+                },
+                "a": {
+                    "a.rs": "fn a() {\n   A\n}",
+                    "README.md": "# A"
+                },
+                "b": {
+                    ".editorconfig": "[*.rs]\nindent_style = tab\ntab_width = 2\n",
+                    "b.rs": "fn b() {\n\tB\n}"
+                }
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir/the-root".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, _| project.worktrees().next().unwrap());
+    let settings_for = |path: &str, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let tree = worktree.read(cx);
+            language_settings(
+                None,
+                Some(
+                    &(File::for_entry(tree.entry_for_path(path).unwrap().clone(), worktree.clone())
+                        as _),
+                ),
+                cx,
+            )
+            .clone()
+        })
+    };
+
+    cx.executor().run_until_parked();
+    let settings_a = settings_for("a/a.rs", cx);
+    assert_eq!(settings_a.tab_size.get(), 3);
+    assert!(settings_a.hard_tabs);
+    assert!(settings_a.remove_trailing_whitespace_on_save);
+    let settings_readme = settings_for("a/README.md", cx);
+    assert!(!settings_readme.remove_trailing_whitespace_on_save);
+    assert_eq!(settings_readme.preferred_line_length, 40);
+
+    let settings_b = settings_for("b/b.rs", cx);
+    assert_eq!(settings_b.tab_size.get(), 2);
+    assert!(settings_b.hard_tabs);
+
+    // Changing an EditorConfig file updates the settings of the files it applies to.
+    fs.insert_file(
+        "/dir/the-root/b/.editorconfig",
+        "[*.rs]\nindent_style = tab\ntab_width = 6\n".into(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    assert_eq!(settings_for("b/b.rs", cx).tab_size.get(), 6);
+    assert_eq!(settings_for("a/a.rs", cx).tab_size.get(), 3);
+
+    // So does changing one above the worktree's root.
+    fs.insert_file(
+        "/dir/.editorconfig",
+        "root = true\n[the-root/a/*.md]\nmax_line_length = 60\n".into(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    assert_eq!(settings_for("a/README.md", cx).preferred_line_length, 60);
+}
+
+#[gpui::test]
+async fn test_large_file_mode(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.large_file.threshold = Some(16);
+                settings.large_file.read_only = Some(true);
+            });
+        })
+    });
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "small.rs": "fn a() {}",
+            "large.rs": "fn large() {}\nfn other() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| {
+        project.languages.add(Arc::new(language));
+    });
+
+    // Files above the threshold are opened read-only, without parsing them or starting
+    // language servers for them.
+    let large_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/large.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    project.update(cx, |project, cx| {
+        assert!(project.large_file_loads().next().is_none());
+        let large_file = project.large_file(large_buffer.read(cx)).unwrap();
+        assert_eq!(large_file.len, 28);
+        assert_eq!(
+            large_file.disabled_features().collect::<Vec<_>>(),
+            LargeFileFeature::ALL
+        );
+
+        let buffer = large_buffer.read(cx);
+        assert!(buffer.read_only());
+        assert!(!buffer.syntax_enabled());
+        assert_eq!(
+            buffer.language().map(|language| language.name()),
+            Some("Rust".into())
+        );
+    });
+    assert!(fake_servers.try_next().is_err());
+
+    let small_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/small.rs", cx)
+        })
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/small.rs"
+    );
+    project.update(cx, |project, cx| {
+        assert!(project.large_file(small_buffer.read(cx)).is_none());
+        assert!(small_buffer.read(cx).syntax_enabled());
+    });
+
+    // Features can be turned back on individually.
+    project.update(cx, |project, cx| {
+        project.set_large_file_feature_enabled(
+            &large_buffer,
+            LargeFileFeature::LanguageServers,
+            true,
+            cx,
+        );
+        project.set_large_file_feature_enabled(
+            &large_buffer,
+            LargeFileFeature::SyntaxHighlighting,
+            true,
+            cx,
+        );
+    });
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/large.rs"
+    );
+    project.update(cx, |project, cx| {
+        assert!(large_buffer.read(cx).syntax_enabled());
+        assert_eq!(
+            project
+                .large_file(large_buffer.read(cx))
+                .unwrap()
+                .disabled_features()
+                .collect::<Vec<_>>(),
+            [LargeFileFeature::GitDiff, LargeFileFeature::InlayHints]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1280,33 +1491,829 @@ async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAp
                 error_count: 0,
                 warning_count: 0,
             }
-        );
-    });
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut language = Language::new(
+        LanguageConfig {
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // Before restarting the server, report diagnostics with an unknown buffer version.
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+        version: Some(10000),
+        diagnostics: Vec::new(),
+    });
+    cx.executor().run_until_parked();
+
+    project.update(cx, |project, cx| {
+        project.restart_language_servers_for_buffers([buffer.clone()], cx);
+    });
+    let mut fake_server = fake_servers.next().await.unwrap();
+    let notification = fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await
+        .text_document;
+    assert_eq!(notification.version, 0);
+}
+
+#[gpui::test]
+async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_rust_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+    let mut js = Language::new(
+        LanguageConfig {
+            name: Arc::from("JavaScript"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["js".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_js_servers = js
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "js-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "", "b.js": "" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| {
+        project.languages.add(Arc::new(rust));
+        project.languages.add(Arc::new(js));
+    });
+
+    let _rs_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let _js_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.js", cx))
+        .await
+        .unwrap();
+
+    let mut fake_rust_server_1 = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server_1
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/a.rs"
+    );
+
+    let mut fake_js_server = fake_js_servers.next().await.unwrap();
+    assert_eq!(
+        fake_js_server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/b.js"
+    );
+
+    // Disable Rust language server, ensuring only that server gets stopped.
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    Arc::from("Rust"),
+                    LanguageSettingsContent {
+                        enable_language_server: Some(false),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+    fake_rust_server_1
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+
+    // Enable Rust and disable JavaScript language servers, ensuring that the
+    // former gets started again and that the latter stops.
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    Arc::from("Rust"),
+                    LanguageSettingsContent {
+                        enable_language_server: Some(true),
+                        ..Default::default()
+                    },
+                );
+                settings.languages.insert(
+                    Arc::from("JavaScript"),
+                    LanguageSettingsContent {
+                        enable_language_server: Some(false),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+    let mut fake_rust_server_2 = fake_rust_servers.next().await.unwrap();
+    assert_eq!(
+        fake_rust_server_2
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await
+            .text_document
+            .uri
+            .as_str(),
+        "file:///dir/a.rs"
+    );
+    fake_js_server
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+}
+
+#[gpui::test]
+async fn test_stopping_and_disabling_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "", "b.rs": "" }))
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let server_name = LanguageServerName("rust-lsp".into());
+    let lifecycles = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project
+                .language_server_details(cx)
+                .into_iter()
+                .map(|details| (details.name.0.to_string(), details.lifecycle))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let _a_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+    cx.executor().run_until_parked();
+    project.update(cx, |project, cx| {
+        let details = project.language_server_details(cx);
+        assert_eq!(details.len(), 1);
+        assert_eq!(details[0].lifecycle, LanguageServerLifecycle::Running);
+        assert_eq!(details[0].buffers.len(), 1);
+    });
+
+    // A stopped server isn't started again when opening another buffer.
+    project
+        .update(cx, |project, cx| {
+            project.stop_language_server_for_worktree(worktree_id, server_name.clone(), cx)
+        })
+        .await;
+    fake_server
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+    let _b_buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    assert_eq!(
+        lifecycles(cx),
+        [("rust-lsp".to_string(), LanguageServerLifecycle::Stopped)]
+    );
+
+    // Starting it explicitly opens the existing buffers in the new server.
+    project.update(cx, |project, cx| {
+        project.start_language_server_for_worktree(worktree_id, server_name.clone(), cx)
+    });
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await;
+    cx.executor().run_until_parked();
+    assert_eq!(
+        lifecycles(cx),
+        [("rust-lsp".to_string(), LanguageServerLifecycle::Running)]
+    );
+
+    // Disabling a server saves it in the project's settings, and it can't be started
+    // until it's enabled again.
+    project
+        .update(cx, |project, cx| {
+            project.set_language_server_disabled(server_name.clone(), true, cx)
+        })
+        .await
+        .unwrap();
+    let settings = fs.load("/dir/.zed/settings.json".as_ref()).await.unwrap();
+    assert!(settings.contains(r#""enabled": false"#), "{settings}");
+    fake_server
+        .receive_notification::<lsp::notification::Exit>()
+        .await;
+    project.update(cx, |project, cx| {
+        project.start_language_server_for_worktree(worktree_id, server_name.clone(), cx)
+    });
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    assert_eq!(
+        lifecycles(cx),
+        [("rust-lsp".to_string(), LanguageServerLifecycle::Disabled)]
+    );
+    assert!(project.update(cx, |project, cx| {
+        project.is_language_server_disabled(worktree_id, &server_name, cx)
+    }));
+
+    project
+        .update(cx, |project, cx| {
+            project.set_language_server_disabled(server_name.clone(), false, cx)
+        })
+        .await
+        .unwrap();
+    fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(
+        lifecycles(cx),
+        [("rust-lsp".to_string(), LanguageServerLifecycle::Running)]
+    );
+}
+
+#[gpui::test]
+async fn test_language_servers_defined_in_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.insert(
+                    Arc::from("custom-rust-lsp"),
+                    LspSettings {
+                        languages: vec![Arc::from("Rust")],
+                        language_ids: [("Rust".to_string(), "rust-custom".to_string())]
+                            .into_iter()
+                            .collect(),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    // Both the language's own server and the one defined in settings are started,
+    // and the latter receives the configured language id.
+    let mut language_ids = Vec::new();
+    let mut servers = Vec::new();
+    for _ in 0..2 {
+        let mut server = fake_servers.next().await.unwrap();
+        let params = server
+            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+            .await;
+        language_ids.push(params.text_document.language_id);
+        servers.push(server);
+    }
+    language_ids.sort();
+    assert_eq!(language_ids, ["", "rust-custom"]);
+
+    let server_names = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project
+                .language_server_details(cx)
+                .into_iter()
+                .map(|details| details.name.0.to_string())
+                .collect::<Vec<_>>()
+        })
+    };
+    cx.executor().run_until_parked();
+    assert_eq!(server_names(cx), ["custom-rust-lsp", "rust-lsp"]);
+
+    // Removing the server from the settings stops it.
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.clear();
+            });
+        })
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(server_names(cx), ["rust-lsp"]);
+}
+
+#[gpui::test]
+async fn test_routing_requests_to_preferred_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.insert(
+                    Arc::from("rust-linter"),
+                    LspSettings {
+                        languages: vec![Arc::from("Rust")],
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn main() {}" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let mut servers = Vec::new();
+    for _ in 0..2 {
+        let server = fake_servers.next().await.unwrap();
+        cx.executor().run_until_parked();
+        let name = project.update(cx, |project, cx| {
+            project
+                .language_server_details(cx)
+                .into_iter()
+                .find(|details| details.id == Some(server.server.server_id()))
+                .unwrap()
+                .name
+                .0
+                .to_string()
+        });
+        server.handle_request::<lsp::request::CodeActionRequest, _, _>({
+            let name = name.clone();
+            move |_, _| {
+                let name = name.clone();
+                async move {
+                    Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+                        lsp::CodeAction {
+                            title: format!("{name} action"),
+                            ..Default::default()
+                        },
+                    )]))
+                }
+            }
+        });
+        server.handle_request::<lsp::request::HoverRequest, _, _>({
+            let name = name.clone();
+            move |_, _| {
+                let name = name.clone();
+                async move {
+                    Ok(Some(lsp::Hover {
+                        contents: lsp::HoverContents::Scalar(lsp::MarkedString::String(name)),
+                        range: None,
+                    }))
+                }
+            }
+        });
+        servers.push(server);
+    }
+
+    let code_action_titles = |cx: &mut gpui::TestAppContext| {
+        let actions = project.update(cx, |project, cx| project.code_actions(&buffer, 0..0, cx));
+        async move {
+            actions
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|action| action.lsp_action.title)
+                .collect::<Vec<_>>()
+        }
+    };
+    let hover_text = |cx: &mut gpui::TestAppContext| {
+        let hover = project.update(cx, |project, cx| project.hover(&buffer, 0, cx));
+        async move { hover.await.unwrap().unwrap().contents[0].text.clone() }
+    };
+
+    // Code actions are merged from both servers, and hovers come from the language's
+    // own server by default.
+    assert_eq!(
+        code_action_titles(cx).await,
+        ["rust-lsp action", "rust-linter action"]
+    );
+    assert_eq!(hover_text(cx).await, "rust-lsp");
+
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    Arc::from("Rust"),
+                    LanguageSettingsContent {
+                        language_server_preferences: Some(
+                            [
+                                (
+                                    LanguageServerFeature::CodeActions,
+                                    vec![Arc::from("rust-linter")],
+                                ),
+                                (LanguageServerFeature::Hover, vec![Arc::from("rust-linter")]),
+                            ]
+                            .into_iter()
+                            .collect(),
+                        ),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    assert_eq!(
+        code_action_titles(cx).await,
+        ["rust-linter action", "rust-lsp action"]
+    );
+    assert_eq!(hover_text(cx).await, "rust-linter");
+}
+
+#[gpui::test]
+async fn test_language_server_binary_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let mut rust = Language::new(
+        LanguageConfig {
+            name: Arc::from("Rust"),
+            matcher: LanguageMatcher {
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = rust
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "rust-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let set_user_binary = |binary: BinarySettings, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            cx.update_global(|settings: &mut SettingsStore, cx| {
+                settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                    settings.lsp.insert(
+                        Arc::from("rust-lsp"),
+                        LspSettings {
+                            binary: Some(binary),
+                            ..Default::default()
+                        },
+                    );
+                });
+            })
+        })
+    };
+    set_user_binary(
+        BinarySettings {
+            path: Some("/usr/bin/rust-lsp".into()),
+            arguments: None,
+            env: Some([("RUST_LOG".into(), "info".into())].into_iter().collect()),
+        },
+        cx,
+    );
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".zed": {
+                "settings.json": r#"{
+                    "lsp": {
+                        "rust-lsp": {
+                            "binary": { "path": "/dir/rust-lsp" }
+                        }
+                    }
+                }"#
+            },
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(rust)));
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let _server = fake_servers.next().await.unwrap();
+
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+    let binary_override = |cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| {
+            project.language_server_binary_override(
+                worktree_id,
+                &LanguageServerName("rust-lsp".into()),
+                cx,
+            )
+        })
+    };
+
+    // The user's settings configure the server's binary, and the project's can't.
+    let binary = binary_override(cx);
+    assert_eq!(binary.path, Some(PathBuf::from("/usr/bin/rust-lsp")));
+    assert_eq!(binary.arguments, None);
+    assert_eq!(
+        binary.env,
+        Some(
+            [("RUST_LOG".to_string(), "info".to_string())]
+                .into_iter()
+                .collect()
+        )
+    );
+
+    // Changing the binary in the user's settings restarts the server.
+    set_user_binary(
+        BinarySettings {
+            path: Some("/usr/bin/rust-lsp".into()),
+            arguments: Some(vec!["--log".into(), "verbose".into()]),
+            env: Some([("RUST_LOG".into(), "debug".into())].into_iter().collect()),
+        },
+        cx,
+    );
+    cx.executor().run_until_parked();
+    let _server = fake_servers.next().await.unwrap();
+    let binary = binary_override(cx);
+    assert_eq!(
+        binary.arguments,
+        Some(vec![OsString::from("--log"), OsString::from("verbose")])
+    );
+    assert_eq!(
+        binary.env,
+        Some(
+            [("RUST_LOG".to_string(), "debug".to_string())]
+                .into_iter()
+                .collect()
+        )
+    );
+
+    // Settings that don't affect the server don't restart it, including a binary in the
+    // project's settings.
+    fs.insert_file(
+        "/dir/.zed/settings.json",
+        r#"{
+            "tab_size": 8,
+            "lsp": {
+                "rust-lsp": {
+                    "binary": { "path": "/dir/other-rust-lsp" }
+                }
+            }
+        }"#
+        .into(),
+    )
+    .await;
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+    assert_eq!(
+        binary_override(cx).path,
+        Some(PathBuf::from("/usr/bin/rust-lsp"))
+    );
 }
 
 #[gpui::test]
-async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::TestAppContext) {
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
     let mut language = Language::new(
         LanguageConfig {
+            name: "Rust".into(),
             matcher: LanguageMatcher {
                 path_suffixes: vec!["rs".to_string()],
                 ..Default::default()
             },
             ..Default::default()
         },
-        None,
+        Some(tree_sitter_rust::language()),
     );
+    let document_requests = Arc::new(Mutex::new(Vec::new()));
+    let workspace_requests = Arc::new(Mutex::new(Vec::new()));
     let mut fake_servers = language
         .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
-            name: "the-lsp",
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
+                ..Default::default()
+            },
+            initializer: Some(Box::new({
+                let document_requests = document_requests.clone();
+                let workspace_requests = workspace_requests.clone();
+                move |fake_server| {
+                    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+                        let document_requests = document_requests.clone();
+                        move |params, _| {
+                            let mut document_requests = document_requests.lock();
+                            document_requests.push(params.previous_result_id.clone());
+                            let result_id = document_requests.len().to_string();
+                            let report = if params.previous_result_id.is_some() {
+                                lsp::DocumentDiagnosticReport::Unchanged(
+                                    lsp::RelatedUnchangedDocumentDiagnosticReport {
+                                        related_documents: None,
+                                        unchanged_document_diagnostic_report:
+                                            lsp::UnchangedDocumentDiagnosticReport { result_id },
+                                    },
+                                )
+                            } else {
+                                lsp::DocumentDiagnosticReport::Full(
+                                    lsp::RelatedFullDocumentDiagnosticReport {
+                                        related_documents: None,
+                                        full_document_diagnostic_report:
+                                            lsp::FullDocumentDiagnosticReport {
+                                                result_id: Some(result_id),
+                                                items: vec![lsp::Diagnostic {
+                                                    range: lsp::Range::new(
+                                                        lsp::Position::new(0, 9),
+                                                        lsp::Position::new(0, 10),
+                                                    ),
+                                                    severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                                    message: "undefined variable 'A'".to_string(),
+                                                    ..Default::default()
+                                                }],
+                                            },
+                                    },
+                                )
+                            };
+                            future::ready(Ok(lsp::DocumentDiagnosticReportResult::Report(report)))
+                        }
+                    });
+                    fake_server.handle_request::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+                        let workspace_requests = workspace_requests.clone();
+                        move |params, _| {
+                            workspace_requests.lock().push(params.previous_result_ids);
+                            future::ready(Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                lsp::WorkspaceDiagnosticReport {
+                                    items: vec![lsp::WorkspaceDocumentDiagnosticReport::Full(
+                                        lsp::WorkspaceFullDocumentDiagnosticReport {
+                                            uri: Url::from_file_path("/dir/b.rs").unwrap(),
+                                            version: None,
+                                            full_document_diagnostic_report:
+                                                lsp::FullDocumentDiagnosticReport {
+                                                    result_id: Some("b".to_string()),
+                                                    items: vec![lsp::Diagnostic {
+                                                        range: lsp::Range::new(
+                                                            lsp::Position::new(0, 9),
+                                                            lsp::Position::new(0, 10),
+                                                        ),
+                                                        severity: Some(
+                                                            lsp::DiagnosticSeverity::WARNING,
+                                                        ),
+                                                        message: "undefined variable 'B'"
+                                                            .to_string(),
+                                                        ..Default::default()
+                                                    }],
+                                                },
+                                        },
+                                    )],
+                                },
+                            )))
+                        }
+                    });
+                }
+            })),
             ..Default::default()
         }))
         .await;
 
     let fs = FakeFs::new(cx.executor());
-    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { A }",
+            "b.rs": "fn b() { B }",
+        }),
+    )
+    .await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
     project.update(cx, |project, _| project.languages.add(Arc::new(language)));
@@ -1315,160 +2322,176 @@ async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::T
         .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
-
-    // Before restarting the server, report diagnostics with an unknown buffer version.
     let fake_server = fake_servers.next().await.unwrap();
-    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
-        uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
-        version: Some(10000),
-        diagnostics: Vec::new(),
-    });
+    cx.executor().advance_clock(Duration::from_secs(1));
     cx.executor().run_until_parked();
-
+    assert_eq!(*document_requests.lock(), [None]);
+    assert_eq!(workspace_requests.lock().len(), 1);
     project.update(cx, |project, cx| {
-        project.restart_language_servers_for_buffers([buffer.clone()], cx);
+        assert_eq!(
+            project.diagnostic_summary(false, cx),
+            DiagnosticSummary {
+                error_count: 1,
+                warning_count: 1,
+            }
+        );
     });
-    let mut fake_server = fake_servers.next().await.unwrap();
-    let notification = fake_server
-        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+
+    // Edits cause the document's diagnostics to be pulled again, sending the previous
+    // result id so that the server can reply that they are unchanged.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(*document_requests.lock(), [None, Some("1".to_string())]);
+    buffer.update(cx, |buffer, _| {
+        let diagnostics = buffer
+            .snapshot()
+            .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+            .map(|entry| (entry.range, entry.diagnostic.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics,
+            [(
+                Point::new(1, 9)..Point::new(1, 10),
+                "undefined variable 'A'".to_string()
+            )]
+        );
+    });
+
+    // Refreshing pulls the workspace diagnostics with the result ids of every document.
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
         .await
-        .text_document;
-    assert_eq!(notification.version, 0);
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(document_requests.lock().len(), 3);
+    let mut previous_result_ids = workspace_requests.lock()[1]
+        .iter()
+        .map(|id| (id.uri.path().to_string(), id.value.clone()))
+        .collect::<Vec<_>>();
+    previous_result_ids.sort();
+    assert_eq!(
+        previous_result_ids,
+        [
+            ("/dir/a.rs".to_string(), "2".to_string()),
+            ("/dir/b.rs".to_string(), "b".to_string()),
+        ]
+    );
 }
 
 #[gpui::test]
-async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
+async fn test_pulling_workspace_diagnostics_while_in_flight(cx: &mut gpui::TestAppContext) {
     init_test(cx);
 
-    let mut rust = Language::new(
+    let mut language = Language::new(
         LanguageConfig {
-            name: Arc::from("Rust"),
+            name: "Rust".into(),
             matcher: LanguageMatcher {
                 path_suffixes: vec!["rs".to_string()],
                 ..Default::default()
             },
             ..Default::default()
         },
-        None,
+        Some(tree_sitter_rust::language()),
     );
-    let mut fake_rust_servers = rust
+    // The server only replies to workspace requests once they are released.
+    let workspace_requests = Arc::new(Mutex::new(
+        Vec::<futures::channel::oneshot::Sender<()>>::new(),
+    ));
+    let mut fake_servers = language
         .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
-            name: "rust-lsp",
-            ..Default::default()
-        }))
-        .await;
-    let mut js = Language::new(
-        LanguageConfig {
-            name: Arc::from("JavaScript"),
-            matcher: LanguageMatcher {
-                path_suffixes: vec!["js".to_string()],
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions {
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
                 ..Default::default()
             },
-            ..Default::default()
-        },
-        None,
-    );
-    let mut fake_js_servers = js
-        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
-            name: "js-lsp",
+            initializer: Some(Box::new({
+                let workspace_requests = workspace_requests.clone();
+                move |fake_server| {
+                    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>(
+                        |_, _| async move {
+                            Ok(lsp::DocumentDiagnosticReportResult::Report(
+                                lsp::DocumentDiagnosticReport::Full(Default::default()),
+                            ))
+                        },
+                    );
+                    fake_server.handle_request::<lsp::request::WorkspaceDiagnosticRequest, _, _>({
+                        let workspace_requests = workspace_requests.clone();
+                        move |_, _| {
+                            let (tx, rx) = futures::channel::oneshot::channel();
+                            workspace_requests.lock().push(tx);
+                            async move {
+                                rx.await.ok();
+                                Ok(lsp::WorkspaceDiagnosticReportResult::Report(
+                                    lsp::WorkspaceDiagnosticReport { items: Vec::new() },
+                                ))
+                            }
+                        }
+                    });
+                }
+            })),
             ..Default::default()
         }))
         .await;
 
     let fs = FakeFs::new(cx.executor());
-    fs.insert_tree("/dir", json!({ "a.rs": "", "b.js": "" }))
-        .await;
+    fs.insert_tree("/dir", json!({ "a.rs": "fn a() {}" })).await;
 
     let project = Project::test(fs, ["/dir".as_ref()], cx).await;
-    project.update(cx, |project, _| {
-        project.languages.add(Arc::new(rust));
-        project.languages.add(Arc::new(js));
-    });
-
-    let _rs_buffer = project
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
         .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
         .await
         .unwrap();
-    let _js_buffer = project
-        .update(cx, |project, cx| project.open_local_buffer("/dir/b.js", cx))
-        .await
-        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(workspace_requests.lock().len(), 1);
 
-    let mut fake_rust_server_1 = fake_rust_servers.next().await.unwrap();
-    assert_eq!(
-        fake_rust_server_1
-            .receive_notification::<lsp::notification::DidOpenTextDocument>()
-            .await
-            .text_document
-            .uri
-            .as_str(),
-        "file:///dir/a.rs"
-    );
+    let release_workspace_request = |index: usize, cx: &mut gpui::TestAppContext| {
+        let tx = mem::replace(
+            &mut workspace_requests.lock()[index],
+            futures::channel::oneshot::channel().0,
+        );
+        tx.send(()).ok();
+        cx.executor().run_until_parked();
+    };
 
-    let mut fake_js_server = fake_js_servers.next().await.unwrap();
-    assert_eq!(
-        fake_js_server
-            .receive_notification::<lsp::notification::DidOpenTextDocument>()
+    // Saving while the workspace diagnostics are being pulled doesn't send another request
+    // until the one in flight completes, and then only sends one.
+    for _ in 0..2 {
+        project
+            .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
             .await
-            .text_document
-            .uri
-            .as_str(),
-        "file:///dir/b.js"
-    );
-
-    // Disable Rust language server, ensuring only that server gets stopped.
-    cx.update(|cx| {
-        cx.update_global(|settings: &mut SettingsStore, cx| {
-            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
-                settings.languages.insert(
-                    Arc::from("Rust"),
-                    LanguageSettingsContent {
-                        enable_language_server: Some(false),
-                        ..Default::default()
-                    },
-                );
-            });
-        })
-    });
-    fake_rust_server_1
-        .receive_notification::<lsp::notification::Exit>()
-        .await;
+            .unwrap();
+        cx.executor().run_until_parked();
+    }
+    assert_eq!(workspace_requests.lock().len(), 1);
+    release_workspace_request(0, cx);
+    assert_eq!(workspace_requests.lock().len(), 2);
+    release_workspace_request(1, cx);
+    assert_eq!(workspace_requests.lock().len(), 2);
 
-    // Enable Rust and disable JavaScript language servers, ensuring that the
-    // former gets started again and that the latter stops.
-    cx.update(|cx| {
-        cx.update_global(|settings: &mut SettingsStore, cx| {
-            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
-                settings.languages.insert(
-                    Arc::from("Rust"),
-                    LanguageSettingsContent {
-                        enable_language_server: Some(true),
-                        ..Default::default()
-                    },
-                );
-                settings.languages.insert(
-                    Arc::from("JavaScript"),
-                    LanguageSettingsContent {
-                        enable_language_server: Some(false),
-                        ..Default::default()
-                    },
-                );
-            });
-        })
-    });
-    let mut fake_rust_server_2 = fake_rust_servers.next().await.unwrap();
-    assert_eq!(
-        fake_rust_server_2
-            .receive_notification::<lsp::notification::DidOpenTextDocument>()
-            .await
-            .text_document
-            .uri
-            .as_str(),
-        "file:///dir/a.rs"
-    );
-    fake_js_server
-        .receive_notification::<lsp::notification::Exit>()
-        .await;
+    // Refreshing replaces the request in flight with a new one.
+    project
+        .update(cx, |project, cx| project.save_buffer(buffer.clone(), cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(workspace_requests.lock().len(), 3);
+    fake_server
+        .request::<lsp::request::WorkspaceDiagnosticRefresh>(())
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    assert_eq!(workspace_requests.lock().len(), 4);
+    release_workspace_request(2, cx);
+    release_workspace_request(3, cx);
+    assert_eq!(workspace_requests.lock().len(), 4);
 }
 
 #[gpui::test(iterations = 3)]
@@ -4394,6 +5417,82 @@ async fn test_create_entry(cx: &mut gpui::TestAppContext) {
     assert!(result.is_err())
 }
 
+#[gpui::test]
+async fn test_review_threads(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "fn one() {}\nfn two() {}\n" }))
+        .await;
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let thread_id = project
+        .update(cx, |project, cx| {
+            let range = buffer.read(cx).anchor_before(12)..buffer.read(cx).anchor_after(23);
+            project.create_review_thread(&buffer, range, "Rename this".into(), cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.reply_to_review_thread(thread_id, "Done".into(), cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.set_review_thread_resolved(thread_id, true, cx)
+        })
+        .await
+        .unwrap();
+
+    // Threads stay attached to their code as the buffer is edited.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// a\n")], None, cx));
+    project.update(cx, |project, cx| {
+        let thread = project.review_thread(thread_id).unwrap();
+        let buffer = buffer.read(cx);
+        assert_eq!(thread.range.to_offset(buffer), 17..28);
+        assert!(thread.resolved);
+        assert_eq!(
+            thread
+                .comments
+                .iter()
+                .map(|comment| comment.body.as_str())
+                .collect::<Vec<_>>(),
+            ["Rename this", "Done"]
+        );
+        assert_eq!(
+            thread.project_path.as_ref().unwrap().path.as_ref(),
+            Path::new("a.rs")
+        );
+    });
+
+    // Unknown threads can't be replied to.
+    let result = project
+        .update(cx, |project, cx| {
+            project.reply_to_review_thread(thread_id + 1, "Hello?".into(), cx)
+        })
+        .await;
+    assert!(result.is_err());
+
+    // Comments can't be blank or too long.
+    for body in [" ".to_string(), "a".repeat(MAX_REVIEW_COMMENT_LEN + 1)] {
+        let result = project
+            .update(cx, |project, cx| {
+                project.reply_to_review_thread(thread_id, body, cx)
+            })
+            .await;
+        assert!(result.is_err());
+    }
+    project.update(cx, |project, _| {
+        assert_eq!(project.review_thread(thread_id).unwrap().comments.len(), 2);
+    });
+}
+
 async fn search(
     project: &Model<Project>,
     query: SearchQuery,
@@ -0,0 +1,365 @@
+//! Diagnostics for language servers that report them when they are requested
+//! (`textDocument/diagnostic` and `workspace/diagnostic`), instead of publishing them.
+
+use std::{mem, time::Duration};
+
+use collections::HashMap;
+use gpui::{AppContext, Model, ModelContext, Task};
+use language::Buffer;
+use lsp::{LanguageServer, LanguageServerId};
+use util::ResultExt;
+
+use crate::{File, LanguageServerState, Project};
+
+const DOCUMENT_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// The pull diagnostics state of a running language server.
+#[derive(Default)]
+pub struct PulledDiagnostics {
+    /// The result id of the latest report for each document, which lets the server
+    /// reply that the document's diagnostics haven't changed since.
+    result_ids: HashMap<lsp::Url, String>,
+    document_requests: HashMap<lsp::Url, Task<()>>,
+    /// The `workspace/diagnostic` request in flight, which the server may keep open until
+    /// any diagnostics change.
+    workspace_request: Option<Task<()>>,
+    /// Whether the workspace diagnostics need to be requested again once the request in
+    /// flight completes.
+    workspace_request_outdated: bool,
+}
+
+fn diagnostic_options(server: &LanguageServer) -> Option<&lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(&options.diagnostic_options)
+        }
+    }
+}
+
+fn buffer_uri(buffer: &Buffer, cx: &AppContext) -> Option<lsp::Url> {
+    let file = File::from_dyn(buffer.file())?.as_local()?;
+    lsp::Url::from_file_path(file.abs_path(cx)).ok()
+}
+
+impl Project {
+    /// Requests the diagnostics of the documents open in a language server, as well as the
+    /// diagnostics of the whole workspace if the server supports it.
+    pub(crate) fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let documents = self
+            .opened_buffers
+            .values()
+            .filter_map(|buffer| {
+                let buffer = buffer.upgrade()?;
+                let buffer = buffer.read(cx);
+                let version = self
+                    .buffer_snapshots
+                    .get(&buffer.remote_id())?
+                    .get(&server_id)?
+                    .last()?
+                    .version;
+                Some((buffer_uri(buffer, cx)?, version))
+            })
+            .collect::<Vec<_>>();
+        for (uri, version) in documents {
+            self.pull_document_diagnostics(server_id, uri, Some(version), cx);
+        }
+        // The server asked for its diagnostics to be pulled again, so the workspace request
+        // in flight is cancelled rather than waited on.
+        if let Some(pulled_diagnostics) = self.pulled_diagnostics_mut(server_id) {
+            pulled_diagnostics.workspace_request.take();
+            pulled_diagnostics.workspace_request_outdated = false;
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    /// Requests the diagnostics of a buffer from the given language server, after a short
+    /// delay so that consecutive edits only cause a single request.
+    pub(crate) fn pull_document_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            server,
+            pulled_diagnostics,
+            ..
+        }) = self.language_servers.get_mut(&server_id)
+        else {
+            return;
+        };
+        let Some(identifier) = diagnostic_options(server).map(|options| options.identifier.clone())
+        else {
+            return;
+        };
+
+        let server = server.clone();
+        let task = cx.spawn({
+            let uri = uri.clone();
+            |this, mut cx| async move {
+                cx.background_executor()
+                    .timer(DOCUMENT_DIAGNOSTICS_DEBOUNCE)
+                    .await;
+                let Ok(Some(previous_result_id)) = this.update(&mut cx, |this, _| {
+                    this.pulled_diagnostics_mut(server_id)
+                        .map(|pulled| pulled.result_ids.get(&uri).cloned())
+                }) else {
+                    return;
+                };
+                let report = server
+                    .request::<lsp::request::DocumentDiagnosticRequest>(
+                        lsp::DocumentDiagnosticParams {
+                            text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                            identifier,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await;
+                let Some(report) = report.log_err() else {
+                    return;
+                };
+                this.update(&mut cx, |this, cx| {
+                    this.apply_document_diagnostic_report(server_id, uri, version, report, cx)
+                })
+                .ok();
+            }
+        });
+        pulled_diagnostics.document_requests.insert(uri, task);
+    }
+
+    /// Requests the diagnostics of every document in the workspace from the given language
+    /// server, if it supports workspace diagnostics. While a request is in flight, another
+    /// one is only sent once it completes.
+    pub(crate) fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            server,
+            pulled_diagnostics,
+            ..
+        }) = self.language_servers.get_mut(&server_id)
+        else {
+            return;
+        };
+        let Some(options) =
+            diagnostic_options(server).filter(|options| options.workspace_diagnostics)
+        else {
+            return;
+        };
+        if pulled_diagnostics.workspace_request.is_some() {
+            pulled_diagnostics.workspace_request_outdated = true;
+            return;
+        }
+
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier.clone(),
+            previous_result_ids: pulled_diagnostics
+                .result_ids
+                .iter()
+                .map(|(uri, result_id)| lsp::PreviousResultId {
+                    uri: uri.clone(),
+                    value: result_id.clone(),
+                })
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let server = server.clone();
+        pulled_diagnostics.workspace_request = Some(cx.spawn(|this, mut cx| async move {
+            let report = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                .await
+                .log_err();
+            this.update(&mut cx, |this, cx| {
+                let Some(pulled_diagnostics) = this.pulled_diagnostics_mut(server_id) else {
+                    return;
+                };
+                pulled_diagnostics.workspace_request.take();
+                let outdated = mem::take(&mut pulled_diagnostics.workspace_request_outdated);
+                if let Some(report) = report {
+                    this.apply_workspace_diagnostic_report(server_id, report, cx);
+                }
+                if outdated {
+                    this.pull_workspace_diagnostics(server_id, cx);
+                }
+            })
+            .ok();
+        }));
+    }
+
+    fn apply_workspace_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        report: lsp::WorkspaceDiagnosticReportResult,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let items = match report {
+            lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+            lsp::WorkspaceDiagnosticReportResult::Partial(report) => report.items,
+        };
+        for item in items {
+            match item {
+                lsp::WorkspaceDocumentDiagnosticReport::Full(report) => {
+                    let version = report
+                        .version
+                        .and_then(|version| i32::try_from(version).ok());
+                    self.apply_pulled_diagnostics(
+                        server_id,
+                        report.uri,
+                        version,
+                        report.full_document_diagnostic_report,
+                        cx,
+                    );
+                }
+                lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => {
+                    self.set_diagnostic_result_id(
+                        server_id,
+                        report.uri,
+                        Some(report.unchanged_document_diagnostic_report.result_id),
+                    );
+                }
+            }
+        }
+    }
+
+    fn apply_document_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportResult,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let related_documents = match report {
+            lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+                report,
+            )) => {
+                self.apply_pulled_diagnostics(
+                    server_id,
+                    uri,
+                    version,
+                    report.full_document_diagnostic_report,
+                    cx,
+                );
+                report.related_documents
+            }
+            lsp::DocumentDiagnosticReportResult::Report(
+                lsp::DocumentDiagnosticReport::Unchanged(report),
+            ) => {
+                self.set_diagnostic_result_id(
+                    server_id,
+                    uri,
+                    Some(report.unchanged_document_diagnostic_report.result_id),
+                );
+                report.related_documents
+            }
+            lsp::DocumentDiagnosticReportResult::Partial(report) => report.related_documents,
+        };
+
+        for (uri, report) in related_documents.into_iter().flatten() {
+            match report {
+                lsp::DocumentDiagnosticReportKind::Full(report) => {
+                    self.apply_pulled_diagnostics(server_id, uri, None, report, cx);
+                }
+                lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                    self.set_diagnostic_result_id(server_id, uri, Some(report.result_id));
+                }
+            }
+        }
+    }
+
+    /// Replaces a document's diagnostics from the given language server, in the same way
+    /// as published diagnostics.
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::FullDocumentDiagnosticReport,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running { adapter, .. }) =
+            self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let adapter = adapter.clone();
+        self.set_diagnostic_result_id(server_id, uri.clone(), report.result_id);
+
+        let mut params = lsp::PublishDiagnosticsParams {
+            uri,
+            diagnostics: report.items,
+            version,
+        };
+        adapter.process_diagnostics(&mut params);
+        self.update_diagnostics(
+            server_id,
+            params,
+            &adapter.disk_based_diagnostic_sources,
+            cx,
+        )
+        .log_err();
+    }
+
+    fn set_diagnostic_result_id(
+        &mut self,
+        server_id: LanguageServerId,
+        uri: lsp::Url,
+        result_id: Option<String>,
+    ) {
+        if let Some(pulled_diagnostics) = self.pulled_diagnostics_mut(server_id) {
+            match result_id {
+                Some(result_id) => {
+                    pulled_diagnostics.result_ids.insert(uri, result_id);
+                }
+                None => {
+                    pulled_diagnostics.result_ids.remove(&uri);
+                }
+            }
+        }
+    }
+
+    fn pulled_diagnostics_mut(
+        &mut self,
+        server_id: LanguageServerId,
+    ) -> Option<&mut PulledDiagnostics> {
+        match self.language_servers.get_mut(&server_id)? {
+            LanguageServerState::Running {
+                pulled_diagnostics, ..
+            } => Some(pulled_diagnostics),
+            LanguageServerState::Starting(_) => None,
+        }
+    }
+
+    /// Requests the diagnostics of a buffer from every language server it's open in.
+    pub(crate) fn pull_buffer_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let buffer = buffer.read(cx);
+        let Some(uri) = buffer_uri(buffer, cx) else {
+            return;
+        };
+        let documents = self
+            .buffer_snapshots
+            .get(&buffer.remote_id())
+            .into_iter()
+            .flatten()
+            .filter_map(|(server_id, snapshots)| Some((*server_id, snapshots.last()?.version)))
+            .collect::<Vec<_>>();
+        for (server_id, version) in documents {
+            self.pull_document_diagnostics(server_id, uri.clone(), Some(version), cx);
+        }
+    }
+}
@@ -0,0 +1,440 @@
+//! Threads of review comments that collaborators attach to ranges of a project's
+//! buffers. The host stores the threads and assigns their ids: guests send their
+//! comments to the host, which broadcasts every thread that changes to all guests.
+//!
+//! Threads are only kept in the host's memory. They survive the project being unshared
+//! and shared again, but are lost once the host closes the project or restarts Zed.
+
+use std::{
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, Context as _, Result};
+use client::{proto, Client, TypedEnvelope};
+use collections::BTreeMap;
+use gpui::{AsyncAppContext, Model, ModelContext, Task};
+use language::{
+    proto::{deserialize_anchor, serialize_anchor},
+    Buffer,
+};
+use text::{Anchor, BufferId};
+use util::{post_inc, ResultExt};
+
+use crate::{Event, File, Project, ProjectClientState, ProjectPath, WorktreeId};
+
+pub type ReviewThreadId = u64;
+
+/// The maximum length of a comment's body, in bytes.
+pub const MAX_REVIEW_COMMENT_LEN: usize = 8 * 1024;
+
+#[derive(Clone, Debug)]
+pub struct ReviewThread {
+    pub id: ReviewThreadId,
+    pub buffer_id: BufferId,
+    /// The path of the buffer when the thread was started.
+    pub project_path: Option<ProjectPath>,
+    pub range: Range<Anchor>,
+    pub resolved: bool,
+    pub comments: Vec<ReviewComment>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ReviewComment {
+    /// The user who wrote the comment, which is unknown for comments written in a
+    /// project that wasn't shared while signed out.
+    pub author_id: Option<u64>,
+    pub body: String,
+    pub timestamp: SystemTime,
+}
+
+#[derive(Default)]
+pub(crate) struct ReviewThreads {
+    threads: BTreeMap<ReviewThreadId, ReviewThread>,
+    next_id: ReviewThreadId,
+}
+
+impl Project {
+    pub fn review_threads(&self) -> impl Iterator<Item = &ReviewThread> {
+        self.review_threads.threads.values()
+    }
+
+    pub fn review_thread(&self, id: ReviewThreadId) -> Option<&ReviewThread> {
+        self.review_threads.threads.get(&id)
+    }
+
+    /// Starts a thread on a range of a buffer, with the given comment.
+    pub fn create_review_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<Anchor>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ReviewThreadId>> {
+        let body = match validate_comment_body(body) {
+            Ok(body) => body,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        if let ProjectClientState::Remote { remote_id, .. } = self.client_state {
+            let request = self.client.request(proto::CreateReviewThread {
+                project_id: remote_id,
+                buffer_id: buffer.read(cx).remote_id().into(),
+                start: Some(serialize_anchor(&range.start)),
+                end: Some(serialize_anchor(&range.end)),
+                body,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_review_thread_response(response, cx)
+                })?
+            })
+        } else {
+            let author_id = self.current_user_id(cx);
+            let thread = self.start_review_thread(buffer, range, author_id, body, cx);
+            Task::ready(Ok(thread.id))
+        }
+    }
+
+    pub fn reply_to_review_thread(
+        &mut self,
+        id: ReviewThreadId,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let body = match validate_comment_body(body) {
+            Ok(body) => body,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        if let ProjectClientState::Remote { remote_id, .. } = self.client_state {
+            let request = self.client.request(proto::ReplyToReviewThread {
+                project_id: remote_id,
+                thread_id: id,
+                body,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_review_thread_response(response, cx)
+                })??;
+                Ok(())
+            })
+        } else {
+            let author_id = self.current_user_id(cx);
+            Task::ready(
+                self.update_review_thread(id, cx, |thread| {
+                    thread.comments.push(ReviewComment {
+                        author_id,
+                        body,
+                        timestamp: SystemTime::now(),
+                    })
+                })
+                .map(drop),
+            )
+        }
+    }
+
+    pub fn set_review_thread_resolved(
+        &mut self,
+        id: ReviewThreadId,
+        resolved: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if let ProjectClientState::Remote { remote_id, .. } = self.client_state {
+            let request = self.client.request(proto::SetReviewThreadResolved {
+                project_id: remote_id,
+                thread_id: id,
+                resolved,
+            });
+            cx.spawn(|this, mut cx| async move {
+                let response = request.await?;
+                this.update(&mut cx, |this, cx| {
+                    this.apply_review_thread_response(response, cx)
+                })??;
+                Ok(())
+            })
+        } else {
+            Task::ready(
+                self.update_review_thread(id, cx, |thread| thread.resolved = resolved)
+                    .map(drop),
+            )
+        }
+    }
+
+    /// Fetches the threads of a project that was just joined.
+    pub(crate) fn fetch_review_threads(&mut self, cx: &mut ModelContext<Self>) {
+        let Some(project_id) = self.remote_id() else {
+            return;
+        };
+        let request = self.client.request(proto::GetReviewThreads { project_id });
+        cx.spawn(|this, mut cx| async move {
+            let response = request.await?;
+            this.update(&mut cx, |this, cx| {
+                for thread in response.threads {
+                    if let Some(thread) = deserialize_review_thread(thread).log_err() {
+                        this.insert_review_thread(thread, cx);
+                    }
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn current_user_id(&self, cx: &ModelContext<Self>) -> Option<u64> {
+        self.user_store.read(cx).current_user().map(|user| user.id)
+    }
+
+    fn start_review_thread(
+        &mut self,
+        buffer: &Model<Buffer>,
+        range: Range<Anchor>,
+        author_id: Option<u64>,
+        body: String,
+        cx: &mut ModelContext<Self>,
+    ) -> ReviewThread {
+        let buffer = buffer.read(cx);
+        let project_path = File::from_dyn(buffer.file()).map(|file| ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        });
+        let thread = ReviewThread {
+            id: post_inc(&mut self.review_threads.next_id),
+            buffer_id: buffer.remote_id(),
+            project_path,
+            range,
+            resolved: false,
+            comments: vec![ReviewComment {
+                author_id,
+                body,
+                timestamp: SystemTime::now(),
+            }],
+        };
+        self.insert_review_thread(thread.clone(), cx);
+        thread
+    }
+
+    fn update_review_thread(
+        &mut self,
+        id: ReviewThreadId,
+        cx: &mut ModelContext<Self>,
+        update: impl FnOnce(&mut ReviewThread),
+    ) -> Result<ReviewThread> {
+        let mut thread = self
+            .review_threads
+            .threads
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| anyhow!("no such review thread {id}"))?;
+        update(&mut thread);
+        self.insert_review_thread(thread.clone(), cx);
+        Ok(thread)
+    }
+
+    /// Stores a thread that was created or changed, and sends it to the guests if
+    /// this project is shared.
+    fn insert_review_thread(&mut self, thread: ReviewThread, cx: &mut ModelContext<Self>) {
+        if let ProjectClientState::Shared { remote_id, .. } = self.client_state {
+            self.client
+                .send(proto::UpdateReviewThread {
+                    project_id: remote_id,
+                    thread: Some(serialize_review_thread(&thread)),
+                })
+                .log_err();
+        }
+        let id = thread.id;
+        self.review_threads.next_id = self.review_threads.next_id.max(id + 1);
+        self.review_threads.threads.insert(id, thread);
+        cx.emit(Event::ReviewThreadUpdated(id));
+    }
+
+    fn apply_review_thread_response(
+        &mut self,
+        response: proto::ReviewThreadResponse,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<ReviewThreadId> {
+        let thread = deserialize_review_thread(response.thread.context("missing thread")?)?;
+        let id = thread.id;
+        self.insert_review_thread(thread, cx);
+        Ok(id)
+    }
+
+    pub(crate) async fn handle_get_review_threads(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::GetReviewThreads>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetReviewThreadsResponse> {
+        this.update(&mut cx, |this, _| proto::GetReviewThreadsResponse {
+            threads: this.review_threads().map(serialize_review_thread).collect(),
+        })
+    }
+
+    pub(crate) async fn handle_create_review_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateReviewThread>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReviewThreadResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let payload = envelope.payload;
+        let body = validate_comment_body(payload.body)?;
+        let buffer_id = BufferId::new(payload.buffer_id)?;
+        let start = payload
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?;
+        let end = payload
+            .end
+            .and_then(deserialize_anchor)
+            .context("invalid end")?;
+        let buffer = this.update(&mut cx, |this, _| {
+            this.buffer_for_id(buffer_id)
+                .ok_or_else(|| anyhow!("unknown buffer id {buffer_id}"))
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| buffer.wait_for_anchors([start, end]))?
+            .await?;
+        this.update(&mut cx, |this, cx| {
+            let author_id = this.collaborator_user_id(sender_id);
+            let thread = this.start_review_thread(&buffer, start..end, author_id, body, cx);
+            proto::ReviewThreadResponse {
+                thread: Some(serialize_review_thread(&thread)),
+            }
+        })
+    }
+
+    pub(crate) async fn handle_reply_to_review_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ReplyToReviewThread>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReviewThreadResponse> {
+        let sender_id = envelope.original_sender_id()?;
+        let payload = envelope.payload;
+        let body = validate_comment_body(payload.body)?;
+        this.update(&mut cx, |this, cx| {
+            let author_id = this.collaborator_user_id(sender_id);
+            let thread = this.update_review_thread(payload.thread_id, cx, |thread| {
+                thread.comments.push(ReviewComment {
+                    author_id,
+                    body,
+                    timestamp: SystemTime::now(),
+                })
+            })?;
+            Ok(proto::ReviewThreadResponse {
+                thread: Some(serialize_review_thread(&thread)),
+            })
+        })?
+    }
+
+    pub(crate) async fn handle_set_review_thread_resolved(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SetReviewThreadResolved>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ReviewThreadResponse> {
+        let payload = envelope.payload;
+        this.update(&mut cx, |this, cx| {
+            let thread = this.update_review_thread(payload.thread_id, cx, |thread| {
+                thread.resolved = payload.resolved
+            })?;
+            Ok(proto::ReviewThreadResponse {
+                thread: Some(serialize_review_thread(&thread)),
+            })
+        })?
+    }
+
+    pub(crate) async fn handle_update_review_thread(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateReviewThread>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let thread = deserialize_review_thread(envelope.payload.thread.context("missing thread")?)?;
+        this.update(&mut cx, |this, cx| this.insert_review_thread(thread, cx))
+    }
+
+    fn collaborator_user_id(&self, peer_id: proto::PeerId) -> Option<u64> {
+        self.collaborators
+            .get(&peer_id)
+            .map(|collaborator| collaborator.user_id)
+    }
+}
+
+/// Checks a comment's body, which the host also does for the comments sent by guests.
+fn validate_comment_body(body: String) -> Result<String> {
+    let body = body.trim();
+    if body.len() > MAX_REVIEW_COMMENT_LEN {
+        return Err(anyhow!("comment is too long"));
+    }
+    if body.is_empty() {
+        return Err(anyhow!("comment can't be blank"));
+    }
+    Ok(body.to_string())
+}
+
+fn serialize_review_thread(thread: &ReviewThread) -> proto::ReviewThread {
+    proto::ReviewThread {
+        id: thread.id,
+        buffer_id: thread.buffer_id.into(),
+        worktree_id: thread
+            .project_path
+            .as_ref()
+            .map(|path| path.worktree_id.to_proto()),
+        path: thread
+            .project_path
+            .as_ref()
+            .map(|path| path.path.to_string_lossy().to_string()),
+        start: Some(serialize_anchor(&thread.range.start)),
+        end: Some(serialize_anchor(&thread.range.end)),
+        resolved: thread.resolved,
+        comments: thread
+            .comments
+            .iter()
+            .map(|comment| proto::ReviewComment {
+                author_id: comment.author_id,
+                body: comment.body.clone(),
+                timestamp: comment
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            })
+            .collect(),
+    }
+}
+
+fn deserialize_review_thread(thread: proto::ReviewThread) -> Result<ReviewThread> {
+    let project_path = thread
+        .worktree_id
+        .zip(thread.path)
+        .map(|(worktree_id, path)| ProjectPath {
+            worktree_id: WorktreeId::from_proto(worktree_id),
+            path: PathBuf::from(path).into(),
+        });
+    Ok(ReviewThread {
+        id: thread.id,
+        buffer_id: BufferId::new(thread.buffer_id)?,
+        project_path,
+        range: thread
+            .start
+            .and_then(deserialize_anchor)
+            .context("invalid start")?
+            ..thread
+                .end
+                .and_then(deserialize_anchor)
+                .context("invalid end")?,
+        resolved: thread.resolved,
+        comments: thread
+            .comments
+            .into_iter()
+            .map(|comment| ReviewComment {
+                author_id: comment.author_id,
+                body: comment.body,
+                timestamp: UNIX_EPOCH + Duration::from_secs(comment.timestamp),
+            })
+            .collect(),
+    })
+}
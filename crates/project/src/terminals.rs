@@ -1,23 +1,71 @@
-use crate::Project;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use crate::{Event, Project, ProjectClientState};
+use anyhow::{anyhow, bail, Result};
+use client::{proto, Client, TypedEnvelope};
+use collections::{HashMap, HashSet};
+use futures::{channel::mpsc, StreamExt};
+use gpui::{
+    AnyWindowHandle, AsyncAppContext, Context, Entity, Model, ModelContext, Subscription, Task,
+    WeakModel,
+};
 use settings::Settings;
-use std::path::{Path, PathBuf};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 use terminal::{
-    terminal_settings::{self, TerminalSettings, VenvSettingsContent},
-    Terminal, TerminalBuilder,
+    alacritty_terminal::{
+        index::{Column, Line, Point as AlacPoint},
+        term::{
+            cell::{Cell, Flags},
+            TermMode,
+        },
+        vte::ansi::{Color, NamedColor, Rgb},
+    },
+    session,
+    terminal_settings::{self, TerminalPersistence, TerminalSettings, VenvSettingsContent},
+    IndexedCell, Terminal, TerminalBuilder, TerminalScreen,
 };
+use util::{post_inc, ResultExt};
 
 // #[cfg(target_os = "macos")]
 // use std::os::unix::ffi::OsStrExt;
 
+/// How long a shared terminal waits for more output before sending its screen to guests.
+const SHARED_TERMINAL_UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+pub type SharedTerminalId = u64;
+
+#[derive(Default)]
 pub struct Terminals {
     pub(crate) local_handles: Vec<WeakModel<terminal::Terminal>>,
+    /// The host's terminals that it shared with the project's guests.
+    shared: HashMap<SharedTerminalId, SharedTerminal>,
+    next_shared_id: SharedTerminalId,
+    /// The terminals that the host shared with this guest.
+    remote: HashMap<SharedTerminalId, Model<Terminal>>,
+    /// The screens of the host's terminals, which the host only sends the changes to.
+    remote_screens: HashMap<SharedTerminalId, proto::UpdateSharedTerminal>,
+}
+
+struct SharedTerminal {
+    terminal: WeakModel<Terminal>,
+    /// The guests that the host allowed to type into the terminal.
+    writers: HashSet<proto::PeerId>,
+    /// The screen that guests were last sent.
+    last_update: Option<proto::UpdateSharedTerminal>,
+    pending_update: Option<Task<()>>,
+    _subscriptions: [Subscription; 2],
 }
 
 impl Project {
+    /// Creates a terminal running the configured shell. When terminals are configured to
+    /// be reattachable and tmux is installed, the shell runs in the given session, which is
+    /// created if it doesn't exist.
     pub fn create_terminal(
         &mut self,
         working_directory: Option<PathBuf>,
+        session: Option<String>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
@@ -27,8 +75,22 @@ impl Project {
             ));
         } else {
             let settings = TerminalSettings::get_global(cx);
-            let python_settings = settings.detect_venv.clone();
-            let shell = settings.shell.clone();
+            let mut python_settings = settings.detect_venv.clone();
+            let mut shell = settings.shell.clone();
+            let session = if settings.persistence != TerminalPersistence::Reattach {
+                None
+            } else if !session::is_available() {
+                log::warn!("tmux isn't installed, so terminal sessions won't be kept alive");
+                None
+            } else {
+                // The virtual environment was already activated when the session was created.
+                if session.is_some() {
+                    python_settings = terminal_settings::VenvSettings::Off;
+                }
+                let session = session.unwrap_or_else(session::new_session_name);
+                shell = session::shell_for_session(&shell, &session);
+                Some(session)
+            };
 
             let terminal = TerminalBuilder::new(
                 working_directory.clone(),
@@ -36,6 +98,7 @@ impl Project {
                 settings.env.clone(),
                 Some(settings.blinking.clone()),
                 settings.alternate_scroll,
+                session,
                 window,
             )
             .map(|builder| {
@@ -137,6 +200,652 @@ impl Project {
     pub fn local_terminal_handles(&self) -> &Vec<WeakModel<terminal::Terminal>> {
         &self.terminals.local_handles
     }
+
+    /// Shows one of the host's terminals to the project's guests. They can only watch
+    /// it until they're allowed to type into it with [`Self::set_terminal_input_allowed`].
+    pub fn share_terminal(
+        &mut self,
+        terminal: &Model<Terminal>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if !matches!(self.client_state, ProjectClientState::Shared { .. }) {
+            bail!("the project isn't shared");
+        }
+        if terminal.read(cx).is_remote() {
+            bail!("only the host's terminals can be shared");
+        }
+        if self.shared_terminal_id(terminal).is_some() {
+            return Ok(());
+        }
+
+        let id = post_inc(&mut self.terminals.next_shared_id);
+        let subscriptions = [
+            cx.subscribe(terminal, move |this, _, event, cx| match event {
+                terminal::Event::Wakeup | terminal::Event::TitleChanged => {
+                    this.schedule_shared_terminal_update(id, cx)
+                }
+                terminal::Event::CloseTerminal => this.stop_sharing_terminal(id, cx),
+                _ => {}
+            }),
+            cx.observe_release(terminal, move |this, _, cx| {
+                this.stop_sharing_terminal(id, cx)
+            }),
+        ];
+        self.terminals.shared.insert(
+            id,
+            SharedTerminal {
+                terminal: terminal.downgrade(),
+                writers: HashSet::default(),
+                last_update: None,
+                pending_update: None,
+                _subscriptions: subscriptions,
+            },
+        );
+        self.send_shared_terminal_update(id, cx);
+        cx.notify();
+        Ok(())
+    }
+
+    pub fn unshare_terminal(&mut self, terminal: &Model<Terminal>, cx: &mut ModelContext<Self>) {
+        if let Some(id) = self.shared_terminal_id(terminal) {
+            self.stop_sharing_terminal(id, cx);
+        }
+    }
+
+    pub fn is_terminal_shared(&self, terminal: &Model<Terminal>) -> bool {
+        self.shared_terminal_id(terminal).is_some()
+    }
+
+    pub fn is_terminal_input_allowed(
+        &self,
+        terminal: &Model<Terminal>,
+        peer_id: proto::PeerId,
+    ) -> bool {
+        self.shared_terminal_id(terminal)
+            .and_then(|id| self.terminals.shared.get(&id))
+            .map_or(false, |shared| shared.writers.contains(&peer_id))
+    }
+
+    /// Allows or forbids a guest to type into a shared terminal. Guests whose role in
+    /// the call doesn't let them edit the project can never type into terminals.
+    pub fn set_terminal_input_allowed(
+        &mut self,
+        terminal: &Model<Terminal>,
+        peer_id: proto::PeerId,
+        allowed: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(id) = self.shared_terminal_id(terminal) else {
+            return;
+        };
+        if let Some(shared) = self.terminals.shared.get_mut(&id) {
+            if allowed {
+                shared.writers.insert(peer_id);
+            } else {
+                shared.writers.remove(&peer_id);
+            }
+        }
+        self.send_shared_terminal_update(id, cx);
+        cx.notify();
+    }
+
+    /// Returns a terminal that the host shared with this guest.
+    pub fn remote_terminal(&self, id: SharedTerminalId) -> Option<Model<Terminal>> {
+        self.terminals.remote.get(&id).cloned()
+    }
+
+    /// Returns the id by which collaborators know a terminal, whether this project shared
+    /// it or received it from the host.
+    pub fn terminal_share_id(&self, terminal: &Model<Terminal>) -> Option<SharedTerminalId> {
+        self.shared_terminal_id(terminal).or_else(|| {
+            self.terminals
+                .remote
+                .iter()
+                .find(|(_, remote)| remote.entity_id() == terminal.entity_id())
+                .map(|(id, _)| *id)
+        })
+    }
+
+    fn shared_terminal_id(&self, terminal: &Model<Terminal>) -> Option<SharedTerminalId> {
+        self.terminals
+            .shared
+            .iter()
+            .find(|(_, shared)| shared.terminal.entity_id() == terminal.entity_id())
+            .map(|(id, _)| *id)
+    }
+
+    fn stop_sharing_terminal(&mut self, id: SharedTerminalId, cx: &mut ModelContext<Self>) {
+        if self.terminals.shared.remove(&id).is_none() {
+            return;
+        }
+        if let ProjectClientState::Shared { remote_id, .. } = self.client_state {
+            self.client
+                .send(proto::UnshareTerminal {
+                    project_id: remote_id,
+                    terminal_id: id,
+                })
+                .log_err();
+        }
+        cx.notify();
+    }
+
+    fn schedule_shared_terminal_update(
+        &mut self,
+        id: SharedTerminalId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(shared) = self.terminals.shared.get_mut(&id) else {
+            return;
+        };
+        if shared.pending_update.is_some() {
+            return;
+        }
+        shared.pending_update = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SHARED_TERMINAL_UPDATE_DEBOUNCE)
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if let Some(shared) = this.terminals.shared.get_mut(&id) {
+                    shared.pending_update = None;
+                }
+                this.send_shared_terminal_update(id, cx);
+            })
+            .ok();
+        }));
+    }
+
+    fn send_shared_terminal_update(&mut self, id: SharedTerminalId, cx: &mut ModelContext<Self>) {
+        let ProjectClientState::Shared { remote_id, .. } = self.client_state else {
+            return;
+        };
+        let Some(shared) = self.terminals.shared.get_mut(&id) else {
+            return;
+        };
+        let Some(terminal) = shared.terminal.upgrade() else {
+            return;
+        };
+        let mut writers = shared.writers.iter().copied().collect::<Vec<_>>();
+        writers.sort_by_key(|peer_id| (peer_id.owner_id, peer_id.id));
+        let screen = terminal.read(cx).screen();
+        let update = serialize_terminal_screen(remote_id, id, screen, writers);
+        let message = match &shared.last_update {
+            Some(last_update) => diff_terminal_update(last_update, &update),
+            None => Some(update.clone()),
+        };
+        shared.last_update = Some(update);
+        if let Some(message) = message {
+            self.client.send(message).log_err();
+        }
+    }
+
+    /// Sends the whole screens of all shared terminals, so that guests who just joined
+    /// can see them.
+    pub(crate) fn resend_shared_terminals(&mut self, cx: &mut ModelContext<Self>) {
+        let mut ids = Vec::new();
+        for (id, shared) in &mut self.terminals.shared {
+            shared.last_update = None;
+            ids.push(*id);
+        }
+        for id in ids {
+            self.send_shared_terminal_update(id, cx);
+        }
+    }
+
+    pub(crate) fn replace_terminal_writer(
+        &mut self,
+        old_peer_id: proto::PeerId,
+        new_peer_id: Option<proto::PeerId>,
+    ) {
+        for shared in self.terminals.shared.values_mut() {
+            if shared.writers.remove(&old_peer_id) {
+                shared.writers.extend(new_peer_id);
+            }
+        }
+    }
+
+    pub(crate) fn unshare_terminals(&mut self) {
+        self.terminals.shared.clear();
+    }
+
+    pub(crate) fn close_remote_terminals(&mut self, cx: &mut gpui::AppContext) {
+        self.terminals.remote_screens.clear();
+        for (_, terminal) in self.terminals.remote.drain() {
+            terminal.update(cx, |terminal, cx| terminal.close_remote(cx));
+        }
+    }
+
+    fn create_remote_terminal(
+        &mut self,
+        id: SharedTerminalId,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Model<Terminal>> {
+        let project_id = self
+            .remote_id()
+            .ok_or_else(|| anyhow!("project isn't remote"))?;
+        let (input_tx, mut input_rx) = mpsc::unbounded();
+        let terminal = cx.new_model(|cx| TerminalBuilder::new_remote(input_tx).subscribe(cx));
+        self.terminals.remote.insert(id, terminal.clone());
+
+        // Input is sent in order, and stops once the terminal is dropped.
+        let client = self.client.clone();
+        cx.background_executor()
+            .spawn(async move {
+                while let Some(input) = input_rx.next().await {
+                    client
+                        .request(proto::TerminalInput {
+                            project_id,
+                            terminal_id: id,
+                            input,
+                        })
+                        .await
+                        .log_err();
+                }
+            })
+            .detach();
+        cx.emit(Event::RemoteTerminalShared(id));
+        Ok(terminal)
+    }
+
+    fn check_sender_is_host(&self, sender_id: proto::PeerId) -> Result<()> {
+        if self.host().map(|host| host.peer_id) == Some(sender_id) {
+            Ok(())
+        } else {
+            Err(anyhow!("only the host can share terminals"))
+        }
+    }
+
+    pub(crate) async fn handle_update_shared_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UpdateSharedTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        let payload = envelope.payload;
+        this.update(&mut cx, |this, cx| {
+            this.check_sender_is_host(sender_id)?;
+            let terminal_id = payload.terminal_id;
+            let previous = this.terminals.remote_screens.remove(&terminal_id);
+            let update = apply_terminal_update(previous, payload);
+            let screen = deserialize_terminal_screen(&update)?;
+            let can_write = this
+                .client
+                .peer_id()
+                .map_or(false, |peer_id| update.writers.contains(&peer_id));
+            this.terminals.remote_screens.insert(terminal_id, update);
+            let terminal = match this.remote_terminal(terminal_id) {
+                Some(terminal) => terminal,
+                None => this.create_remote_terminal(terminal_id, cx)?,
+            };
+            terminal.update(cx, |terminal, cx| {
+                terminal.set_remote_screen(screen, can_write, cx)
+            });
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_unshare_terminal(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::UnshareTerminal>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<()> {
+        let sender_id = envelope.original_sender_id()?;
+        this.update(&mut cx, |this, cx| {
+            this.check_sender_is_host(sender_id)?;
+            let terminal_id = envelope.payload.terminal_id;
+            this.terminals.remote_screens.remove(&terminal_id);
+            if let Some(terminal) = this.terminals.remote.remove(&terminal_id) {
+                terminal.update(cx, |terminal, cx| terminal.close_remote(cx));
+            }
+            Ok(())
+        })?
+    }
+
+    pub(crate) async fn handle_terminal_input(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::TerminalInput>,
+        _: Arc<Client>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let sender_id = envelope.original_sender_id()?;
+        let payload = envelope.payload;
+        this.update(&mut cx, |this, cx| {
+            let shared = this
+                .terminals
+                .shared
+                .get(&payload.terminal_id)
+                .ok_or_else(|| anyhow!("unknown terminal {}", payload.terminal_id))?;
+            if !shared.writers.contains(&sender_id) {
+                bail!("not allowed to type into this terminal");
+            }
+            let terminal = shared
+                .terminal
+                .upgrade()
+                .ok_or_else(|| anyhow!("terminal was closed"))?;
+            terminal.update(cx, |terminal, _| terminal.input_bytes(payload.input));
+            Ok(proto::Ack {})
+        })?
+    }
+}
+
+/// Encodes a terminal's screen as runs of identically styled text on each line.
+pub fn serialize_terminal_screen(
+    project_id: u64,
+    terminal_id: SharedTerminalId,
+    screen: TerminalScreen,
+    writers: Vec<proto::PeerId>,
+) -> proto::UpdateSharedTerminal {
+    let lines = screen
+        .cells
+        .chunks(screen.columns.max(1))
+        .enumerate()
+        .map(|(index, cells)| {
+            let mut spans = Vec::<proto::TerminalSpan>::new();
+            let mut style = None;
+            for cell in cells {
+                let cell_style = Some((cell.fg, cell.bg, cell.flags));
+                if style != cell_style {
+                    style = cell_style;
+                    spans.push(proto::TerminalSpan {
+                        text: String::new(),
+                        foreground: Some(serialize_color(cell.fg)),
+                        background: Some(serialize_color(cell.bg)),
+                        flags: cell.flags.bits() as u32,
+                    });
+                }
+                if let Some(span) = spans.last_mut() {
+                    span.text.push(cell.c);
+                }
+            }
+            proto::TerminalLine {
+                spans,
+                index: index as u32,
+            }
+        })
+        .collect();
+
+    proto::UpdateSharedTerminal {
+        project_id,
+        terminal_id,
+        title: screen.title,
+        columns: screen.columns as u32,
+        lines,
+        cursor_line: screen.cursor.line.0.max(0) as u32,
+        cursor_column: screen.cursor.column.0 as u32,
+        mode: screen.mode.bits(),
+        writers,
+        line_count: screen.lines as u32,
+    }
+}
+
+/// Returns an update with only the lines of a terminal's screen that changed since the
+/// previous one, or `None` if nothing changed.
+fn diff_terminal_update(
+    previous: &proto::UpdateSharedTerminal,
+    update: &proto::UpdateSharedTerminal,
+) -> Option<proto::UpdateSharedTerminal> {
+    if previous == update {
+        return None;
+    }
+    let mut diff = update.clone();
+    // Every line is sent when the terminal is resized.
+    if previous.columns == update.columns && previous.line_count == update.line_count {
+        diff.lines
+            .retain(|line| previous.lines.get(line.index as usize) != Some(line));
+    }
+    Some(diff)
+}
+
+/// Applies the lines of a terminal's screen that changed to the screen that was last
+/// received, returning the whole screen.
+fn apply_terminal_update(
+    previous: Option<proto::UpdateSharedTerminal>,
+    mut update: proto::UpdateSharedTerminal,
+) -> proto::UpdateSharedTerminal {
+    let mut lines = previous.map(|previous| previous.lines).unwrap_or_default();
+    lines.resize_with(update.line_count as usize, Default::default);
+    for line in update.lines.drain(..) {
+        if let Some(previous_line) = lines.get_mut(line.index as usize) {
+            *previous_line = line;
+        }
+    }
+    update.lines = lines;
+    update
+}
+
+/// Decodes a terminal's screen from a message that has all of its lines.
+pub fn deserialize_terminal_screen(
+    message: &proto::UpdateSharedTerminal,
+) -> Result<TerminalScreen> {
+    let lines = message.lines.len();
+    let columns = message.columns as usize;
+    if lines == 0 || columns == 0 {
+        bail!("terminal screen is empty");
+    }
+
+    let mut cells = Vec::with_capacity(lines * columns);
+    for (line, proto_line) in message.lines.iter().enumerate() {
+        let mut column = 0;
+        for span in &proto_line.spans {
+            let fg = deserialize_color(span.foreground.as_ref())?;
+            let bg = deserialize_color(span.background.as_ref())?;
+            let flags = Flags::from_bits_truncate(span.flags as u16);
+            for c in span.text.chars().take(columns.saturating_sub(column)) {
+                cells.push(IndexedCell {
+                    point: AlacPoint::new(Line(line as i32), Column(column)),
+                    cell: Cell {
+                        c,
+                        fg,
+                        bg,
+                        flags,
+                        ..Default::default()
+                    },
+                });
+                column += 1;
+            }
+        }
+    }
+
+    Ok(TerminalScreen {
+        title: message.title.clone(),
+        lines,
+        columns,
+        cells,
+        cursor: AlacPoint::new(
+            Line((message.cursor_line as usize).min(lines - 1) as i32),
+            Column((message.cursor_column as usize).min(columns - 1)),
+        ),
+        mode: TermMode::from_bits_truncate(message.mode),
+    })
+}
+
+const NAMED_COLORS: [NamedColor; 29] = [
+    NamedColor::Black,
+    NamedColor::Red,
+    NamedColor::Green,
+    NamedColor::Yellow,
+    NamedColor::Blue,
+    NamedColor::Magenta,
+    NamedColor::Cyan,
+    NamedColor::White,
+    NamedColor::BrightBlack,
+    NamedColor::BrightRed,
+    NamedColor::BrightGreen,
+    NamedColor::BrightYellow,
+    NamedColor::BrightBlue,
+    NamedColor::BrightMagenta,
+    NamedColor::BrightCyan,
+    NamedColor::BrightWhite,
+    NamedColor::Foreground,
+    NamedColor::Background,
+    NamedColor::Cursor,
+    NamedColor::DimBlack,
+    NamedColor::DimRed,
+    NamedColor::DimGreen,
+    NamedColor::DimYellow,
+    NamedColor::DimBlue,
+    NamedColor::DimMagenta,
+    NamedColor::DimCyan,
+    NamedColor::DimWhite,
+    NamedColor::BrightForeground,
+    NamedColor::DimForeground,
+];
+
+fn serialize_color(color: Color) -> proto::TerminalColor {
+    let variant = match color {
+        Color::Named(named) => proto::terminal_color::Variant::Named(named as u32),
+        Color::Indexed(index) => proto::terminal_color::Variant::Indexed(index as u32),
+        Color::Spec(rgb) => {
+            proto::terminal_color::Variant::Rgb(u32::from_be_bytes([0, rgb.r, rgb.g, rgb.b]))
+        }
+    };
+    proto::TerminalColor {
+        variant: Some(variant),
+    }
+}
+
+fn deserialize_color(color: Option<&proto::TerminalColor>) -> Result<Color> {
+    let variant = color
+        .and_then(|color| color.variant.as_ref())
+        .ok_or_else(|| anyhow!("missing terminal color"))?;
+    match variant {
+        proto::terminal_color::Variant::Named(named) => NAMED_COLORS
+            .iter()
+            .find(|color| **color as u32 == *named)
+            .map(|color| Color::Named(*color))
+            .ok_or_else(|| anyhow!("invalid named color {named}")),
+        proto::terminal_color::Variant::Indexed(index) => Ok(Color::Indexed(u8::try_from(*index)?)),
+        proto::terminal_color::Variant::Rgb(rgb) => {
+            let [_, r, g, b] = rgb.to_be_bytes();
+            Ok(Color::Spec(Rgb { r, g, b }))
+        }
+    }
 }
 
 // TODO: Add a few tests for adding and removing terminal tabs
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_screen_serialization() {
+        let cell = |c: char, fg: Color, flags: Flags| Cell {
+            c,
+            fg,
+            flags,
+            ..Default::default()
+        };
+        let rows = [
+            vec![
+                cell('$', Color::Named(NamedColor::Green), Flags::BOLD),
+                cell(' ', Color::Named(NamedColor::Foreground), Flags::empty()),
+                cell('l', Color::Named(NamedColor::Foreground), Flags::empty()),
+            ],
+            vec![
+                cell('界', Color::Indexed(208), Flags::WIDE_CHAR),
+                cell(' ', Color::Indexed(208), Flags::WIDE_CHAR_SPACER),
+                cell('x', Color::Spec(Rgb { r: 1, g: 2, b: 3 }), Flags::empty()),
+            ],
+        ];
+        let screen = TerminalScreen {
+            title: "zsh".into(),
+            lines: 2,
+            columns: 3,
+            cells: rows
+                .iter()
+                .enumerate()
+                .flat_map(|(line, cells)| {
+                    cells
+                        .iter()
+                        .enumerate()
+                        .map(move |(column, cell)| IndexedCell {
+                            point: AlacPoint::new(Line(line as i32), Column(column)),
+                            cell: cell.clone(),
+                        })
+                })
+                .collect(),
+            cursor: AlacPoint::new(Line(1), Column(2)),
+            mode: TermMode::SHOW_CURSOR | TermMode::APP_CURSOR,
+        };
+
+        let message = serialize_terminal_screen(1, 2, screen.clone(), Vec::new());
+        assert_eq!(message.lines[0].spans.len(), 2);
+        assert_eq!(message.lines[1].spans.len(), 3);
+
+        let deserialized = deserialize_terminal_screen(&message).unwrap();
+        assert_eq!(deserialized.title, screen.title);
+        assert_eq!((deserialized.lines, deserialized.columns), (2, 3));
+        assert_eq!(deserialized.cursor, screen.cursor);
+        assert_eq!(deserialized.mode, screen.mode);
+        assert_eq!(
+            deserialized
+                .cells
+                .iter()
+                .map(|cell| (cell.point, cell.c, cell.fg, cell.flags))
+                .collect::<Vec<_>>(),
+            screen
+                .cells
+                .iter()
+                .map(|cell| (cell.point, cell.c, cell.fg, cell.flags))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_terminal_update_diffing() {
+        let screen = |rows: &[&str]| {
+            let columns = rows[0].len();
+            TerminalScreen {
+                title: "zsh".into(),
+                lines: rows.len(),
+                columns,
+                cells: rows
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(line, row)| {
+                        row.chars().enumerate().map(move |(column, c)| IndexedCell {
+                            point: AlacPoint::new(Line(line as i32), Column(column)),
+                            cell: Cell {
+                                c,
+                                ..Default::default()
+                            },
+                        })
+                    })
+                    .collect(),
+                cursor: AlacPoint::new(Line(0), Column(0)),
+                mode: TermMode::SHOW_CURSOR,
+            }
+        };
+        let text = |update: &proto::UpdateSharedTerminal| {
+            deserialize_terminal_screen(update)
+                .unwrap()
+                .cells
+                .iter()
+                .map(|cell| cell.c)
+                .collect::<String>()
+        };
+
+        let first = serialize_terminal_screen(1, 2, screen(&["ab", "cd", "ef"]), Vec::new());
+        let second = serialize_terminal_screen(1, 2, screen(&["ab", "xy", "ef"]), Vec::new());
+
+        // Unchanged screens aren't sent again, and changed ones only send their
+        // changed lines.
+        assert_eq!(diff_terminal_update(&first, &first), None);
+        let diff = diff_terminal_update(&first, &second).unwrap();
+        assert_eq!(
+            diff.lines.iter().map(|line| line.index).collect::<Vec<_>>(),
+            [1]
+        );
+        let received = apply_terminal_update(Some(first.clone()), diff);
+        assert_eq!(text(&received), "abxyef");
+
+        // Resizing the terminal sends every line.
+        let resized = serialize_terminal_screen(1, 2, screen(&["abc", "xyz"]), Vec::new());
+        let diff = diff_terminal_update(&second, &resized).unwrap();
+        assert_eq!(diff.lines.len(), 2);
+        let received = apply_terminal_update(Some(received), diff);
+        assert_eq!(text(&received), "abcxyz");
+    }
+}
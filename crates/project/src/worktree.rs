@@ -51,6 +51,7 @@ use std::{
     ffi::OsStr,
     fmt,
     future::Future,
+    io::{self, Read as _},
     mem,
     ops::{AddAssign, Deref, DerefMut, Sub},
     path::{Path, PathBuf},
@@ -713,21 +714,46 @@ fn path_matchers(values: Option<&[String]>, context: &'static str) -> Vec<PathMa
         .collect()
 }
 
+const LARGE_FILE_CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+async fn read_in_chunks(
+    mut file: Box<dyn io::Read + Send>,
+    progress: UnboundedSender<u64>,
+) -> Result<String> {
+    let mut bytes = Vec::new();
+    let mut chunk = vec![0; LARGE_FILE_CHUNK_LEN];
+    loop {
+        let len = file.read(&mut chunk)?;
+        if len == 0 {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..len]);
+        progress.unbounded_send(bytes.len() as u64).ok();
+    }
+    Ok(String::from_utf8(bytes)?)
+}
+
 impl LocalWorktree {
     pub fn contains_abs_path(&self, path: &Path) -> bool {
         path.starts_with(&self.abs_path)
     }
 
+    /// Loads the file at `path` into a new buffer. When `large_file_progress` is given, the
+    /// file is read in chunks, sending the number of bytes read so far after each one, and
+    /// its Git diff base isn't loaded.
     pub(crate) fn load_buffer(
         &mut self,
         id: BufferId,
         path: &Path,
+        large_file_progress: Option<UnboundedSender<u64>>,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Model<Buffer>>> {
         let path = Arc::from(path);
         cx.spawn(move |this, mut cx| async move {
             let (file, contents, diff_base) = this
-                .update(&mut cx, |t, cx| t.as_local().unwrap().load(&path, cx))?
+                .update(&mut cx, |t, cx| {
+                    t.as_local().unwrap().load(&path, large_file_progress, cx)
+                })?
                 .await?;
             let text_buffer = cx
                 .background_executor()
@@ -1007,6 +1033,7 @@ impl LocalWorktree {
     fn load(
         &self,
         path: &Path,
+        large_file_progress: Option<UnboundedSender<u64>>,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<(File, String, Option<String>)>> {
         let path = Arc::from(path);
@@ -1016,10 +1043,22 @@ impl LocalWorktree {
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let is_large_file = large_file_progress.is_some();
+            let text = if let Some(progress) = large_file_progress {
+                let file = fs.open_sync(&abs_path).await?;
+                cx.background_executor()
+                    .spawn(read_in_chunks(file, progress))
+                    .await
+                    .with_context(|| format!("loading large file {abs_path:?}"))?
+            } else {
+                fs.load(&abs_path).await?
+            };
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
-            if let Some(repo) = snapshot.repository_for_path(&path) {
+            if let Some(repo) = snapshot
+                .repository_for_path(&path)
+                .filter(|_| !is_large_file)
+            {
                 if let Some(repo_path) = repo.work_directory.relativize(&snapshot, &path).log_err()
                 {
                     if let Some(git_repo) = snapshot.git_repositories.get(&*repo.work_directory) {
@@ -574,6 +574,7 @@ async fn test_open_gitignored_files(cx: &mut TestAppContext) {
             tree.as_local_mut().unwrap().load_buffer(
                 BufferId::new(1).unwrap(),
                 "one/node_modules/b/b1.js".as_ref(),
+                None,
                 cx,
             )
         })
@@ -618,6 +619,7 @@ async fn test_open_gitignored_files(cx: &mut TestAppContext) {
             tree.as_local_mut().unwrap().load_buffer(
                 BufferId::new(1).unwrap(),
                 "one/node_modules/a/a2.js".as_ref(),
+                None,
                 cx,
             )
         })
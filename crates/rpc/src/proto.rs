@@ -136,6 +136,9 @@ messages!(
     (Ack, Foreground),
     (AckBufferOperation, Background),
     (AckChannelMessage, Background),
+    (AddChannelMessageReaction, Foreground),
+    (AddChannelProjectLink, Foreground),
+    (AddChannelProjectLinkResponse, Foreground),
     (AddNotification, Foreground),
     (AddProjectCollaborator, Foreground),
     (ApplyCodeAction, Background),
@@ -148,11 +151,13 @@ messages!(
     (CallCanceled, Foreground),
     (CancelCall, Foreground),
     (ChannelMessageSent, Foreground),
+    (ChannelMessageUpdate, Foreground),
     (CopyProjectEntry, Foreground),
     (CreateBufferForPeer, Foreground),
     (CreateChannel, Foreground),
     (CreateChannelResponse, Foreground),
     (CreateProjectEntry, Foreground),
+    (CreateReviewThread, Foreground),
     (CreateRoom, Foreground),
     (CreateRoomResponse, Foreground),
     (DeclineCall, Foreground),
@@ -172,6 +177,14 @@ messages!(
     (GetChannelMessages, Background),
     (GetChannelMessagesById, Background),
     (GetChannelMessagesResponse, Background),
+    (GetChannelNotesAuthorship, Background),
+    (GetChannelNotesAuthorshipResponse, Background),
+    (GetChannelNotesHistory, Background),
+    (GetChannelNotesHistoryResponse, Background),
+    (GetChannelNotesVersion, Background),
+    (GetChannelNotesVersionResponse, Background),
+    (GetChannelProjectLinks, Foreground),
+    (GetChannelProjectLinksResponse, Foreground),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
     (GetCompletions, Background),
@@ -190,6 +203,8 @@ messages!(
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetReviewThreads, Foreground),
+    (GetReviewThreadsResponse, Foreground),
     (GetTypeDefinition, Background),
     (GetTypeDefinitionResponse, Background),
     (GetUsers, Foreground),
@@ -235,11 +250,14 @@ messages!(
     (ReloadBuffersResponse, Foreground),
     (RemoveChannelMember, Foreground),
     (RemoveChannelMessage, Foreground),
+    (RemoveChannelMessageReaction, Foreground),
+    (RemoveChannelProjectLink, Foreground),
     (RemoveContact, Foreground),
     (RemoveProjectCollaborator, Foreground),
     (RenameChannel, Foreground),
     (RenameChannelResponse, Foreground),
     (RenameProjectEntry, Foreground),
+    (ReplyToReviewThread, Foreground),
     (RequestContact, Foreground),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
@@ -247,10 +265,14 @@ messages!(
     (ResolveInlayHintResponse, Background),
     (RespondToChannelInvite, Foreground),
     (RespondToContactRequest, Foreground),
+    (ReviewThreadResponse, Foreground),
     (RoomUpdated, Foreground),
     (SaveBuffer, Foreground),
     (SetChannelMemberRole, Foreground),
     (SetChannelVisibility, Foreground),
+    (SetReviewThreadResolved, Foreground),
+    (SearchChannelMessages, Background),
+    (SearchChannelMessagesResponse, Background),
     (SearchProject, Background),
     (SearchProjectResponse, Background),
     (SendChannelMessage, Background),
@@ -261,13 +283,16 @@ messages!(
     (StartLanguageServer, Foreground),
     (SynchronizeBuffers, Foreground),
     (SynchronizeBuffersResponse, Foreground),
+    (TerminalInput, Foreground),
     (Test, Foreground),
     (Unfollow, Foreground),
     (UnshareProject, Foreground),
+    (UnshareTerminal, Foreground),
     (UpdateBuffer, Foreground),
     (UpdateBufferFile, Foreground),
     (UpdateChannelBuffer, Foreground),
     (UpdateChannelBufferCollaborators, Foreground),
+    (UpdateChannelMessage, Foreground),
     (UpdateChannels, Foreground),
     (UpdateUserChannels, Foreground),
     (UpdateContacts, Foreground),
@@ -279,6 +304,8 @@ messages!(
     (UpdateParticipantLocation, Foreground),
     (UpdateProject, Foreground),
     (UpdateProjectCollaborator, Foreground),
+    (UpdateReviewThread, Foreground),
+    (UpdateSharedTerminal, Foreground),
     (UpdateWorktree, Foreground),
     (UpdateWorktreeSettings, Foreground),
     (UsersResponse, Foreground),
@@ -288,6 +315,8 @@ messages!(
 );
 
 request_messages!(
+    (AddChannelMessageReaction, Ack),
+    (AddChannelProjectLink, AddChannelProjectLinkResponse),
     (ApplyCodeAction, ApplyCodeActionResponse),
     (
         ApplyCompletionAdditionalEdits,
@@ -298,6 +327,7 @@ request_messages!(
     (CopyProjectEntry, ProjectEntryResponse),
     (CreateChannel, CreateChannelResponse),
     (CreateProjectEntry, ProjectEntryResponse),
+    (CreateReviewThread, ReviewThreadResponse),
     (CreateRoom, CreateRoomResponse),
     (DeclineCall, Ack),
     (DeleteChannel, Ack),
@@ -309,6 +339,10 @@ request_messages!(
     (GetChannelMembers, GetChannelMembersResponse),
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
+    (GetChannelNotesAuthorship, GetChannelNotesAuthorshipResponse),
+    (GetChannelNotesHistory, GetChannelNotesHistoryResponse),
+    (GetChannelNotesVersion, GetChannelNotesVersionResponse),
+    (GetChannelProjectLinks, GetChannelProjectLinksResponse),
     (GetCodeActions, GetCodeActionsResponse),
     (GetCompletions, GetCompletionsResponse),
     (GetDefinition, GetDefinitionResponse),
@@ -318,6 +352,7 @@ request_messages!(
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetReviewThreads, GetReviewThreadsResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (GetUsers, UsersResponse),
     (IncomingCall, Ack),
@@ -345,9 +380,12 @@ request_messages!(
     (ReloadBuffers, ReloadBuffersResponse),
     (RemoveChannelMember, Ack),
     (RemoveChannelMessage, Ack),
+    (RemoveChannelMessageReaction, Ack),
+    (RemoveChannelProjectLink, Ack),
     (RemoveContact, Ack),
     (RenameChannel, RenameChannelResponse),
     (RenameProjectEntry, ProjectEntryResponse),
+    (ReplyToReviewThread, ReviewThreadResponse),
     (RequestContact, Ack),
     (
         ResolveCompletionDocumentation,
@@ -357,14 +395,18 @@ request_messages!(
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
     (SaveBuffer, BufferSaved),
+    (SearchChannelMessages, SearchChannelMessagesResponse),
     (SearchProject, SearchProjectResponse),
     (SendChannelMessage, SendChannelMessageResponse),
     (SetChannelMemberRole, Ack),
     (SetChannelVisibility, Ack),
+    (SetReviewThreadResolved, ReviewThreadResponse),
     (ShareProject, ShareProjectResponse),
     (SynchronizeBuffers, SynchronizeBuffersResponse),
+    (TerminalInput, Ack),
     (Test, Test),
     (UpdateBuffer, Ack),
+    (UpdateChannelMessage, Ack),
     (UpdateParticipantLocation, Ack),
     (UpdateProject, Ack),
     (UpdateWorktree, Ack),
@@ -382,6 +424,7 @@ entity_messages!(
     CopyProjectEntry,
     CreateBufferForPeer,
     CreateProjectEntry,
+    CreateReviewThread,
     DeleteProjectEntry,
     ExpandProjectEntry,
     FormatBuffers,
@@ -392,6 +435,7 @@ entity_messages!(
     GetHover,
     GetProjectSymbols,
     GetReferences,
+    GetReviewThreads,
     GetTypeDefinition,
     InlayHints,
     JoinProject,
@@ -406,13 +450,17 @@ entity_messages!(
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ReplyToReviewThread,
     ResolveCompletionDocumentation,
     ResolveInlayHint,
     SaveBuffer,
     SearchProject,
+    SetReviewThreadResolved,
     StartLanguageServer,
     SynchronizeBuffers,
+    TerminalInput,
     UnshareProject,
+    UnshareTerminal,
     UpdateBuffer,
     UpdateBufferFile,
     UpdateDiagnosticSummary,
@@ -420,6 +468,8 @@ entity_messages!(
     UpdateLanguageServer,
     UpdateProject,
     UpdateProjectCollaborator,
+    UpdateReviewThread,
+    UpdateSharedTerminal,
     UpdateWorktree,
     UpdateWorktreeSettings,
     LspExtExpandMacro,
@@ -428,6 +478,7 @@ entity_messages!(
 entity_messages!(
     {channel_id, Channel},
     ChannelMessageSent,
+    ChannelMessageUpdate,
     RemoveChannelMessage,
     UpdateChannelBuffer,
     UpdateChannelBufferCollaborators,
@@ -4,7 +4,7 @@ use crate::{
     SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
     ToggleReplace, ToggleWholeWord,
 };
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, Context as _, Result};
 use collections::HashMap;
 use editor::{
     actions::SelectAll, items::active_match_index, scroll::Autoscroll, Anchor, Editor, EditorEvent,
@@ -23,6 +23,7 @@ use project::{
     search::{SearchInputs, SearchQuery},
     Entry, Project,
 };
+use rpc::proto::{self, PeerId};
 use semantic_index::{SemanticIndex, SemanticIndexStatus};
 
 use collections::HashSet;
@@ -44,10 +45,10 @@ use ui::{
 };
 use util::{paths::PathMatcher, ResultExt as _};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle},
     searchable::{Direction, SearchableItem, SearchableItemHandle},
-    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
-    WorkspaceId,
+    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, ViewId,
+    Workspace, WorkspaceId,
 };
 
 actions!(
@@ -62,6 +63,7 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    workspace::register_followable_item::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &ToggleFilters, cx| {
             search_bar.toggle_filters(cx);
@@ -165,6 +167,9 @@ pub struct ProjectSearchView {
     filters_enabled: bool,
     replace_enabled: bool,
     current_mode: SearchMode,
+    remote_id: Option<ViewId>,
+    /// A scroll position received from the leader that the results don't contain yet.
+    pending_scroll_position: Option<proto::BufferScrollPosition>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -333,6 +338,7 @@ pub enum ViewEvent {
     Activate,
     EditorEvent(editor::EditorEvent),
     Dismiss,
+    SearchStarted { local: bool },
 }
 
 impl EventEmitter<ViewEvent> for ProjectSearchView {}
@@ -963,6 +969,8 @@ impl ProjectSearchView {
             filters_enabled,
             current_mode,
             replace_enabled: false,
+            remote_id: None,
+            pending_scroll_position: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -1108,6 +1116,12 @@ impl ProjectSearchView {
     }
 
     fn search(&mut self, cx: &mut ViewContext<Self>) {
+        self.run_search(true, cx);
+    }
+
+    /// Searches for the entered query. `local` is false when the leader that this view
+    /// follows started the search.
+    fn run_search(&mut self, local: bool, cx: &mut ViewContext<Self>) {
         let mode = self.current_mode;
         match mode {
             SearchMode::Semantic => {
@@ -1122,6 +1136,7 @@ impl ProjectSearchView {
             _ => {
                 if let Some(query) = self.build_search_query(cx) {
                     self.model.update(cx, |model, cx| model.search(query, cx));
+                    cx.emit(ViewEvent::SearchStarted { local });
                 }
             }
         }
@@ -1310,6 +1325,7 @@ impl ProjectSearchView {
                 self.focus_results_editor(cx);
             }
         }
+        self.scroll_to_leader_position(cx);
 
         cx.emit(ViewEvent::UpdateTab);
         cx.notify();
@@ -1328,6 +1344,81 @@ impl ProjectSearchView {
         }
     }
 
+    fn query_to_proto(&self, cx: &AppContext) -> Option<proto::ProjectSearchQuery> {
+        if self.current_mode == SearchMode::Semantic {
+            return None;
+        }
+        let query = self.model.read(cx).active_query.as_ref()?;
+        let join_matchers = |matchers: &[PathMatcher]| {
+            matchers
+                .iter()
+                .map(|matcher| matcher.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        Some(proto::ProjectSearchQuery {
+            query: query.as_str().to_string(),
+            regex: query.is_regex(),
+            whole_word: query.whole_word(),
+            case_sensitive: query.case_sensitive(),
+            include_ignored: query.include_ignored(),
+            files_to_include: join_matchers(query.files_to_include()),
+            files_to_exclude: join_matchers(query.files_to_exclude()),
+        })
+    }
+
+    /// Repeats the leader's search and scrolls to where the leader is looking.
+    fn apply_leader_state(
+        &mut self,
+        query: Option<proto::ProjectSearchQuery>,
+        scroll_position: Option<proto::BufferScrollPosition>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(query) = query {
+            self.set_query(&query.query, cx);
+            self.included_files_editor.update(cx, |editor, cx| {
+                editor.set_text(query.files_to_include.as_str(), cx)
+            });
+            self.excluded_files_editor.update(cx, |editor, cx| {
+                editor.set_text(query.files_to_exclude.as_str(), cx)
+            });
+            self.filters_enabled |=
+                !query.files_to_include.is_empty() || !query.files_to_exclude.is_empty();
+            self.search_options = SearchOptions::NONE;
+            self.search_options
+                .set(SearchOptions::WHOLE_WORD, query.whole_word);
+            self.search_options
+                .set(SearchOptions::CASE_SENSITIVE, query.case_sensitive);
+            self.search_options
+                .set(SearchOptions::INCLUDE_IGNORED, query.include_ignored);
+            self.semantic_state = None;
+            self.current_mode = if query.regex {
+                SearchMode::Regex
+            } else {
+                SearchMode::Text
+            };
+            self.pending_scroll_position = None;
+            self.run_search(false, cx);
+        }
+
+        if let Some(scroll_position) = scroll_position {
+            self.pending_scroll_position = Some(scroll_position);
+            self.scroll_to_leader_position(cx);
+        }
+        cx.notify();
+    }
+
+    fn scroll_to_leader_position(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(position) = self.pending_scroll_position.take() {
+            let scrolled = self.results_editor.update(cx, |editor, cx| {
+                editor.set_buffer_scroll_position_remote(&position, cx)
+            });
+            if !scrolled {
+                self.pending_scroll_position = Some(position);
+            }
+        }
+    }
+
     pub fn has_matches(&self) -> bool {
         self.active_match_index.is_some()
     }
@@ -1355,6 +1446,116 @@ impl ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::ProjectSearch(
+            proto::view::ProjectSearch {
+                query: self.query_to_proto(cx),
+                scroll_position: Some(self.results_editor.read(cx).buffer_scroll_position()),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        let existing_view = pane
+            .read(cx)
+            .items_of_type::<Self>()
+            .find(|view| view.read(cx).remote_id == Some(remote_id));
+        let view = existing_view.unwrap_or_else(|| {
+            let project = workspace.read(cx).project().clone();
+            let model = cx.new_model(|cx| ProjectSearch::new(project, cx));
+            cx.new_view(|cx| {
+                let mut view = Self::new(model, cx, None);
+                view.remote_id = Some(remote_id);
+                view
+            })
+        });
+        view.update(cx, |view, cx| {
+            view.apply_leader_state(state.query, state.scroll_position, cx)
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    fn to_follow_event(event: &ViewEvent) -> Option<FollowEvent> {
+        match event {
+            ViewEvent::SearchStarted { local: true }
+            | ViewEvent::EditorEvent(EditorEvent::ScrollPositionChanged {
+                local: true,
+                autoscroll: false,
+            }) => Some(FollowEvent::Unfollow),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &ViewEvent,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let update = update
+            .get_or_insert_with(|| proto::update_view::Variant::ProjectSearch(Default::default()));
+        let proto::update_view::Variant::ProjectSearch(update) = update else {
+            return false;
+        };
+
+        match event {
+            ViewEvent::SearchStarted { .. } => {
+                update.query = self.query_to_proto(cx);
+                update.query.is_some()
+            }
+            ViewEvent::EditorEvent(EditorEvent::ScrollPositionChanged { .. }) => {
+                update.scroll_position =
+                    Some(self.results_editor.read(cx).buffer_scroll_position());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::ProjectSearch(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "received a non-search update for a project search"
+            )));
+        };
+        self.apply_leader_state(message.query, message.scroll_position, cx);
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        });
+    }
+}
+
 impl ProjectSearchBar {
     pub fn new() -> Self {
         Self {
@@ -2096,7 +2297,7 @@ fn register_workspace_action_for_present_search<A: Action>(
 pub mod tests {
     use super::*;
     use editor::DisplayPoint;
-    use gpui::{Action, TestAppContext};
+    use gpui::{Action, TestAppContext, VisualTestContext};
     use project::FakeFs;
     use semantic_index::semantic_index_settings::SemanticIndexSettings;
     use serde_json::json;
@@ -2234,6 +2435,135 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_following_project_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+                "four.rs": "const FOUR: usize = one::ONE + three::THREE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let workspace = window.root(cx).unwrap();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+
+        let leader = cx.new_view(|cx| {
+            let model = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+            ProjectSearchView::new(model, cx, None)
+        });
+        leader.update(cx, |leader, cx| {
+            leader.set_query("ONE", cx);
+            leader.search(cx);
+        });
+        cx.run_until_parked();
+
+        // The follower repeats the leader's search.
+        let state = cx.update(|cx| leader.read(cx).to_state_proto(cx)).unwrap();
+        let follower = cx
+            .update(|cx| {
+                let pane = workspace.read(cx).active_pane().clone();
+                let remote_id = ViewId {
+                    creator: PeerId { owner_id: 0, id: 1 },
+                    id: 1,
+                };
+                ProjectSearchView::from_state_proto(
+                    pane,
+                    workspace.clone(),
+                    remote_id,
+                    &mut Some(state),
+                    cx,
+                )
+                .unwrap()
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        let leader_results = leader.update(cx, |leader, cx| {
+            leader
+                .results_editor
+                .update(cx, |editor, cx| editor.display_text(cx))
+        });
+        follower.update(cx, |follower, cx| {
+            assert_eq!(follower.query_editor.read(cx).text(cx), "ONE");
+            assert_eq!(
+                follower
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx)),
+                leader_results
+            );
+        });
+
+        // The follower scrolls to where the leader is looking, even though its excerpts
+        // were created independently.
+        let scroll_event = ViewEvent::EditorEvent(EditorEvent::ScrollPositionChanged {
+            local: true,
+            autoscroll: false,
+        });
+        leader.update(cx, |leader, cx| {
+            leader.results_editor.update(cx, |editor, cx| {
+                editor.set_scroll_position(gpui::point(0., 4.), cx)
+            })
+        });
+        let mut update = None;
+        assert!(cx.update(|cx| {
+            leader
+                .read(cx)
+                .add_event_to_update_proto(&scroll_event, &mut update, cx)
+        }));
+        follower
+            .update(cx, |follower, cx| {
+                follower.apply_update_proto(&project, update.unwrap(), cx)
+            })
+            .await
+            .unwrap();
+        follower.update(cx, |follower, cx| {
+            assert_eq!(
+                follower
+                    .results_editor
+                    .update(cx, |editor, cx| editor.scroll_position(cx)),
+                gpui::point(0., 4.)
+            );
+        });
+
+        // A new search by the leader is repeated by the follower.
+        leader.update(cx, |leader, cx| {
+            leader.set_query("TWO", cx);
+            leader.search(cx);
+        });
+        let mut update = None;
+        assert!(cx.update(|cx| {
+            leader.read(cx).add_event_to_update_proto(
+                &ViewEvent::SearchStarted { local: true },
+                &mut update,
+                cx,
+            )
+        }));
+        follower
+            .update(cx, |follower, cx| {
+                follower.apply_update_proto(&project, update.unwrap(), cx)
+            })
+            .await
+            .unwrap();
+        cx.run_until_parked();
+        follower.update(cx, |follower, cx| {
+            assert_eq!(follower.query_editor.read(cx).text(cx), "TWO");
+            assert_eq!(
+                follower
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx)),
+                "\n\nconst THREE: usize = one::ONE + two::TWO;\n\n\nconst TWO: usize = one::ONE + one::ONE;"
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -4,7 +4,7 @@ use crate::{
 };
 use ai::embedding::Embedding;
 use anyhow::{anyhow, Context, Result};
-use collections::HashMap;
+use collections::{hash_map::Entry, HashMap};
 use futures::channel::oneshot;
 use gpui::BackgroundExecutor;
 use ndarray::{Array1, Array2};
@@ -14,7 +14,9 @@ use rpc::proto::Timestamp;
 use rusqlite::params;
 use rusqlite::types::Value;
 use std::{
+    cmp::Reverse,
     future::Future,
+    hash::Hash,
     ops::Range,
     path::{Path, PathBuf},
     rc::Rc,
@@ -30,6 +32,63 @@ pub fn argsort<T: Ord>(data: &[T]) -> Vec<usize> {
     indices
 }
 
+/// Dampens the influence of the top ranks when fusing rankings, as in the original
+/// reciprocal rank fusion paper.
+const RANK_FUSION_K: f32 = 60.;
+
+/// Fuses several rankings, each sorted from best to worst, into a single ranking using
+/// reciprocal rank fusion. This allows combining rankings whose scores aren't comparable,
+/// such as embedding similarities and BM25 scores.
+pub fn fuse_rankings<T: Copy + Eq + Hash>(
+    rankings: &[&[(T, OrderedFloat<f32>)]],
+    limit: usize,
+) -> Vec<(T, OrderedFloat<f32>)> {
+    let mut scores = Vec::<(T, OrderedFloat<f32>)>::new();
+    let mut indices_by_id = HashMap::<T, usize>::default();
+    for ranking in rankings {
+        for (rank, (id, _)) in ranking.iter().enumerate() {
+            let score = 1. / (RANK_FUSION_K + rank as f32 + 1.);
+            match indices_by_id.entry(*id) {
+                Entry::Occupied(entry) => scores[*entry.get()].1 .0 += score,
+                Entry::Vacant(entry) => {
+                    entry.insert(scores.len());
+                    scores.push((*id, OrderedFloat(score)));
+                }
+            }
+        }
+    }
+
+    // Sorting is stable, so ties are broken by the order of the rankings.
+    scores.sort_by_key(|(_, score)| Reverse(*score));
+    scores.truncate(limit);
+    scores
+}
+
+/// Returns how many of the words in `query` occur in `content`, ignoring case.
+pub fn keyword_score(query: &str, content: &str) -> usize {
+    let content = content.to_lowercase();
+    query
+        .split_whitespace()
+        .filter(|term| content.contains(&term.to_lowercase()))
+        .count()
+}
+
+/// Converts a search query into an FTS5 query that matches spans containing any of its
+/// words, quoting each word so that identifiers and punctuation are matched literally.
+pub fn keyword_query(query: &str) -> Option<String> {
+    let terms = query
+        .split_whitespace()
+        .map(|term| term.replace('"', ""))
+        .filter(|term| !term.is_empty())
+        .map(|term| format!("\"{term}\""))
+        .collect::<Vec<_>>();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" OR "))
+    }
+}
+
 #[derive(Debug)]
 pub struct FileRecord {
     pub id: usize,
@@ -136,6 +195,8 @@ impl VectorDatabase {
             // `documents` without recreating it if it exists.
             db.execute("DROP TABLE IF EXISTS documents", [])
                 .context("failed to drop 'documents' table")?;
+            db.execute("DROP TABLE IF EXISTS spans_fts", [])
+                .context("failed to drop 'spans_fts' table")?;
             db.execute("DROP TABLE IF EXISTS spans", [])
                 .context("failed to drop 'spans' table")?;
             db.execute("DROP TABLE IF EXISTS files", [])
@@ -203,6 +264,18 @@ impl VectorDatabase {
                 [],
             )?;
 
+            // The full-text index of span contents shares its rowids with the spans table.
+            db.execute(
+                "CREATE VIRTUAL TABLE spans_fts USING fts5(content)",
+                [],
+            )?;
+            db.execute(
+                "CREATE TRIGGER spans_fts_delete AFTER DELETE ON spans BEGIN
+                    DELETE FROM spans_fts WHERE rowid = old.id;
+                END",
+                [],
+            )?;
+
             log::trace!("vector database initialized with updated schema.");
             Ok(())
         })
@@ -251,6 +324,8 @@ impl VectorDatabase {
                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
                 ",
             )?;
+            let mut fts_query =
+                db.prepare("INSERT INTO spans_fts (rowid, content) VALUES (?1, ?2)")?;
 
             for span in spans {
                 query.execute(params![
@@ -261,6 +336,7 @@ impl VectorDatabase {
                     span.embedding,
                     span.digest
                 ])?;
+                fts_query.execute(params![db.last_insert_rowid(), span.content])?;
             }
 
             Ok(())
@@ -504,6 +580,49 @@ impl VectorDatabase {
         })
     }
 
+    /// Returns the spans in the given files whose contents match `query`, ranked by BM25.
+    pub fn keyword_search(
+        &self,
+        query: &str,
+        limit: usize,
+        file_ids: &[i64],
+    ) -> impl Future<Output = Result<Vec<(i64, OrderedFloat<f32>)>>> {
+        let query = keyword_query(query);
+        let file_ids = file_ids.to_vec();
+        self.transact(move |db| {
+            let Some(query) = query else {
+                return Ok(Vec::new());
+            };
+
+            let mut statement = db.prepare(
+                "
+                    SELECT
+                        spans.id, bm25(spans_fts)
+                    FROM
+                        spans_fts, spans
+                    WHERE
+                        spans_fts MATCH ?1 AND
+                        spans.id = spans_fts.rowid AND
+                        spans.file_id IN rarray(?2)
+                    ORDER BY
+                        bm25(spans_fts)
+                    LIMIT ?3
+                ",
+            )?;
+
+            // BM25 scores are negative, with lower scores being better matches.
+            let results = statement
+                .query_map(params![query, ids_to_sql(&file_ids), limit], |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        OrderedFloat(-row.get::<_, f64>(1)? as f32),
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+            Ok(results)
+        })
+    }
+
     pub fn retrieve_included_file_ids(
         &self,
         worktree_ids: &[i64],
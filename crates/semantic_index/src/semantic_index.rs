@@ -42,7 +42,7 @@ use util::paths::PathMatcher;
 use util::{http::HttpClient, paths::EMBEDDINGS_DIR, ResultExt};
 use workspace::Workspace;
 
-const SEMANTIC_INDEX_VERSION: usize = 11;
+const SEMANTIC_INDEX_VERSION: usize = 12;
 const BACKGROUND_INDEXING_DELAY: Duration = Duration::from_secs(5 * 60);
 const EMBEDDING_QUEUE_FLUSH_TIMEOUT: Duration = Duration::from_millis(250);
 
@@ -279,6 +279,8 @@ pub struct PendingFile {
 pub struct SearchResult {
     pub buffer: Model<Buffer>,
     pub range: Range<Anchor>,
+    /// How relevant the result is, combining its similarity to the query with how well it
+    /// matches the query's keywords.
     pub similarity: OrderedFloat<f32>,
 }
 
@@ -739,6 +741,7 @@ impl SemanticIndex {
             index.await?;
             let t0 = Instant::now();
 
+            let keywords = query.clone();
             let query = embedding_provider
                 .embed_batch(vec![query])
                 .await?
@@ -751,6 +754,7 @@ impl SemanticIndex {
                 this.search_modified_buffers(
                     &project,
                     query.clone(),
+                    keywords.clone(),
                     limit,
                     &includes,
                     &excludes,
@@ -758,7 +762,7 @@ impl SemanticIndex {
                 )
             })?;
             let file_results = this.update(&mut cx, |this, cx| {
-                this.search_files(project, query, limit, includes, excludes, cx)
+                this.search_files(project, query, keywords, limit, includes, excludes, cx)
             })?;
             let (modified_buffer_results, file_results) =
                 futures::join!(modified_buffer_results, file_results);
@@ -782,10 +786,13 @@ impl SemanticIndex {
         })
     }
 
+    /// Searches the indexed files of the project, fusing the ranking of spans by their
+    /// similarity to `query` with their ranking by how well they match `keywords`.
     pub fn search_files(
         &mut self,
         project: Model<Project>,
         query: Embedding,
+        keywords: String,
         limit: usize,
         includes: Vec<PathMatcher>,
         excludes: Vec<PathMatcher>,
@@ -874,6 +881,13 @@ impl SemanticIndex {
                 }
             }
 
+            let keyword_results = database
+                .keyword_search(&keywords, limit, &file_ids)
+                .await
+                .log_err()
+                .unwrap_or_default();
+            let results = db::fuse_rankings(&[&results, &keyword_results], limit);
+
             let ids = results.iter().map(|(id, _)| *id).collect::<Vec<i64>>();
             let scores = results
                 .into_iter()
@@ -930,6 +944,7 @@ impl SemanticIndex {
         &self,
         project: &Model<Project>,
         query: Embedding,
+        keywords: String,
         limit: usize,
         includes: &[PathMatcher],
         excludes: &[PathMatcher],
@@ -968,7 +983,8 @@ impl SemanticIndex {
         let background = cx.background_executor().clone();
         cx.background_executor().spawn(async move {
             let db = VectorDatabase::new(fs, db_path.clone(), background).await?;
-            let mut results = Vec::<SearchResult>::new();
+            let mut candidates = Vec::<SearchResult>::new();
+            let mut keyword_ranking = Vec::new();
 
             let mut retriever = CodeContextRetriever::new(embedding_provider.clone());
             for (buffer, snapshot) in modified_buffers {
@@ -986,14 +1002,12 @@ impl SemanticIndex {
                     .is_some()
                 {
                     for span in spans {
-                        let similarity = span.embedding.unwrap().similarity(&query);
-                        let ix = match results
-                            .binary_search_by_key(&Reverse(similarity), |result| {
-                                Reverse(result.similarity)
-                            }) {
-                            Ok(ix) => ix,
-                            Err(ix) => ix,
-                        };
+                        // These spans aren't in the full-text index, so match keywords in memory.
+                        let keyword_score = db::keyword_score(&keywords, &span.content);
+                        if keyword_score > 0 {
+                            keyword_ranking
+                                .push((candidates.len(), OrderedFloat(keyword_score as f32)));
+                        }
 
                         let range = {
                             let start = snapshot.clip_offset(span.range.start, Bias::Left);
@@ -1001,19 +1015,32 @@ impl SemanticIndex {
                             snapshot.anchor_before(start)..snapshot.anchor_after(end)
                         };
 
-                        results.insert(
-                            ix,
-                            SearchResult {
-                                buffer: buffer.clone(),
-                                range,
-                                similarity,
-                            },
-                        );
-                        results.truncate(limit);
+                        candidates.push(SearchResult {
+                            buffer: buffer.clone(),
+                            range,
+                            similarity: span.embedding.unwrap().similarity(&query),
+                        });
                     }
                 }
             }
 
+            let mut vector_ranking = candidates
+                .iter()
+                .enumerate()
+                .map(|(ix, result)| (ix, result.similarity))
+                .collect::<Vec<_>>();
+            vector_ranking.sort_by_key(|(_, similarity)| Reverse(*similarity));
+            vector_ranking.truncate(limit);
+            keyword_ranking.sort_by_key(|(_, score)| Reverse(*score));
+            keyword_ranking.truncate(limit);
+
+            let results = db::fuse_rankings(&[&vector_ranking, &keyword_ranking], limit)
+                .into_iter()
+                .map(|(ix, score)| SearchResult {
+                    similarity: score,
+                    ..candidates[ix].clone()
+                })
+                .collect();
             Ok(results)
         })
     }
@@ -1,4 +1,5 @@
 use crate::{
+    db::{fuse_rankings, keyword_query},
     embedding_queue::EmbeddingQueue,
     parsing::{subtract_ranges, CodeContextRetriever, Span, SpanDigest},
     semantic_index_settings::SemanticIndexSettings,
@@ -8,6 +9,7 @@ use ai::test::FakeEmbeddingProvider;
 
 use gpui::{Task, TestAppContext};
 use language::{Language, LanguageConfig, LanguageMatcher, LanguageRegistry, ToOffset};
+use ordered_float::OrderedFloat;
 use parking_lot::Mutex;
 use pretty_assertions::assert_eq;
 use project::{project_settings::ProjectSettings, FakeFs, Fs, Project};
@@ -192,6 +194,133 @@ async fn test_semantic_index(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_hybrid_search(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    // The first function mentions the queried identifier, but its embedding is dominated by
+    // the letter 'e'. The second one's name is an anagram of the query, so its embedding is
+    // nearly identical to the query's.
+    let animals = "
+        fn zebra() {
+            println!(\"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee\");
+        }
+
+        fn bazer() {
+            println!(\"bazer\");
+        }
+    "
+    .unindent();
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            "src": {
+                "animals.rs": animals.clone(),
+                "config.toml": "zebra = 1\n",
+            }
+        }),
+    )
+    .await;
+
+    let languages = Arc::new(LanguageRegistry::new(Task::ready(())));
+    languages.add(rust_lang());
+    languages.add(toml_lang());
+
+    let db_dir = tempfile::Builder::new()
+        .prefix("vector-store")
+        .tempdir()
+        .unwrap();
+    let db_path = db_dir.path().join("db.sqlite");
+
+    let embedding_provider = Arc::new(FakeEmbeddingProvider::default());
+    let semantic_index = SemanticIndex::new(
+        fs.clone(),
+        db_path,
+        embedding_provider.clone(),
+        languages,
+        cx.to_async(),
+    )
+    .await
+    .unwrap();
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+
+    let search_results = semantic_index.update(cx, |store, cx| {
+        store.search_project(
+            project.clone(),
+            "zebra".to_string(),
+            2,
+            vec![],
+            vec![PathMatcher::new("*.toml").unwrap()],
+            cx,
+        )
+    });
+    cx.background_executor.run_until_parked();
+    cx.background_executor
+        .advance_clock(EMBEDDING_QUEUE_FLUSH_TIMEOUT);
+
+    // The exact keyword match outranks the span that is only similar to the query.
+    let search_results = search_results.await.unwrap();
+    assert_search_results(
+        &search_results,
+        &[
+            (Path::new("src/animals.rs").into(), 0),
+            (
+                Path::new("src/animals.rs").into(),
+                animals.find("fn bazer").unwrap(),
+            ),
+        ],
+        cx,
+    );
+
+    // Keyword matches are subject to the same filters as embedding matches.
+    let toml_search_results = semantic_index
+        .update(cx, |store, cx| {
+            store.search_project(
+                project.clone(),
+                "zebra".to_string(),
+                2,
+                vec![PathMatcher::new("*.toml").unwrap()],
+                vec![],
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert_search_results(
+        &toml_search_results,
+        &[(Path::new("src/config.toml").into(), 0)],
+        cx,
+    );
+}
+
+#[test]
+fn test_fuse_rankings() {
+    let vector_ranking = [(1, OrderedFloat(0.9)), (2, OrderedFloat(0.8))];
+    let keyword_ranking = [(3, OrderedFloat(10.)), (2, OrderedFloat(5.))];
+    assert_eq!(
+        fuse_rankings(&[&vector_ranking, &keyword_ranking], 3)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        [2, 1, 3]
+    );
+    assert_eq!(
+        fuse_rankings(&[&vector_ranking, &[]], 1)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        [1]
+    );
+
+    assert_eq!(keyword_query("  "), None);
+    assert_eq!(
+        keyword_query("parse_config \"path\""),
+        Some("\"parse_config\" OR \"path\"".to_string())
+    );
+}
+
 #[gpui::test(iterations = 10)]
 async fn test_embedding_batching(cx: &mut TestAppContext, mut rng: StdRng) {
     let (outstanding_job_count, _) = postage::watch::channel_with(0);
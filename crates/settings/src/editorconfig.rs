@@ -0,0 +1,305 @@
+//! Support for [EditorConfig](https://editorconfig.org) files, which are applied on top of
+//! the language settings of the files they match.
+
+use anyhow::{anyhow, Context, Result};
+use collections::HashMap;
+use globset::{GlobBuilder, GlobMatcher};
+use std::{borrow::Cow, num::NonZeroU32, path::Path};
+
+/// The name of EditorConfig files.
+pub const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// A parsed `.editorconfig` file.
+#[derive(Clone, Debug)]
+pub struct EditorConfig {
+    /// Whether EditorConfig files in parent directories are ignored.
+    pub root: bool,
+    sections: Vec<EditorConfigSection>,
+}
+
+#[derive(Clone, Debug)]
+struct EditorConfigSection {
+    glob: GlobMatcher,
+    properties: Vec<(String, String)>,
+}
+
+impl EditorConfig {
+    pub fn parse(content: &str) -> Result<Self> {
+        let mut root = false;
+        let mut sections = Vec::<EditorConfigSection>::new();
+        for (row, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(pattern) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                sections.push(EditorConfigSection {
+                    glob: section_glob(pattern)
+                        .with_context(|| format!("invalid section on line {}", row + 1))?,
+                    properties: Vec::new(),
+                });
+            } else if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim().to_lowercase();
+                let value = value.trim().to_string();
+                if let Some(section) = sections.last_mut() {
+                    section.properties.push((key, value));
+                } else if key == "root" {
+                    root = value.eq_ignore_ascii_case("true");
+                }
+            } else {
+                return Err(anyhow!("invalid line {}: {line:?}", row + 1));
+            }
+        }
+
+        Ok(Self { root, sections })
+    }
+
+    /// Sets the properties of every section matching the given path, which is relative to
+    /// the directory containing this file. Later sections take precedence.
+    fn apply(&self, path: &Path, properties: &mut HashMap<String, String>) {
+        for section in &self.sections {
+            if section.glob.is_match(path) {
+                for (key, value) in &section.properties {
+                    properties.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// Converts a section name into a glob. Names without a slash match files in any
+/// subdirectory, `*` never matches a slash, and `**` matches any string.
+fn section_glob(pattern: &str) -> Result<GlobMatcher> {
+    let mut pattern = if let Some(pattern) = pattern.strip_prefix('/') {
+        pattern.to_string()
+    } else if pattern.contains('/') {
+        pattern.to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    // Unlike EditorConfig, globset only accepts `**` as a whole path component.
+    let mut ix = 0;
+    while let Some(offset) = pattern[ix..].find("**") {
+        ix += offset + 2;
+        if ix < pattern.len() && !pattern[ix..].starts_with('/') {
+            pattern.insert_str(ix, "/*");
+        }
+    }
+    Ok(GlobBuilder::new(&pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher())
+}
+
+/// Returns the properties that apply to a file, given the EditorConfig files found in its
+/// ancestor directories, ordered from the outermost directory to the innermost, along with
+/// the file's path relative to each of those directories.
+pub(crate) fn properties_for_path<'a>(
+    configs: impl IntoIterator<Item = (Cow<'a, Path>, &'a EditorConfig)>,
+) -> EditorConfigProperties {
+    let mut properties = HashMap::default();
+    for (relative_path, config) in configs {
+        config.apply(&relative_path, &mut properties);
+    }
+    EditorConfigProperties::from_raw(&properties)
+}
+
+/// The EditorConfig properties supported by Zed. Properties that are absent, `unset`, or
+/// invalid are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EditorConfigProperties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<NonZeroU32>,
+    pub end_of_line: Option<EndOfLine>,
+    pub charset: Option<Charset>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+    pub max_line_length: Option<MaxLineLength>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentSize {
+    Columns(NonZeroU32),
+    /// Indent by the `tab_width`.
+    Tab,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EndOfLine {
+    Lf,
+    CrLf,
+    Cr,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Charset {
+    Latin1,
+    Utf8,
+    Utf8Bom,
+    Utf16Be,
+    Utf16Le,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaxLineLength {
+    Columns(u32),
+    Off,
+}
+
+impl EditorConfigProperties {
+    fn from_raw(properties: &HashMap<String, String>) -> Self {
+        let get = |key: &str| {
+            properties
+                .get(key)
+                .map(|value| value.to_lowercase())
+                .filter(|value| value != "unset")
+        };
+        let get_bool = |key: &str| match get(key)?.as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+
+        Self {
+            indent_style: get("indent_style").and_then(|value| match value.as_str() {
+                "tab" => Some(IndentStyle::Tab),
+                "space" => Some(IndentStyle::Space),
+                _ => None,
+            }),
+            indent_size: get("indent_size").and_then(|value| match value.as_str() {
+                "tab" => Some(IndentSize::Tab),
+                value => value.parse().ok().map(IndentSize::Columns),
+            }),
+            tab_width: get("tab_width").and_then(|value| value.parse().ok()),
+            end_of_line: get("end_of_line").and_then(|value| match value.as_str() {
+                "lf" => Some(EndOfLine::Lf),
+                "crlf" => Some(EndOfLine::CrLf),
+                "cr" => Some(EndOfLine::Cr),
+                _ => None,
+            }),
+            charset: get("charset").and_then(|value| match value.as_str() {
+                "latin1" => Some(Charset::Latin1),
+                "utf-8" => Some(Charset::Utf8),
+                "utf-8-bom" => Some(Charset::Utf8Bom),
+                "utf-16be" => Some(Charset::Utf16Be),
+                "utf-16le" => Some(Charset::Utf16Le),
+                _ => None,
+            }),
+            trim_trailing_whitespace: get_bool("trim_trailing_whitespace"),
+            insert_final_newline: get_bool("insert_final_newline"),
+            max_line_length: get("max_line_length").and_then(|value| match value.as_str() {
+                "off" => Some(MaxLineLength::Off),
+                value => value.parse().ok().map(MaxLineLength::Columns),
+            }),
+        }
+    }
+
+    /// Returns the width of a level of indentation, following the precedence between
+    /// `indent_size` and `tab_width` that the EditorConfig specification defines.
+    pub fn tab_size(&self) -> Option<NonZeroU32> {
+        let indent_columns = match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            _ => None,
+        };
+        if self.indent_style == Some(IndentStyle::Tab) {
+            self.tab_width.or(indent_columns)
+        } else {
+            indent_columns.or(self.tab_width)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_editorconfig_properties() {
+        let root = EditorConfig::parse(
+            "
+            # Top-most EditorConfig file
+            root = true
+
+            [*]
+            indent_style = space
+            indent_size = 4
+            end_of_line = lf
+            insert_final_newline = true
+
+            [*.{js,ts}]
+            indent_size = 2
+
+            [Makefile]
+            indent_style = tab
+            tab_width = 8
+
+            [/docs/**.md]
+            trim_trailing_whitespace = false
+            max_line_length = off
+            ",
+        )
+        .unwrap();
+        let nested = EditorConfig::parse(
+            "
+            [*.ts]
+            indent_size = unset
+            charset = UTF-8
+            ",
+        )
+        .unwrap();
+        assert!(root.root);
+        assert!(!nested.root);
+
+        let properties = |path: &str| {
+            properties_for_path(
+                [(Path::new(""), &root), (Path::new("web"), &nested)]
+                    .into_iter()
+                    .filter_map(|(directory, config)| {
+                        let relative_path = Path::new(path).strip_prefix(directory).ok()?;
+                        Some((Cow::Borrowed(relative_path), config))
+                    }),
+            )
+        };
+
+        let rust = properties("src/main.rs");
+        assert_eq!(rust.indent_style, Some(IndentStyle::Space));
+        assert_eq!(rust.tab_size(), NonZeroU32::new(4));
+        assert_eq!(rust.end_of_line, Some(EndOfLine::Lf));
+        assert_eq!(rust.insert_final_newline, Some(true));
+        assert_eq!(rust.trim_trailing_whitespace, None);
+
+        assert_eq!(properties("lib/index.js").tab_size(), NonZeroU32::new(2));
+        assert_eq!(properties("Makefile").tab_size(), NonZeroU32::new(8));
+        assert_eq!(
+            properties("src/Makefile").indent_style,
+            Some(IndentStyle::Tab)
+        );
+
+        let docs = properties("docs/guide/intro.md");
+        assert_eq!(docs.trim_trailing_whitespace, Some(false));
+        assert_eq!(docs.max_line_length, Some(MaxLineLength::Off));
+        assert_eq!(
+            properties("src/docs/intro.md").trim_trailing_whitespace,
+            None
+        );
+
+        let nested_ts = properties("web/app.ts");
+        assert_eq!(nested_ts.indent_size, None);
+        assert_eq!(nested_ts.tab_size(), None);
+        assert_eq!(nested_ts.charset, Some(Charset::Utf8));
+        assert_eq!(properties("app.ts").tab_size(), NonZeroU32::new(2));
+
+        assert!(EditorConfig::parse("[*.rs\nindent_size = 2").is_err());
+    }
+}
@@ -7,6 +7,8 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
+    borrow::Cow,
+    cell::RefCell,
     fmt::Debug,
     ops::Range,
     path::Path,
@@ -15,6 +17,8 @@ use std::{
 };
 use util::{merge_non_null_json_value_into, RangeExt, ResultExt as _};
 
+use crate::editorconfig::{self, EditorConfig, EditorConfigProperties};
+
 /// A value that can be defined as a user setting.
 ///
 /// Settings can be loaded from a combination of multiple JSON files.
@@ -132,6 +136,14 @@ pub struct SettingsStore {
     raw_default_settings: serde_json::Value,
     raw_user_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    editorconfigs: HashMap<usize, BTreeMap<Arc<Path>, (String, EditorConfig)>>,
+    parent_editorconfigs: HashMap<usize, BTreeMap<Arc<Path>, (String, EditorConfig)>>,
+    resolved_editorconfig_properties:
+        RefCell<HashMap<(usize, Arc<Path>), Option<Arc<EditorConfigProperties>>>>,
+    /// Values computed from the EditorConfig properties of each file, by type. They're dropped
+    /// whenever settings or EditorConfig files change.
+    editorconfig_values:
+        RefCell<HashMap<(TypeId, usize, Arc<Path>), Option<Box<dyn Any + Send + Sync>>>>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -147,6 +159,10 @@ impl Default for SettingsStore {
             raw_default_settings: serde_json::json!({}),
             raw_user_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            editorconfigs: Default::default(),
+            parent_editorconfigs: Default::default(),
+            resolved_editorconfig_properties: Default::default(),
+            editorconfig_values: Default::default(),
             tab_size_callback: Default::default(),
         }
     }
@@ -184,6 +200,7 @@ struct DeserializedSetting(Box<dyn Any>);
 impl SettingsStore {
     /// Add a new type of setting to the store.
     pub fn register_setting<T: Settings>(&mut self, cx: &mut AppContext) {
+        self.editorconfig_values.get_mut().clear();
         let setting_type_id = TypeId::of::<T>();
         let entry = self.setting_values.entry(setting_type_id);
         if matches!(entry, hash_map::Entry::Occupied(_)) {
@@ -247,6 +264,7 @@ impl SettingsStore {
     ///
     /// The given value will be overwritten if the user settings file changes.
     pub fn override_global<T: Settings>(&mut self, value: T) {
+        self.editorconfig_values.get_mut().clear();
         self.setting_values
             .get_mut(&TypeId::of::<T>())
             .unwrap_or_else(|| panic!("unregistered setting type {}", type_name::<T>()))
@@ -420,9 +438,178 @@ impl SettingsStore {
         Ok(())
     }
 
+    /// Add or remove the EditorConfig file in the given directory of a worktree.
+    pub fn set_local_editorconfig(
+        &mut self,
+        root_id: usize,
+        directory: Arc<Path>,
+        editorconfig_content: Option<&str>,
+    ) -> Result<()> {
+        set_editorconfig(
+            self.editorconfigs.entry(root_id).or_default(),
+            directory,
+            editorconfig_content,
+        )?;
+        self.resolved_editorconfig_properties
+            .get_mut()
+            .retain(|(id, _), _| *id != root_id);
+        self.editorconfig_values.get_mut().clear();
+        Ok(())
+    }
+
+    /// Add or remove the EditorConfig file in a directory above the root of a worktree,
+    /// given the path of the worktree's root relative to that directory.
+    pub fn set_parent_editorconfig(
+        &mut self,
+        root_id: usize,
+        root_path: Arc<Path>,
+        editorconfig_content: Option<&str>,
+    ) -> Result<()> {
+        set_editorconfig(
+            self.parent_editorconfigs.entry(root_id).or_default(),
+            root_path,
+            editorconfig_content,
+        )?;
+        self.resolved_editorconfig_properties
+            .get_mut()
+            .retain(|(id, _), _| *id != root_id);
+        self.editorconfig_values.get_mut().clear();
+        Ok(())
+    }
+
+    /// Returns the EditorConfig properties for the file at the given path in a worktree,
+    /// or `None` if no EditorConfig file applies to it. The properties are resolved once
+    /// per file, until the EditorConfig files of the worktree change.
+    pub fn editorconfig_properties(
+        &self,
+        root_id: usize,
+        path: &Arc<Path>,
+    ) -> Option<Arc<EditorConfigProperties>> {
+        if !self.editorconfigs.contains_key(&root_id)
+            && !self.parent_editorconfigs.contains_key(&root_id)
+        {
+            return None;
+        }
+
+        self.resolved_editorconfig_properties
+            .borrow_mut()
+            .entry((root_id, path.clone()))
+            .or_insert_with(|| {
+                self.resolve_editorconfig_properties(root_id, path)
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+
+    /// Returns a value computed from the EditorConfig properties of the file at the given
+    /// path in a worktree, such as settings merged with those properties, or `None` if no
+    /// EditorConfig file sets any property of the file. Each type of value is computed once
+    /// per file, until the settings or the EditorConfig files change.
+    pub fn editorconfig_value<T: 'static + Send + Sync>(
+        &self,
+        root_id: usize,
+        path: &Arc<Path>,
+        compute: impl FnOnce(&EditorConfigProperties) -> T,
+    ) -> Option<&T> {
+        if !self.editorconfigs.contains_key(&root_id)
+            && !self.parent_editorconfigs.contains_key(&root_id)
+        {
+            return None;
+        }
+
+        let key = (TypeId::of::<T>(), root_id, path.clone());
+        if !self.editorconfig_values.borrow().contains_key(&key) {
+            let value = self
+                .editorconfig_properties(root_id, path)
+                .filter(|properties| **properties != EditorConfigProperties::default())
+                .map(|properties| Box::new(compute(&properties)) as Box<dyn Any + Send + Sync>);
+            self.editorconfig_values
+                .borrow_mut()
+                .insert(key.clone(), value);
+        }
+
+        let value: *const (dyn Any + Send + Sync) =
+            self.editorconfig_values.borrow().get(&key)?.as_deref()?;
+        // SAFETY: The values are boxed, so they don't move when other values are cached, and
+        // they're only dropped through `&mut self`, which can't be borrowed while the returned
+        // reference borrows `self`.
+        unsafe { &*value }.downcast_ref()
+    }
+
+    fn resolve_editorconfig_properties(
+        &self,
+        root_id: usize,
+        path: &Path,
+    ) -> Option<EditorConfigProperties> {
+        let mut applicable = Vec::new();
+        let mut found_root = false;
+        if let Some(editorconfigs) = self.editorconfigs.get(&root_id) {
+            for directory in path.ancestors().skip(1) {
+                if let Some((_, editorconfig)) = editorconfigs.get(directory) {
+                    let relative_path = path.strip_prefix(directory).unwrap_or(path);
+                    applicable.push((Cow::Borrowed(relative_path), editorconfig));
+                    if editorconfig.root {
+                        found_root = true;
+                        break;
+                    }
+                }
+            }
+        }
+        if let Some(parent_editorconfigs) = self
+            .parent_editorconfigs
+            .get(&root_id)
+            .filter(|_| !found_root)
+        {
+            let mut parent_editorconfigs = parent_editorconfigs.iter().collect::<Vec<_>>();
+            parent_editorconfigs.sort_by_key(|(root_path, _)| root_path.components().count());
+            for (root_path, (_, editorconfig)) in parent_editorconfigs {
+                applicable.push((Cow::Owned(root_path.join(path)), editorconfig));
+                if editorconfig.root {
+                    break;
+                }
+            }
+        }
+        if applicable.is_empty() {
+            return None;
+        }
+
+        applicable.reverse();
+        Some(editorconfig::properties_for_path(applicable))
+    }
+
+    /// Returns the contents of the EditorConfig files in the given worktree, by directory.
+    pub fn local_editorconfigs(
+        &self,
+        root_id: usize,
+    ) -> impl '_ + Iterator<Item = (Arc<Path>, &str)> {
+        self.editorconfigs
+            .get(&root_id)
+            .into_iter()
+            .flatten()
+            .map(|(directory, (content, _))| (directory.clone(), content.as_str()))
+    }
+
+    /// Returns the contents of the EditorConfig files above the root of the given worktree,
+    /// by the path of the worktree's root relative to their directory.
+    pub fn parent_editorconfigs(
+        &self,
+        root_id: usize,
+    ) -> impl '_ + Iterator<Item = (Arc<Path>, &str)> {
+        self.parent_editorconfigs
+            .get(&root_id)
+            .into_iter()
+            .flatten()
+            .map(|(root_path, (content, _))| (root_path.clone(), content.as_str()))
+    }
+
     /// Add or remove a set of local settings via a JSON string.
     pub fn clear_local_settings(&mut self, root_id: usize, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings.retain(|k, _| k.0 != root_id);
+        self.editorconfigs.remove(&root_id);
+        self.parent_editorconfigs.remove(&root_id);
+        self.resolved_editorconfig_properties
+            .get_mut()
+            .retain(|(id, _), _| *id != root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
@@ -524,6 +711,8 @@ impl SettingsStore {
         changed_local_path: Option<(usize, &Path)>,
         cx: &mut AppContext,
     ) -> Result<()> {
+        self.editorconfig_values.get_mut().clear();
+
         // Reload the global and local values for every setting.
         let mut user_settings_stack = Vec::<DeserializedSetting>::new();
         let mut paths_stack = Vec::<Option<(usize, &Path)>>::new();
@@ -915,6 +1104,20 @@ fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len:
     adjusted_text
 }
 
+fn set_editorconfig(
+    editorconfigs: &mut BTreeMap<Arc<Path>, (String, EditorConfig)>,
+    directory: Arc<Path>,
+    editorconfig_content: Option<&str>,
+) -> Result<()> {
+    if let Some(content) = editorconfig_content {
+        let editorconfig = EditorConfig::parse(content)?;
+        editorconfigs.insert(directory, (content.to_string(), editorconfig));
+    } else {
+        editorconfigs.remove(&directory);
+    }
+    Ok(())
+}
+
 pub fn parse_json_with_comments<T: DeserializeOwned>(content: &str) -> Result<T> {
     Ok(serde_json_lenient::from_str(content)?)
 }
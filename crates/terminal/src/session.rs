@@ -0,0 +1,115 @@
+//! Terminal sessions that outlive Zed. The shell of each session runs in a tmux server,
+//! which keeps it alive while no terminal is attached to it. When tmux isn't installed,
+//! terminals run their shell directly instead.
+
+use crate::terminal_settings::Shell;
+use anyhow::{ensure, Result};
+use lazy_static::lazy_static;
+use std::{
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SESSION_PROGRAM: &str = "tmux";
+const SESSION_NAME_PREFIX: &str = "zed-";
+
+lazy_static! {
+    static ref IS_AVAILABLE: bool = Command::new(SESSION_PROGRAM)
+        .arg("-V")
+        .output()
+        .map_or(false, |output| output.status.success());
+}
+
+/// Returns whether the program that runs sessions is installed.
+pub fn is_available() -> bool {
+    *IS_AVAILABLE
+}
+
+/// Returns a session name that is unique across Zed processes.
+pub fn new_session_name() -> String {
+    static NEXT_SESSION_ID: AtomicUsize = AtomicUsize::new(0);
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    format!(
+        "{SESSION_NAME_PREFIX}{}-{timestamp}-{}",
+        std::process::id(),
+        NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+/// Returns the shell that attaches to the given session, creating the session to run
+/// `shell` if it doesn't exist yet.
+pub fn shell_for_session(shell: &Shell, session: &str) -> Shell {
+    let mut args = vec![
+        "new-session".to_string(),
+        "-A".to_string(),
+        "-s".to_string(),
+        session.to_string(),
+    ];
+    match shell {
+        Shell::System => {}
+        Shell::Program(program) => args.push(program.clone()),
+        Shell::WithArguments {
+            program,
+            args: shell_args,
+        } => {
+            args.push(program.clone());
+            args.extend(shell_args.iter().cloned());
+        }
+    }
+    // The terminal's tab already identifies the session, so hide tmux's status line.
+    args.extend([";", "set-option", "status", "off"].map(String::from));
+
+    Shell::WithArguments {
+        program: SESSION_PROGRAM.to_string(),
+        args,
+    }
+}
+
+/// Ends the given session, terminating its shell.
+pub fn kill_session(session: &str) -> Result<()> {
+    let status = Command::new(SESSION_PROGRAM)
+        .args(["kill-session", "-t", session])
+        .status()?;
+    ensure!(status.success(), "failed to end terminal session {session}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_for_session() {
+        assert_eq!(
+            shell_for_session(
+                &Shell::WithArguments {
+                    program: "fish".into(),
+                    args: vec!["--login".into()],
+                },
+                "zed-1"
+            ),
+            Shell::WithArguments {
+                program: "tmux".into(),
+                args: [
+                    "new-session",
+                    "-A",
+                    "-s",
+                    "zed-1",
+                    "fish",
+                    "--login",
+                    ";",
+                    "set-option",
+                    "status",
+                    "off"
+                ]
+                .map(String::from)
+                .to_vec(),
+            }
+        );
+
+        assert_ne!(new_session_name(), new_session_name());
+    }
+}
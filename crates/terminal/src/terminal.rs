@@ -1,5 +1,6 @@
 pub mod mappings;
 pub use alacritty_terminal;
+pub mod session;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -10,12 +11,12 @@ use alacritty_terminal::{
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Processor, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
@@ -39,6 +40,7 @@ use theme::{ActiveTheme, Theme};
 use util::truncate_and_trailoff;
 
 use std::{
+    borrow::Cow,
     cmp::{self, min},
     fmt::Display,
     ops::{Deref, Index, RangeInclusive},
@@ -80,6 +82,8 @@ pub enum Event {
     CloseTerminal,
     Bell,
     Wakeup,
+    /// The screen was scrolled through the scrollback.
+    Scrolled,
     BlinkChanged,
     SelectionsChanged,
     NewNavigationTarget(Option<MaybeNavigationTarget>),
@@ -173,6 +177,20 @@ impl TerminalSize {
     pub fn line_height(&self) -> Pixels {
         self.line_height
     }
+
+    /// Returns a size with the same cell dimensions that fits exactly the given number
+    /// of lines and columns.
+    fn with_grid_size(&self, lines: usize, columns: usize) -> Self {
+        // Half a cell of slack keeps rounding errors from losing a line or column.
+        TerminalSize::new(
+            self.line_height,
+            self.cell_width,
+            Size {
+                width: self.cell_width * (columns as f32 + 0.5),
+                height: self.line_height * (lines as f32 + 0.5),
+            },
+        )
+    }
 }
 impl Default for TerminalSize {
     fn default() -> Self {
@@ -289,6 +307,7 @@ impl TerminalBuilder {
         env: HashMap<String, String>,
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
+        session: Option<String>,
         window: AnyWindowHandle,
     ) -> Result<TerminalBuilder> {
         let pty_options = {
@@ -363,7 +382,7 @@ impl TerminalBuilder {
             }
         };
 
-        let fd = pty.file().as_raw_fd();
+        let shell_fd = pty.file().as_raw_fd() as u32;
         let shell_pid = pty.child().id();
 
         //And connect them together
@@ -379,19 +398,63 @@ impl TerminalBuilder {
         let pty_tx = event_loop.channel();
         let _io_thread = event_loop.spawn(); // DANGER
 
+        let connection = TerminalConnection::Pty {
+            pty_tx: Notifier(pty_tx),
+            shell_pid,
+            shell_fd,
+        };
+        Ok(Self::build(term, connection, events_rx, session))
+    }
+
+    /// Creates a terminal that mirrors the screen of a terminal a collaborator shared,
+    /// sending the input typed into it to `input_tx` once they allow it.
+    pub fn new_remote(input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            Config::default(),
+            &TerminalSize::default(),
+            ZedListener(events_tx),
+        );
+        let connection = TerminalConnection::Remote(RemoteTerminal {
+            input_tx,
+            can_write: false,
+            title: String::new(),
+            mode: TermMode::default(),
+        });
+        Self::build(Arc::new(FairMutex::new(term)), connection, events_rx, None)
+    }
+
+    /// Creates a terminal that isn't connected to a shell. Its input is sent to
+    /// `input_tx`, and its output is written with [`Terminal::write_output`].
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn new_fake(input_tx: UnboundedSender<Vec<u8>>) -> TerminalBuilder {
+        let (events_tx, events_rx) = unbounded();
+        let term = Term::new(
+            Config::default(),
+            &TerminalSize::default(),
+            ZedListener(events_tx),
+        );
+        let connection = TerminalConnection::Fake { input_tx };
+        Self::build(Arc::new(FairMutex::new(term)), connection, events_rx, None)
+    }
+
+    fn build(
+        term: Arc<FairMutex<Term<ZedListener>>>,
+        connection: TerminalConnection,
+        events_rx: UnboundedReceiver<AlacTermEvent>,
+        session: Option<String>,
+    ) -> TerminalBuilder {
         let url_regex = RegexSearch::new(r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`]+"#).unwrap();
         let word_regex = RegexSearch::new(r#"[\w.\[\]:/@\-~]+"#).unwrap();
 
         let terminal = Terminal {
-            pty_tx: Notifier(pty_tx),
+            connection,
             term,
             events: VecDeque::with_capacity(10), //Should never get this high.
             last_content: Default::default(),
             last_mouse: None,
             matches: Vec::new(),
             selection_head: None,
-            shell_fd: fd as u32,
-            shell_pid,
             foreground_process_info: None,
             breadcrumb_text: String::new(),
             scroll_px: px(0.),
@@ -402,12 +465,13 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            session,
         };
 
-        Ok(TerminalBuilder {
+        TerminalBuilder {
             terminal,
             events_rx,
-        })
+        }
     }
 
     pub fn subscribe(mut self, cx: &mut ModelContext<Terminal>) -> Terminal {
@@ -536,8 +600,42 @@ pub enum SelectionPhase {
     Ended,
 }
 
+/// The screen of a terminal, as shown to the collaborators it's shared with.
+#[derive(Clone, Debug)]
+pub struct TerminalScreen {
+    pub title: String,
+    pub lines: usize,
+    pub columns: usize,
+    /// The cells of the screen, line by line.
+    pub cells: Vec<IndexedCell>,
+    pub cursor: AlacPoint,
+    pub mode: TermMode,
+}
+
+/// Where a terminal's screen comes from and its input goes to.
+enum TerminalConnection {
+    /// A shell running in a local pty.
+    Pty {
+        pty_tx: Notifier,
+        shell_pid: u32,
+        shell_fd: u32,
+    },
+    /// A terminal that a collaborator shared.
+    Remote(RemoteTerminal),
+    /// A terminal without a shell, whose input is sent to a channel.
+    #[cfg(any(test, feature = "test-support"))]
+    Fake { input_tx: UnboundedSender<Vec<u8>> },
+}
+
+struct RemoteTerminal {
+    input_tx: UnboundedSender<Vec<u8>>,
+    can_write: bool,
+    title: String,
+    mode: TermMode,
+}
+
 pub struct Terminal {
-    pty_tx: Notifier,
+    connection: TerminalConnection,
     term: Arc<FairMutex<Term<ZedListener>>>,
     events: VecDeque<InternalEvent>,
     /// This is only used for mouse mode cell change detection
@@ -548,8 +646,6 @@ pub struct Terminal {
     pub last_content: TerminalContent,
     pub selection_head: Option<AlacPoint>,
     pub breadcrumb_text: String,
-    shell_pid: u32,
-    shell_fd: u32,
     pub foreground_process_info: Option<LocalProcessInfo>,
     scroll_px: Pixels,
     next_link_id: usize,
@@ -558,6 +654,8 @@ pub struct Terminal {
     hovered_word: bool,
     url_regex: RegexSearch,
     word_regex: RegexSearch,
+    /// The persistent session this terminal's shell runs in, if any.
+    pub session: Option<String>,
 }
 
 impl Terminal {
@@ -613,9 +711,17 @@ impl Terminal {
 
     /// Updates the cached process info, returns whether the Zed-relevant info has changed
     fn update_process_info(&mut self) -> bool {
-        let mut pid = unsafe { libc::tcgetpgrp(self.shell_fd as i32) };
+        let TerminalConnection::Pty {
+            shell_pid,
+            shell_fd,
+            ..
+        } = self.connection
+        else {
+            return false;
+        };
+        let mut pid = unsafe { libc::tcgetpgrp(shell_fd as i32) };
         if pid < 0 {
-            pid = self.shell_pid as i32;
+            pid = shell_pid as i32;
         }
 
         if let Some(process_info) = LocalProcessInfo::with_root_pid(pid as u32) {
@@ -659,11 +765,25 @@ impl Terminal {
                 new_size.size.height = cmp::max(new_size.line_height, new_size.height());
                 new_size.size.width = cmp::max(new_size.cell_width, new_size.width());
 
-                self.last_content.size = new_size.clone();
+                match &self.connection {
+                    TerminalConnection::Pty { pty_tx, .. } => {
+                        self.last_content.size = new_size.clone();
 
-                self.pty_tx.0.send(Msg::Resize(new_size.into())).ok();
+                        pty_tx.0.send(Msg::Resize(new_size.into())).ok();
 
-                term.resize(new_size);
+                        term.resize(new_size);
+                    }
+                    #[cfg(any(test, feature = "test-support"))]
+                    TerminalConnection::Fake { .. } => {
+                        self.last_content.size = new_size.clone();
+                        term.resize(new_size);
+                    }
+                    // The screen keeps the size of the terminal that was shared.
+                    TerminalConnection::Remote(_) => {
+                        self.last_content.size =
+                            new_size.with_grid_size(term.screen_lines(), term.columns());
+                    }
+                }
             }
             InternalEvent::Clear => {
                 // Clear back buffer
@@ -698,8 +818,12 @@ impl Terminal {
                 cx.emit(Event::Wakeup);
             }
             InternalEvent::Scroll(scroll) => {
+                let display_offset = term.grid().display_offset();
                 term.scroll_display(*scroll);
                 self.refresh_hovered_word();
+                if term.grid().display_offset() != display_offset {
+                    cx.emit(Event::Scrolled);
+                }
             }
             InternalEvent::SetSelection(selection) => {
                 term.selection = selection.as_ref().map(|(sel, _)| sel.clone());
@@ -888,6 +1012,65 @@ impl Terminal {
         &self.last_content
     }
 
+    /// Returns the text displayed in the terminal's viewport, one line per row,
+    /// with trailing whitespace removed.
+    pub fn visible_text(&self) -> String {
+        let mut lines = Vec::new();
+        let mut current_line = None;
+        for cell in &self.last_content.cells {
+            if current_line != Some(cell.point.line) {
+                current_line = Some(cell.point.line);
+                lines.push(String::new());
+            }
+            if !cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+                if let Some(line) = lines.last_mut() {
+                    line.push(cell.c);
+                }
+            }
+        }
+
+        let mut text = lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.truncate(text.trim_end().len());
+        text
+    }
+
+    /// Returns up to the last `max_lines` lines of the terminal's scrollback and screen.
+    pub fn scrollback_text(&self, max_lines: usize) -> String {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let end_line = grid.bottommost_line();
+        let start_line = Line(cmp::max(
+            grid.topmost_line().0,
+            end_line.0 - max_lines.saturating_sub(1) as i32,
+        ));
+        let text = term.bounds_to_string(
+            AlacPoint::new(start_line, Column(0)),
+            AlacPoint::new(end_line, grid.last_column()),
+        );
+        text.trim_end().to_string()
+    }
+
+    /// Displays scrollback that was saved with [`Terminal::scrollback_text`], above the
+    /// output of the shell.
+    pub fn restore_scrollback(&mut self, scrollback: &str) {
+        let output = format!("{}\r\n", scrollback.replace('\n', "\r\n"));
+        self.write_output(output.as_bytes());
+    }
+
+    /// Displays the given bytes as though the shell had written them, without passing
+    /// them to the shell.
+    pub fn write_output(&mut self, bytes: &[u8]) {
+        let mut term = self.term.lock();
+        let mut processor: Processor = Processor::new();
+        for byte in bytes {
+            processor.advance(&mut *term, *byte);
+        }
+    }
+
     //To test:
     //- Activate match on terminal (scrolling and selection)
     //- Editor search snapping behavior
@@ -944,11 +1127,25 @@ impl Terminal {
 
     ///Write the Input payload to the tty.
     fn write_to_pty(&self, input: String) {
-        self.pty_tx.notify(input.into_bytes());
-    }
-
-    fn write_bytes_to_pty(&self, input: Vec<u8>) {
-        self.pty_tx.notify(input);
+        self.write_bytes_to_pty(input.into_bytes());
+    }
+
+    fn write_bytes_to_pty(&self, input: impl Into<Cow<'static, [u8]>>) {
+        match &self.connection {
+            TerminalConnection::Pty { pty_tx, .. } => pty_tx.notify(input),
+            TerminalConnection::Remote(remote) => {
+                if remote.can_write {
+                    remote
+                        .input_tx
+                        .unbounded_send(input.into().into_owned())
+                        .ok();
+                }
+            }
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { input_tx } => {
+                input_tx.unbounded_send(input.into().into_owned()).ok();
+            }
+        }
     }
 
     pub fn input(&mut self, input: String) {
@@ -1006,6 +1203,9 @@ impl Terminal {
         }
 
         self.last_content = Self::make_content(&terminal, &self.last_content);
+        if let TerminalConnection::Remote(remote) = &self.connection {
+            self.last_content.mode = remote.mode;
+        }
     }
 
     fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
@@ -1082,7 +1282,7 @@ impl Terminal {
 
             if self.mouse_changed(point, side) {
                 if let Some(bytes) = mouse_moved_report(point, e, self.last_content.mode) {
-                    self.pty_tx.notify(bytes);
+                    self.write_bytes_to_pty(bytes);
                 }
             }
         } else if self.cmd_pressed {
@@ -1156,7 +1356,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, true, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else if e.button == MouseButton::Left {
             let position = e.position - origin;
@@ -1203,7 +1403,7 @@ impl Terminal {
             if let Some(bytes) =
                 mouse_button_report(point, e.button, e.modifiers, false, self.last_content.mode)
             {
-                self.pty_tx.notify(bytes);
+                self.write_bytes_to_pty(bytes);
             }
         } else {
             if e.button == MouseButton::Left && setting.copy_on_select {
@@ -1242,7 +1442,7 @@ impl Terminal {
                     scroll_report(point, scroll_lines as i32, e, self.last_content.mode)
                 {
                     for scroll in scrolls {
-                        self.pty_tx.notify(scroll);
+                        self.write_bytes_to_pty(scroll);
                     }
                 };
             } else if self
@@ -1251,7 +1451,7 @@ impl Terminal {
                 .contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
                 && !e.shift
             {
-                self.pty_tx.notify(alt_scroll(scroll_lines))
+                self.write_bytes_to_pty(alt_scroll(scroll_lines))
             } else {
                 if scroll_lines != 0 {
                     let scroll = AlacScroll::Delta(scroll_lines);
@@ -1307,6 +1507,14 @@ impl Terminal {
     }
 
     pub fn title(&self, truncate: bool) -> String {
+        if let TerminalConnection::Remote(remote) = &self.connection {
+            return if truncate {
+                truncate_and_trailoff(&remote.title, 50)
+            } else {
+                remote.title.clone()
+            };
+        }
+
         self.foreground_process_info
             .as_ref()
             .map(|fpi| {
@@ -1340,11 +1548,124 @@ impl Terminal {
     pub fn can_navigate_to_selected_word(&self) -> bool {
         self.cmd_pressed && self.hovered_word
     }
+
+    /// Whether this terminal mirrors one that a collaborator shared.
+    pub fn is_remote(&self) -> bool {
+        matches!(self.connection, TerminalConnection::Remote(_))
+    }
+
+    /// Whether input typed into this terminal is discarded, because the collaborator
+    /// who shared it hasn't allowed it.
+    pub fn is_read_only(&self) -> bool {
+        match &self.connection {
+            TerminalConnection::Pty { .. } => false,
+            TerminalConnection::Remote(remote) => !remote.can_write,
+            #[cfg(any(test, feature = "test-support"))]
+            TerminalConnection::Fake { .. } => false,
+        }
+    }
+
+    /// Returns what is currently displayed on the terminal's screen, regardless of
+    /// how far it's scrolled back.
+    pub fn screen(&self) -> TerminalScreen {
+        self.screen_at(0)
+    }
+
+    /// Returns the part of the terminal's screen and scrollback that is in view.
+    pub fn displayed_screen(&self) -> TerminalScreen {
+        let display_offset = self.term.lock().grid().display_offset();
+        self.screen_at(display_offset)
+    }
+
+    fn screen_at(&self, display_offset: usize) -> TerminalScreen {
+        let term = self.term.lock();
+        let grid = term.grid();
+        let lines = grid.screen_lines();
+        let columns = grid.columns();
+        let mut cells = Vec::with_capacity(lines * columns);
+        for line in 0..lines {
+            for column in 0..columns {
+                let grid_point =
+                    AlacPoint::new(Line(line as i32 - display_offset as i32), Column(column));
+                cells.push(IndexedCell {
+                    point: AlacPoint::new(Line(line as i32), Column(column)),
+                    cell: grid[grid_point].clone(),
+                });
+            }
+        }
+
+        // The cursor is moved down with the rest of the screen, and hidden once it is
+        // scrolled out of view.
+        let mut cursor = grid.cursor.point;
+        cursor.line = Line(cursor.line.0 + display_offset as i32);
+        let mut mode = *term.mode();
+        if cursor.line.0 >= lines as i32 {
+            cursor.line = Line(lines as i32 - 1);
+            mode.remove(TermMode::SHOW_CURSOR);
+        }
+
+        TerminalScreen {
+            title: self.title(false),
+            lines,
+            columns,
+            cells,
+            cursor,
+            mode,
+        }
+    }
+
+    /// Replaces the screen of a remote terminal with the one its collaborator sent.
+    pub fn set_remote_screen(
+        &mut self,
+        screen: TerminalScreen,
+        can_write: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let TerminalConnection::Remote(remote) = &mut self.connection else {
+            return;
+        };
+        let title_changed = remote.title != screen.title;
+        remote.title = screen.title;
+        remote.mode = screen.mode;
+        remote.can_write = can_write;
+
+        let mut term = self.term.lock();
+        if term.screen_lines() != screen.lines || term.columns() != screen.columns {
+            let size = self
+                .last_content
+                .size
+                .with_grid_size(screen.lines, screen.columns);
+            term.resize(size);
+            self.last_content.size = size;
+        }
+        let grid = term.grid_mut();
+        for cell in screen.cells {
+            if (cell.point.line.0 as usize) < screen.lines && cell.point.column.0 < screen.columns {
+                grid[cell.point] = cell.cell;
+            }
+        }
+        grid.cursor.point = screen.cursor;
+        drop(term);
+
+        if title_changed {
+            cx.emit(Event::TitleChanged);
+        }
+        cx.emit(Event::Wakeup);
+    }
+
+    /// Closes a remote terminal, whose collaborator stopped sharing it.
+    pub fn close_remote(&mut self, cx: &mut ModelContext<Self>) {
+        if self.is_remote() {
+            cx.emit(Event::CloseTerminal);
+        }
+    }
 }
 
 impl Drop for Terminal {
     fn drop(&mut self) {
-        self.pty_tx.0.send(Msg::Shutdown).ok();
+        if let TerminalConnection::Pty { pty_tx, .. } = &self.connection {
+            pty_tx.0.send(Msg::Shutdown).ok();
+        }
     }
 }
 
@@ -1487,11 +1808,13 @@ mod tests {
         index::{Column, Line, Point as AlacPoint},
         term::cell::Cell,
     };
-    use gpui::{point, size, Pixels};
+    use futures::channel::mpsc;
+    use gpui::{point, size, Context as _, Pixels, TestAppContext};
     use rand::{distributions::Alphanumeric, rngs::ThreadRng, thread_rng, Rng};
 
     use crate::{
-        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalContent, TerminalSize,
+        content_index_for_mouse, rgb_for_index, IndexedCell, TerminalBuilder, TerminalContent,
+        TerminalSize,
     };
 
     #[test]
@@ -1579,6 +1902,34 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_scrollback_persistence(cx: &mut TestAppContext) {
+        let (input_tx, _input_rx) = mpsc::unbounded();
+        let terminal = cx.new_model(|cx| TerminalBuilder::new_fake(input_tx.clone()).subscribe(cx));
+        terminal.update(cx, |terminal, _| {
+            terminal.write_output(b"$ echo one\r\none\r\n$ echo two\r\ntwo\r\n$ ")
+        });
+        let scrollback = terminal.read_with(cx, |terminal, _| terminal.scrollback_text(1000));
+        assert_eq!(
+            scrollback.lines().collect::<Vec<_>>(),
+            ["$ echo one", "one", "$ echo two", "two", "$"]
+        );
+
+        // Restoring the scrollback into a new terminal displays the same lines, above the
+        // output of its shell.
+        let restored = cx.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+        restored.update(cx, |terminal, _| {
+            terminal.restore_scrollback(&scrollback);
+            terminal.write_output(b"$ ");
+        });
+        let restored_scrollback =
+            restored.read_with(cx, |terminal, _| terminal.scrollback_text(1000));
+        assert_eq!(
+            restored_scrollback.lines().collect::<Vec<_>>(),
+            ["$ echo one", "one", "$ echo two", "two", "$", "$"]
+        );
+    }
+
     fn get_cells(size: TerminalSize, rng: &mut ThreadRng) -> Vec<Vec<char>> {
         let mut cells = Vec::new();
 
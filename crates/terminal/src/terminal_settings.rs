@@ -35,6 +35,7 @@ pub struct TerminalSettings {
     pub default_width: Pixels,
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
+    pub persistence: TerminalPersistence,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -146,6 +147,10 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: on
     pub detect_venv: Option<VenvSettings>,
+    /// What to keep of a terminal when its workspace is closed and restored.
+    ///
+    /// Default: off
+    pub persistence: Option<TerminalPersistence>,
 }
 
 impl settings::Settings for TerminalSettings {
@@ -243,6 +248,18 @@ pub enum Shell {
     },
 }
 
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalPersistence {
+    /// Only keep the terminal's working directory and title.
+    #[default]
+    Off,
+    /// Also keep the terminal's scrollback, and replay it into the new shell.
+    Scrollback,
+    /// Keep the shell running in a tmux session, and reattach to it.
+    Reattach,
+}
+
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum AlternateScroll {
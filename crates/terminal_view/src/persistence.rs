@@ -34,6 +34,11 @@ define_connection! {
             DROP TABLE terminals;
 
             ALTER TABLE terminals2 RENAME TO terminals;
+        ),
+        sql!(
+            ALTER TABLE terminals ADD COLUMN title TEXT;
+            ALTER TABLE terminals ADD COLUMN scrollback TEXT;
+            ALTER TABLE terminals ADD COLUMN session TEXT;
         )];
 }
 
@@ -56,8 +61,9 @@ impl TerminalDb {
             workspace_id: WorkspaceId,
             working_directory: PathBuf
         ) -> Result<()> {
-            INSERT OR REPLACE INTO terminals(item_id, workspace_id, working_directory)
-            VALUES (?, ?, ?)
+            INSERT INTO terminals(item_id, workspace_id, working_directory)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(workspace_id, item_id) DO UPDATE SET working_directory = ?3
         }
     }
 
@@ -65,7 +71,67 @@ impl TerminalDb {
         pub fn get_working_directory(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<PathBuf>> {
             SELECT working_directory
             FROM terminals
-            WHERE item_id = ? AND workspace_id = ?
+            WHERE item_id = ? AND workspace_id = ? AND working_directory IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_title(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            title: Option<String>
+        ) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, title)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(workspace_id, item_id) DO UPDATE SET title = ?3
+        }
+    }
+
+    query! {
+        pub fn get_title(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT title
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ? AND title IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_scrollback(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            scrollback: String
+        ) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, scrollback)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(workspace_id, item_id) DO UPDATE SET scrollback = ?3
+        }
+    }
+
+    query! {
+        pub fn get_scrollback(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT scrollback
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ? AND scrollback IS NOT NULL
+        }
+    }
+
+    query! {
+        pub async fn save_session(
+            item_id: ItemId,
+            workspace_id: WorkspaceId,
+            session: String
+        ) -> Result<()> {
+            INSERT INTO terminals(item_id, workspace_id, session)
+            VALUES (?1, ?2, ?3)
+            ON CONFLICT(workspace_id, item_id) DO UPDATE SET session = ?3
+        }
+    }
+
+    query! {
+        pub fn get_session(item_id: ItemId, workspace_id: WorkspaceId) -> Result<Option<String>> {
+            SELECT session
+            FROM terminals
+            WHERE item_id = ? AND workspace_id = ? AND session IS NOT NULL
         }
     }
 }
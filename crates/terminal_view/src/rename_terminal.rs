@@ -0,0 +1,110 @@
+use editor::Editor;
+use gpui::{
+    div, prelude::*, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Styled, Subscription, View, ViewContext, VisualContext,
+};
+use theme::ActiveTheme;
+use ui::{h_flex, prelude::*, v_flex, Label};
+use workspace::ModalView;
+
+use crate::TerminalView;
+
+/// A modal for giving a terminal a title of its own. Confirming an empty title restores
+/// the title reported by the shell.
+pub struct RenameTerminalModal {
+    title_editor: View<Editor>,
+    terminal_view: View<TerminalView>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ModalView for RenameTerminalModal {}
+
+impl FocusableView for RenameTerminalModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.title_editor.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RenameTerminalModal {}
+
+impl RenameTerminalModal {
+    pub fn new(
+        terminal_view: View<TerminalView>,
+        custom_title: Option<String>,
+        shell_title: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let title_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text(shell_title, cx);
+            if let Some(custom_title) = custom_title {
+                editor.set_text(custom_title, cx);
+                editor.select_all(&editor::actions::SelectAll, cx);
+            }
+            editor
+        });
+        let title_editor_change = cx.subscribe(&title_editor, Self::on_title_editor_event);
+
+        Self {
+            title_editor,
+            terminal_view,
+            _subscriptions: vec![title_editor_change],
+        }
+    }
+
+    fn on_title_editor_event(
+        &mut self,
+        _: View<Editor>,
+        event: &editor::EditorEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let editor::EditorEvent::Blurred = event {
+            cx.emit(DismissEvent);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let title = self.title_editor.read(cx).text(cx).trim().to_string();
+        let title = if title.is_empty() { None } else { Some(title) };
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_custom_title(title, cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for RenameTerminalModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("RenameTerminal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.title_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(h_flex().px_2().py_1().child(
+                        Label::new("Leave empty to use the shell's title").color(Color::Muted),
+                    )),
+            )
+    }
+}
@@ -1,18 +1,22 @@
-use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
+use std::{iter, ops::ControlFlow, path::PathBuf, sync::Arc};
 
 use crate::TerminalView;
+use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
 use gpui::{
-    actions, AppContext, AsyncWindowContext, Entity, EventEmitter, ExternalPaths, FocusHandle,
-    FocusableView, IntoElement, ParentElement, Pixels, Render, Styled, Subscription, Task, View,
-    ViewContext, VisualContext, WeakView, WindowContext,
+    actions, AppContext, AsyncWindowContext, Axis, Entity, EntityId, EventEmitter, ExternalPaths,
+    FocusHandle, FocusableView, IntoElement, Model, ParentElement, Pixels, Render, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use terminal::terminal_settings::{TerminalDockPosition, TerminalSettings};
+use terminal::{
+    terminal_settings::{TerminalDockPosition, TerminalSettings},
+    Terminal,
+};
 use ui::{h_flex, ButtonCommon, Clickable, IconButton, IconSize, Selectable, Tooltip};
 use util::{ResultExt, TryFutureExt};
 use workspace::{
@@ -20,7 +24,7 @@ use workspace::{
     item::Item,
     pane,
     ui::IconName,
-    DraggedTab, Pane, Workspace,
+    DraggedTab, Member, Pane, PaneAxis, PaneGroup, SplitDirection, Workspace,
 };
 
 use anyhow::Result;
@@ -42,61 +46,108 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 }
 
+/// The terminal panel, which holds one or more panes of terminals that can be split in
+/// any direction.
 pub struct TerminalPanel {
-    pane: View<Pane>,
+    center: PaneGroup,
+    active_pane: View<Pane>,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
     height: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
-    _subscriptions: Vec<Subscription>,
+    pane_subscriptions: HashMap<EntityId, [Subscription; 2]>,
+    _project_subscription: Subscription,
 }
 
 impl TerminalPanel {
     fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let pane = Self::new_pane(workspace.weak_handle(), workspace.project().clone(), cx);
+        let project_subscription = cx.subscribe(workspace.project(), |this, project, event, cx| {
+            if let project::Event::RemoteTerminalShared(id) = event {
+                if let Some(terminal) = project.read(cx).remote_terminal(*id) {
+                    this.add_remote_terminal(terminal, cx);
+                }
+            }
+        });
+        let mut this = Self {
+            center: PaneGroup::new(pane.clone()),
+            active_pane: pane.clone(),
+            fs: workspace.app_state().fs.clone(),
+            workspace: workspace.weak_handle(),
+            pending_serialization: Task::ready(None),
+            width: None,
+            height: None,
+            pane_subscriptions: HashMap::default(),
+            _project_subscription: project_subscription,
+        };
+        this.subscribe_to_pane(&pane, cx);
+        this
+    }
+
+    fn new_pane(
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Pane> {
         let terminal_panel = cx.view().downgrade();
-        let pane = cx.new_view(|cx| {
-            let mut pane = Pane::new(
-                workspace.weak_handle(),
-                workspace.project().clone(),
-                Default::default(),
-                None,
-                cx,
-            );
-            pane.set_can_split(false, cx);
+        cx.new_view(|cx| {
+            let mut pane = Pane::new(workspace.clone(), project, Default::default(), None, cx);
             pane.set_can_navigate(false, cx);
             pane.display_nav_history_buttons(false);
-            pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
+            pane.set_render_tab_bar_buttons(cx, {
                 let terminal_panel = terminal_panel.clone();
-                h_flex()
-                    .gap_2()
-                    .child(
-                        IconButton::new("plus", IconName::Plus)
-                            .icon_size(IconSize::Small)
-                            .on_click(move |_, cx| {
-                                terminal_panel
-                                    .update(cx, |panel, cx| panel.add_terminal(None, cx))
-                                    .log_err();
-                            })
-                            .tooltip(|cx| Tooltip::text("New Terminal", cx)),
-                    )
-                    .child({
-                        let zoomed = pane.is_zoomed();
-                        IconButton::new("toggle_zoom", IconName::Maximize)
-                            .icon_size(IconSize::Small)
-                            .selected(zoomed)
-                            .selected_icon(IconName::Minimize)
-                            .on_click(cx.listener(|pane, _, cx| {
-                                pane.toggle_zoom(&workspace::ToggleZoom, cx);
-                            }))
-                            .tooltip(move |cx| {
-                                Tooltip::text(if zoomed { "Zoom Out" } else { "Zoom In" }, cx)
-                            })
-                    })
-                    .into_any_element()
+                move |pane, cx| {
+                    let terminal_panel = terminal_panel.clone();
+                    let this_pane = cx.view().downgrade();
+                    let split_direction = match TerminalSettings::get_global(cx).dock {
+                        TerminalDockPosition::Bottom => SplitDirection::Right,
+                        TerminalDockPosition::Left | TerminalDockPosition::Right => {
+                            SplitDirection::Down
+                        }
+                    };
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            IconButton::new("plus", IconName::Plus)
+                                .icon_size(IconSize::Small)
+                                .on_click(move |_, cx| {
+                                    terminal_panel
+                                        .update(cx, |panel, cx| {
+                                            if let Some(pane) = this_pane.upgrade() {
+                                                panel.active_pane = pane;
+                                            }
+                                            panel.add_terminal(None, cx)
+                                        })
+                                        .log_err();
+                                })
+                                .tooltip(|cx| Tooltip::text("New Terminal", cx)),
+                        )
+                        .child(
+                            IconButton::new("split", IconName::Split)
+                                .icon_size(IconSize::Small)
+                                .on_click(cx.listener(move |pane, _, cx| {
+                                    pane.split(split_direction, cx);
+                                }))
+                                .tooltip(|cx| Tooltip::text("Split Terminal", cx)),
+                        )
+                        .child({
+                            let zoomed = pane.is_zoomed();
+                            IconButton::new("toggle_zoom", IconName::Maximize)
+                                .icon_size(IconSize::Small)
+                                .selected(zoomed)
+                                .selected_icon(IconName::Minimize)
+                                .on_click(cx.listener(|pane, _, cx| {
+                                    pane.toggle_zoom(&workspace::ToggleZoom, cx);
+                                }))
+                                .tooltip(move |cx| {
+                                    Tooltip::text(if zoomed { "Zoom Out" } else { "Zoom In" }, cx)
+                                })
+                        })
+                        .into_any_element()
+                }
             });
 
-            let workspace = workspace.weak_handle();
             pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
                 if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
                     let item = if &tab.pane == cx.view() {
@@ -106,7 +157,32 @@ impl TerminalPanel {
                     };
                     if let Some(item) = item {
                         if item.downcast::<TerminalView>().is_some() {
-                            return ControlFlow::Continue(());
+                            let Some(direction) = pane.drag_split_direction() else {
+                                return ControlFlow::Continue(());
+                            };
+                            // Move the terminal into a new split of this panel, rather than
+                            // of the workspace's center.
+                            let from_pane = tab.pane.clone();
+                            let item_id = item.item_id();
+                            let terminal_panel = terminal_panel.clone();
+                            let workspace = workspace.clone();
+                            cx.defer(move |_, cx| {
+                                let this_pane = cx.view().clone();
+                                let Some(new_pane) = terminal_panel
+                                    .update(cx, |panel, cx| {
+                                        panel.split_pane(&this_pane, direction, cx)
+                                    })
+                                    .log_err()
+                                    .flatten()
+                                else {
+                                    return;
+                                };
+                                workspace
+                                    .update(cx, |workspace, cx| {
+                                        workspace.move_item(from_pane, new_pane, item_id, 0, cx)
+                                    })
+                                    .log_err();
+                            });
                         } else if let Some(project_path) = item.project_path(cx) {
                             if let Some(entry_path) = workspace
                                 .update(cx, |workspace, cx| {
@@ -145,21 +221,60 @@ impl TerminalPanel {
             pane.toolbar()
                 .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
             pane
-        });
-        let subscriptions = vec![
-            cx.observe(&pane, |_, _, cx| cx.notify()),
-            cx.subscribe(&pane, Self::handle_pane_event),
+        })
+    }
+
+    fn subscribe_to_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        let subscriptions = [
+            cx.observe(pane, |_, _, cx| cx.notify()),
+            cx.subscribe(pane, Self::handle_pane_event),
         ];
-        let this = Self {
-            pane,
-            fs: workspace.app_state().fs.clone(),
-            workspace: workspace.weak_handle(),
-            pending_serialization: Task::ready(None),
-            width: None,
-            height: None,
-            _subscriptions: subscriptions,
+        self.pane_subscriptions
+            .insert(pane.entity_id(), subscriptions);
+    }
+
+    /// Adds an empty pane next to the given one, in the given direction, and activates it.
+    fn split_pane(
+        &mut self,
+        pane: &View<Pane>,
+        direction: SplitDirection,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Pane>> {
+        let workspace = self.workspace.upgrade()?;
+        let project = workspace.read(cx).project().clone();
+        let new_pane = Self::new_pane(self.workspace.clone(), project, cx);
+        self.center.split(pane, &new_pane, direction).log_err()?;
+        self.subscribe_to_pane(&new_pane, cx);
+
+        self.active_pane = new_pane.clone();
+        cx.focus_view(&new_pane);
+        self.serialize(cx);
+        cx.notify();
+        Some(new_pane)
+    }
+
+    /// Removes a pane that no longer has any terminals, closing the panel if it was the last.
+    fn remove_pane(&mut self, pane: &View<Pane>, cx: &mut ViewContext<Self>) {
+        let Some(ix) = self
+            .center
+            .panes()
+            .into_iter()
+            .position(|candidate| candidate == pane)
+        else {
+            return;
         };
-        this
+        if !self.center.remove(pane).log_err().unwrap_or(false) {
+            cx.emit(PanelEvent::Close);
+            return;
+        }
+
+        self.pane_subscriptions.remove(&pane.entity_id());
+        if &self.active_pane == pane {
+            self.active_pane = self.center.panes()[ix.saturating_sub(1)].clone();
+            cx.focus_view(&self.active_pane);
+        }
+        self.serialize(cx);
+        cx.notify();
     }
 
     pub async fn load(
@@ -177,55 +292,109 @@ impl TerminalPanel {
             .log_err()
             .flatten();
 
-        let (panel, pane, items) = workspace.update(&mut cx, |workspace, cx| {
+        let (panel, panes) = workspace.update(&mut cx, |workspace, cx| {
             let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
-            let items = if let Some(serialized_panel) = serialized_panel.as_ref() {
+            let panes = if let Some(serialized_panel) = serialized_panel.as_ref() {
                 panel.update(cx, |panel, cx| {
                     cx.notify();
                     panel.height = serialized_panel.height;
                     panel.width = serialized_panel.width;
-                    panel.pane.update(cx, |_, cx| {
-                        serialized_panel
-                            .items
-                            .iter()
-                            .map(|item_id| {
-                                TerminalView::deserialize(
-                                    workspace.project().clone(),
-                                    workspace.weak_handle(),
-                                    workspace.database_id(),
-                                    *item_id,
-                                    cx,
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                    })
+                    let mut panes = vec![panel.active_pane.clone()];
+                    for _ in &serialized_panel.splits {
+                        let pane = Self::new_pane(
+                            workspace.weak_handle(),
+                            workspace.project().clone(),
+                            cx,
+                        );
+                        panel.subscribe_to_pane(&pane, cx);
+                        panes.push(pane);
+                    }
+                    panel.center = serialized_panel
+                        .layout
+                        .as_ref()
+                        .and_then(|layout| layout.deserialize(&panes))
+                        .map(PaneGroup::with_root)
+                        .filter(|center| {
+                            let laid_out = center.panes();
+                            laid_out.len() == panes.len()
+                                && panes.iter().all(|pane| laid_out.contains(&pane))
+                        })
+                        .unwrap_or_else(|| match panes.as_slice() {
+                            [pane] => PaneGroup::new(pane.clone()),
+                            _ => PaneGroup::with_root(Member::Axis(PaneAxis::new(
+                                Axis::Horizontal,
+                                panes.iter().cloned().map(Member::Pane).collect(),
+                            ))),
+                        });
+                    if let Some(pane) = panes.get(serialized_panel.active_pane_ix) {
+                        panel.active_pane = pane.clone();
+                    }
+
+                    panes
+                        .iter()
+                        .zip(serialized_panel.panes())
+                        .map(|(pane, serialized_pane)| {
+                            let items = pane.update(cx, |_, cx| {
+                                serialized_pane
+                                    .items
+                                    .iter()
+                                    .map(|item_id| {
+                                        TerminalView::deserialize(
+                                            workspace.project().clone(),
+                                            workspace.weak_handle(),
+                                            workspace.database_id(),
+                                            *item_id,
+                                            cx,
+                                        )
+                                    })
+                                    .collect::<Vec<_>>()
+                            });
+                            (pane.downgrade(), serialized_pane.active_item_id, items)
+                        })
+                        .collect::<Vec<_>>()
                 })
             } else {
                 Default::default()
             };
-            let pane = panel.read(cx).pane.clone();
-            (panel, pane, items)
+            (panel, panes)
         })?;
 
-        let pane = pane.downgrade();
-        let items = futures::future::join_all(items).await;
-        pane.update(&mut cx, |pane, cx| {
-            let active_item_id = serialized_panel
-                .as_ref()
-                .and_then(|panel| panel.active_item_id);
-            let mut active_ix = None;
-            for item in items {
-                if let Some(item) = item.log_err() {
-                    let item_id = item.entity_id().as_u64();
-                    pane.add_item(Box::new(item), false, false, None, cx);
-                    if Some(item_id) == active_item_id {
-                        active_ix = Some(pane.items_len() - 1);
+        for (pane, active_item_id, items) in panes {
+            let items = futures::future::join_all(items).await;
+            pane.update(&mut cx, |pane, cx| {
+                let mut active_ix = None;
+                for item in items {
+                    if let Some(item) = item.log_err() {
+                        let item_id = item.entity_id().as_u64();
+                        pane.add_item(Box::new(item), false, false, None, cx);
+                        if Some(item_id) == active_item_id {
+                            active_ix = Some(pane.items_len() - 1);
+                        }
                     }
                 }
-            }
 
-            if let Some(active_ix) = active_ix {
-                pane.activate_item(active_ix, false, false, cx)
+                if let Some(active_ix) = active_ix {
+                    pane.activate_item(active_ix, false, false, cx)
+                }
+            })?;
+        }
+
+        panel.update(&mut cx, |panel, cx| {
+            // Drop the splits whose terminals all failed to restore.
+            let empty_panes = panel
+                .center
+                .panes()
+                .into_iter()
+                .filter(|pane| pane.read(cx).items_len() == 0)
+                .cloned()
+                .collect::<Vec<_>>();
+            for pane in empty_panes {
+                if panel.center.remove(&pane).log_err().unwrap_or(false) {
+                    panel.pane_subscriptions.remove(&pane.entity_id());
+                    if panel.active_pane == pane {
+                        panel.active_pane = panel.center.first_pane();
+                    }
+                }
             }
         })?;
 
@@ -234,20 +403,32 @@ impl TerminalPanel {
 
     fn handle_pane_event(
         &mut self,
-        _pane: View<Pane>,
+        pane: View<Pane>,
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
             pane::Event::ActivateItem { .. } => self.serialize(cx),
             pane::Event::RemoveItem { .. } => self.serialize(cx),
-            pane::Event::Remove => cx.emit(PanelEvent::Close),
+            pane::Event::Remove => self.remove_pane(&pane, cx),
+            pane::Event::Split(direction) => {
+                if self.split_pane(&pane, *direction, cx).is_some() {
+                    self.add_terminal(None, cx);
+                }
+            }
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
 
+            pane::Event::Focus => {
+                if self.active_pane != pane {
+                    self.active_pane = pane;
+                    self.serialize(cx);
+                    cx.notify();
+                }
+            }
+
             pane::Event::AddItem { item } => {
                 if let Some(workspace) = self.workspace.upgrade() {
-                    let pane = self.pane.clone();
                     workspace.update(cx, |workspace, cx| item.added_to_pane(workspace, pane, cx))
                 }
             }
@@ -256,6 +437,13 @@ impl TerminalPanel {
         }
     }
 
+    pub fn active_terminal_view(&self, cx: &AppContext) -> Option<View<TerminalView>> {
+        self.active_pane
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<TerminalView>())
+    }
+
     pub fn open_terminal(
         workspace: &mut Workspace,
         action: &workspace::OpenTerminal,
@@ -286,7 +474,7 @@ impl TerminalPanel {
     fn add_terminal(&mut self, working_directory: Option<PathBuf>, cx: &mut ViewContext<Self>) {
         let workspace = self.workspace.clone();
         cx.spawn(|this, mut cx| async move {
-            let pane = this.update(&mut cx, |this, _| this.pane.clone())?;
+            let pane = this.update(&mut cx, |this, _| this.active_pane.clone())?;
             workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
@@ -299,7 +487,7 @@ impl TerminalPanel {
                 let window = cx.window_handle();
                 if let Some(terminal) = workspace.project().update(cx, |project, cx| {
                     project
-                        .create_terminal(working_directory, window, cx)
+                        .create_terminal(working_directory, None, window, cx)
                         .log_err()
                 }) {
                     let terminal = Box::new(cx.new_view(|cx| {
@@ -322,18 +510,50 @@ impl TerminalPanel {
         .detach_and_log_err(cx);
     }
 
+    /// Opens a terminal that the host of the project shared, without focusing it.
+    fn add_remote_terminal(&mut self, terminal: Model<Terminal>, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let workspace_id = workspace.read(cx).database_id();
+        let terminal =
+            Box::new(cx.new_view(|cx| {
+                TerminalView::new(terminal, self.workspace.clone(), workspace_id, cx)
+            }));
+        self.active_pane.update(cx, |pane, cx| {
+            pane.add_item(terminal, false, false, None, cx);
+        });
+    }
+
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
-        let items = self
-            .pane
-            .read(cx)
-            .items()
-            .map(|item| item.item_id().as_u64())
+        let panes = self.center.panes();
+        let mut splits = panes
+            .iter()
+            .map(|pane| {
+                let pane = pane.read(cx);
+                SerializedTerminalPane {
+                    // Terminals shared by collaborators can't be restored.
+                    items: pane
+                        .items()
+                        .filter(|item| {
+                            item.downcast::<TerminalView>()
+                                .map_or(true, |view| !view.read(cx).terminal().read(cx).is_remote())
+                        })
+                        .map(|item| item.item_id().as_u64())
+                        .collect(),
+                    active_item_id: pane.active_item().map(|item| item.item_id().as_u64()),
+                }
+            })
             .collect::<Vec<_>>();
-        let active_item_id = self
-            .pane
-            .read(cx)
-            .active_item()
-            .map(|item| item.item_id().as_u64());
+        let SerializedTerminalPane {
+            items,
+            active_item_id,
+        } = splits.remove(0);
+        let layout = SerializedPaneGroup::serialize(&self.center.root, &panes);
+        let active_pane_ix = panes
+            .iter()
+            .position(|pane| *pane == &self.active_pane)
+            .unwrap_or(0);
         let height = self.height;
         let width = self.width;
         self.pending_serialization = cx.background_executor().spawn(
@@ -344,6 +564,9 @@ impl TerminalPanel {
                         serde_json::to_string(&SerializedTerminalPanel {
                             items,
                             active_item_id,
+                            splits,
+                            layout,
+                            active_pane_ix,
                             height,
                             width,
                         })?,
@@ -379,7 +602,7 @@ impl Render for TerminalPanel {
         let mut registrar = DivRegistrar::new(
             |panel, cx| {
                 panel
-                    .pane
+                    .active_pane
                     .read(cx)
                     .toolbar()
                     .read(cx)
@@ -388,13 +611,28 @@ impl Render for TerminalPanel {
             cx,
         );
         BufferSearchBar::register(&mut registrar);
-        registrar.into_div().size_full().child(self.pane.clone())
+
+        let registrar = registrar.into_div().size_full();
+        let Some(workspace) = self.workspace.upgrade() else {
+            return registrar;
+        };
+        workspace.update(cx, |workspace, cx| {
+            registrar.child(self.center.render(
+                workspace.project(),
+                &HashMap::default(),
+                None,
+                &self.active_pane,
+                workspace.zoomed_item(),
+                workspace.app_state(),
+                cx,
+            ))
+        })
     }
 }
 
 impl FocusableView for TerminalPanel {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
-        self.pane.focus_handle(cx)
+        self.active_pane.focus_handle(cx)
     }
 }
 
@@ -442,21 +680,38 @@ impl Panel for TerminalPanel {
     }
 
     fn is_zoomed(&self, cx: &WindowContext) -> bool {
-        self.pane.read(cx).is_zoomed()
+        self.center
+            .panes()
+            .into_iter()
+            .any(|pane| pane.read(cx).is_zoomed())
     }
 
     fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
-        self.pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        for pane in self.center.panes() {
+            let zoomed = zoomed && pane == &self.active_pane;
+            pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        }
     }
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
-        if active && self.pane.read(cx).items_len() == 0 {
+        if active
+            && self
+                .center
+                .panes()
+                .into_iter()
+                .all(|pane| pane.read(cx).items_len() == 0)
+        {
             self.add_terminal(None, cx)
         }
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.pane.read(cx).items_len();
+        let count = self
+            .center
+            .panes()
+            .into_iter()
+            .map(|pane| pane.read(cx).items_len())
+            .sum::<usize>();
         if count == 0 {
             None
         } else {
@@ -483,8 +738,187 @@ impl Panel for TerminalPanel {
 
 #[derive(Serialize, Deserialize)]
 struct SerializedTerminalPanel {
+    /// The terminals in the first pane.
     items: Vec<u64>,
     active_item_id: Option<u64>,
+    /// The panes after the first.
+    #[serde(default)]
+    splits: Vec<SerializedTerminalPane>,
+    /// How the panes are laid out, which refers to them by their index, counting the
+    /// first pane.
+    #[serde(default)]
+    layout: Option<SerializedPaneGroup>,
+    #[serde(default)]
+    active_pane_ix: usize,
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+impl SerializedTerminalPanel {
+    fn panes(&self) -> impl Iterator<Item = SerializedTerminalPane> + '_ {
+        iter::once(SerializedTerminalPane {
+            items: self.items.clone(),
+            active_item_id: self.active_item_id,
+        })
+        .chain(self.splits.iter().cloned())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SerializedTerminalPane {
+    items: Vec<u64>,
+    active_item_id: Option<u64>,
+}
+
+/// Mirrors the workspace's serialized pane groups, referring to the panes by their index.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum SerializedPaneGroup {
+    Pane(usize),
+    Group {
+        vertical: bool,
+        #[serde(default)]
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedPaneGroup>,
+    },
+}
+
+impl SerializedPaneGroup {
+    fn serialize(member: &Member, panes: &[&View<Pane>]) -> Option<Self> {
+        match member {
+            Member::Pane(pane) => Some(SerializedPaneGroup::Pane(
+                panes.iter().position(|candidate| *candidate == pane)?,
+            )),
+            Member::Axis(axis) => Some(SerializedPaneGroup::Group {
+                vertical: axis.axis == Axis::Vertical,
+                flexes: Some(axis.flexes.lock().clone()),
+                children: axis
+                    .members
+                    .iter()
+                    .map(|member| Self::serialize(member, panes))
+                    .collect::<Option<_>>()?,
+            }),
+        }
+    }
+
+    fn deserialize(&self, panes: &[View<Pane>]) -> Option<Member> {
+        match self {
+            SerializedPaneGroup::Pane(ix) => Some(Member::Pane(panes.get(*ix)?.clone())),
+            SerializedPaneGroup::Group {
+                vertical,
+                flexes,
+                children,
+            } => {
+                let axis = if *vertical {
+                    Axis::Vertical
+                } else {
+                    Axis::Horizontal
+                };
+                let members = children
+                    .iter()
+                    .map(|child| child.deserialize(panes))
+                    .collect::<Option<Vec<_>>>()?;
+                let flexes = flexes
+                    .clone()
+                    .filter(|flexes| flexes.len() == members.len());
+                Some(Member::Axis(PaneAxis::load(axis, members, flexes)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_split_panes(cx: &mut TestAppContext) {
+        let app_state = cx.update(AppState::test);
+        cx.update(|cx| {
+            theme::init(theme::LoadThemes::JustBase, cx);
+            Project::init_settings(cx);
+            language::init(cx);
+            terminal::init(cx);
+        });
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+        let panel = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| TerminalPanel::new(workspace, cx))
+        });
+
+        // Splitting to the right and then down nests the second split inside the first.
+        let (first, second, third) = panel.update(cx, |panel, cx| {
+            let first = panel.active_pane.clone();
+            let second = panel.split_pane(&first, SplitDirection::Right, cx).unwrap();
+            let third = panel.split_pane(&second, SplitDirection::Down, cx).unwrap();
+            assert_eq!(panel.active_pane, third);
+            (first, second, third)
+        });
+        panel.update(cx, |panel, _| {
+            let panes = panel.center.panes();
+            assert_eq!(panes, [&first, &second, &third]);
+            let Member::Axis(axis) = &panel.center.root else {
+                panic!("expected the panel to be split");
+            };
+            assert_eq!(axis.axis, Axis::Horizontal);
+            assert!(matches!(&axis.members[0], Member::Pane(pane) if pane == &first));
+            let Member::Axis(nested) = &axis.members[1] else {
+                panic!("expected the second split to be nested");
+            };
+            assert_eq!(nested.axis, Axis::Vertical);
+
+            // The layout refers to the panes by index, and keeps the size of each split.
+            *nested.flexes.lock() = vec![0.5, 1.5];
+            let serialized = SerializedPaneGroup::serialize(&panel.center.root, &panes).unwrap();
+            assert_eq!(
+                serialized,
+                SerializedPaneGroup::Group {
+                    vertical: false,
+                    flexes: Some(vec![1., 1.]),
+                    children: vec![
+                        SerializedPaneGroup::Pane(0),
+                        SerializedPaneGroup::Group {
+                            vertical: true,
+                            flexes: Some(vec![0.5, 1.5]),
+                            children: vec![
+                                SerializedPaneGroup::Pane(1),
+                                SerializedPaneGroup::Pane(2)
+                            ],
+                        },
+                    ],
+                }
+            );
+            let panes = panes.into_iter().cloned().collect::<Vec<_>>();
+            let deserialized = serialized.deserialize(&panes).unwrap();
+            assert_eq!(
+                SerializedPaneGroup::serialize(&deserialized, &panes.iter().collect::<Vec<_>>()),
+                Some(serialized)
+            );
+        });
+
+        // Splitting to the left inserts the new pane before the existing one.
+        let fourth = panel.update(cx, |panel, cx| {
+            panel.split_pane(&first, SplitDirection::Left, cx).unwrap()
+        });
+        panel.update(cx, |panel, _| {
+            assert_eq!(panel.center.panes()[..2], [&fourth, &first]);
+        });
+
+        // Removing a pane collapses the axis it leaves with a single member.
+        panel.update(cx, |panel, cx| {
+            panel.remove_pane(&third, cx);
+            panel.remove_pane(&fourth, cx);
+            assert_eq!(panel.center.panes(), [&first, &second]);
+            let Member::Axis(axis) = &panel.center.root else {
+                panic!("expected the panel to be split");
+            };
+            assert_eq!(axis.axis, Axis::Horizontal);
+            assert!(axis
+                .members
+                .iter()
+                .all(|member| matches!(member, Member::Pane(_))));
+            assert_eq!(panel.active_pane, second);
+        });
+    }
+}
@@ -1,39 +1,44 @@
 mod persistence;
+mod rename_terminal;
 pub mod terminal_element;
 pub mod terminal_panel;
 
 use collections::HashSet;
 use editor::{scroll::Autoscroll, Editor};
-use futures::{stream::FuturesUnordered, StreamExt};
+use futures::{channel::mpsc, stream::FuturesUnordered, StreamExt};
 use gpui::{
-    div, impl_actions, overlay, AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton, MouseDownEvent, Pixels,
-    Render, Styled, Subscription, Task, View, VisualContext, WeakView,
+    actions, div, impl_actions, overlay, AnyElement, AppContext, DismissEvent, EventEmitter,
+    FocusHandle, FocusableView, KeyContext, KeyDownEvent, Keystroke, Model, MouseButton,
+    MouseDownEvent, Pixels, Render, Styled, Subscription, Task, View, VisualContext, WeakView,
 };
 use language::Bias;
 use persistence::TERMINAL_DB;
-use project::{search::SearchQuery, Fs, LocalWorktree, Metadata, Project};
+use project::{search::SearchQuery, terminals, Fs, LocalWorktree, Metadata, Project};
+use rename_terminal::RenameTerminalModal;
+use rpc::proto;
 use terminal::{
     alacritty_terminal::{
         index::Point,
         term::{search::RegexSearch, TermMode},
     },
-    terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
+    session,
+    terminal_settings::{TerminalBlink, TerminalPersistence, TerminalSettings, WorkingDirectory},
     Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, Terminal,
+    TerminalBuilder,
 };
 use terminal_element::TerminalElement;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent},
     notifications::NotifyResultExt,
-    register_deserializable_item,
+    register_deserializable_item, register_followable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
-    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
+    CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, ViewId, Workspace,
     WorkspaceId,
 };
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use dirs::home_dir;
 use serde::Deserialize;
 use settings::Settings;
@@ -51,6 +56,8 @@ const REGEX_SPECIAL_CHARS: &[char] = &[
 ];
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLLBACK_SAVE_DEBOUNCE: Duration = Duration::from_secs(2);
+const MAX_PERSISTED_SCROLLBACK_LINES: usize = 1000;
 
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -64,11 +71,14 @@ pub struct SendKeystroke(String);
 
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
+actions!(terminal, [RenameTerminal, ToggleTerminalSharing]);
+
 pub fn init(cx: &mut AppContext) {
     terminal_panel::init(cx);
     terminal::init(cx);
 
     register_deserializable_item::<TerminalView>(cx);
+    register_followable_item::<TerminalView>(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _| {
         workspace.register_action(TerminalView::deploy);
@@ -90,6 +100,9 @@ pub struct TerminalView {
     blink_epoch: usize,
     can_navigate_to_selected_word: bool,
     workspace_id: WorkspaceId,
+    custom_title: Option<String>,
+    pending_scrollback_save: Option<Task<()>>,
+    remote_id: Option<ViewId>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -118,7 +131,7 @@ impl TerminalView {
         let terminal = workspace
             .project()
             .update(cx, |project, cx| {
-                project.create_terminal(working_directory, window, cx)
+                project.create_terminal(working_directory, None, window, cx)
             })
             .notify_err(workspace, cx);
 
@@ -149,6 +162,7 @@ impl TerminalView {
                 cx.emit(Event::Wakeup);
                 cx.emit(ItemEvent::UpdateTab);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                this.schedule_scrollback_save(cx);
             }
 
             Event::Bell => {
@@ -292,6 +306,11 @@ impl TerminalView {
                     .detach_and_log_err(cx)
                 }
             },
+            Event::Scrolled => {
+                cx.notify();
+                // Also sends the scrolled screen to followers.
+                cx.emit(ItemEvent::UpdateTab);
+            }
             Event::BreadcrumbsChanged => cx.emit(ItemEvent::UpdateBreadcrumbs),
             Event::CloseTerminal => cx.emit(ItemEvent::CloseItem),
             Event::SelectionsChanged => cx.emit(SearchEvent::ActiveMatchChanged),
@@ -305,6 +324,30 @@ impl TerminalView {
         let focus_out = cx.on_focus_out(&focus_handle, |terminal_view, cx| {
             terminal_view.focus_out(cx);
         });
+        let mut subscriptions = vec![focus_in, focus_out];
+
+        if let Some(session) = terminal.read(cx).session.clone() {
+            let item_id = cx.entity_id().as_u64();
+            let saved_session = session.clone();
+            cx.background_executor()
+                .spawn(async move {
+                    TERMINAL_DB
+                        .save_session(item_id, workspace_id, saved_session)
+                        .await
+                        .log_err();
+                })
+                .detach();
+
+            // Closing the terminal ends its session, but closing its window keeps the session
+            // alive so that it can be reattached when the workspace is restored.
+            subscriptions.push(cx.on_release(move |_, window, cx| {
+                if window.update(cx, |_, _| {}).is_ok() {
+                    cx.background_executor()
+                        .spawn(async move { session::kill_session(&session).log_err() })
+                        .detach();
+                }
+            }));
+        }
 
         Self {
             terminal,
@@ -318,7 +361,10 @@ impl TerminalView {
             blink_epoch: 0,
             can_navigate_to_selected_word: false,
             workspace_id,
-            _subscriptions: vec![focus_in, focus_out],
+            custom_title: None,
+            pending_scrollback_save: None,
+            remote_id: None,
+            _subscriptions: subscriptions,
         }
     }
 
@@ -335,14 +381,173 @@ impl TerminalView {
         cx.emit(Event::Wakeup);
     }
 
+    /// Returns the title given to this terminal by the user, or else the title of the
+    /// process running in it.
+    pub fn title(&self, truncate: bool, cx: &AppContext) -> String {
+        self.custom_title
+            .clone()
+            .unwrap_or_else(|| self.terminal.read(cx).title(truncate))
+    }
+
+    pub fn set_custom_title(&mut self, title: Option<String>, cx: &mut ViewContext<Self>) {
+        self.custom_title = title.clone();
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+
+        let item_id = cx.entity_id().as_u64();
+        let workspace_id = self.workspace_id;
+        cx.background_executor()
+            .spawn(async move {
+                TERMINAL_DB
+                    .save_title(item_id, workspace_id, title)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+
+    fn rename(&mut self, _: &RenameTerminal, cx: &mut ViewContext<Self>) {
+        let terminal_view = cx.view().clone();
+        let custom_title = self.custom_title.clone();
+        let shell_title = self.terminal.read(cx).title(false);
+        self.workspace
+            .update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, |cx| {
+                    RenameTerminalModal::new(terminal_view, custom_title, shell_title, cx)
+                })
+            })
+            .ok();
+    }
+
+    fn project(&self, cx: &AppContext) -> Option<Model<Project>> {
+        Some(self.workspace.upgrade()?.read(cx).project().clone())
+    }
+
+    /// The screen that followers see. It isn't sent through the project, so it has no ids.
+    fn follower_screen(&self, cx: &AppContext) -> proto::UpdateSharedTerminal {
+        let screen = self.terminal.read(cx).displayed_screen();
+        terminals::serialize_terminal_screen(0, 0, screen, Vec::new())
+    }
+
+    /// Shows this terminal to the guests of the shared project, or stops showing it.
+    fn toggle_sharing(&mut self, _: &ToggleTerminalSharing, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project(cx) else {
+            return;
+        };
+        project.update(cx, |project, cx| {
+            if project.is_terminal_shared(&self.terminal) {
+                project.unshare_terminal(&self.terminal, cx);
+            } else {
+                project.share_terminal(&self.terminal, cx).log_err();
+            }
+        });
+        cx.emit(ItemEvent::UpdateTab);
+    }
+
+    fn set_input_allowed(
+        &mut self,
+        peer_id: proto::PeerId,
+        allowed: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(project) = self.project(cx) {
+            project.update(cx, |project, cx| {
+                project.set_terminal_input_allowed(&self.terminal, peer_id, allowed, cx)
+            });
+        }
+    }
+
+    fn schedule_scrollback_save(&mut self, cx: &mut ViewContext<Self>) {
+        if self.pending_scrollback_save.is_some()
+            || self.terminal.read(cx).is_remote()
+            || TerminalSettings::get_global(cx).persistence != TerminalPersistence::Scrollback
+        {
+            return;
+        }
+
+        self.pending_scrollback_save = Some(cx.spawn(|this, mut cx| async move {
+            Timer::after(SCROLLBACK_SAVE_DEBOUNCE).await;
+            let Ok((item_id, workspace_id, scrollback)) = this.update(&mut cx, |this, cx| {
+                this.pending_scrollback_save = None;
+                (
+                    cx.entity_id().as_u64(),
+                    this.workspace_id,
+                    this.terminal
+                        .read(cx)
+                        .scrollback_text(MAX_PERSISTED_SCROLLBACK_LINES),
+                )
+            }) else {
+                return;
+            };
+            TERMINAL_DB
+                .save_scrollback(item_id, workspace_id, scrollback)
+                .await
+                .log_err();
+        }));
+    }
+
     pub fn deploy_context_menu(
         &mut self,
         position: gpui::Point<Pixels>,
         cx: &mut ViewContext<Self>,
     ) {
+        // The host of a shared project can show its terminals to the guests, and choose
+        // which of them can type into each one.
+        let sharing = self.project(cx).and_then(|project| {
+            let project = project.read(cx);
+            if !project.is_shared() || self.terminal.read(cx).is_remote() {
+                return None;
+            }
+            let shared = project.is_terminal_shared(&self.terminal);
+            let user_store = project.user_store().read(cx);
+            let mut guests = Vec::new();
+            if shared {
+                for collaborator in project.collaborators().values() {
+                    let login = user_store
+                        .get_cached_user(collaborator.user_id)
+                        .map(|user| user.github_login.clone())
+                        .unwrap_or_else(|| format!("user {}", collaborator.user_id));
+                    let allowed =
+                        project.is_terminal_input_allowed(&self.terminal, collaborator.peer_id);
+                    guests.push((collaborator.peer_id, login, allowed));
+                }
+                guests.sort_by(|a, b| a.1.cmp(&b.1));
+            }
+            Some((shared, guests))
+        });
+
+        let terminal_view = cx.view().downgrade();
         let context_menu = ContextMenu::build(cx, |menu, _| {
-            menu.action("Clear", Box::new(Clear))
-                .action("Close", Box::new(CloseActiveItem { save_intent: None }))
+            let mut menu = menu
+                .action("Clear", Box::new(Clear))
+                .action("Rename", Box::new(RenameTerminal))
+                .action("Close", Box::new(CloseActiveItem { save_intent: None }));
+            if let Some((shared, guests)) = sharing {
+                menu = menu.separator().action(
+                    if shared {
+                        "Stop Sharing"
+                    } else {
+                        "Share with Collaborators"
+                    },
+                    Box::new(ToggleTerminalSharing),
+                );
+                for (peer_id, login, allowed) in guests {
+                    let label = if allowed {
+                        format!("Stop Input from @{login}")
+                    } else {
+                        format!("Allow Input from @{login}")
+                    };
+                    let terminal_view = terminal_view.clone();
+                    menu = menu.entry(label, None, move |cx| {
+                        terminal_view
+                            .update(cx, |terminal_view, cx| {
+                                terminal_view.set_input_allowed(peer_id, !allowed, cx)
+                            })
+                            .ok();
+                    });
+                }
+            }
+            menu
         });
 
         cx.focus_view(&context_menu);
@@ -712,6 +917,8 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::clear))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
+            .on_action(cx.listener(TerminalView::rename))
+            .on_action(cx.listener(TerminalView::toggle_sharing))
             .on_key_down(cx.listener(Self::key_down))
             .on_mouse_down(
                 MouseButton::Right,
@@ -746,7 +953,7 @@ impl Item for TerminalView {
     type Event = ItemEvent;
 
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        Some(self.terminal().read(cx).title(false).into())
+        Some(self.title(false, cx).into())
     }
 
     fn tab_content(
@@ -755,7 +962,13 @@ impl Item for TerminalView {
         selected: bool,
         cx: &WindowContext,
     ) -> AnyElement {
-        let title = self.terminal().read(cx).title(true);
+        let title = self.title(true, cx);
+        let terminal = self.terminal.read(cx);
+        let read_only = terminal.is_read_only();
+        let shared = !terminal.is_remote()
+            && self.project(cx).map_or(false, |project| {
+                project.read(cx).is_terminal_shared(&self.terminal)
+            });
         h_flex()
             .gap_2()
             .child(Icon::new(IconName::Terminal))
@@ -764,6 +977,20 @@ impl Item for TerminalView {
             } else {
                 Color::Muted
             }))
+            .when(shared, |this| {
+                this.child(
+                    Icon::new(IconName::Public)
+                        .size(IconSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
+            .when(read_only, |this| {
+                this.child(
+                    Label::new("read-only")
+                        .size(LabelSize::XSmall)
+                        .color(Color::Muted),
+                )
+            })
             .into_any()
     }
 
@@ -844,11 +1071,41 @@ impl Item for TerminalView {
                     .flatten()
                 });
 
+            let title = TERMINAL_DB
+                .get_title(item_id, workspace_id)
+                .log_err()
+                .flatten();
+            let session = TERMINAL_DB
+                .get_session(item_id, workspace_id)
+                .log_err()
+                .flatten();
+
             let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, window, cx)
+                project.create_terminal(cwd, session, window, cx)
             })??;
+
+            let persistence = cx.update(|cx| TerminalSettings::get_global(cx).persistence)?;
+            if persistence == TerminalPersistence::Scrollback {
+                if let Some(scrollback) = TERMINAL_DB
+                    .get_scrollback(item_id, workspace_id)
+                    .log_err()
+                    .flatten()
+                {
+                    terminal.update(&mut cx, |terminal, _| {
+                        terminal.restore_scrollback(&scrollback)
+                    })?;
+                }
+            }
+
             pane.update(&mut cx, |_, cx| {
-                cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
+                cx.new_view(|cx| {
+                    let mut view = TerminalView::new(terminal, workspace, workspace_id, cx);
+                    // Saves the title again, under the restored item's id.
+                    if title.is_some() {
+                        view.set_custom_title(title, cx);
+                    }
+                    view
+                })
             })
         })
     }
@@ -869,6 +1126,110 @@ impl Item for TerminalView {
     }
 }
 
+/// Followers see a read-only copy of their leader's terminal, as far as the leader
+/// scrolled it back, whether or not the terminal was shared with the project.
+impl FollowableItem for TerminalView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        Some(proto::view::Variant::Terminal(proto::view::Terminal {
+            screen: Some(self.follower_screen(cx)),
+        }))
+    }
+
+    fn from_state_proto(
+        _: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<anyhow::Result<View<Self>>>> {
+        let Some(proto::view::Variant::Terminal(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::Terminal(state)) = state.take() else {
+            unreachable!()
+        };
+        let screen = match state
+            .screen
+            .context("missing terminal screen")
+            .and_then(|screen| terminals::deserialize_terminal_screen(&screen))
+        {
+            Ok(screen) => screen,
+            Err(error) => return Some(Task::ready(Err(error))),
+        };
+
+        let weak_workspace = workspace.downgrade();
+        let workspace_id = workspace.read(cx).database_id();
+        // Followers can't type into the copy, so nothing reads its input.
+        let (input_tx, _) = mpsc::unbounded();
+        let terminal = cx.new_model(|cx| {
+            let mut terminal = TerminalBuilder::new_remote(input_tx).subscribe(cx);
+            terminal.set_remote_screen(screen, false, cx);
+            terminal
+        });
+        let view = cx.new_view(|cx| {
+            let mut view = TerminalView::new(terminal, weak_workspace, workspace_id, cx);
+            view.remote_id = Some(remote_id);
+            view
+        });
+        Some(Task::ready(Ok(view)))
+    }
+
+    // Followers only ever see the leader's screen, so there's nothing on it to unfollow.
+    fn to_follow_event(_: &ItemEvent) -> Option<FollowEvent> {
+        None
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &ItemEvent,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        if *event != ItemEvent::UpdateTab {
+            return false;
+        }
+        *update = Some(proto::update_view::Variant::Terminal(
+            proto::update_view::Terminal {
+                screen: Some(self.follower_screen(cx)),
+            },
+        ));
+        true
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        _: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<anyhow::Result<()>> {
+        let proto::update_view::Variant::Terminal(message) = message else {
+            return Task::ready(Err(anyhow!("received an update for another kind of view")));
+        };
+        let screen = match message
+            .screen
+            .context("missing terminal screen")
+            .and_then(|screen| terminals::deserialize_terminal_screen(&screen))
+        {
+            Ok(screen) => screen,
+            Err(error) => return Task::ready(Err(error)),
+        };
+        self.terminal.update(cx, |terminal, cx| {
+            terminal.set_remote_screen(screen, false, cx)
+        });
+        Task::ready(Ok(()))
+    }
+
+    fn is_project_item(&self, _: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, _: Option<proto::PeerId>, _: &mut ViewContext<Self>) {}
+}
+
 impl SearchableItem for TerminalView {
     type Match = RangeInclusive<Point>;
 
@@ -1045,6 +1406,31 @@ mod tests {
     use std::path::Path;
     use workspace::AppState;
 
+    #[gpui::test]
+    async fn test_rename_terminal(cx: &mut TestAppContext) {
+        let (project, _) = init_test(cx).await;
+        cx.update(terminal::init);
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let (input_tx, _input_rx) = mpsc::unbounded();
+        let terminal = cx.new_model(|cx| TerminalBuilder::new_fake(input_tx).subscribe(cx));
+        let terminal_view = workspace.update(cx, |workspace, cx| {
+            let workspace_id = workspace.database_id();
+            cx.new_view(|cx| {
+                TerminalView::new(terminal.clone(), workspace.weak_handle(), workspace_id, cx)
+            })
+        });
+        let default_title = terminal.read_with(cx, |terminal, _| terminal.title(false));
+
+        terminal_view.update(cx, |view, cx| {
+            view.set_custom_title(Some("build".into()), cx);
+            assert_eq!(view.title(false, cx), "build");
+
+            view.set_custom_title(None, cx);
+            assert_eq!(view.title(false, cx), default_title);
+        });
+    }
+
     // Working directory calculation tests
 
     // No Worktrees in project -> home_dir()
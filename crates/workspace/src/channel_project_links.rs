@@ -0,0 +1,263 @@
+//! Repositories can be linked to a channel, so that joining the channel opens the
+//! member's own clone of them instead of requiring someone to share a project on
+//! every call. Clones are found among the open and recent local workspaces, by
+//! comparing their repository's remotes with the linked one.
+
+use crate::{open_paths, AppState, Workspace, WORKSPACE_DB};
+use anyhow::Result;
+use call::Room;
+use client::proto;
+use fs::Fs;
+use gpui::{AsyncAppContext, Model, PromptLevel, WindowHandle};
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+use util::ResultExt;
+
+const REMOTE_NAME: &str = "origin";
+
+/// Returns the `origin` remote of the repository containing `abs_path`, or its first
+/// remote if it has no `origin`, along with the path of `abs_path` relative to the
+/// repository's root, in the form they're linked to channels with.
+pub async fn project_link_for_path(fs: &dyn Fs, abs_path: &Path) -> Option<(String, String)> {
+    let (repository_root, remote_urls) = containing_repository(fs, abs_path).await?;
+    let remote_url = remote_urls.into_iter().next()?;
+    let path = abs_path
+        .strip_prefix(&repository_root)
+        .ok()?
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    Some((remote_url, path))
+}
+
+/// Looks for a local clone of a repository linked to the channel and offers to open
+/// it, then offers to share it into the channel's room when the current user is the
+/// link's host. Returns whether a project was opened.
+pub(crate) async fn open_channel_project_link(
+    channel_id: u64,
+    room: &Model<Room>,
+    app_state: &Arc<AppState>,
+    requesting_window: Option<WindowHandle<Workspace>>,
+    cx: &mut AsyncAppContext,
+) -> Result<bool> {
+    let links = app_state
+        .client
+        .request(proto::GetChannelProjectLinks { channel_id })
+        .await?
+        .links;
+    if links.is_empty() {
+        return Ok(false);
+    }
+
+    let open_roots = cx.update(|cx| {
+        let mut roots = Vec::new();
+        for window in cx.windows() {
+            let Some(workspace) = window.downcast::<Workspace>() else {
+                continue;
+            };
+            workspace
+                .update(cx, |workspace, cx| {
+                    let project = workspace.project.read(cx);
+                    if project.is_local() {
+                        roots.extend(
+                            project
+                                .visible_worktrees(cx)
+                                .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+                        );
+                    }
+                })
+                .log_err();
+        }
+        roots
+    })?;
+    let recent_roots = WORKSPACE_DB
+        .recent_workspaces_on_disk()
+        .await
+        .log_err()
+        .unwrap_or_default()
+        .into_iter()
+        .flat_map(|(_, location)| location.paths().iter().cloned().collect::<Vec<_>>());
+
+    let fs = app_state.fs.as_ref();
+    let mut found = None;
+    for root in open_roots.iter().cloned().chain(recent_roots) {
+        if let Some(clone) = find_linked_clone(fs, &root, &links).await {
+            found = Some(clone);
+            break;
+        }
+    }
+    let Some((link, abs_path)) = found else {
+        return Ok(false);
+    };
+
+    // The clone is only opened after asking, so joining a channel without a window to ask
+    // in never opens one. A clone that's already open is just brought to the front.
+    let Some(requesting_window) = requesting_window else {
+        return Ok(false);
+    };
+    if !open_roots.contains(&abs_path) {
+        let detail = format!(
+            "This channel is linked to {}. Do you want to open your clone of it at {}?",
+            link.remote_url,
+            abs_path.display()
+        );
+        let answer = requesting_window
+            .update(cx, |_, cx| {
+                cx.prompt(
+                    PromptLevel::Info,
+                    "Open the channel's project?",
+                    Some(&detail),
+                    &["Open Project", "Not Now"],
+                )
+            })?
+            .await;
+        if answer != Ok(0) {
+            return Ok(false);
+        }
+    }
+
+    let (window, _) = cx
+        .update(|cx| open_paths(&[abs_path.clone()], app_state, None, cx))?
+        .await?;
+
+    let is_host = link.host_user_id.is_some() && link.host_user_id == app_state.client.user_id();
+    let is_guest = room.update(cx, |room, _| room.local_participant_is_guest())?;
+    if is_host && !is_guest {
+        let detail = format!(
+            "Everyone in the call will be able to see and edit the files in {}.",
+            abs_path.display()
+        );
+        let answer = window
+            .update(cx, |_, cx| {
+                cx.prompt(
+                    PromptLevel::Info,
+                    "Share the channel's project?",
+                    Some(&detail),
+                    &["Share Project", "Not Now"],
+                )
+            })?
+            .await;
+        if answer == Ok(0) {
+            let project = window.update(cx, |workspace, _| workspace.project.clone())?;
+            room.update(cx, |room, cx| room.share_project(project, cx))?
+                .await?;
+        }
+    }
+
+    Ok(true)
+}
+
+async fn find_linked_clone(
+    fs: &dyn Fs,
+    abs_path: &Path,
+    links: &[proto::ChannelProjectLink],
+) -> Option<(proto::ChannelProjectLink, PathBuf)> {
+    let (repository_root, remote_urls) = containing_repository(fs, abs_path).await?;
+    let remote_urls = remote_urls
+        .iter()
+        .map(|url| normalize_remote_url(url))
+        .collect::<Vec<_>>();
+    for link in links {
+        if !remote_urls.contains(&normalize_remote_url(&link.remote_url)) {
+            continue;
+        }
+
+        let Some(linked_path) = linked_path(&repository_root, &link.path) else {
+            continue;
+        };
+        if fs.is_dir(&linked_path).await {
+            return Some((link.clone(), linked_path));
+        }
+    }
+    None
+}
+
+/// Resolves a link's path within a clone, refusing paths that could lead outside of it.
+fn linked_path(repository_root: &Path, path: &str) -> Option<PathBuf> {
+    let mut linked_path = repository_root.to_path_buf();
+    for component in path.split('/').filter(|component| !component.is_empty()) {
+        let mut components = Path::new(component).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(component)), None) => linked_path.push(component),
+            _ => return None,
+        }
+    }
+    Some(linked_path)
+}
+
+/// Returns the root of the repository containing `abs_path` and the URLs of its
+/// remotes, with `origin` first.
+async fn containing_repository(fs: &dyn Fs, abs_path: &Path) -> Option<(PathBuf, Vec<String>)> {
+    for ancestor in abs_path.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if fs.is_dir(&dot_git).await {
+            let repository = fs.open_repo(&dot_git)?;
+            let repository = repository.lock();
+            let mut remote_urls = repository.remote_urls();
+            if let Some(origin_url) = repository.remote_url(REMOTE_NAME) {
+                remote_urls.retain(|url| *url != origin_url);
+                remote_urls.insert(0, origin_url);
+            }
+            return Some((ancestor.to_path_buf(), remote_urls));
+        }
+    }
+    None
+}
+
+/// Reduces the ways a remote can be spelled, such as over SSH or HTTPS and with or
+/// without a `.git` suffix, to a single form, so that clones made with different URLs
+/// are still recognized as the same repository.
+fn normalize_remote_url(url: &str) -> String {
+    let url = url.trim();
+    let (host, path) = if let Some((_, rest)) = url.split_once("://") {
+        rest.split_once('/').unwrap_or((rest, ""))
+    } else {
+        // An scp-like address, such as `git@github.com:owner/repo.git`.
+        url.split_once(':').unwrap_or(("", url))
+    };
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split_once(':').map_or(host, |(host, _)| host);
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    format!("{}/{}", host.to_lowercase(), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{linked_path, normalize_remote_url};
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn test_normalize_remote_url() {
+        let expected = "github.com/zed-industries/zed";
+        for url in [
+            "git@github.com:zed-industries/zed.git",
+            "ssh://git@github.com/zed-industries/zed.git",
+            "ssh://git@github.com:22/zed-industries/zed",
+            "https://github.com/zed-industries/zed",
+            "https://user@GitHub.com/zed-industries/zed.git/",
+        ] {
+            assert_eq!(normalize_remote_url(url), expected, "{url}");
+        }
+        assert_ne!(
+            normalize_remote_url("git@github.com:zed-industries/zed.dev.git"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_linked_path() {
+        let root = Path::new("/code/zed");
+        assert_eq!(linked_path(root, ""), Some(PathBuf::from("/code/zed")));
+        assert_eq!(
+            linked_path(root, "crates//collab/"),
+            Some(PathBuf::from("/code/zed/crates/collab"))
+        );
+        for path in ["..", "crates/../..", "./crates"] {
+            assert_eq!(linked_path(root, path), None, "{path}");
+        }
+    }
+}
@@ -410,6 +410,11 @@ impl Pane {
         cx.notify();
     }
 
+    /// The direction that an item dragged over this pane would split it in, if dropped.
+    pub fn drag_split_direction(&self) -> Option<SplitDirection> {
+        self.drag_split_direction
+    }
+
     pub fn set_can_navigate(&mut self, can_navigate: bool, cx: &mut ViewContext<Self>) {
         self.toolbar.update(cx, |toolbar, cx| {
             toolbar.set_can_navigate(can_navigate, cx);
@@ -1,3 +1,4 @@
+mod channel_project_links;
 pub mod dock;
 pub mod item;
 mod modal_layer;
@@ -13,6 +14,8 @@ mod workspace_settings;
 
 use anyhow::{anyhow, Context as _, Result};
 use call::{call_settings::CallSettings, ActiveCall};
+use channel_project_links::open_channel_project_link;
+pub use channel_project_links::project_link_for_path;
 use client::{
     proto::{self, ErrorCode, PeerId},
     Client, ErrorExt, Status, TypedEnvelope, UserStore,
@@ -49,7 +52,9 @@ pub use persistence::{
     WorkspaceDb, DB as WORKSPACE_DB,
 };
 use postage::stream::Stream;
-use project::{Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
+use project::{
+    offline_edits::OfflineEditConflict, Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId,
+};
 use serde::Deserialize;
 use settings::Settings;
 use shared_screen::SharedScreen;
@@ -68,7 +73,7 @@ use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
 use ui::Label;
-use util::ResultExt;
+use util::{paths::OFFLINE_EDITS_DIR, ResultExt};
 use uuid::Uuid;
 pub use workspace_settings::{AutosaveSetting, WorkspaceSettings};
 
@@ -516,7 +521,7 @@ pub struct ViewId {
 }
 
 #[derive(Default)]
-struct FollowerState {
+pub struct FollowerState {
     leader_id: PeerId,
     active_view_id: Option<ViewId>,
     items_by_leader_view_id: HashMap<ViewId, Box<dyn FollowableItemHandle>>,
@@ -940,6 +945,10 @@ impl Workspace {
         &self.project
     }
 
+    pub fn zoomed_item(&self) -> Option<&AnyWeakView> {
+        self.zoomed.as_ref()
+    }
+
     pub fn recent_navigation_history(
         &self,
         limit: Option<usize>,
@@ -1257,7 +1266,22 @@ impl Workspace {
         mut save_intent: SaveIntent,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<bool>> {
-        if self.project.read(cx).is_disconnected() {
+        let project = self.project.read(cx);
+        if project.is_disconnected() {
+            // Edits that never reached the host can't be saved into the project anymore,
+            // so they're kept on disk instead of being lost with the window.
+            if save_intent == SaveIntent::Close && project.has_offline_edits() {
+                let dir = OFFLINE_EDITS_DIR.join(project.remote_id().unwrap_or(0).to_string());
+                let save = self
+                    .project
+                    .update(cx, |project, cx| project.save_offline_edits(&dir, cx));
+                return cx.background_executor().spawn(async move {
+                    for path in save.await? {
+                        log::info!("saved unsent edits to {path:?}");
+                    }
+                    Ok(true)
+                });
+            }
             return Task::ready(Ok(true));
         }
         let dirty_items = self
@@ -4097,10 +4121,24 @@ async fn join_channel_internal(
         .await;
 
     let task = room.update(cx, |room, cx| {
-        if let Some((project, host)) = room.most_active_project(cx) {
-            return Some(join_remote_project(project, host, app_state.clone(), cx));
-        }
+        room.most_active_project(cx)
+            .map(|(project, host)| join_remote_project(project, host, app_state.clone(), cx))
+    })?;
+    if let Some(task) = task {
+        task.await?;
+        return anyhow::Ok(true);
+    }
+
+    // if the channel is linked to a repository you have a clone of, open it
+    if open_channel_project_link(channel_id, &room, app_state, requesting_window, cx)
+        .await
+        .log_err()
+        .unwrap_or(false)
+    {
+        return anyhow::Ok(true);
+    }
 
+    let task = room.update(cx, |room, cx| {
         // if you are the first to join a channel, share your project
         if room.remote_participants().len() == 0 && !room.local_participant_is_guest() {
             if let Some(workspace) = requesting_window {
@@ -4341,14 +4379,21 @@ pub fn join_remote_project(
 ) -> Task<Result<()>> {
     let windows = cx.windows();
     cx.spawn(|mut cx| async move {
+        let mut offline_workspace = None;
         let existing_workspace = windows.into_iter().find_map(|window| {
             window.downcast::<Workspace>().and_then(|window| {
                 window
                     .update(&mut cx, |workspace, cx| {
-                        if workspace.project().read(cx).remote_id() == Some(project_id) {
-                            Some(window)
-                        } else {
+                        let project = workspace.project().read(cx);
+                        if project.remote_id() != Some(project_id) {
+                            None
+                        } else if project.is_disconnected() {
+                            if project.has_offline_edits() {
+                                offline_workspace = Some(window);
+                            }
                             None
+                        } else {
+                            Some(window)
                         }
                     })
                     .unwrap_or(None)
@@ -4374,12 +4419,19 @@ pub fn join_remote_project(
                 .await?;
 
             let window_bounds_override = window_bounds_env_override(&cx);
-            cx.update(|cx| {
+            let workspace = cx.update(|cx| {
                 let options = (app_state.build_window_options)(window_bounds_override, None, cx);
                 cx.open_window(options, |cx| {
-                    cx.new_view(|cx| Workspace::new(0, project, app_state.clone(), cx))
+                    cx.new_view(|cx| Workspace::new(0, project.clone(), app_state.clone(), cx))
                 })
-            })?
+            })?;
+
+            if let Some(offline_workspace) = offline_workspace {
+                replay_offline_edits(offline_workspace, workspace, project, &mut cx)
+                    .await
+                    .log_err();
+            }
+            workspace
         };
 
         workspace.update(&mut cx, |workspace, cx| {
@@ -4414,6 +4466,61 @@ pub fn join_remote_project(
     })
 }
 
+/// Replays the edits that a workspace disconnected from its host was still holding
+/// into the project that replaced it. The disconnected workspace is closed unless
+/// some of its edits couldn't be applied, so the user can still recover them.
+async fn replay_offline_edits(
+    offline_workspace: WindowHandle<Workspace>,
+    workspace: WindowHandle<Workspace>,
+    project: Model<Project>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let offline_project =
+        offline_workspace.update(cx, |workspace, _| workspace.project().clone())?;
+    let report = project
+        .update(cx, |project, cx| {
+            project.replay_offline_edits(offline_project, cx)
+        })?
+        .await?;
+
+    let all_edits_applied = report
+        .conflicts
+        .iter()
+        .all(|conflict| matches!(conflict, OfflineEditConflict::HostDiverged(_)));
+    if all_edits_applied {
+        offline_workspace.update(cx, |_, cx| cx.remove_window())?;
+    }
+    if report.conflicts.is_empty() {
+        return Ok(());
+    }
+
+    let mut message = String::from("Some edits made while offline need reviewing:");
+    for conflict in &report.conflicts {
+        let reason = match conflict {
+            OfflineEditConflict::HostDiverged(_) => "merged with the host's changes",
+            OfflineEditConflict::HostClosed(_) => "not applied, the host reopened the file",
+            OfflineEditConflict::FileRemoved(_) => "not applied, the file is no longer available",
+            OfflineEditConflict::ReplicaReassigned(_) => {
+                "not applied, they would clash with another collaborator's edits"
+            }
+        };
+        message.push_str(&format!("\n{}: {reason}", conflict.path().path.display()));
+    }
+    if !all_edits_applied {
+        message.push_str(
+            "\nEdits that weren't applied remain in the disconnected window, \
+            and are saved to disk when it's closed.",
+        );
+    }
+    let notification_id = project.read_with(cx, |project, _| project.remote_id())?;
+    workspace.update(cx, |workspace, cx| {
+        workspace.show_notification(notification_id.unwrap_or(0) as usize, cx, |cx| {
+            cx.new_view(|_| MessageNotification::new(message))
+        })
+    })?;
+    Ok(())
+}
+
 pub fn restart(_: &Restart, cx: &mut AppContext) {
     let should_confirm = WorkspaceSettings::get_global(cx).confirm_quit;
     let mut workspace_windows = cx
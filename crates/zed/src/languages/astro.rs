@@ -9,6 +9,7 @@ use smol::fs;
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -16,8 +17,8 @@ use util::ResultExt;
 
 const SERVER_PATH: &'static str = "node_modules/@astrojs/language-server/bin/nodeServer.js";
 
-fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn server_binary_arguments(server_path: &Path, arguments: Vec<OsString>) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 pub struct AstroLspAdapter {
@@ -40,6 +41,10 @@ impl LspAdapter for AstroLspAdapter {
         "astro"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -71,7 +76,8 @@ impl LspAdapter for AstroLspAdapter {
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: server_binary_arguments(&server_path),
+            arguments: server_binary_arguments(&server_path, self.default_binary_arguments()),
+            env: None,
         })
     }
 
@@ -80,14 +86,14 @@ impl LspAdapter for AstroLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
@@ -107,6 +113,7 @@ impl LspAdapter for AstroLspAdapter {
 async fn get_cached_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     (|| async move {
         let mut last_version_dir = None;
@@ -122,7 +129,8 @@ async fn get_cached_server_binary(
         if server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: server_binary_arguments(&server_path),
+                arguments: server_binary_arguments(&server_path, arguments),
+                env: None,
             })
         } else {
             Err(anyhow!(
@@ -100,6 +100,7 @@ impl super::LspAdapter for ClojureLspAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
+            env: None,
         })
     }
 
@@ -113,6 +114,7 @@ impl super::LspAdapter for ClojureLspAdapter {
             Some(LanguageServerBinary {
                 path: binary_path,
                 arguments: vec![],
+                env: None,
             })
         } else {
             None
@@ -128,6 +130,7 @@ impl super::LspAdapter for ClojureLspAdapter {
             Some(LanguageServerBinary {
                 path: binary_path,
                 arguments: vec!["--version".into()],
+                env: None,
             })
         } else {
             None
@@ -3,7 +3,7 @@ use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
 use async_trait::async_trait;
 use futures::{io::BufReader, StreamExt};
-use language::{LanguageServerName, LspAdapterDelegate};
+use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use smol::fs;
 use std::env::consts::ARCH;
@@ -16,7 +16,7 @@ use util::{github::GitHubLspBinaryVersion, ResultExt};
 pub struct OmniSharpAdapter;
 
 #[async_trait]
-impl super::LspAdapter for OmniSharpAdapter {
+impl LspAdapter for OmniSharpAdapter {
     fn name(&self) -> LanguageServerName {
         LanguageServerName("OmniSharp".into())
     }
@@ -25,6 +25,10 @@ impl super::LspAdapter for OmniSharpAdapter {
         "OmniSharp"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["-lsp".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -88,7 +92,8 @@ impl super::LspAdapter for OmniSharpAdapter {
         .await?;
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: server_binary_arguments(),
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -97,23 +102,21 @@ impl super::LspAdapter for OmniSharpAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--help".into()];
-                binary
-            })
+        get_cached_server_binary(container_dir, vec!["--help".into()]).await
     }
 }
 
-async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last_binary_path = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -132,7 +135,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
         if let Some(path) = last_binary_path {
             Ok(LanguageServerBinary {
                 path,
-                arguments: server_binary_arguments(),
+                arguments,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
@@ -141,7 +145,3 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
     .await
     .log_err()
 }
-
-fn server_binary_arguments() -> Vec<OsString> {
-    vec!["-lsp".into()]
-}
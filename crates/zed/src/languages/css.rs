@@ -9,6 +9,7 @@ use smol::fs;
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -17,8 +18,8 @@ use util::{async_maybe, ResultExt};
 const SERVER_PATH: &'static str =
     "node_modules/vscode-langservers-extracted/bin/vscode-css-language-server";
 
-fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn server_binary_arguments(server_path: &Path, arguments: Vec<OsString>) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 pub struct CssLspAdapter {
@@ -41,6 +42,10 @@ impl LspAdapter for CssLspAdapter {
         "css"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -72,7 +77,8 @@ impl LspAdapter for CssLspAdapter {
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: server_binary_arguments(&server_path),
+            arguments: server_binary_arguments(&server_path, self.default_binary_arguments()),
+            env: None,
         })
     }
 
@@ -81,14 +87,14 @@ impl LspAdapter for CssLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
@@ -101,6 +107,7 @@ impl LspAdapter for CssLspAdapter {
 async fn get_cached_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last_version_dir = None;
@@ -116,7 +123,8 @@ async fn get_cached_server_binary(
         if server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: server_binary_arguments(&server_path),
+                arguments: server_binary_arguments(&server_path, arguments),
+                env: None,
             })
         } else {
             Err(anyhow!(
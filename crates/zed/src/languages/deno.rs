@@ -44,10 +44,6 @@ impl Settings for DenoSettings {
     }
 }
 
-fn deno_server_binary_arguments() -> Vec<OsString> {
-    vec!["lsp".into()]
-}
-
 pub struct DenoLspAdapter {}
 
 impl DenoLspAdapter {
@@ -66,6 +62,10 @@ impl LspAdapter for DenoLspAdapter {
         "deno-ts"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["lsp".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -128,7 +128,8 @@ impl LspAdapter for DenoLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: deno_server_binary_arguments(),
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -137,14 +138,14 @@ impl LspAdapter for DenoLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
@@ -200,7 +201,10 @@ impl LspAdapter for DenoLspAdapter {
     }
 }
 
-async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -214,7 +218,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
                 if fs::metadata(&binary).await.is_ok() {
                     return Ok(LanguageServerBinary {
                         path: binary,
-                        arguments: deno_server_binary_arguments(),
+                        arguments,
+                        env: None,
                     });
                 }
             }
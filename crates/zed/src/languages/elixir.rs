@@ -11,6 +11,7 @@ use smol::fs::{self, File};
 use std::{
     any::Any,
     env::consts,
+    ffi::OsString,
     ops::Deref,
     path::PathBuf,
     sync::{
@@ -174,7 +175,8 @@ impl LspAdapter for ElixirLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: vec![],
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -183,14 +185,14 @@ impl LspAdapter for ElixirLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary_elixir_ls(container_dir).await
+        get_cached_server_binary_elixir_ls(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary_elixir_ls(container_dir).await
+        get_cached_server_binary_elixir_ls(container_dir, self.default_binary_arguments()).await
     }
 
     async fn label_for_completion(
@@ -279,12 +281,14 @@ impl LspAdapter for ElixirLspAdapter {
 
 async fn get_cached_server_binary_elixir_ls(
     container_dir: PathBuf,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     let server_path = container_dir.join("elixir-ls/language_server.sh");
     if server_path.exists() {
         Some(LanguageServerBinary {
             path: server_path,
-            arguments: vec![],
+            arguments,
+            env: None,
         })
     } else {
         log::error!("missing executable in directory {:?}", server_path);
@@ -304,6 +308,10 @@ impl LspAdapter for NextLspAdapter {
         "next-ls"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -365,7 +373,8 @@ impl LspAdapter for NextLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: vec!["--stdio".into()],
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -374,24 +383,14 @@ impl LspAdapter for NextLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary_next(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--stdio".into()];
-                binary
-            })
+        get_cached_server_binary_next(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary_next(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--help".into()];
-                binary
-            })
+        get_cached_server_binary_next(container_dir, vec!["--help".into()]).await
     }
 
     async fn label_for_completion(
@@ -412,7 +411,10 @@ impl LspAdapter for NextLspAdapter {
     }
 }
 
-async fn get_cached_server_binary_next(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary_next(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last_binary_path = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -431,7 +433,8 @@ async fn get_cached_server_binary_next(container_dir: PathBuf) -> Option<Languag
         if let Some(path) = last_binary_path {
             Ok(LanguageServerBinary {
                 path,
-                arguments: Vec::new(),
+                arguments,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
@@ -473,6 +476,7 @@ impl LspAdapter for LocalLspAdapter {
         Ok(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            env: None,
         })
     }
 
@@ -485,6 +489,7 @@ impl LspAdapter for LocalLspAdapter {
         Some(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            env: None,
         })
     }
 
@@ -493,6 +498,7 @@ impl LspAdapter for LocalLspAdapter {
         Some(LanguageServerBinary {
             path: PathBuf::from(path.deref()),
             arguments: self.arguments.iter().map(|arg| arg.into()).collect(),
+            env: None,
         })
     }
 
@@ -12,6 +12,7 @@ use smol::fs;
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -20,8 +21,8 @@ use util::ResultExt;
 const SERVER_NAME: &'static str = "elm-language-server";
 const SERVER_PATH: &'static str = "node_modules/@elm-tooling/elm-language-server/out/node/index.js";
 
-fn server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn server_binary_arguments(server_path: &Path, arguments: Vec<OsString>) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 pub struct ElmLspAdapter {
@@ -44,6 +45,10 @@ impl LspAdapter for ElmLspAdapter {
         "elmLS"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -75,7 +80,8 @@ impl LspAdapter for ElmLspAdapter {
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: server_binary_arguments(&server_path),
+            arguments: server_binary_arguments(&server_path, self.default_binary_arguments()),
+            env: None,
         })
     }
 
@@ -84,14 +90,14 @@ impl LspAdapter for ElmLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     fn workspace_configuration(&self, _workspace_root: &Path, cx: &mut AppContext) -> Value {
@@ -119,6 +125,7 @@ impl LspAdapter for ElmLspAdapter {
 async fn get_cached_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     (|| async move {
         let mut last_version_dir = None;
@@ -134,7 +141,8 @@ async fn get_cached_server_binary(
         if server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: server_binary_arguments(&server_path),
+                arguments: server_binary_arguments(&server_path, arguments),
+                env: None,
             })
         } else {
             Err(anyhow!(
@@ -42,6 +42,7 @@ impl LspAdapter for ErlangLspAdapter {
         Some(LanguageServerBinary {
             path: "erlang_ls".into(),
             arguments: vec![],
+            env: None,
         })
     }
 
@@ -53,6 +54,7 @@ impl LspAdapter for ErlangLspAdapter {
         Some(LanguageServerBinary {
             path: "erlang_ls".into(),
             arguments: vec!["--version".into()],
+            env: None,
         })
     }
 }
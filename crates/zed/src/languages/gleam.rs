@@ -14,10 +14,6 @@ use smol::fs;
 use util::github::{latest_github_release, GitHubLspBinaryVersion};
 use util::{async_maybe, ResultExt};
 
-fn server_binary_arguments() -> Vec<OsString> {
-    vec!["lsp".into()]
-}
-
 pub struct GleamLspAdapter;
 
 #[async_trait]
@@ -30,6 +26,10 @@ impl LspAdapter for GleamLspAdapter {
         "gleam"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["lsp".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -75,7 +75,8 @@ impl LspAdapter for GleamLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: server_binary_arguments(),
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -84,23 +85,21 @@ impl LspAdapter for GleamLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--version".into()];
-                binary
-            })
+        get_cached_server_binary(container_dir, vec!["--version".into()]).await
     }
 }
 
-async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -110,7 +109,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
 
         anyhow::Ok(LanguageServerBinary {
             path: last.ok_or_else(|| anyhow!("no cached binary"))?,
-            arguments: server_binary_arguments(),
+            arguments,
+            env: None,
         })
     })
     .await
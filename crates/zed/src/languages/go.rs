@@ -21,10 +21,6 @@ use std::{
 };
 use util::{async_maybe, fs::remove_matching, github::latest_github_release, ResultExt};
 
-fn server_binary_arguments() -> Vec<OsString> {
-    vec!["-mode=stdio".into()]
-}
-
 #[derive(Copy, Clone)]
 pub struct GoLspAdapter;
 
@@ -42,6 +38,10 @@ impl super::LspAdapter for GoLspAdapter {
         "gopls"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["-mode=stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -106,7 +106,8 @@ impl super::LspAdapter for GoLspAdapter {
 
                     return Ok(LanguageServerBinary {
                         path: binary_path.to_path_buf(),
-                        arguments: server_binary_arguments(),
+                        arguments: self.default_binary_arguments(),
+                        env: None,
                     });
                 }
             }
@@ -147,7 +148,8 @@ impl super::LspAdapter for GoLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path.to_path_buf(),
-            arguments: server_binary_arguments(),
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -156,19 +158,14 @@ impl super::LspAdapter for GoLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--help".into()];
-                binary
-            })
+        get_cached_server_binary(container_dir, vec!["--help".into()]).await
     }
 
     fn initialization_options(&self) -> Option<serde_json::Value> {
@@ -346,7 +343,10 @@ impl super::LspAdapter for GoLspAdapter {
     }
 }
 
-async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last_binary_path = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -365,7 +365,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
         if let Some(path) = last_binary_path {
             Ok(LanguageServerBinary {
                 path,
-                arguments: server_binary_arguments(),
+                arguments,
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
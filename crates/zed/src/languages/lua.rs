@@ -85,6 +85,7 @@ impl super::LspAdapter for LuaLspAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: Vec::new(),
+            env: None,
         })
     }
 
@@ -129,6 +130,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             Ok(LanguageServerBinary {
                 path,
                 arguments: Vec::new(),
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
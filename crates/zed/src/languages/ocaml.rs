@@ -48,6 +48,7 @@ impl LspAdapter for OCamlLspAdapter {
         Some(LanguageServerBinary {
             path: "ocamllsp".into(),
             arguments: vec![],
+            env: None,
         })
     }
 
@@ -11,13 +11,17 @@ use smol::{fs, stream::StreamExt};
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
 use util::{async_maybe, ResultExt};
 
-fn intelephense_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn intelephense_server_binary_arguments(
+    server_path: &Path,
+    arguments: Vec<OsString>,
+) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 pub struct IntelephenseVersion(String);
@@ -44,6 +48,10 @@ impl LspAdapter for IntelephenseLspAdapter {
         "php"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _delegate: &dyn LspAdapterDelegate,
@@ -69,7 +77,11 @@ impl LspAdapter for IntelephenseLspAdapter {
         }
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: intelephense_server_binary_arguments(&server_path),
+            arguments: intelephense_server_binary_arguments(
+                &server_path,
+                self.default_binary_arguments(),
+            ),
+            env: None,
         })
     }
 
@@ -78,14 +90,14 @@ impl LspAdapter for IntelephenseLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir, &*self.node).await
+        get_cached_server_binary(container_dir, &*self.node, self.default_binary_arguments()).await
     }
 
     async fn label_for_completion(
@@ -107,6 +119,7 @@ impl LspAdapter for IntelephenseLspAdapter {
 async fn get_cached_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last_version_dir = None;
@@ -122,7 +135,8 @@ async fn get_cached_server_binary(
         if server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: intelephense_server_binary_arguments(&server_path),
+                arguments: intelephense_server_binary_arguments(&server_path, arguments),
+                env: None,
             })
         } else {
             Err(anyhow!(
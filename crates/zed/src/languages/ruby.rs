@@ -2,7 +2,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
-use std::{any::Any, path::PathBuf, sync::Arc};
+use std::{any::Any, ffi::OsString, path::PathBuf, sync::Arc};
 
 pub struct RubyLanguageServer;
 
@@ -16,6 +16,10 @@ impl LspAdapter for RubyLanguageServer {
         "solargraph"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -39,7 +43,8 @@ impl LspAdapter for RubyLanguageServer {
     ) -> Option<LanguageServerBinary> {
         Some(LanguageServerBinary {
             path: "solargraph".into(),
-            arguments: vec!["stdio".into()],
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -80,6 +80,7 @@ impl LspAdapter for RustLspAdapter {
         Ok(LanguageServerBinary {
             path: destination_path,
             arguments: Default::default(),
+            env: None,
         })
     }
 
@@ -287,6 +288,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
         anyhow::Ok(LanguageServerBinary {
             path: last.ok_or_else(|| anyhow!("no cached binary"))?,
             arguments: Default::default(),
+            env: None,
         })
     })
     .await
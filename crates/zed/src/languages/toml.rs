@@ -5,7 +5,7 @@ use futures::{io::BufReader, StreamExt};
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
 use smol::fs::{self, File};
-use std::{any::Any, path::PathBuf};
+use std::{any::Any, ffi::OsString, path::PathBuf};
 use util::async_maybe;
 use util::github::latest_github_release;
 use util::{github::GitHubLspBinaryVersion, ResultExt};
@@ -22,6 +22,10 @@ impl LspAdapter for TaploLspAdapter {
         "taplo-ls"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["lsp".into(), "stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -72,7 +76,8 @@ impl LspAdapter for TaploLspAdapter {
 
         Ok(LanguageServerBinary {
             path: binary_path,
-            arguments: vec!["lsp".into(), "stdio".into()],
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -81,23 +86,21 @@ impl LspAdapter for TaploLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir).await
+        get_cached_server_binary(container_dir, self.default_binary_arguments()).await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_server_binary(container_dir)
-            .await
-            .map(|mut binary| {
-                binary.arguments = vec!["--help".into()];
-                binary
-            })
+        get_cached_server_binary(container_dir, vec!["--help".into()]).await
     }
 }
 
-async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServerBinary> {
+async fn get_cached_server_binary(
+    container_dir: PathBuf,
+    arguments: Vec<OsString>,
+) -> Option<LanguageServerBinary> {
     async_maybe!({
         let mut last = None;
         let mut entries = fs::read_dir(&container_dir).await?;
@@ -107,7 +110,8 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
 
         anyhow::Ok(LanguageServerBinary {
             path: last.context("no cached binary")?,
-            arguments: Default::default(),
+            arguments,
+            env: None,
         })
     })
     .await
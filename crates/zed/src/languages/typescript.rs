@@ -12,6 +12,7 @@ use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -22,12 +23,15 @@ use util::{
     ResultExt,
 };
 
-fn typescript_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn typescript_server_binary_arguments(
+    server_path: &Path,
+    arguments: Vec<OsString>,
+) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
-fn eslint_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn eslint_server_binary_arguments(server_path: &Path, arguments: Vec<OsString>) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 pub struct TypeScriptLspAdapter {
@@ -58,6 +62,10 @@ impl LspAdapter for TypeScriptLspAdapter {
         "tsserver"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -97,7 +105,11 @@ impl LspAdapter for TypeScriptLspAdapter {
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: typescript_server_binary_arguments(&server_path),
+            arguments: typescript_server_binary_arguments(
+                &server_path,
+                self.default_binary_arguments(),
+            ),
+            env: None,
         })
     }
 
@@ -106,14 +118,16 @@ impl LspAdapter for TypeScriptLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_ts_server_binary(container_dir, &*self.node).await
+        get_cached_ts_server_binary(container_dir, &*self.node, self.default_binary_arguments())
+            .await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_ts_server_binary(container_dir, &*self.node).await
+        get_cached_ts_server_binary(container_dir, &*self.node, self.default_binary_arguments())
+            .await
     }
 
     fn code_action_kinds(&self) -> Option<Vec<CodeActionKind>> {
@@ -185,6 +199,7 @@ impl LspAdapter for TypeScriptLspAdapter {
 async fn get_cached_ts_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     async_maybe!({
         let old_server_path = container_dir.join(TypeScriptLspAdapter::OLD_SERVER_PATH);
@@ -192,12 +207,14 @@ async fn get_cached_ts_server_binary(
         if new_server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: typescript_server_binary_arguments(&new_server_path),
+                arguments: typescript_server_binary_arguments(&new_server_path, arguments),
+                env: None,
             })
         } else if old_server_path.exists() {
             Ok(LanguageServerBinary {
                 path: node.binary_path().await?,
-                arguments: typescript_server_binary_arguments(&old_server_path),
+                arguments: typescript_server_binary_arguments(&old_server_path, arguments),
+                env: None,
             })
         } else {
             Err(anyhow!(
@@ -249,6 +266,10 @@ impl LspAdapter for EsLintLspAdapter {
         "eslint"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         delegate: &dyn LspAdapterDelegate,
@@ -307,7 +328,11 @@ impl LspAdapter for EsLintLspAdapter {
 
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: eslint_server_binary_arguments(&server_path),
+            arguments: eslint_server_binary_arguments(
+                &server_path,
+                self.default_binary_arguments(),
+            ),
+            env: None,
         })
     }
 
@@ -316,14 +341,16 @@ impl LspAdapter for EsLintLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        get_cached_eslint_server_binary(container_dir, &*self.node).await
+        get_cached_eslint_server_binary(container_dir, &*self.node, self.default_binary_arguments())
+            .await
     }
 
     async fn installation_test_binary(
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        get_cached_eslint_server_binary(container_dir, &*self.node).await
+        get_cached_eslint_server_binary(container_dir, &*self.node, self.default_binary_arguments())
+            .await
     }
 
     async fn label_for_completion(
@@ -342,6 +369,7 @@ impl LspAdapter for EsLintLspAdapter {
 async fn get_cached_eslint_server_binary(
     container_dir: PathBuf,
     node: &dyn NodeRuntime,
+    arguments: Vec<OsString>,
 ) -> Option<LanguageServerBinary> {
     async_maybe!({
         // This is unfortunate but we don't know what the version is to build a path directly
@@ -354,7 +382,8 @@ async fn get_cached_eslint_server_binary(
 
         Ok(LanguageServerBinary {
             path: node.binary_path().await?,
-            arguments: eslint_server_binary_arguments(&server_path),
+            arguments: eslint_server_binary_arguments(&server_path, arguments),
+            env: None,
         })
     })
     .await
@@ -2,7 +2,7 @@ use anyhow::{anyhow, Result};
 use async_trait::async_trait;
 use language::{LanguageServerName, LspAdapter, LspAdapterDelegate};
 use lsp::LanguageServerBinary;
-use std::{any::Any, path::PathBuf};
+use std::{any::Any, ffi::OsString, path::PathBuf};
 
 pub struct UiuaLanguageServer;
 
@@ -16,6 +16,10 @@ impl LspAdapter for UiuaLanguageServer {
         "uiua"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["lsp".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -41,7 +45,8 @@ impl LspAdapter for UiuaLanguageServer {
     ) -> Option<LanguageServerBinary> {
         Some(LanguageServerBinary {
             path: "uiua".into(),
-            arguments: vec!["lsp".into()],
+            arguments: self.default_binary_arguments(),
+            env: None,
         })
     }
 
@@ -10,6 +10,7 @@ use smol::fs::{self};
 use std::{
     any::Any,
     ffi::OsString,
+    iter,
     path::{Path, PathBuf},
     sync::Arc,
 };
@@ -48,6 +49,10 @@ impl super::LspAdapter for VueLspAdapter {
         "vue-language-server"
     }
 
+    fn default_binary_arguments(&self) -> Vec<OsString> {
+        vec!["--stdio".into()]
+    }
+
     async fn fetch_latest_server_version(
         &self,
         _: &dyn LspAdapterDelegate,
@@ -118,7 +123,8 @@ impl super::LspAdapter for VueLspAdapter {
         *self.typescript_install_path.lock() = Some(ts_path);
         Ok(LanguageServerBinary {
             path: self.node.binary_path().await?,
-            arguments: vue_server_binary_arguments(&server_path),
+            arguments: vue_server_binary_arguments(&server_path, self.default_binary_arguments()),
+            env: None,
         })
     }
 
@@ -127,7 +133,12 @@ impl super::LspAdapter for VueLspAdapter {
         container_dir: PathBuf,
         _: &dyn LspAdapterDelegate,
     ) -> Option<LanguageServerBinary> {
-        let (server, ts_path) = get_cached_server_binary(container_dir, self.node.clone()).await?;
+        let (server, ts_path) = get_cached_server_binary(
+            container_dir,
+            self.node.clone(),
+            self.default_binary_arguments(),
+        )
+        .await?;
         *self.typescript_install_path.lock() = Some(ts_path);
         Some(server)
     }
@@ -136,12 +147,16 @@ impl super::LspAdapter for VueLspAdapter {
         &self,
         container_dir: PathBuf,
     ) -> Option<LanguageServerBinary> {
-        let (server, ts_path) = get_cached_server_binary(container_dir, self.node.clone())
-            .await
-            .map(|(mut binary, ts_path)| {
-                binary.arguments = vec!["--help".into()];
-                (binary, ts_path)
-            })?;
+        let (server, ts_path) = get_cached_server_binary(
+            container_dir,
+            self.node.clone(),
+            self.default_binary_arguments(),
+        )
+        .await
+        .map(|(mut binary, ts_path)| {
+            binary.arguments = vec!["--help".into()];
+            (binary, ts_path)
+        })?;
         *self.typescript_install_path.lock() = Some(ts_path);
         Some(server)
     }
@@ -179,14 +194,15 @@ impl super::LspAdapter for VueLspAdapter {
     }
 }
 
-fn vue_server_binary_arguments(server_path: &Path) -> Vec<OsString> {
-    vec![server_path.into(), "--stdio".into()]
+fn vue_server_binary_arguments(server_path: &Path, arguments: Vec<OsString>) -> Vec<OsString> {
+    iter::once(server_path.into()).chain(arguments).collect()
 }
 
 type TypescriptPath = PathBuf;
 async fn get_cached_server_binary(
     container_dir: PathBuf,
     node: Arc<dyn NodeRuntime>,
+    arguments: Vec<OsString>,
 ) -> Option<(LanguageServerBinary, TypescriptPath)> {
     async_maybe!({
         let mut last_version_dir = None;
@@ -204,7 +220,8 @@ async fn get_cached_server_binary(
             Ok((
                 LanguageServerBinary {
                     path: node.binary_path().await?,
-                    arguments: vue_server_binary_arguments(&server_path),
+                    arguments: vue_server_binary_arguments(&server_path, arguments),
+                    env: None,
                 },
                 typescript_path,
             ))
@@ -72,6 +72,7 @@ impl LspAdapter for ZlsAdapter {
         Ok(LanguageServerBinary {
             path: binary_path,
             arguments: vec![],
+            env: None,
         })
     }
 
@@ -116,6 +117,7 @@ async fn get_cached_server_binary(container_dir: PathBuf) -> Option<LanguageServ
             Ok(LanguageServerBinary {
                 path,
                 arguments: Vec::new(),
+                env: None,
             })
         } else {
             Err(anyhow!("no cached binary"))
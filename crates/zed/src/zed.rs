@@ -126,10 +126,13 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
         let feedback_button =
             cx.new_view(|_| feedback::deploy_feedback_button::DeployFeedbackButton::new(workspace));
         let cursor_position = cx.new_view(|_| editor::items::CursorPosition::new());
+        let large_file_indicator =
+            cx.new_view(|_| editor::large_file_indicator::LargeFileIndicator::new());
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
             status_bar.add_right_item(feedback_button, cx);
+            status_bar.add_right_item(large_file_indicator, cx);
             status_bar.add_right_item(copilot, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
@@ -186,6 +189,10 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                let language_server_panel = language_tools::LanguageServerPanel::new(workspace, cx);
+                workspace.add_panel(language_server_panel, cx);
+                let review_panel = collab_ui::review_panel::ReviewPanel::new(workspace, cx);
+                workspace.add_panel(review_panel, cx);
                 cx.focus_self();
             })
         })
@@ -358,6 +365,8 @@ fn initialize_pane(workspace: &mut Workspace, pane: &View<Pane>, cx: &mut ViewCo
             toolbar.add_item(quick_action_bar, cx);
             let diagnostic_editor_controls = cx.new_view(|_| diagnostics::ToolbarControls::new());
             toolbar.add_item(diagnostic_editor_controls, cx);
+            let diagnostics_filter_bar = cx.new_view(|_| diagnostics::DiagnosticsFilterBar::new());
+            toolbar.add_item(diagnostics_filter_bar, cx);
             let project_search_bar = cx.new_view(|_| ProjectSearchBar::new());
             toolbar.add_item(project_search_bar, cx);
             let lsp_log_item = cx.new_view(|_| language_tools::LspLogToolbarItemView::new());